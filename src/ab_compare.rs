@@ -0,0 +1,222 @@
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::history::{GameEvent, SessionControl};
+use crate::i18n::t;
+
+/// 检查窗口标题的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 等待进入游戏的默认时长（秒）
+pub const DEFAULT_TIMEOUT_SECS: u64 = 90;
+
+/// A/B 对比中一个 profile 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbOutcome {
+    /// 已启动，等待进入游戏
+    Waiting,
+    /// 窗口标题中出现了角色名
+    Connected { after_secs: u64 },
+    /// 进入游戏前进程就结束了
+    Exited(Option<i32>),
+    /// 超时仍未进入游戏
+    TimedOut,
+    /// 没有设置角色名，无法判断是否进入游戏，超时时进程仍在运行
+    Unconfirmed,
+    Failed(String),
+}
+
+impl AbOutcome {
+    pub fn is_connected(&self) -> bool {
+        matches!(self, AbOutcome::Connected { .. })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            AbOutcome::Waiting => t!("ab_compare.waiting").to_string(),
+            AbOutcome::Connected { after_secs } => t!("ab_compare.connected", secs = after_secs).to_string(),
+            AbOutcome::Exited(code) => {
+                let code = code.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string());
+                t!("ab_compare.exited", code = code).to_string()
+            }
+            AbOutcome::TimedOut => t!("ab_compare.timed_out").to_string(),
+            AbOutcome::Unconfirmed => t!("ab_compare.unconfirmed").to_string(),
+            AbOutcome::Failed(err) => t!("ab_compare.failed", error = err).to_string(),
+        }
+    }
+}
+
+/// 参与对比的一个 profile
+#[derive(Debug, Clone)]
+pub struct AbSide {
+    pub profile_name: String,
+    pub character: String,
+    pub record_id: Option<String>,
+    pub outcome: AbOutcome,
+}
+
+impl AbSide {
+    pub fn new(profile_name: &str, character: &str) -> Self {
+        Self {
+            profile_name: profile_name.to_string(),
+            character: character.trim().to_string(),
+            record_id: None,
+            outcome: AbOutcome::Waiting,
+        }
+    }
+}
+
+/// 用两个 profile 同时启动同一个服务器，记录哪个能进入游戏
+#[derive(Debug)]
+pub struct AbComparison {
+    pub sides: [AbSide; 2],
+    started: Instant,
+    timeout: Duration,
+}
+
+impl AbComparison {
+    pub fn new(sides: [AbSide; 2], timeout: Duration, now: Instant) -> Self {
+        Self { sides, started: now, timeout }
+    }
+
+    fn side_mut(&mut self, record_id: &str) -> Option<&mut AbSide> {
+        self.sides
+            .iter_mut()
+            .find(|s| s.record_id.as_deref() == Some(record_id) && s.outcome == AbOutcome::Waiting)
+    }
+
+    pub fn on_connected(&mut self, record_id: &str, now: Instant) {
+        let after_secs = now.duration_since(self.started).as_secs();
+        if let Some(side) = self.side_mut(record_id) {
+            side.outcome = AbOutcome::Connected { after_secs };
+        }
+    }
+
+    pub fn on_exited(&mut self, record_id: &str, exit_code: Option<i32>) {
+        if let Some(side) = self.side_mut(record_id) {
+            side.outcome = AbOutcome::Exited(exit_code);
+        }
+    }
+
+    /// 超时后结束等待，返回是否有状态变化
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.started) < self.timeout {
+            return false;
+        }
+        let mut changed = false;
+        for side in self.sides.iter_mut().filter(|s| s.outcome == AbOutcome::Waiting) {
+            side.outcome = if side.character.is_empty() {
+                AbOutcome::Unconfirmed
+            } else {
+                AbOutcome::TimedOut
+            };
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.sides.iter().all(|s| s.outcome != AbOutcome::Waiting)
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.timeout.saturating_sub(now.duration_since(self.started))
+    }
+
+    /// 纯文本报告，方便贴给服务器管理员
+    pub fn report(&self, shard: &str) -> String {
+        let mut report = format!("{}\n", t!("ab_compare.report_title", shard = shard));
+        for (label, side) in ["A", "B"].iter().zip(&self.sides) {
+            report.push_str(&format!("{}: {} — {}\n", label, side.profile_name, side.outcome.label()));
+        }
+        let verdict = match (self.sides[0].outcome.is_connected(), self.sides[1].outcome.is_connected()) {
+            (true, false) => t!("ab_compare.verdict_one", profile = &self.sides[0].profile_name).to_string(),
+            (false, true) => t!("ab_compare.verdict_one", profile = &self.sides[1].profile_name).to_string(),
+            (true, true) => t!("ab_compare.verdict_both").to_string(),
+            (false, false) => t!("ab_compare.verdict_none").to_string(),
+        };
+        report.push_str(&verdict);
+        report
+    }
+}
+
+/// 窗口标题中出现角色名时视为已进入游戏
+fn is_in_game(title: &str, character: &str) -> bool {
+    let character = character.trim().to_lowercase();
+    !character.is_empty() && title.to_lowercase().contains(&character)
+}
+
+/// 在后台线程监视游戏窗口，进入游戏后发送 `GameEvent::Connected`
+pub fn spawn_probe(
+    pid: u32,
+    character: String,
+    record_id: String,
+    control: Arc<SessionControl>,
+    timeout: Duration,
+    tx: mpsc::Sender<GameEvent>,
+) {
+    if character.trim().is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        while !control.has_exited() && started.elapsed() < timeout {
+            std::thread::sleep(POLL_INTERVAL);
+            if crate::watchdog::window_title(pid).is_some_and(|title| is_in_game(&title, &character)) {
+                let _ = tx.send(GameEvent::Connected { record_id });
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comparison(now: Instant) -> AbComparison {
+        let mut a = AbSide::new("Encrypted", "Lord British");
+        a.record_id = Some("a".to_string());
+        let mut b = AbSide::new("Plain", "Lord British");
+        b.record_id = Some("b".to_string());
+        AbComparison::new([a, b], Duration::from_secs(60), now)
+    }
+
+    #[test]
+    fn test_is_in_game() {
+        assert!(is_in_game("OpenUO - Lord British (Britannia)", "lord british"));
+        assert!(!is_in_game("OpenUO 1.0.0", "Lord British"));
+        assert!(!is_in_game("OpenUO 1.0.0", " "));
+    }
+
+    #[test]
+    fn test_outcomes() {
+        let start = Instant::now();
+        let mut ab = comparison(start);
+        ab.on_connected("a", start + Duration::from_secs(12));
+        assert_eq!(ab.sides[0].outcome, AbOutcome::Connected { after_secs: 12 });
+        assert!(!ab.is_finished());
+
+        // 已有结果的一方不再改变（例如进入游戏后正常退出）
+        ab.on_exited("a", Some(0));
+        assert!(ab.sides[0].outcome.is_connected());
+
+        assert!(!ab.tick(start + Duration::from_secs(30)));
+        assert!(ab.tick(start + Duration::from_secs(61)));
+        assert_eq!(ab.sides[1].outcome, AbOutcome::TimedOut);
+        assert!(ab.is_finished());
+    }
+
+    #[test]
+    fn test_exit_and_unconfirmed() {
+        let start = Instant::now();
+        let mut ab = comparison(start);
+        ab.sides[1].character.clear();
+        ab.on_exited("a", Some(1));
+        assert_eq!(ab.sides[0].outcome, AbOutcome::Exited(Some(1)));
+        ab.on_exited("unknown", None);
+        ab.tick(start + Duration::from_secs(60));
+        assert_eq!(ab.sides[1].outcome, AbOutcome::Unconfirmed);
+    }
+}
@@ -0,0 +1,308 @@
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// 日志文件名（位于 Launcher 目录）
+const LOG_FILE: &str = "launcher.log";
+/// 日志文件超过该大小时轮换为 launcher.log.1
+const MAX_LOG_FILE_BYTES: u64 = 1024 * 1024;
+
+/// 日志条目类型
+#[derive(Debug, Clone)]
+pub enum LogEntryType {
+    Info,
+    Success,
+    Warning,
+    Error,
+    Checking,
+}
+
+/// 日志面板显示哪些级别（所有日志都会写入磁盘）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LogVerbosity {
+    /// 只显示错误
+    Errors,
+    /// 显示错误和警告
+    Warnings,
+    /// 不显示“检查中”之类的过程信息
+    Normal,
+    #[default]
+    Verbose,
+}
+
+impl LogVerbosity {
+    pub const ALL: [LogVerbosity; 4] = [
+        LogVerbosity::Errors,
+        LogVerbosity::Warnings,
+        LogVerbosity::Normal,
+        LogVerbosity::Verbose,
+    ];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            LogVerbosity::Errors => "log_settings.verbosity_errors",
+            LogVerbosity::Warnings => "log_settings.verbosity_warnings",
+            LogVerbosity::Normal => "log_settings.verbosity_normal",
+            LogVerbosity::Verbose => "log_settings.verbosity_verbose",
+        }
+    }
+
+    pub fn shows(self, entry_type: &LogEntryType) -> bool {
+        match entry_type {
+            LogEntryType::Error => true,
+            LogEntryType::Warning => self != LogVerbosity::Errors,
+            LogEntryType::Info | LogEntryType::Success => {
+                matches!(self, LogVerbosity::Normal | LogVerbosity::Verbose)
+            }
+            LogEntryType::Checking => self == LogVerbosity::Verbose,
+        }
+    }
+}
+
+/// 日志面板的配色方案
+///
+/// 每个级别都有形状不同的图标，颜色只是辅助，不单独表达级别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LogPalette {
+    #[default]
+    Standard,
+    /// Okabe-Ito 配色，红绿色盲也能区分
+    ColorBlindSafe,
+    /// 高对比度
+    HighContrast,
+}
+
+impl LogPalette {
+    pub const ALL: [LogPalette; 3] = [LogPalette::Standard, LogPalette::ColorBlindSafe, LogPalette::HighContrast];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            LogPalette::Standard => "log_settings.palette_standard",
+            LogPalette::ColorBlindSafe => "log_settings.palette_color_blind",
+            LogPalette::HighContrast => "log_settings.palette_high_contrast",
+        }
+    }
+
+    pub fn color(self, entry_type: &LogEntryType) -> Color32 {
+        match (self, entry_type) {
+            (LogPalette::Standard, LogEntryType::Info) => Color32::from_rgb(150, 150, 200),
+            (LogPalette::Standard, LogEntryType::Success) => Color32::from_rgb(100, 200, 100),
+            (LogPalette::Standard, LogEntryType::Warning) => Color32::from_rgb(200, 200, 100),
+            (LogPalette::Standard, LogEntryType::Error) => Color32::from_rgb(200, 100, 100),
+            (LogPalette::Standard, LogEntryType::Checking) => Color32::from_rgb(150, 150, 200),
+            (LogPalette::ColorBlindSafe, LogEntryType::Info) => Color32::from_rgb(86, 180, 233),
+            (LogPalette::ColorBlindSafe, LogEntryType::Success) => Color32::from_rgb(0, 158, 115),
+            (LogPalette::ColorBlindSafe, LogEntryType::Warning) => Color32::from_rgb(230, 159, 0),
+            (LogPalette::ColorBlindSafe, LogEntryType::Error) => Color32::from_rgb(213, 94, 0),
+            (LogPalette::ColorBlindSafe, LogEntryType::Checking) => Color32::from_rgb(0, 114, 178),
+            (LogPalette::HighContrast, LogEntryType::Info) => Color32::WHITE,
+            (LogPalette::HighContrast, LogEntryType::Success) => Color32::from_rgb(0, 255, 255),
+            (LogPalette::HighContrast, LogEntryType::Warning) => Color32::from_rgb(255, 255, 0),
+            (LogPalette::HighContrast, LogEntryType::Error) => Color32::from_rgb(255, 80, 255),
+            (LogPalette::HighContrast, LogEntryType::Checking) => Color32::from_rgb(200, 200, 200),
+        }
+    }
+
+    /// 日志正文的颜色
+    pub fn text_color(self) -> Color32 {
+        match self {
+            LogPalette::HighContrast => Color32::WHITE,
+            _ => Color32::from_rgb(200, 200, 200),
+        }
+    }
+}
+
+/// 各级别的图标，形状互不相同
+pub fn icon(entry_type: &LogEntryType) -> &'static str {
+    match entry_type {
+        LogEntryType::Info => "ℹ",
+        LogEntryType::Success => "✓",
+        LogEntryType::Warning => "⚠",
+        LogEntryType::Error => "✗",
+        LogEntryType::Checking => "⟳",
+    }
+}
+
+/// 日志面板保留多少条目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRetention {
+    pub max_entries: usize,
+    /// 超过该时长（分钟）的条目会被移除，0 表示不限
+    pub max_age_minutes: u64,
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        Self {
+            max_entries: 50,
+            max_age_minutes: 0,
+        }
+    }
+}
+
+impl LogRetention {
+    /// 移除超出数量或过期的条目（条目按时间顺序排列）
+    pub fn prune<T>(&self, entries: &mut Vec<T>, now: Instant, timestamp: impl Fn(&T) -> Instant) {
+        if self.max_age_minutes > 0 {
+            let max_age = Duration::from_secs(self.max_age_minutes * 60);
+            let expired = entries
+                .iter()
+                .take_while(|e| now.saturating_duration_since(timestamp(e)) > max_age)
+                .count();
+            entries.drain(..expired);
+        }
+        let max_entries = self.max_entries.max(1);
+        if entries.len() > max_entries {
+            entries.drain(..entries.len() - max_entries);
+        }
+    }
+}
+
+fn log_file_path() -> PathBuf {
+    crate::config::base_dir().join(LOG_FILE)
+}
+
+/// 把日志追加到磁盘，不受显示级别影响
+///
+/// 写文件在后台线程中进行，避免每条日志都阻塞界面；Drop 时写完剩余的日志。
+pub struct LogWriter {
+    tx: Option<mpsc::Sender<String>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LogWriter {
+    pub fn start() -> Self {
+        Self::start_at(log_file_path())
+    }
+
+    fn start_at(path: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel::<String>();
+        let handle = std::thread::spawn(move || {
+            let mut size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            while let Ok(line) = rx.recv() {
+                // 一次写入已经排队的所有日志
+                let mut lines = line;
+                lines.extend(rx.try_iter());
+                if size > MAX_LOG_FILE_BYTES {
+                    let _ = fs::rename(&path, path.with_extension("log.1"));
+                    size = 0;
+                }
+                let result = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .and_then(|mut f| f.write_all(lines.as_bytes()));
+                match result {
+                    Ok(()) => size += lines.len() as u64,
+                    Err(e) => tracing::debug!("Failed to write {}: {}", path.display(), e),
+                }
+            }
+        });
+        Self {
+            tx: Some(tx),
+            handle: Some(handle),
+        }
+    }
+
+    pub fn write(&self, entry_type: &LogEntryType, message: &str) {
+        let line = format!(
+            "{} [{}] {}\n",
+            crate::clock::now_local().format(&time::format_description::well_known::Rfc3339).unwrap_or_default(),
+            level_name(entry_type),
+            message.replace('\n', " ")
+        );
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(line);
+        }
+    }
+}
+
+impl Drop for LogWriter {
+    fn drop(&mut self) {
+        self.tx = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn level_name(entry_type: &LogEntryType) -> &'static str {
+    match entry_type {
+        LogEntryType::Info => "INFO",
+        LogEntryType::Success => "OK",
+        LogEntryType::Warning => "WARN",
+        LogEntryType::Error => "ERROR",
+        LogEntryType::Checking => "CHECK",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_verbosity() {
+        assert!(LogVerbosity::Errors.shows(&LogEntryType::Error));
+        assert!(!LogVerbosity::Errors.shows(&LogEntryType::Warning));
+        assert!(LogVerbosity::Warnings.shows(&LogEntryType::Warning));
+        assert!(!LogVerbosity::Warnings.shows(&LogEntryType::Success));
+        assert!(LogVerbosity::Normal.shows(&LogEntryType::Info));
+        assert!(!LogVerbosity::Normal.shows(&LogEntryType::Checking));
+        assert!(LogVerbosity::Verbose.shows(&LogEntryType::Checking));
+    }
+
+    #[test]
+    fn test_icons_are_distinct() {
+        let types = [
+            LogEntryType::Info,
+            LogEntryType::Success,
+            LogEntryType::Warning,
+            LogEntryType::Error,
+            LogEntryType::Checking,
+        ];
+        let icons: std::collections::HashSet<_> = types.iter().map(icon).collect();
+        assert_eq!(icons.len(), types.len());
+    }
+
+    #[test]
+    fn test_prune() {
+        let now = Instant::now() + Duration::from_secs(3600);
+        let ages = [50u64, 40, 20, 5, 0];
+        let mut entries: Vec<Instant> = ages.iter().map(|m| now - Duration::from_secs(m * 60)).collect();
+
+        let retention = LogRetention { max_entries: 10, max_age_minutes: 30 };
+        retention.prune(&mut entries, now, |e| *e);
+        assert_eq!(entries.len(), 3);
+
+        let retention = LogRetention { max_entries: 2, max_age_minutes: 0 };
+        retention.prune(&mut entries, now, |e| *e);
+        assert_eq!(entries, vec![now - Duration::from_secs(300), now]);
+    }
+
+    #[test]
+    fn test_log_writer() {
+        let dir = temp_dir("log");
+        let path = dir.join(LOG_FILE);
+        fs::write(&path, vec![b'x'; MAX_LOG_FILE_BYTES as usize + 1]).unwrap();
+
+        let writer = LogWriter::start_at(path.clone());
+        writer.write(&LogEntryType::Warning, "first\nline");
+        writer.write(&LogEntryType::Error, "second");
+        drop(writer);
+
+        // 超过大小的旧文件被轮换，Drop 时所有日志都已写入
+        assert!(path.with_extension("log.1").exists());
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[WARN] first line"));
+        assert!(lines[1].ends_with("[ERROR] second"));
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// 支持的安装包格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+    TarXz,
+    SevenZ,
+}
+
+/// 按文件名识别格式时使用的扩展名
+const EXTENSIONS: [(&str, ArchiveFormat); 6] = [
+    (".zip", ArchiveFormat::Zip),
+    (".tar.gz", ArchiveFormat::TarGz),
+    (".tgz", ArchiveFormat::TarGz),
+    (".tar.xz", ArchiveFormat::TarXz),
+    (".txz", ArchiveFormat::TarXz),
+    (".7z", ArchiveFormat::SevenZ),
+];
+
+impl ArchiveFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        EXTENSIONS
+            .iter()
+            .find(|(ext, _)| lower.ends_with(ext))
+            .map(|(_, format)| *format)
+    }
+
+    /// 按文件头识别，简化格式的更新源下载的文件名不一定反映真实格式
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        const MAGICS: [(&[u8], ArchiveFormat); 5] = [
+            (b"PK\x03\x04", ArchiveFormat::Zip),
+            // 空的 zip 只有目录结束标记
+            (b"PK\x05\x06", ArchiveFormat::Zip),
+            (&[0x1F, 0x8B], ArchiveFormat::TarGz),
+            (&[0xFD, b'7', b'z', b'X', b'Z', 0x00], ArchiveFormat::TarXz),
+            (&[b'7', b'z', 0xBC, 0xAF, 0x27, 0x1C], ArchiveFormat::SevenZ),
+        ];
+        MAGICS
+            .iter()
+            .find(|(magic, _)| header.starts_with(magic))
+            .map(|(_, format)| *format)
+    }
+
+    /// 优先按文件头识别，无法识别时按扩展名
+    pub fn detect(path: &Path) -> Result<Self> {
+        let mut header = [0u8; 8];
+        let read = fs::File::open(path)
+            .and_then(|mut f| f.read(&mut header))
+            .with_context(|| format!("无法读取 {}", path.display()))?;
+        Self::from_magic(&header[..read])
+            .or_else(|| path.file_name().and_then(|n| n.to_str()).and_then(Self::from_name))
+            .with_context(|| format!("不支持的压缩包格式: {}", path.display()))
+    }
+}
+
+/// 去掉文件名中的压缩包扩展名，例如 `linux-x64.tar.gz` -> `linux-x64`
+pub fn strip_extension(name: &str) -> Option<&str> {
+    let lower = name.to_ascii_lowercase();
+    let (ext, _) = EXTENSIONS.iter().find(|(ext, _)| lower.ends_with(ext))?;
+    Some(&name[..name.len() - ext.len()])
+}
+
+/// 解压安装包到目标目录，格式按文件头或扩展名识别
+pub fn extract(archive: &Path, target_dir: &Path) -> Result<()> {
+    let format = ArchiveFormat::detect(archive)?;
+    tracing::info!("Extracting {} as {:?}", archive.display(), format);
+    let file = fs::File::open(archive)?;
+    match format {
+        ArchiveFormat::Zip => extract_zip(file, target_dir),
+        ArchiveFormat::TarGz => extract_tar(flate2::read::GzDecoder::new(file), target_dir),
+        ArchiveFormat::TarXz => extract_tar(xz2::read::XzDecoder::new(file), target_dir),
+        ArchiveFormat::SevenZ => extract_7z(file, target_dir),
+    }
+}
+
+/// 解压后的总大小：zip 读取目录中记录的大小，其他格式需要完整解压才能知道，按倍数估算
+pub fn unpacked_size(archive: &Path) -> Result<u64> {
+    if ArchiveFormat::detect(archive)? == ArchiveFormat::Zip {
+        let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+        return Ok((0..zip.len()).filter_map(|i| zip.by_index_raw(i).ok().map(|f| f.size())).sum());
+    }
+    Ok(fs::metadata(archive)?.len() * crate::disk_space::UNPACK_FACTOR)
+}
+
+fn extract_zip(file: fs::File, target_dir: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let mut outpath = target_dir.to_path_buf();
+        outpath.push(file.mangled_name());
+
+        if (*file.name()).ends_with('/') {
+            fs::create_dir_all(&outpath)?;
+        } else {
+            if let Some(parent) = outpath.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut outfile = fs::File::create(&outpath)?;
+            std::io::copy(&mut file, &mut outfile)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                if let Some(mode) = file.unix_mode() {
+                    fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// tar 会跳过指向目标目录之外的条目
+fn extract_tar(reader: impl Read, target_dir: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    archive.set_preserve_permissions(true);
+    archive.set_overwrite(true);
+    archive.unpack(target_dir).context("解压 tar 包失败")?;
+    Ok(())
+}
+
+fn extract_7z(file: fs::File, target_dir: &Path) -> Result<()> {
+    sevenz_rust::decompress_with_extract_fn(file, target_dir, |entry, reader, _| {
+        // 自行拼接路径，忽略包含 `..` 或绝对路径的条目
+        let Some(relative) = safe_relative_path(entry.name()) else {
+            tracing::warn!("Skipping unsafe 7z entry: {}", entry.name());
+            return Ok(true);
+        };
+        sevenz_rust::default_entry_extract_fn(entry, reader, &target_dir.join(relative))
+    })
+    .context("解压 7z 包失败")
+}
+
+/// 把压缩包中的条目名转换为相对路径，不允许离开目标目录
+pub fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    if name.starts_with(['/', '\\']) || name.contains(':') {
+        return None;
+    }
+    let mut path = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match Path::new(part).components().next() {
+            None | Some(Component::CurDir) => {}
+            Some(Component::Normal(part)) => path.push(part),
+            _ => return None,
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    fn tar_bytes() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, data) in [("OpenUO", &b"\x7fELF"[..]), ("Data/art.mul", b"abc")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn assert_extracted(dir: &Path) {
+        assert_eq!(fs::read(dir.join("OpenUO")).unwrap(), b"\x7fELF");
+        assert_eq!(fs::read(dir.join("Data").join("art.mul")).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(ArchiveFormat::from_name("linux-x64.TAR.GZ"), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_name("win-x64.7z"), Some(ArchiveFormat::SevenZ));
+        assert_eq!(ArchiveFormat::from_name("OpenUO.exe"), None);
+        assert_eq!(ArchiveFormat::from_magic(&[0xFD, b'7', b'z', b'X', b'Z', 0, 0]), Some(ArchiveFormat::TarXz));
+        assert_eq!(ArchiveFormat::from_magic(b"MZ\x90\x00"), None);
+        assert_eq!(strip_extension("osx-arm64.tar.xz"), Some("osx-arm64"));
+        assert_eq!(strip_extension("osx-arm64"), None);
+    }
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(safe_relative_path("Data/./art.mul"), Some(PathBuf::from("Data").join("art.mul")));
+        assert_eq!(safe_relative_path("Data\\art.mul"), Some(PathBuf::from("Data").join("art.mul")));
+        assert_eq!(safe_relative_path("../evil"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+        assert_eq!(safe_relative_path("C:\\Windows\\evil.dll"), None);
+        assert_eq!(safe_relative_path(""), None);
+    }
+
+    #[test]
+    fn test_extract_tar_gz_and_xz() {
+        let root = temp_dir("archive-tar");
+        // 文件名与真实格式不符时按文件头识别
+        let gz = root.join("linux-x64.zip");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        std::io::Write::write_all(&mut encoder, &tar_bytes()).unwrap();
+        fs::write(&gz, encoder.finish().unwrap()).unwrap();
+        extract(&gz, &root.join("gz")).unwrap();
+        assert_extracted(&root.join("gz"));
+
+        let xz = root.join("linux-x64.tar.xz");
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 1);
+        std::io::Write::write_all(&mut encoder, &tar_bytes()).unwrap();
+        fs::write(&xz, encoder.finish().unwrap()).unwrap();
+        extract(&xz, &root.join("xz")).unwrap();
+        assert_extracted(&root.join("xz"));
+    }
+
+    #[test]
+    fn test_extract_7z() {
+        let root = temp_dir("archive-7z");
+        let src = root.join("src");
+        fs::create_dir_all(src.join("Data")).unwrap();
+        fs::write(src.join("OpenUO"), b"\x7fELF").unwrap();
+        fs::write(src.join("Data").join("art.mul"), b"abc").unwrap();
+        let archive = root.join("win-x64.7z");
+        sevenz_rust::compress_to_path(&src, &archive).unwrap();
+
+        extract(&archive, &root.join("out")).unwrap();
+        assert_extracted(&root.join("out"));
+    }
+
+    #[test]
+    fn test_unpacked_size() {
+        let root = temp_dir("archive-size");
+        let zip_path = root.join("linux-x64.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        writer.start_file("OpenUO", options).unwrap();
+        std::io::Write::write_all(&mut writer, &[0u8; 10_000]).unwrap();
+        writer.start_file("Data/art.mul", options).unwrap();
+        std::io::Write::write_all(&mut writer, b"abc").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(unpacked_size(&zip_path).unwrap(), 10_003);
+
+        // 其他格式按压缩包大小估算
+        let gz = root.join("linux-x64.tar.gz");
+        fs::write(&gz, [0x1F, 0x8B, 0, 0]).unwrap();
+        assert_eq!(unpacked_size(&gz).unwrap(), 4 * crate::disk_space::UNPACK_FACTOR);
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        let root = temp_dir("archive-unknown");
+        let path = root.join("OpenUO.bin");
+        fs::write(&path, b"not an archive").unwrap();
+        assert!(extract(&path, &root.join("out")).is_err());
+    }
+}
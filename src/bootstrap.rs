@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::github::UpdateSourceConfig;
+use crate::tls_trust::TlsTrust;
+
+/// 引导链接的格式：`openuo://bootstrap?url=<配置地址>&key=<minisign 公钥>`
+const LINK_PREFIX: &str = "openuo://";
+
+/// 引导地址：服主只需给玩家一个地址，Launcher 每次检查更新前从这里重新下载完整的 update_source.json
+///
+/// 配置必须附带 minisign 签名（`<url>.minisig`），避免被篡改的配置把更新指向其他服务器。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bootstrap {
+    pub url: String,
+    /// 校验签名的公钥；为空时只能使用编译时嵌入的公钥
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+impl Bootstrap {
+    /// 接受 openuo://bootstrap 链接，或直接填写的 http(s) 地址（不带公钥）
+    pub fn parse(input: &str) -> Result<Bootstrap> {
+        let input = input.trim();
+        let url = reqwest::Url::parse(input).with_context(|| format!("无效的地址: {}", input))?;
+        let bootstrap = if input.starts_with(LINK_PREFIX) {
+            if url.host_str() != Some("bootstrap") {
+                anyhow::bail!("无法识别的链接: {}", input);
+            }
+            let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.trim().to_string());
+            Bootstrap {
+                url: param("url").filter(|u| !u.is_empty()).context("链接中没有配置地址")?,
+                public_key: param("key").filter(|k| !k.is_empty()),
+            }
+        } else {
+            Bootstrap { url: input.to_string(), public_key: None }
+        };
+        let scheme = reqwest::Url::parse(&bootstrap.url).map(|u| u.scheme().to_string()).unwrap_or_default();
+        if scheme != "https" && scheme != "http" {
+            anyhow::bail!("配置地址必须是 http(s) 地址: {}", bootstrap.url);
+        }
+        if let Some(key) = &bootstrap.public_key {
+            crate::signature::parse_public_key(key)?;
+        }
+        Ok(bootstrap)
+    }
+}
+
+/// 命令行中的 openuo:// 链接
+///
+/// 只有在 ⚙ 菜单中注册了 openuo://（见 `url_scheme`）后，Windows 和 Linux 才会在浏览器打开链接时传入；
+/// macOS 不通过命令行传递链接，玩家需要把链接粘贴到 ⚙ 菜单中。
+pub fn link_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    args.into_iter().find(|arg| arg.starts_with(LINK_PREFIX))
+}
+
+/// Launcher 设置中的引导地址
+static CURRENT: RwLock<Option<Bootstrap>> = RwLock::new(None);
+
+pub fn set(bootstrap: Option<Bootstrap>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = bootstrap;
+    }
+}
+
+pub fn current() -> Option<Bootstrap> {
+    CURRENT.read().ok().and_then(|c| c.clone())
+}
+
+/// 校验签名后把配置写入 `path`，内容没有变化时返回 false
+fn apply(path: &Path, bootstrap: &Bootstrap, body: &str, signature: &str) -> Result<bool> {
+    let key = crate::signature::trusted_key(bootstrap.public_key.as_deref())?
+        .context("引导地址没有可用的签名公钥，已拒绝使用")?;
+    crate::signature::verify_bytes(body.as_bytes(), &key, signature, &bootstrap.url)?;
+    serde_json::from_str::<UpdateSourceConfig>(body).context("引导地址返回的不是有效的更新源配置")?;
+    if fs::read_to_string(path).is_ok_and(|current| current == body) {
+        return Ok(false);
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, body)?;
+    fs::rename(&tmp, path)?;
+    Ok(true)
+}
+
+/// 从引导地址更新 update_source.json；没有设置引导地址或内容没有变化时返回 false
+///
+/// 失败时保留原来的配置。
+pub fn refresh() -> Result<bool> {
+    let Some(bootstrap) = current() else {
+        return Ok(false);
+    };
+    let tls = TlsTrust::default();
+    let body = crate::github::fetch_text(&bootstrap.url, &tls).context("无法下载更新源配置")?;
+    let signature = crate::github::fetch_text(&format!("{}.minisig", bootstrap.url), &tls)
+        .context("更新源配置没有签名，已拒绝使用")?;
+    let changed = apply(&crate::github::update_source_config_path(), &bootstrap, &body, &signature)?;
+    if changed {
+        tracing::info!("已从 {} 更新 update_source.json", bootstrap.url);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_parse() {
+        let key = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+        let link = format!("openuo://bootstrap?url=https%3A%2F%2Fshard.example%2Fupdate_source.json&key={}", key.replace('+', "%2B"));
+        let bootstrap = Bootstrap::parse(&link).unwrap();
+        assert_eq!(bootstrap.url, "https://shard.example/update_source.json");
+        assert_eq!(bootstrap.public_key.as_deref(), Some(key));
+
+        let plain = Bootstrap::parse(" https://shard.example/update_source.json ").unwrap();
+        assert_eq!(plain.public_key, None);
+
+        for input in ["openuo://other?url=https://a", "openuo://bootstrap", "openuo://bootstrap?url=file:///etc/passwd", "ftp://a/b", "openuo://bootstrap?url=https://a&key=bad"] {
+            assert!(Bootstrap::parse(input).is_err(), "{input}");
+        }
+        assert_eq!(link_from_args(["launcher".to_string(), link.clone()]), Some(link));
+    }
+
+    #[test]
+    fn test_apply_rejects_unsigned() {
+        let dir = temp_dir("bootstrap");
+        let path = dir.join("update_source.json");
+        fs::write(&path, "{}").unwrap();
+        let bootstrap = Bootstrap {
+            url: "https://shard.example/update_source.json".to_string(),
+            public_key: Some("RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4".to_string()),
+        };
+        let body = r#"{ "openuo_url": "https://evil.example/latest.json" }"#;
+        assert!(apply(&path, &bootstrap, body, "not a signature").is_err());
+        // 校验失败时保留原来的配置
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// 计算文件的 SHA-256（小写十六进制）
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path).with_context(|| format!("无法打开 {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// 计算内存中数据的 SHA-256（小写十六进制）
+pub fn sha256_bytes(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// 校验下载的文件，不匹配时返回错误
+pub fn verify(path: &Path, expected: &str) -> Result<()> {
+    let expected = normalize_hash(expected).context("无效的 SHA-256 校验值")?;
+    let actual = sha256_file(path)?;
+    if actual != expected {
+        anyhow::bail!(
+            "SHA-256 校验失败: {}（期望 {}，实际 {}）",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// 从 `sha256sum` 格式的校验文件中查找指定文件的校验值
+///
+/// 每行为 `<hash>  <name>` 或 `<hash> *<name>`，文件名可以带路径。
+pub fn find_in_checksums(content: &str, asset_name: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start().trim_start_matches('*');
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        if name == asset_name {
+            normalize_hash(hash)
+        } else {
+            None
+        }
+    })
+}
+
+/// 接受 `sha256:` 前缀（GitHub 资产的 digest 字段）和大写形式
+pub fn normalize_hash(value: &str) -> Option<String> {
+    let value = value.trim();
+    let hex = value.strip_prefix("sha256:").unwrap_or(value);
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| hex.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_verify_file() {
+        let dir = temp_dir("checksum");
+        let path = dir.join("OpenUO.zip");
+        fs::write(&path, b"hello").unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), HELLO_SHA256);
+        assert!(verify(&path, &format!("sha256:{}", HELLO_SHA256.to_uppercase())).is_ok());
+
+        let err = verify(&path, &"0".repeat(64)).unwrap_err().to_string();
+        assert!(err.contains(HELLO_SHA256), "{err}");
+        assert!(verify(&path, "not-a-hash").is_err());
+    }
+
+    #[test]
+    fn test_find_in_checksums() {
+        let content = format!(
+            "{hash}  linux-x64.zip\n{hash_upper} *dist/OpenUO-Launcher-windows-x64.exe\n\n",
+            hash = HELLO_SHA256,
+            hash_upper = HELLO_SHA256.to_uppercase()
+        );
+        assert_eq!(find_in_checksums(&content, "linux-x64.zip").as_deref(), Some(HELLO_SHA256));
+        assert_eq!(
+            find_in_checksums(&content, "OpenUO-Launcher-windows-x64.exe").as_deref(),
+            Some(HELLO_SHA256)
+        );
+        assert_eq!(find_in_checksums(&content, "osx-arm64.zip"), None);
+    }
+}
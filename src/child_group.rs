@@ -0,0 +1,142 @@
+use std::process::{Child, Command};
+
+// “Launcher 退出时结束游戏”：把启动的进程及其子进程归到一起，Launcher 退出时一起结束。
+// Windows 上使用设置了 JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE 的作业对象，Launcher 崩溃时系统也会结束它们；
+// Unix 上让游戏成为新进程组的组长，Launcher 正常退出时向还在运行的游戏的进程组发送 SIGTERM。
+
+/// 在 spawn 之前调用
+pub fn prepare(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // 组号就是游戏的 PID，游戏再启动的进程默认也在这个组里
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+#[cfg(unix)]
+static GROUPS: std::sync::Mutex<Vec<u32>> = std::sync::Mutex::new(Vec::new());
+
+/// 在 spawn 之后调用，之后 Launcher 退出时会结束这个进程和它启动的进程
+#[cfg(unix)]
+pub fn adopt(child: &Child) {
+    GROUPS.lock().unwrap_or_else(|e| e.into_inner()).push(child.id());
+}
+
+/// 游戏进程结束后调用：组长退出后组号可能被系统分配给无关的进程，不能再向它发送信号
+#[cfg(unix)]
+pub fn release(pid: u32) {
+    GROUPS.lock().unwrap_or_else(|e| e.into_inner()).retain(|pgid| *pgid != pid);
+}
+
+/// 结束所有交给 Launcher 管理的进程，在 Launcher 退出前调用
+#[cfg(unix)]
+pub fn terminate_all() {
+    let groups = std::mem::take(&mut *GROUPS.lock().unwrap_or_else(|e| e.into_inner()));
+    for pgid in groups {
+        // 进程组已经全部结束时返回 ESRCH
+        if unsafe { libc::kill(-(pgid as libc::pid_t), libc::SIGTERM) } == 0 {
+            tracing::info!("已结束进程组 {}", pgid);
+        }
+    }
+}
+
+/// 作业对象的句柄，第一次 `adopt` 时创建，创建失败时为 None；句柄在 Launcher 退出时由系统关闭
+#[cfg(target_os = "windows")]
+static JOB: std::sync::OnceLock<Option<usize>> = std::sync::OnceLock::new();
+
+#[cfg(target_os = "windows")]
+pub fn adopt(child: &Child) {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::JobObjects::AssignProcessToJobObject;
+
+    let job = JOB.get_or_init(|| {
+        create_job()
+            .inspect_err(|e| tracing::warn!("无法创建作业对象: {}", e))
+            .ok()
+    });
+    let Some(job) = *job else {
+        return;
+    };
+    // 游戏在加入作业对象之前启动的进程不受管理（通常游戏刚启动时还没有子进程）
+    if let Err(e) = unsafe { AssignProcessToJobObject(HANDLE(job as _), HANDLE(child.as_raw_handle())) } {
+        tracing::warn!("无法把进程 {} 加入作业对象: {}", child.id(), e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn create_job() -> windows::core::Result<usize> {
+    use windows::Win32::System::JobObjects::{
+        CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JobObjectExtendedLimitInformation, SetInformationJobObject,
+    };
+    use windows::core::PCWSTR;
+
+    unsafe {
+        let job = CreateJobObjectW(None, PCWSTR::null())?;
+        let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        )?;
+        Ok(job.0 as usize)
+    }
+}
+
+/// 作业对象在进程结束时自动移除它，不需要处理
+#[cfg(not(unix))]
+pub fn release(_pid: u32) {}
+
+#[cfg(target_os = "windows")]
+pub fn terminate_all() {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::JobObjects::TerminateJobObject;
+
+    if let Some(Some(job)) = JOB.get()
+        && let Err(e) = unsafe { TerminateJobObject(HANDLE(*job as _), 1) }
+    {
+        tracing::warn!("无法结束作业对象中的进程: {}", e);
+    }
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn adopt(_child: &Child) {}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn terminate_all() {}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn test_terminate_all_ends_process_group() {
+        // 已经结束的游戏不再记录，组号可能被其他进程重用
+        let mut exited = Command::new("true");
+        prepare(&mut exited);
+        let mut exited = exited.spawn().unwrap();
+        adopt(&exited);
+        exited.wait().unwrap();
+        release(exited.id());
+        assert!(!GROUPS.lock().unwrap().contains(&exited.id()));
+
+        // sh 再启动的 sleep 也在同一个进程组里
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 30 & wait"]);
+        prepare(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+        adopt(&child);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        terminate_all();
+        let status = child.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+        assert!(GROUPS.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{ProfileConfig, open_uo_binary_in, open_uo_dir};
+
+/// 与最新版并存安装的 OpenUO 版本，位于 OpenUO/<tag>，供固定了版本的档案使用
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledVersion {
+    pub tag: String,
+    /// 安装时记录的版本号（release 的名称）
+    pub version: String,
+    pub dir: PathBuf,
+}
+
+/// 版本的安装目录；标签不能用作目录名时为 None
+pub fn version_dir(tag: &str) -> Option<PathBuf> {
+    valid_tag(tag).then(|| open_uo_dir().join(tag))
+}
+
+/// 标签只能是单个普通的目录名，不能借 `..`、路径分隔符等跳出 OpenUO 目录
+fn valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && !tag.starts_with('.')
+        && tag.trim() == tag
+        && !tag.chars().any(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control())
+}
+
+/// 档案使用的 OpenUO 目录：固定了版本时为该版本的目录，否则为主安装目录
+pub fn dir_for(profile: &ProfileConfig) -> PathBuf {
+    profile
+        .index
+        .pinned_open_uo
+        .as_deref()
+        .and_then(version_dir)
+        .unwrap_or_else(open_uo_dir)
+}
+
+pub fn is_installed(tag: &str) -> bool {
+    version_dir(tag).is_some_and(|dir| open_uo_binary_in(&dir).is_file())
+}
+
+/// 已并存安装的全部版本，按标签排列
+pub fn installed() -> Vec<InstalledVersion> {
+    installed_in(&open_uo_dir())
+}
+
+/// `root` 目录中并存安装的版本
+pub fn installed_in(root: &Path) -> Vec<InstalledVersion> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<InstalledVersion> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let tag = entry.file_name().to_str()?.to_string();
+            let dir = entry.path();
+            // 主安装的 Data 等目录中没有可执行文件和版本记录
+            if !valid_tag(&tag) || !open_uo_binary_in(&dir).is_file() {
+                return None;
+            }
+            let version = crate::github::read_open_uo_version_in(&dir)?;
+            Some(InstalledVersion { tag, version, dir })
+        })
+        .collect();
+    versions.sort_by(|a, b| a.tag.cmp(&b.tag));
+    versions
+}
+
+/// 确认可以安装到版本目录：目录不存在，或者是之前安装的同一版本
+pub fn prepare_dir(tag: &str) -> Result<PathBuf> {
+    let dir = version_dir(tag).with_context(|| format!("版本标签 {} 不能用作目录名", tag))?;
+    let occupied = dir.exists()
+        && crate::github::read_open_uo_version_in(&dir).is_none()
+        && fs::read_dir(&dir).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied {
+        anyhow::bail!("{} 已被其他文件占用，无法安装版本 {}", dir.display(), tag);
+    }
+    Ok(dir)
+}
+
+/// 删除并存安装的版本
+pub fn remove(tag: &str) -> Result<()> {
+    let dir = version_dir(tag).with_context(|| format!("版本标签 {} 无效", tag))?;
+    fs::remove_dir_all(&dir).with_context(|| format!("无法删除 {}，游戏可能正在运行", dir.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_installed_in() {
+        let root = temp_dir("versions");
+        let binary = open_uo_binary_in(&root);
+        let binary = binary.file_name().unwrap();
+        for (tag, version) in [("v1.2.0", Some("OpenUO 1.2.0")), ("v0.9.1", Some("v0.9.1")), ("Data", None)] {
+            fs::create_dir_all(root.join(tag)).unwrap();
+            if let Some(version) = version {
+                fs::write(root.join(tag).join(binary), "").unwrap();
+                fs::write(root.join(tag).join(".open_uo_version"), version).unwrap();
+            }
+        }
+        // 主安装本身的文件
+        fs::write(root.join(binary), "").unwrap();
+
+        let versions = installed_in(&root);
+        let tags: Vec<&str> = versions.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, ["v0.9.1", "v1.2.0"]);
+        assert_eq!(versions[1].version, "OpenUO 1.2.0");
+
+        assert!(valid_tag("v1.2.0"));
+        assert!(valid_tag("2024.05.01 hotfix"));
+        for tag in ["", "..", ".hidden", "a/b", "a\\b", "C:", " v1"] {
+            assert!(!valid_tag(tag), "{tag}");
+        }
+    }
+}
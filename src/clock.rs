@@ -0,0 +1,160 @@
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicI64, Ordering};
+use time::{Date, Month, OffsetDateTime, Time, UtcOffset};
+
+/// 本地时区偏移（启动时获取一次）
+static LOCAL_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
+
+/// 本机时钟与服务器时间的偏差（秒，服务器减本机），根据 HTTP Date 头估算
+static CLOCK_SKEW: AtomicI64 = AtomicI64::new(0);
+
+/// 偏差在此范围内视为网络延迟和 Date 头精度造成的误差
+const SKEW_TOLERANCE_SECS: i64 = 2;
+
+/// 偏差超过此值时提醒用户，此时 TLS 证书校验也可能失败
+pub const SKEW_WARNING_SECS: i64 = 300;
+
+/// 获取本地时区偏移
+///
+/// 在 Unix 上只有单线程时才能安全获取，因此必须在创建任何线程之前调用一次。
+pub fn init_local_offset() {
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    let _ = LOCAL_OFFSET.set(offset);
+}
+
+pub fn local_offset() -> UtcOffset {
+    LOCAL_OFFSET.get().copied().unwrap_or(UtcOffset::UTC)
+}
+
+/// 根据服务器返回的 Date 头更新时钟偏差
+pub fn record_server_date(date: &str) {
+    let Some(server) = parse_http_date(date) else {
+        return;
+    };
+    let skew = estimate_skew(server, OffsetDateTime::now_utc().unix_timestamp());
+    if CLOCK_SKEW.swap(skew, Ordering::Relaxed) != skew && skew != 0 {
+        tracing::info!("Clock skew estimated from server Date: {}s", skew);
+    }
+}
+
+fn estimate_skew(server: i64, local: i64) -> i64 {
+    let skew = server - local;
+    if skew.abs() <= SKEW_TOLERANCE_SECS { 0 } else { skew }
+}
+
+/// 本机时钟的偏差（秒，正数表示本机时间偏慢）
+pub fn skew_secs() -> i64 {
+    CLOCK_SKEW.load(Ordering::Relaxed)
+}
+
+/// 修正偏差后的当前 UTC 时间
+fn now_utc() -> OffsetDateTime {
+    OffsetDateTime::now_utc() + time::Duration::seconds(skew_secs())
+}
+
+/// 当前本地时间（已修正时钟偏差）
+pub fn now_local() -> OffsetDateTime {
+    now_utc().to_offset(local_offset())
+}
+
+/// 当前 Unix 时间戳（秒，已修正时钟偏差）
+pub fn now_unix() -> i64 {
+    now_utc().unix_timestamp()
+}
+
+/// 解析 HTTP Date 头，例如 "Sun, 06 Nov 1994 08:49:37 GMT"
+fn parse_http_date(date: &str) -> Option<i64> {
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let [_, day, month, year, clock, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let month = MONTHS.iter().position(|m| m == month)?;
+    let date = Date::from_calendar_date(year.parse().ok()?, Month::try_from(month as u8 + 1).ok()?, day.parse().ok()?).ok()?;
+    let mut hms = clock.split(':').map(|p| p.parse::<u8>().ok());
+    let time = Time::from_hms(hms.next()??, hms.next()??, hms.next()??).ok()?;
+    Some(date.with_time(time).assume_utc().unix_timestamp())
+}
+
+/// 解析 RFC 3339 时间，例如 "2024-06-01T18:00:00Z" 或 "2024-06-01T20:00:00+02:00"（秒的小数部分忽略）
+pub fn parse_rfc3339(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (date, rest) = text.split_once(['T', 't', ' '])?;
+    let mut ymd = date.split('-');
+    let (year, month, day) = (ymd.next()?.parse().ok()?, ymd.next()?.parse::<u8>().ok()?, ymd.next()?.parse().ok()?);
+    if ymd.next().is_some() {
+        return None;
+    }
+    let date = Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+    let (clock, offset) = if let Some(clock) = rest.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let split = rest.rfind(['+', '-'])?;
+        let (clock, offset) = rest.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (h, m) = offset[1..].split_once(':')?;
+        (clock, sign * (h.parse::<i64>().ok()? * 3600 + m.parse::<i64>().ok()? * 60))
+    };
+    let clock = clock.split('.').next()?;
+    let mut hms = clock.split(':').map(|p| p.parse::<u8>().ok());
+    let time = Time::from_hms(hms.next()??, hms.next()??, hms.next()??).ok()?;
+    if hms.next().is_some() {
+        return None;
+    }
+    Some(date.with_time(time).assume_utc().unix_timestamp() - offset)
+}
+
+/// 将 Unix 时间戳格式化为本地时间，例如 "2024-05-01 18:30"
+pub fn format_local(unix: i64) -> String {
+    let Ok(utc) = OffsetDateTime::from_unix_timestamp(unix) else {
+        return String::new();
+    };
+    let local = utc.to_offset(local_offset());
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        local.year(),
+        local.month() as u8,
+        local.day(),
+        local.hour(),
+        local.minute()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_local() {
+        // 未初始化时按 UTC 处理
+        assert_eq!(format_local(0), "1970-01-01 00:00");
+        assert_eq!(format_local(1_714_588_200), "2024-05-01 18:30");
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(784_111_777));
+        assert_eq!(parse_http_date("Wed, 01 May 2024 18:30:00 GMT"), Some(1_714_588_200));
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date(""), None);
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        assert_eq!(parse_rfc3339("2024-05-01T18:30:00Z"), Some(1_714_588_200));
+        assert_eq!(parse_rfc3339("2024-05-01T20:30:00.250+02:00"), Some(1_714_588_200));
+        assert_eq!(parse_rfc3339("2024-05-01 13:30:00-05:00"), Some(1_714_588_200));
+        assert_eq!(parse_rfc3339("2024-05-01T18:30:00"), None);
+        assert_eq!(parse_rfc3339("2024-13-01T18:30:00Z"), None);
+        assert_eq!(parse_rfc3339(""), None);
+    }
+
+    #[test]
+    fn test_estimate_skew() {
+        assert_eq!(estimate_skew(1000, 999), 0);
+        assert_eq!(estimate_skew(1000, 1002), 0);
+        assert_eq!(estimate_skew(1000, 1600), -600);
+        assert_eq!(estimate_skew(4600, 1000), 3600);
+    }
+}
@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// 会锁定或替换文件的云同步客户端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudProvider {
+    OneDrive,
+    Dropbox,
+    GoogleDrive,
+    ICloud,
+}
+
+impl CloudProvider {
+    pub fn name(self) -> &'static str {
+        match self {
+            CloudProvider::OneDrive => "OneDrive",
+            CloudProvider::Dropbox => "Dropbox",
+            CloudProvider::GoogleDrive => "Google Drive",
+            CloudProvider::ICloud => "iCloud Drive",
+        }
+    }
+}
+
+/// 检查路径是否位于云同步目录中
+pub fn detect(path: &Path) -> Option<CloudProvider> {
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    detect_with_roots(path, &env_sync_roots())
+}
+
+/// OneDrive 会把同步根目录写入环境变量（可能被改名或放在其他磁盘上）
+fn env_sync_roots() -> Vec<(PathBuf, CloudProvider)> {
+    ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .filter(|value| !value.is_empty())
+        .map(|value| (PathBuf::from(value), CloudProvider::OneDrive))
+        .collect()
+}
+
+fn detect_with_roots(path: &Path, roots: &[(PathBuf, CloudProvider)]) -> Option<CloudProvider> {
+    if let Some((_, provider)) = roots.iter().find(|(root, _)| path.starts_with(root)) {
+        return Some(*provider);
+    }
+    path.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .find_map(provider_for_component)
+}
+
+/// 按目录名识别各客户端默认的同步目录
+fn provider_for_component(name: &str) -> Option<CloudProvider> {
+    let lower = name.to_ascii_lowercase();
+    if lower == "onedrive" || lower.starts_with("onedrive - ") {
+        Some(CloudProvider::OneDrive)
+    } else if lower == "dropbox" || lower.starts_with("dropbox (") {
+        Some(CloudProvider::Dropbox)
+    } else if lower == "google drive" || lower == "googledrive" || lower == "my drive" || lower.starts_with("googledrive-") {
+        Some(CloudProvider::GoogleDrive)
+    } else if lower == "icloud drive" || lower == "iclouddrive" || lower == "com~apple~clouddocs" {
+        Some(CloudProvider::ICloud)
+    } else {
+        None
+    }
+}
+
+/// 迁移目录的进度事件
+pub enum RelocateEvent {
+    Progress { copied: u64, total: u64 },
+    Done(PathBuf),
+    Failed(String),
+}
+
+/// 在后台线程把 `src` 复制到 `dest_parent` 下的同名目录
+///
+/// 原目录保留不动，由用户确认新位置可用后自行删除。
+pub fn spawn_relocate(src: PathBuf, dest_parent: PathBuf) -> mpsc::Receiver<RelocateEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = relocate(&src, &dest_parent, |copied, total| {
+            let _ = tx.send(RelocateEvent::Progress { copied, total });
+        });
+        let _ = tx.send(match result {
+            Ok(dest) => RelocateEvent::Done(dest),
+            Err(e) => RelocateEvent::Failed(format!("{e:#}")),
+        });
+    });
+    rx
+}
+
+fn relocate(src: &Path, dest_parent: &Path, mut progress: impl FnMut(u64, u64)) -> Result<PathBuf> {
+    let name = src.file_name().context("无效的源目录")?;
+    let dest = dest_parent.join(name);
+    if dest.starts_with(src) {
+        anyhow::bail!("目标目录不能位于源目录内");
+    }
+    if dest.exists() && fs::read_dir(&dest)?.next().is_some() {
+        anyhow::bail!("目标目录已存在且不为空: {}", dest.display());
+    }
+
+    let total = dir_size(src)?;
+    let mut copied = 0;
+    copy_dir(src, &dest, &mut |bytes| {
+        copied += bytes;
+        progress(copied, total);
+    })?;
+    Ok(dest)
+}
+
+fn dir_size(dir: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        size += if meta.is_dir() { dir_size(&entry.path())? } else { meta.len() };
+    }
+    Ok(size)
+}
+
+pub fn copy_dir(src: &Path, dest: &Path, on_copied: &mut dyn FnMut(u64)) -> Result<()> {
+    fs::create_dir_all(dest).with_context(|| format!("无法创建目录 {}", dest.display()))?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target, on_copied)?;
+        } else {
+            let bytes = fs::copy(entry.path(), &target)
+                .with_context(|| format!("无法复制 {}", entry.path().display()))?;
+            on_copied(bytes);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_detect_by_component() {
+        let cases = [
+            ("/Users/me/Dropbox/Games/UO", Some(CloudProvider::Dropbox)),
+            ("C:/Users/me/OneDrive - Contoso/UO", Some(CloudProvider::OneDrive)),
+            ("G:/My Drive/UO", Some(CloudProvider::GoogleDrive)),
+            ("/Users/me/Library/Mobile Documents/com~apple~CloudDocs/UO", Some(CloudProvider::ICloud)),
+            ("/home/me/games/dropbox-backup/UO", None),
+            ("C:/Games/Ultima Online", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(detect_with_roots(Path::new(path), &[]), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_detect_by_env_root() {
+        let roots = [(PathBuf::from("/data/Cloud"), CloudProvider::OneDrive)];
+        assert_eq!(detect_with_roots(Path::new("/data/Cloud/UO"), &roots), Some(CloudProvider::OneDrive));
+        assert_eq!(detect_with_roots(Path::new("/data/CloudBackup/UO"), &roots), None);
+    }
+
+    #[test]
+    fn test_relocate_copies_tree() {
+        let root = temp_dir("relocate");
+        let src = root.join("src").join("UO");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::write(src.join("art.mul"), b"abcd").unwrap();
+        fs::write(src.join("sub").join("map0.mul"), b"xy").unwrap();
+
+        let mut last = (0, 0);
+        let dest = relocate(&src, &root.join("dest"), |copied, total| last = (copied, total)).unwrap();
+        assert_eq!(dest, root.join("dest").join("UO"));
+        assert_eq!(fs::read(dest.join("sub").join("map0.mul")).unwrap(), b"xy");
+        assert_eq!(last, (6, 6));
+        assert!(src.join("art.mul").exists());
+
+        // 目标已存在且不为空时拒绝覆盖
+        assert!(relocate(&src, &root.join("dest"), |_, _| {}).is_err());
+    }
+}
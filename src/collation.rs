@@ -0,0 +1,50 @@
+use icu_collator::{Collator, CollatorOptions, Strength};
+use icu_locid::Locale;
+
+/// 创建指定语言的排序规则（例如中文按拼音、德语忽略变音符号的差异）
+fn collator(locale: &str) -> Option<Collator> {
+    let locale: Locale = locale.parse().ok()?;
+    let mut options = CollatorOptions::new();
+    // 只区分到重音级别：大小写不同的名称视为相同，再按原始字符串决定先后
+    options.strength = Some(Strength::Secondary);
+    Collator::try_new(&locale.into(), options).ok()
+}
+
+/// 按指定语言的规则对列表排序（稳定排序）
+pub fn sort_by_key<T>(items: &mut [T], locale: &str, key: impl Fn(&T) -> &str) {
+    let collator = collator(locale);
+    items.sort_by(|a, b| {
+        let (a, b) = (key(a), key(b));
+        let primary = match &collator {
+            Some(collator) => collator.compare(a, b),
+            None => a.to_lowercase().cmp(&b.to_lowercase()),
+        };
+        primary.then_with(|| a.cmp(b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(locale: &str, names: &[&'static str]) -> Vec<&'static str> {
+        let mut names = names.to_vec();
+        sort_by_key(&mut names, locale, |s| s);
+        names
+    }
+
+    #[test]
+    fn test_accents_and_case() {
+        // 按字节排序时 "Émile" 和 "zeta" 会排在最后
+        assert_eq!(
+            sorted("en", &["zeta", "Émile", "Adam", "eve", "émile"]),
+            vec!["Adam", "Émile", "émile", "eve", "zeta"]
+        );
+    }
+
+    #[test]
+    fn test_chinese_pinyin() {
+        // 北京 (běi) < 上海 (shàng) < 天津 (tiān)
+        assert_eq!(sorted("zh-CN", &["天津", "上海", "北京"]), vec!["北京", "上海", "天津"]);
+    }
+}
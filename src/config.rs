@@ -1,12 +1,32 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::activity_log::{LogPalette, LogRetention, LogVerbosity};
+use crate::bootstrap::Bootstrap;
+use crate::github::GithubToken;
+use crate::launch_target::{LaunchConfirmation, LaunchTarget};
+use crate::network_check::NetworkRequirement;
+use crate::process_tuning::ProcessTuning;
+use crate::profile_backup::{ProfileBackupConfig, UpdateBackupConfig};
+use crate::requirements::RequirementThresholds;
+use crate::session_password::SessionPasswordConfig;
+use crate::staff_mode::StaffConfig;
+use crate::update_policy::{UpdateCheckInterval, UpdatePolicy, UpdateReminder};
+use crate::watchdog::WatchdogConfig;
 
 
 const PROFILES_DIR: &str = "Profiles";
 const SETTINGS_DIR: &str = "Profiles/Settings";
 const LAUNCHER_SETTINGS_FILENAME: &str = ".launcher_settings";
+const LAUNCH_HISTORY_FILENAME: &str = ".launch_history";
+const RELEASE_CACHE_FILENAME: &str = ".release_cache";
+const UPDATE_CHAIN_FILENAME: &str = ".update_chain";
+const BACKUPS_DIR: &str = "Backups";
+const MODS_DIR: &str = "Mods";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LauncherConfig {
@@ -19,13 +39,103 @@ pub struct LauncherConfig {
 }
 
 // Launcher 全局设置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LauncherSettings {
     #[serde(rename = "language")]
     pub language: Option<String>,
     #[serde(rename = "last_profile")]
     pub last_profile: Option<String>,
+    /// 游戏结束后弹出笔记输入框
+    #[serde(rename = "prompt_session_notes")]
+    pub prompt_session_notes: bool,
+    /// 日志面板显示的级别
+    #[serde(rename = "log_verbosity")]
+    pub log_verbosity: LogVerbosity,
+    /// 日志面板保留的条目数和时长
+    #[serde(rename = "log_retention")]
+    pub log_retention: LogRetention,
+    /// 日志面板的配色方案
+    #[serde(rename = "log_palette")]
+    pub log_palette: LogPalette,
+    /// 自定义的 OpenUO 安装目录（默认为 Launcher 目录下的 OpenUO）
+    #[serde(rename = "openuo_dir")]
+    pub openuo_dir: Option<String>,
+    /// 半透明窗口（重启后生效）
+    #[serde(rename = "translucent_window")]
+    pub translucent_window: bool,
+    /// 下载 OpenUO 和 Launcher 时的并发连接数，0 或 1 为单连接
+    #[serde(rename = "download_connections")]
+    pub download_connections: u8,
+    /// 建立连接的超时（秒），0 为默认值
+    #[serde(rename = "connect_timeout_secs")]
+    pub connect_timeout_secs: u16,
+    /// 等待下一块数据的超时（秒），0 为默认值；不限制整个下载的时长
+    #[serde(rename = "read_timeout_secs")]
+    pub read_timeout_secs: u16,
+    /// 网络请求遇到临时错误时的最大尝试次数，0 为默认值
+    #[serde(rename = "retry_attempts")]
+    pub retry_attempts: u8,
+    /// 后台检查更新的频率
+    #[serde(rename = "update_check_interval")]
+    pub update_check_interval: UpdateCheckInterval,
+    /// 启动 OpenUO 档案前，有新版本时先自动下载安装
+    #[serde(rename = "auto_update_openuo")]
+    pub auto_update_openuo: bool,
+    /// GitHub API 令牌，避免共享 IP 被限流（环境变量 OPENUO_GITHUB_TOKEN / GITHUB_TOKEN 优先）
+    #[serde(rename = "github_token", skip_serializing_if = "GithubToken::is_empty")]
+    pub github_token: GithubToken,
+    /// Launcher 退出时结束它启动的游戏和游戏启动的进程
+    #[serde(rename = "tie_children")]
+    pub tie_children: bool,
+    /// 使用电池时也不降低帧率、不推迟自动下载
+    #[serde(rename = "ignore_battery")]
+    pub ignore_battery: bool,
+    /// 按流量计费的网络上也不推迟自动下载
+    #[serde(rename = "ignore_metered")]
+    pub ignore_metered: bool,
+    /// “跳过此版本”和“稍后提醒”的 Launcher 更新
+    #[serde(rename = "launcher_update_reminder")]
+    pub launcher_update_reminder: UpdateReminder,
+    /// 已经提示过从其他 Launcher 迁移，之后启动时不再查找
+    #[serde(rename = "legacy_migration_offered")]
+    pub legacy_migration_offered: bool,
+    /// 上次运行时的机器名（密码的密钥），机器名变化后用来解密旧密码
+    #[serde(rename = "machine_name")]
+    pub machine_name: Option<String>,
+    /// 服务器活动开始前显示系统通知
+    #[serde(rename = "event_notifications")]
+    pub event_notifications: bool,
+    /// 提前几分钟通知，0 为默认值
+    #[serde(rename = "event_notify_minutes")]
+    pub event_notify_minutes: u32,
+    /// 每个投票网站（按 URL）上次投票的 Unix 时间戳
+    #[serde(rename = "vote_times")]
+    pub vote_times: BTreeMap<String, i64>,
+    /// 投票冷却结束时显示系统通知
+    #[serde(rename = "vote_reminders")]
+    pub vote_reminders: bool,
+    /// 在本机解锁员工模式（服务器清单没有开放时）
+    #[serde(rename = "staff_mode")]
+    pub staff_mode: bool,
+    /// 恢复 Launcher 窗口的全局快捷键，例如 "Ctrl+Alt+U"；加按 Shift 时启动当前档案
+    #[serde(rename = "global_hotkey")]
+    pub global_hotkey: Option<String>,
+    /// 自动更新 update_source.json 的引导地址
+    #[serde(rename = "bootstrap", skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<Bootstrap>,
+    /// 更新 OpenUO 前备份 Data/Profiles
+    #[serde(rename = "update_backup")]
+    pub update_backup: UpdateBackupConfig,
+    /// 锁定模式：档案只能启动，不能修改设置（也可以用 Launcher 旁边的 .kiosk 文件开启）
+    #[serde(rename = "kiosk")]
+    pub kiosk: bool,
+    /// 同步档案和游戏内设置的云盘目录（Dropbox、OneDrive、Syncthing 等）
+    #[serde(rename = "sync_folder", skip_serializing_if = "Option::is_none")]
+    pub sync_folder: Option<PathBuf>,
+    /// 拖动排列的档案顺序（档案文件名）；为空时按名称排序
+    #[serde(rename = "profile_order", skip_serializing_if = "Vec::is_empty")]
+    pub profile_order: Vec<String>,
 }
 
 impl Default for LauncherConfig {
@@ -38,15 +148,6 @@ impl Default for LauncherConfig {
     }
 }
 
-impl Default for LauncherSettings {
-    fn default() -> Self {
-        Self {
-            language: None,
-            last_profile: None,
-        }
-    }
-}
-
 // Profile 索引文件结构（Profiles/{uuid}.json）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileIndex {
@@ -60,6 +161,66 @@ pub struct ProfileIndex {
     pub last_character_name: String,
     #[serde(rename = "AdditionalArgs")]
     pub additional_args: String,
+    /// 启动前向登录服务器查询服务器列表，自动填写 lastservernum/last_server_name
+    #[serde(rename = "QueryServerList", default)]
+    pub query_server_list: bool,
+    /// 启动目标：OpenUO / 原版客户端 / 自定义程序
+    #[serde(rename = "LaunchTarget", default)]
+    pub launch_target: LaunchTarget,
+    /// 原版客户端或自定义程序的路径
+    #[serde(rename = "ExecutablePath", default)]
+    pub executable_path: String,
+    /// 原版客户端的参数模板
+    #[serde(rename = "ClassicArgumentTemplate", default)]
+    pub classic_argument_template: String,
+    /// 自定义程序的参数模板
+    #[serde(rename = "CustomArgumentTemplate", default)]
+    pub custom_argument_template: String,
+    /// 旧版本中两种启动目标共用的参数模板，加载时归入当时的启动目标
+    #[serde(rename = "ArgumentTemplate", default, skip_serializing)]
+    pub legacy_argument_template: String,
+    /// 启动前的硬件检查阈值
+    #[serde(rename = "HardwareCheck", default)]
+    pub hardware_check: RequirementThresholds,
+    /// 启动前的 OpenUO 更新策略
+    #[serde(rename = "UpdatePolicy", default)]
+    pub update_policy: UpdatePolicy,
+    /// 挂机看门狗：检测到掉线时自动重新启动
+    #[serde(rename = "Watchdog", default)]
+    pub watchdog: WatchdogConfig,
+    /// 启动前的二次确认
+    #[serde(rename = "LaunchConfirmation", default)]
+    pub launch_confirmation: LaunchConfirmation,
+    /// 每次启动时询问密码，不保存到磁盘
+    #[serde(rename = "SessionPassword", default)]
+    pub session_password: SessionPasswordConfig,
+    /// 启动后调整游戏进程的优先级和 CPU 亲和性
+    #[serde(rename = "ProcessTuning", default)]
+    pub process_tuning: ProcessTuning,
+    /// 双显卡笔记本上让游戏使用独立显卡
+    #[serde(rename = "PreferDedicatedGpu", default)]
+    pub prefer_dedicated_gpu: bool,
+    /// 启动前备份 OpenUO 的角色设置
+    #[serde(rename = "ProfileBackup", default)]
+    pub profile_backup: ProfileBackupConfig,
+    /// 固定使用的 OpenUO 版本（标签），并存安装在 OpenUO/<标签>；为空时使用主安装并跟随更新
+    #[serde(rename = "PinnedOpenUO", default, skip_serializing_if = "Option::is_none")]
+    pub pinned_open_uo: Option<String>,
+    /// 玩家用这个档案确认过的服务器规则版本
+    #[serde(rename = "RulesAcknowledged", default, skip_serializing_if = "Option::is_none")]
+    pub rules_acknowledged: Option<String>,
+    /// 员工模式的额外启动参数
+    #[serde(rename = "StaffMode", default)]
+    pub staff: StaffConfig,
+    /// 游戏使用的 cliloc 语言文件（例如 "DEU" 对应 cliloc.deu）；为空时跟随 Launcher 界面语言
+    #[serde(rename = "ClilocLanguage", default, skip_serializing_if = "Option::is_none")]
+    pub cliloc_language: Option<String>,
+    /// 启动前必须满足的网络条件（例如连上 VPN）
+    #[serde(rename = "NetworkRequirement", default)]
+    pub network_requirement: NetworkRequirement,
+    /// 加密保存的密码所用密钥的标识，见 `crypter::key_id`
+    #[serde(rename = "PasswordKeyId", default, skip_serializing_if = "String::is_empty")]
+    pub password_key_id: String,
 }
 
 impl Default for ProfileIndex {
@@ -70,6 +231,54 @@ impl Default for ProfileIndex {
             file_name: uuid::Uuid::new_v4().to_string(),
             last_character_name: String::new(),
             additional_args: String::new(),
+            query_server_list: false,
+            launch_target: LaunchTarget::OpenUO,
+            executable_path: String::new(),
+            classic_argument_template: String::new(),
+            custom_argument_template: String::new(),
+            legacy_argument_template: String::new(),
+            hardware_check: RequirementThresholds::default(),
+            update_policy: UpdatePolicy::default(),
+            watchdog: WatchdogConfig::default(),
+            launch_confirmation: LaunchConfirmation::default(),
+            session_password: SessionPasswordConfig::default(),
+            process_tuning: ProcessTuning::default(),
+            prefer_dedicated_gpu: false,
+            profile_backup: ProfileBackupConfig::default(),
+            pinned_open_uo: None,
+            rules_acknowledged: None,
+            staff: StaffConfig::default(),
+            cliloc_language: None,
+            network_requirement: NetworkRequirement::default(),
+            password_key_id: String::new(),
+        }
+    }
+}
+
+impl ProfileIndex {
+    /// 当前启动目标的参数模板，OpenUO 不使用参数模板
+    pub fn argument_template(&self) -> &str {
+        match self.launch_target {
+            LaunchTarget::OpenUO => "",
+            LaunchTarget::ClassicClient => &self.classic_argument_template,
+            LaunchTarget::Custom => &self.custom_argument_template,
+        }
+    }
+
+    pub fn argument_template_mut(&mut self) -> Option<&mut String> {
+        match self.launch_target {
+            LaunchTarget::OpenUO => None,
+            LaunchTarget::ClassicClient => Some(&mut self.classic_argument_template),
+            LaunchTarget::Custom => Some(&mut self.custom_argument_template),
+        }
+    }
+
+    fn migrate_argument_template(&mut self) {
+        let legacy = std::mem::take(&mut self.legacy_argument_template);
+        if let Some(template) = self.argument_template_mut()
+            && template.is_empty()
+        {
+            *template = legacy;
         }
     }
 }
@@ -99,6 +308,10 @@ pub fn new_profile(name: &str) -> ProfileConfig {
         let launcher_dir = base_dir();
         profile.settings.ultima_online_directory = launcher_dir.to_string_lossy().to_string();
     }
+    if let Some(detected) = detect_client_version_from_uo_resources(&profile.settings.ultima_online_directory) {
+        profile.settings.encryption = crate::encryption_helper::suggest_encryption_from_version(&detected.version);
+        profile.settings.client_version = detected.version;
+    }
     
     profile
 }
@@ -182,6 +395,9 @@ pub struct OuoSettings {
     pub launcher_scale_factor: Option<f64>,
     #[serde(rename = "launcher_is_hidpi", skip_serializing_if = "Option::is_none")]
     pub launcher_is_hidpi: Option<bool>,
+    /// Launcher 不认识的字段（较新版本 OpenUO 的设置等），原样写回
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Default for OuoSettings {
@@ -220,10 +436,55 @@ impl Default for OuoSettings {
             launcher_screen_height: None,
             launcher_scale_factor: None,
             launcher_is_hidpi: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// 游戏窗口模式，对应 OpenUO 的 is_win_maximized
+///
+/// OpenUO 的 settings.json 没有无边框窗口的设置，因此不提供这个选项。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    Windowed,
+    Maximized,
+}
+
+impl WindowMode {
+    pub const ALL: [WindowMode; 2] = [WindowMode::Windowed, WindowMode::Maximized];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "window_mode.windowed",
+            WindowMode::Maximized => "window_mode.maximized",
         }
     }
 }
 
+impl OuoSettings {
+    pub fn window_mode(&self) -> WindowMode {
+        if self.is_window_maximized {
+            WindowMode::Maximized
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    pub fn set_window_mode(&mut self, mode: WindowMode) {
+        self.is_window_maximized = mode == WindowMode::Maximized;
+    }
+
+    /// 自动登录但不保存账号：账号密码只保存在内存中，启动时通过命令行传给 OpenUO，不写入磁盘
+    pub fn uses_session_credentials(&self) -> bool {
+        self.auto_login && !self.save_account
+    }
+
+    /// 需要在启动前询问本次运行使用的账号密码
+    pub fn session_credentials_missing(&self) -> bool {
+        self.uses_session_credentials() && (self.username.is_empty() || self.password.is_empty())
+    }
+}
+
 /// 将 Launcher 语言代码转换为 OpenUO 支持的语言代码
 /// 
 /// 支持的语言：
@@ -274,9 +535,22 @@ fn convert_launcher_lang_to_uo_lang(launcher_lang: &str) -> String {
     }
 }
 
+/// 用户选择的 OpenUO 安装目录，启动时从 LauncherSettings 载入
+static OPEN_UO_DIR_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+pub fn set_open_uo_dir_override(dir: Option<String>) {
+    if let Ok(mut current) = OPEN_UO_DIR_OVERRIDE.write() {
+        *current = dir.filter(|d| !d.trim().is_empty());
+    }
+}
+
 // Path helpers
 pub fn client_path() -> String {
-    "OpenUO".to_string()
+    OPEN_UO_DIR_OVERRIDE
+        .read()
+        .ok()
+        .and_then(|dir| dir.clone())
+        .unwrap_or_else(|| "OpenUO".to_string())
 }
 
 pub fn uo_data_path() -> String {
@@ -300,7 +574,11 @@ pub fn open_uo_dir() -> PathBuf {
 }
 
 pub fn open_uo_binary_path() -> PathBuf {
-    let dir = open_uo_dir();
+    open_uo_binary_in(&open_uo_dir())
+}
+
+/// 指定 OpenUO 目录中的可执行文件（也用于并存安装的版本）
+pub fn open_uo_binary_in(dir: &Path) -> PathBuf {
     let exe = if cfg!(target_os = "windows") {
         "OpenUO.exe"
     } else {
@@ -325,6 +603,38 @@ pub fn launcher_settings_path() -> PathBuf {
     base_dir().join(LAUNCHER_SETTINGS_FILENAME)
 }
 
+pub fn launch_history_path() -> PathBuf {
+    base_dir().join(LAUNCH_HISTORY_FILENAME)
+}
+
+pub fn release_cache_path() -> PathBuf {
+    base_dir().join(RELEASE_CACHE_FILENAME)
+}
+
+pub fn update_chain_path() -> PathBuf {
+    base_dir().join(UPDATE_CHAIN_FILENAME)
+}
+
+/// 所有档案的角色设置备份
+pub fn backups_dir() -> PathBuf {
+    base_dir().join(BACKUPS_DIR)
+}
+
+/// 已导入的补丁包
+pub fn mods_dir() -> PathBuf {
+    base_dir().join(MODS_DIR)
+}
+
+/// 更新前 Data/Profiles 的备份（档案的备份目录名是 UUID，不会冲突）
+pub fn game_profiles_backups_dir() -> PathBuf {
+    backups_dir().join("OpenUO")
+}
+
+/// 档案的角色设置备份目录（Backups/{file_name}）
+pub fn profile_backups_dir(profile: &ProfileConfig) -> PathBuf {
+    backups_dir().join(&profile.index.file_name)
+}
+
 pub fn profile_index_path(profile: &ProfileConfig) -> PathBuf {
     profiles_dir().join(format!("{}.json", profile.index.file_name))
 }
@@ -366,6 +676,7 @@ pub fn load_config_from_disk() -> LauncherConfig {
     }
     
     config.profiles = profiles;
+    sort_profiles(&mut config);
     
     // 恢复上次选择的 profile
     config.active_profile = 0;
@@ -378,9 +689,34 @@ pub fn load_config_from_disk() -> LauncherConfig {
     config
 }
 
+/// 按当前界面语言的规则对档案按名称排序，拖动排列过时按保存的顺序，保持当前选中的档案不变
+pub fn sort_profiles(config: &mut LauncherConfig) {
+    let active_id = config
+        .profiles
+        .get(config.active_profile)
+        .map(|p| p.index.file_name.clone());
+    let locale = crate::i18n::current_locale();
+    crate::collation::sort_by_key(&mut config.profiles, &locale, |p| p.index.name.as_str());
+    apply_profile_order(&mut config.profiles, &config.launcher_settings.profile_order);
+    if let Some(id) = active_id
+        && let Some(idx) = config.profiles.iter().position(|p| p.index.file_name == id)
+    {
+        config.active_profile = idx;
+    }
+}
+
+/// 保存的顺序中没有的档案（之后新建、导入或同步来的）按名称排在后面
+fn apply_profile_order(profiles: &mut [ProfileConfig], order: &[String]) {
+    if order.is_empty() {
+        return;
+    }
+    profiles.sort_by_key(|p| order.iter().position(|id| *id == p.index.file_name).unwrap_or(usize::MAX));
+}
+
 fn load_profile_from_file(path: &PathBuf) -> Result<ProfileConfig> {
     let raw = fs::read_to_string(path)?;
-    let index: ProfileIndex = serde_json::from_str(&raw)?;
+    let mut index: ProfileIndex = serde_json::from_str(&raw)?;
+    index.migrate_argument_template();
     
     tracing::info!("{}: {}", crate::i18n::t!("log.profile_loaded"), index.name);
     
@@ -424,8 +760,10 @@ pub fn save_profile_with_screen_info(
     fs::create_dir_all(profiles_dir())?;
     fs::create_dir_all(settings_dir())?;
     
-    // 保存索引文件
-    let index_json = serde_json::to_string_pretty(&profile.index)?;
+    // 保存索引文件，记录密码所用的密钥
+    let mut index = profile.index.clone();
+    index.password_key_id = if profile.settings.password.is_empty() { String::new() } else { crate::crypter::key_id() };
+    let index_json = serde_json::to_string_pretty(&index)?;
     let index_path = profile_index_path(profile);
     let tmp = index_path.with_extension("tmp");
     fs::write(&tmp, index_json)?;
@@ -452,6 +790,7 @@ pub fn save_profile_with_screen_info(
     settings.save_account = profile.settings.save_account;
     settings.auto_login = profile.settings.auto_login;
     settings.reconnect = profile.settings.reconnect;
+    settings.login_music = profile.settings.login_music;
     settings.client_version = profile.settings.client_version.clone();
     
     // 处理加密设置：如果强制禁用加密，设置为 0
@@ -465,30 +804,21 @@ pub fn save_profile_with_screen_info(
     // 同步一些必要的字段
     // profilespath 留空，让 OpenUO 使用默认位置（OpenUO/Data/Profiles/）
     settings.profiles_path = String::new();
-    settings.last_server_name = settings.ip.clone();
+    // 服务器列表查询得到的名称优先，否则退回到服务器地址
+    settings.last_server_num = profile.settings.last_server_num;
+    settings.last_server_name = if profile.settings.last_server_name.is_empty() {
+        settings.ip.clone()
+    } else {
+        profile.settings.last_server_name.clone()
+    };
     
     // 添加屏幕信息（如果提供）
-    if let Some(info) = screen_info {
-        settings.launcher_screen_width = Some(info.width);
-        settings.launcher_screen_height = Some(info.height);
-        settings.launcher_scale_factor = Some(info.scale_factor);
-        
-        // launcher_is_hidpi: 只有 scale_factor > 1 且系统为 macOS 时才设置为 true
-        #[cfg(target_os = "macos")]
-        {
-            settings.launcher_is_hidpi = Some(info.scale_factor > 1.0);
-        }
-        
-        #[cfg(not(target_os = "macos"))]
-        {
-            settings.launcher_is_hidpi = Some(false);
-        }
-        
-        // 转换 Launcher 语言代码为 OpenUO 支持的语言代码
-        let uo_lang = convert_launcher_lang_to_uo_lang(&info.lang);
-        if !uo_lang.is_empty() {
-            settings.language = uo_lang;
-        }
+    if let Some(info) = &screen_info {
+        info.apply_to(&mut settings);
+    }
+    // 档案选择的 cliloc 语言优先于 Launcher 界面语言
+    if let Some(language) = &profile.index.cliloc_language {
+        settings.language = language.clone();
     }
     
     // 如果不保存账号，清空用户名和密码
@@ -496,6 +826,10 @@ pub fn save_profile_with_screen_info(
         settings.username.clear();
         settings.password.clear();
     }
+    // 仅本次使用的密码只在启动时写入，保存档案时不写
+    if profile.index.session_password.enabled {
+        settings.password.clear();
+    }
     
     let settings_json = serde_json::to_string_pretty(&settings)?;
     let tmp = settings_path.with_extension("tmp");
@@ -505,14 +839,54 @@ pub fn save_profile_with_screen_info(
     Ok(())
 }
 
-#[derive(Debug, Clone)]
+/// 启动时的屏幕信息，保存档案时写入 OpenUO 的设置文件
+#[derive(Debug, Clone, PartialEq)]
 pub struct ScreenInfo {
     pub width: u32,
     pub height: u32,
     pub scale_factor: f64,
+    /// 只有 macOS 上缩放大于 1 时为 true（OpenUO 只在 macOS 上按 HiDPI 渲染）
+    pub is_hidpi: bool,
+    /// 运行的系统，与 `std::env::consts::OS` 相同
+    pub os: &'static str,
+    /// 窗口所在显示器的名称，仅用于日志
+    pub monitor: Option<String>,
+    /// Launcher 的界面语言
     pub lang: String,
 }
 
+impl ScreenInfo {
+    pub fn new(width: u32, height: u32, scale_factor: f64, monitor: Option<String>, lang: String) -> Self {
+        Self::for_os(std::env::consts::OS, width, height, scale_factor, monitor, lang)
+    }
+
+    fn for_os(os: &'static str, width: u32, height: u32, scale_factor: f64, monitor: Option<String>, lang: String) -> Self {
+        Self {
+            width,
+            height,
+            scale_factor,
+            is_hidpi: os == "macos" && scale_factor > 1.0,
+            os,
+            monitor,
+            lang,
+        }
+    }
+
+    /// 写入 OpenUO 读取的 launcher_screen_* 字段和游戏语言，系统和显示器不写入
+    pub fn apply_to(&self, settings: &mut OuoSettings) {
+        settings.launcher_screen_width = Some(self.width);
+        settings.launcher_screen_height = Some(self.height);
+        settings.launcher_scale_factor = Some(self.scale_factor);
+        settings.launcher_is_hidpi = Some(self.is_hidpi);
+
+        // 转换 Launcher 语言代码为 OpenUO 支持的语言代码
+        let uo_lang = convert_launcher_lang_to_uo_lang(&self.lang);
+        if !uo_lang.is_empty() {
+            settings.language = uo_lang;
+        }
+    }
+}
+
 pub fn save_config(config: &LauncherConfig) -> Result<()> {
     // 保存所有档案
     for profile in &config.profiles {
@@ -554,18 +928,84 @@ pub fn delete_profile(profile: &ProfileConfig) -> Result<()> {
     if settings_path.exists() {
         fs::remove_file(settings_path)?;
     }
+    fs::remove_dir_all(profile_backups_dir(profile)).ok();
     
     Ok(())
 }
 
-fn detect_client_version_from_uo_resources(_path: &str) -> Option<String> {
-    // TODO: parse client.exe version when available
-    None
+/// 从 UO 资源目录识别出的客户端版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedClientVersion {
+    pub version: String,
+    /// 从 client.exe 读取；否则是根据数据文件推断出的最低版本
+    pub exact: bool,
+}
+
+/// 7.0.9.0（High Seas）之前的 tiledata.mul：地面 512 组，每组 4 字节头 + 32 × 26 字节；物品每组 4 + 32 × 37 字节
+const TILEDATA_OLD: (u64, u64) = (512 * (4 + 32 * 26), 4 + 32 * 37);
+/// 7.0.9.0 起标志位扩展为 64 位：地面每组 4 + 32 × 30 字节，物品每组 4 + 32 × 41 字节
+const TILEDATA_HS: (u64, u64) = (512 * (4 + 32 * 30), 4 + 32 * 41);
+
+fn tiledata_matches(size: u64, (land, item_group): (u64, u64)) -> bool {
+    size > land && (size - land).is_multiple_of(item_group)
+}
+
+/// 识别 UO 资源目录对应的客户端版本：优先读取 client.exe 的版本信息，
+/// 没有 client.exe 时根据 tiledata.mul 格式和 UOP 文件推断
+pub fn detect_client_version_from_uo_resources(path: &str) -> Option<DetectedClientVersion> {
+    if path.is_empty() {
+        return None;
+    }
+    // 文件名不区分大小写
+    let files: BTreeMap<String, PathBuf> = fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| Some((entry.file_name().to_str()?.to_lowercase(), entry.path())))
+        .collect();
+
+    if let Some(version) = files.get("client.exe").and_then(|exe| crate::version_reader::read_pe_version(exe)) {
+        return Some(DetectedClientVersion { version, exact: true });
+    }
+
+    let tiledata_size = files.get("tiledata.mul").and_then(|f| fs::metadata(f).ok()).map(|m| m.len());
+    // 两种格式的大小有极少数重合，此时不作判断
+    let high_seas = tiledata_size.is_some_and(|size| tiledata_matches(size, TILEDATA_HS) && !tiledata_matches(size, TILEDATA_OLD));
+    let uop = files.keys().any(|name| name.ends_with(".uop"));
+    let version = if high_seas {
+        "7.0.9.0"
+    } else if uop {
+        "7.0.0.0"
+    } else {
+        return None;
+    };
+    Some(DetectedClientVersion { version: version.to_string(), exact: false })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_apply_profile_order() {
+        let mut profiles: Vec<ProfileConfig> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|id| {
+                let mut profile = ProfileConfig::default();
+                profile.index.file_name = id.to_string();
+                profile
+            })
+            .collect();
+        let ids = |profiles: &[ProfileConfig]| profiles.iter().map(|p| p.index.file_name.clone()).collect::<Vec<_>>();
+
+        apply_profile_order(&mut profiles, &[]);
+        assert_eq!(ids(&profiles), ["a", "b", "c", "d"]);
+        // 没有记录的档案保持原来（按名称）的顺序排在后面，已删除的档案忽略
+        let order = ["c", "gone", "a"].map(String::from);
+        apply_profile_order(&mut profiles, &order);
+        assert_eq!(ids(&profiles), ["c", "a", "b", "d"]);
+    }
 
     #[test]
     fn test_language_conversion() {
@@ -594,4 +1034,124 @@ mod tests {
         assert_eq!(convert_launcher_lang_to_uo_lang("unknown"), "");
         assert_eq!(convert_launcher_lang_to_uo_lang("ar"), "");
     }
+
+    #[test]
+    fn test_session_credentials() {
+        let mut settings = OuoSettings {
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            ..Default::default()
+        };
+        // 默认保存账号，使用设置文件中的账号
+        assert!(!settings.uses_session_credentials());
+
+        settings.save_account = false;
+        assert!(settings.uses_session_credentials());
+        assert!(!settings.session_credentials_missing());
+        settings.password.clear();
+        assert!(settings.session_credentials_missing());
+
+        // 不自动登录时由玩家在游戏中输入
+        settings.auto_login = false;
+        assert!(!settings.session_credentials_missing());
+    }
+
+    #[test]
+    fn test_screen_info_hidpi() {
+        let info = |os, scale| ScreenInfo::for_os(os, 2560, 1440, scale, None, "en".to_string());
+        assert!(info("macos", 2.0).is_hidpi);
+        assert!(!info("macos", 1.0).is_hidpi);
+        // 其他系统由 OpenUO 自己处理缩放
+        assert!(!info("windows", 2.0).is_hidpi);
+        assert!(!info("linux", 1.5).is_hidpi);
+    }
+
+    #[test]
+    fn test_screen_info_written_fields() {
+        let before = serde_json::to_value(OuoSettings::default()).unwrap();
+        let mut settings = OuoSettings::default();
+        ScreenInfo::for_os("macos", 2560, 1600, 2.0, Some("Built-in Retina Display".to_string()), "zh-CN".to_string())
+            .apply_to(&mut settings);
+        let after = serde_json::to_value(&settings).unwrap();
+
+        let (before, after) = (before.as_object().unwrap(), after.as_object().unwrap());
+        let mut changed: Vec<_> = after.keys().filter(|k| before.get(*k) != after.get(*k)).map(String::as_str).collect();
+        changed.sort_unstable();
+        assert_eq!(
+            changed,
+            [
+                "lang",
+                "launcher_is_hidpi",
+                "launcher_scale_factor",
+                "launcher_screen_height",
+                "launcher_screen_width"
+            ]
+        );
+        assert_eq!(after["launcher_screen_width"], 2560);
+        assert_eq!(after["launcher_screen_height"], 1600);
+        assert_eq!(after["launcher_scale_factor"], 2.0);
+        assert_eq!(after["launcher_is_hidpi"], true);
+        assert_eq!(after["lang"], "CHT");
+    }
+
+    #[test]
+    fn test_window_mode() {
+        let mut settings = OuoSettings::default();
+        assert_eq!(settings.window_mode(), WindowMode::Maximized);
+
+        for mode in WindowMode::ALL {
+            settings.set_window_mode(mode);
+            assert_eq!(settings.window_mode(), mode);
+        }
+
+        settings.set_window_mode(WindowMode::Windowed);
+        assert!(!settings.is_window_maximized);
+    }
+
+    #[test]
+    fn test_argument_template_per_target() {
+        let mut index = ProfileIndex::default();
+        assert!(index.argument_template_mut().is_none());
+        index.launch_target = LaunchTarget::ClassicClient;
+        *index.argument_template_mut().unwrap() = "-uo {uo_dir}".to_string();
+        index.launch_target = LaunchTarget::Custom;
+        assert_eq!(index.argument_template(), "");
+        *index.argument_template_mut().unwrap() = "--host {ip}".to_string();
+        index.launch_target = LaunchTarget::ClassicClient;
+        assert_eq!(index.argument_template(), "-uo {uo_dir}");
+
+        // 旧版本保存的共用模板归入当时的启动目标，保存时不再写出
+        let mut raw = serde_json::to_value(ProfileIndex::default()).unwrap();
+        raw["LaunchTarget"] = "Custom".into();
+        raw["ArgumentTemplate"] = "--host {ip}".into();
+        let mut old: ProfileIndex = serde_json::from_value(raw).unwrap();
+        old.migrate_argument_template();
+        assert_eq!(old.custom_argument_template, "--host {ip}");
+        assert!(old.classic_argument_template.is_empty());
+        let saved = serde_json::to_value(&old).unwrap();
+        assert!(saved.get("ArgumentTemplate").is_none());
+        assert_eq!(saved["CustomArgumentTemplate"], "--host {ip}");
+    }
+
+    #[test]
+    fn test_detect_client_version() {
+        let dir = temp_dir("client-version");
+        let path = dir.to_string_lossy().to_string();
+        assert_eq!(detect_client_version_from_uo_resources(&path), None);
+
+        // 旧格式的 tiledata.mul（0x8000 个物品）
+        fs::write(dir.join("TileData.mul"), vec![0u8; 1_644_544]).unwrap();
+        assert_eq!(detect_client_version_from_uo_resources(&path), None);
+        fs::write(dir.join("artLegacyMUL.uop"), "").unwrap();
+        let detected = detect_client_version_from_uo_resources(&path).unwrap();
+        assert_eq!((detected.version.as_str(), detected.exact), ("7.0.0.0", false));
+
+        // High Seas 格式（0x10000 个物品）
+        fs::write(dir.join("TileData.mul"), vec![0u8; 3_188_736]).unwrap();
+        assert_eq!(detect_client_version_from_uo_resources(&path).unwrap().version, "7.0.9.0");
+
+        // 无法解析的 client.exe 不影响推断
+        fs::write(dir.join("client.exe"), "MZ").unwrap();
+        assert_eq!(detect_client_version_from_uo_resources(&path).unwrap().version, "7.0.9.0");
+    }
 }
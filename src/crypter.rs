@@ -2,11 +2,14 @@
 
 /// 加密字符串，使用机器名作为密钥
 pub fn encrypt(source: &str) -> String {
+    encrypt_with_key(source, &calculate_key())
+}
+
+fn encrypt_with_key(source: &str, key: &str) -> String {
     if source.is_empty() {
         return String::new();
     }
 
-    let key = calculate_key();
     if key.is_empty() {
         return String::new();
     }
@@ -30,13 +33,17 @@ pub fn encrypt(source: &str) -> String {
 
 /// 解密字符串，使用机器名作为密钥
 pub fn decrypt(source: &str) -> String {
+    decrypt_with_key(source, &calculate_key())
+}
+
+/// 用指定的密钥解密（例如其他 Launcher 使用的机器名）
+pub fn decrypt_with_key(source: &str, key: &str) -> String {
     if source.is_empty() {
         return String::new();
     }
 
     // 新格式：以 "1-" 或 "1+" 开头
     if source.len() > 2 && source.starts_with("1-") || source.starts_with("1+") {
-        let key = calculate_key();
         if key.is_empty() {
             return String::new();
         }
@@ -82,7 +89,29 @@ pub fn decrypt(source: &str) -> String {
     }
 }
 
-fn calculate_key() -> String {
+/// 当前密钥的标识（密钥 SHA-256 的前 8 位），与加密的密码一起保存，用于发现机器名的变化
+pub fn key_id() -> String {
+    key_id_for(&calculate_key())
+}
+
+pub fn key_id_for(key: &str) -> String {
+    crate::checksum::sha256_bytes(key.as_bytes())[..8].to_string()
+}
+
+/// 把用标识为 `key_id` 的旧密钥加密的字符串改用当前密钥加密
+///
+/// 在 `candidates`（可能的旧机器名）中查找标识相同的密钥，找不到时返回 None。
+pub fn reencrypt(source: &str, key_id: &str, candidates: &[String]) -> Option<String> {
+    reencrypt_with_key(source, key_id, candidates, &calculate_key())
+}
+
+fn reencrypt_with_key(source: &str, key_id: &str, candidates: &[String], current: &str) -> Option<String> {
+    let old = candidates.iter().find(|key| key_id_for(key) == key_id)?;
+    Some(encrypt_with_key(&decrypt_with_key(source, old), current))
+}
+
+/// 当前使用的密钥，即机器名
+pub fn calculate_key() -> String {
     // 使用机器名作为密钥
     hostname::get()
         .ok()
@@ -102,6 +131,17 @@ mod tests {
         assert_eq!(original, decrypted);
     }
 
+    #[test]
+    fn test_reencrypt() {
+        let encrypted = encrypt_with_key("hunter2", "old-pc");
+        let candidates = vec!["other".to_string(), "old-pc".to_string()];
+        let migrated = reencrypt_with_key(&encrypted, &key_id_for("old-pc"), &candidates, "new-pc").unwrap();
+        assert_eq!(decrypt_with_key(&migrated, "new-pc"), "hunter2");
+        // 没有标识相同的候选密钥
+        assert!(reencrypt_with_key(&encrypted, &key_id_for("renamed"), &candidates, "new-pc").is_none());
+        assert_eq!(key_id_for("old-pc").len(), 8);
+    }
+
     #[test]
     fn test_empty_string() {
         assert_eq!(encrypt(""), "");
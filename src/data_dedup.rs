@@ -0,0 +1,305 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// 内容完全相同的几份 UO 数据：保留 `keep`，其余的可以替换为指向它的链接
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    pub keep: PathBuf,
+    pub duplicates: Vec<PathBuf>,
+    /// 每一份的大小
+    pub size: u64,
+}
+
+impl DuplicateGroup {
+    /// 合并后可以节省的空间
+    pub fn savings(&self) -> u64 {
+        self.size * self.duplicates.len() as u64
+    }
+}
+
+/// 查找和合并的后台事件
+pub enum DedupEvent {
+    /// 已比较的字节数和需要比较的总字节数
+    Progress { hashed: u64, total: u64 },
+    Found(Vec<DuplicateGroup>),
+    /// 合并完成，释放的字节数和合并失败的目录
+    Consolidated { freed: u64, failed: Vec<String> },
+    /// 链接已换回独立的副本
+    CopiedBack(Result<PathBuf, String>),
+    Failed(String),
+}
+
+/// 目录中所有文件的相对路径（统一用 / 分隔）和大小
+type Listing = BTreeMap<String, u64>;
+
+fn list_files(root: &Path, dir: &Path, listing: &mut Listing) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("无法读取 {}", dir.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            list_files(root, &path, listing)?;
+        } else if file_type.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let key = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            listing.insert(key, entry.metadata()?.len());
+        }
+    }
+    Ok(())
+}
+
+/// 可以合并的目录：存在、本身不是链接、包含完整的 UO 数据；已经指向同一位置的只保留一个
+fn candidates(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        let is_link = fs::symlink_metadata(dir).is_ok_and(|m| m.file_type().is_symlink());
+        let Ok(canonical) = dir.canonicalize() else {
+            continue;
+        };
+        if is_link || !crate::uo_data::missing(&canonical).is_empty() {
+            continue;
+        }
+        // 互相包含的目录不能用链接替换
+        if !found.iter().any(|other| other.starts_with(&canonical) || canonical.starts_with(other)) {
+            found.push(canonical);
+        }
+    }
+    found
+}
+
+/// 查找内容相同的 UO 数据目录：先按文件列表和大小分组，再逐个比较 SHA-256
+pub fn find_duplicates(dirs: &[PathBuf], mut progress: impl FnMut(u64, u64)) -> Result<Vec<DuplicateGroup>> {
+    let mut by_listing: Vec<(Listing, Vec<PathBuf>)> = Vec::new();
+    for dir in candidates(dirs) {
+        let mut listing = Listing::new();
+        list_files(&dir, &dir, &mut listing)?;
+        match by_listing.iter_mut().find(|(l, _)| *l == listing) {
+            Some((_, group)) => group.push(dir),
+            None => by_listing.push((listing, vec![dir])),
+        }
+    }
+    by_listing.retain(|(_, group)| group.len() > 1);
+
+    let total: u64 = by_listing.iter().map(|(listing, group)| listing.values().sum::<u64>() * group.len() as u64).sum();
+    let mut hashed = 0;
+    let mut groups = Vec::new();
+    for (listing, dirs) in by_listing {
+        let mut by_content: Vec<(Vec<String>, Vec<PathBuf>)> = Vec::new();
+        for dir in dirs {
+            let mut hashes = Vec::with_capacity(listing.len());
+            for (file, size) in &listing {
+                hashes.push(crate::checksum::sha256_file(&dir.join(file))?);
+                hashed += size;
+                progress(hashed, total);
+            }
+            match by_content.iter_mut().find(|(h, _)| *h == hashes) {
+                Some((_, group)) => group.push(dir),
+                None => by_content.push((hashes, vec![dir])),
+            }
+        }
+        let size = listing.values().sum();
+        groups.extend(by_content.into_iter().filter(|(_, group)| group.len() > 1).map(|(_, mut group)| {
+            let keep = group.remove(0);
+            DuplicateGroup { keep, duplicates: group, size }
+        }));
+    }
+    Ok(groups)
+}
+
+/// 把 `duplicate` 替换为指向 `keep` 的链接（Windows 上为目录联接，不需要管理员权限）
+///
+/// 先把原目录改名，链接建立后才删除；任何一步失败都恢复原目录。
+pub fn replace_with_link(keep: &Path, duplicate: &Path) -> Result<()> {
+    let (mut keep_listing, mut dup_listing) = (Listing::new(), Listing::new());
+    list_files(keep, keep, &mut keep_listing)?;
+    list_files(duplicate, duplicate, &mut dup_listing)?;
+    if keep_listing != dup_listing {
+        anyhow::bail!("{} 在比较之后发生了变化", duplicate.display());
+    }
+    let name = duplicate.file_name().context("无效的目录")?.to_string_lossy();
+    let backup = duplicate.with_file_name(format!("{}.openuo-dedup", name));
+    fs::rename(duplicate, &backup).with_context(|| format!("无法移动 {}，游戏可能正在使用", duplicate.display()))?;
+    if let Err(e) = link_dir(keep, duplicate) {
+        fs::rename(&backup, duplicate).ok();
+        return Err(e.context(format!("无法创建链接 {}", duplicate.display())));
+    }
+    if let Err(e) = fs::remove_dir_all(&backup) {
+        tracing::warn!("Failed to remove {}: {}", backup.display(), e);
+    }
+    Ok(())
+}
+
+/// 目录是否为链接（Windows 上的目录联接也算）
+pub fn is_link(dir: &Path) -> bool {
+    fs::symlink_metadata(dir).is_ok_and(|m| m.file_type().is_symlink())
+}
+
+/// 撤销 `replace_with_link`：把链接指向的数据复制回原位置，之后各自独立
+///
+/// 先复制到旁边的临时目录，完成后才删除链接；任何一步失败都保留链接。
+pub fn copy_back(link: &Path) -> Result<()> {
+    if !is_link(link) {
+        anyhow::bail!("{} 不是链接", link.display());
+    }
+    let target = link.canonicalize().with_context(|| format!("无法找到 {} 指向的目录", link.display()))?;
+    let name = link.file_name().context("无效的目录")?.to_string_lossy();
+    let copy = link.with_file_name(format!("{}.openuo-dedup", name));
+    if copy.exists() {
+        anyhow::bail!("{} 已经存在", copy.display());
+    }
+    if let Err(e) = crate::cloud_sync::copy_dir(&target, &copy, &mut |_| {}) {
+        fs::remove_dir_all(&copy).ok();
+        return Err(e);
+    }
+    if let Err(e) = remove_link(link) {
+        fs::remove_dir_all(&copy).ok();
+        return Err(anyhow::Error::from(e).context(format!("无法删除链接 {}", link.display())));
+    }
+    if let Err(e) = fs::rename(&copy, link) {
+        link_dir(&target, link).ok();
+        fs::remove_dir_all(&copy).ok();
+        return Err(anyhow::Error::from(e).context(format!("无法移动到 {}", link.display())));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn remove_link(link: &Path) -> std::io::Result<()> {
+    // 目录联接按目录删除，不影响指向的数据
+    fs::remove_dir(link)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove_link(link: &Path) -> std::io::Result<()> {
+    fs::remove_file(link)
+}
+
+#[cfg(unix)]
+fn link_dir(target: &Path, link: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, link)?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn link_dir(target: &Path, link: &Path) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    let output = std::process::Command::new("cmd")
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .arg("/C")
+        .arg("mklink")
+        .arg("/J")
+        .arg(link)
+        .arg(target)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("mklink 失败: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn link_dir(_target: &Path, _link: &Path) -> Result<()> {
+    anyhow::bail!("当前平台不支持目录链接")
+}
+
+/// 在后台线程查找可以合并的目录
+pub fn spawn_scan(dirs: Vec<PathBuf>) -> mpsc::Receiver<DedupEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = find_duplicates(&dirs, |hashed, total| {
+            let _ = tx.send(DedupEvent::Progress { hashed, total });
+        });
+        let _ = tx.send(match result {
+            Ok(groups) => DedupEvent::Found(groups),
+            Err(e) => DedupEvent::Failed(format!("{e:#}")),
+        });
+    });
+    rx
+}
+
+/// 在后台线程把每组的副本替换为链接
+pub fn spawn_consolidate(groups: Vec<DuplicateGroup>) -> mpsc::Receiver<DedupEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut freed = 0;
+        let mut failed = Vec::new();
+        for group in &groups {
+            for duplicate in &group.duplicates {
+                match replace_with_link(&group.keep, duplicate) {
+                    Ok(()) => freed += group.size,
+                    Err(e) => failed.push(format!("{e:#}")),
+                }
+            }
+        }
+        let _ = tx.send(DedupEvent::Consolidated { freed, failed });
+    });
+    rx
+}
+
+/// 在后台线程把链接换回独立的副本
+pub fn spawn_copy_back(link: PathBuf) -> mpsc::Receiver<DedupEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = copy_back(&link).map(|()| link).map_err(|e| format!("{e:#}"));
+        let _ = tx.send(DedupEvent::CopiedBack(result));
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    fn write_uo_data(dir: &Path, cliloc: &str) {
+        fs::create_dir_all(dir).unwrap();
+        for file in ["artLegacyMUL.uop", "gumpartLegacyMUL.uop", "map0LegacyMUL.uop", "MultiCollection.uop", "tiledata.mul", "hues.mul", "fonts.mul"] {
+            fs::write(dir.join(file), file).unwrap();
+        }
+        fs::write(dir.join("cliloc.enu"), cliloc).unwrap();
+    }
+
+    #[test]
+    fn test_find_and_link() {
+        let root = temp_dir("dedup");
+        let (a, b, c, d) = (root.join("a"), root.join("b"), root.join("c"), root.join("d"));
+        write_uo_data(&a, "same");
+        write_uo_data(&b, "same");
+        // 大小相同、内容不同
+        write_uo_data(&c, "diff");
+        fs::create_dir_all(&d).unwrap();
+
+        let dirs = vec![a.clone(), b.clone(), c.clone(), d, a.clone(), root.join("missing")];
+        let groups = find_duplicates(&dirs, |_, _| {}).unwrap();
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.keep, a.canonicalize().unwrap());
+        assert_eq!(group.duplicates, [b.canonicalize().unwrap()]);
+        assert!(group.savings() > 0);
+
+        #[cfg(unix)]
+        {
+            replace_with_link(&group.keep, &group.duplicates[0]).unwrap();
+            assert!(fs::symlink_metadata(&b).unwrap().file_type().is_symlink());
+            assert_eq!(fs::read_to_string(b.join("cliloc.enu")).unwrap(), "same");
+            // 已经是链接的目录不再出现
+            assert!(find_duplicates(&dirs, |_, _| {}).unwrap().is_empty());
+            // 比较之后发生变化的目录保持不动
+            fs::write(c.join("extra.mul"), "").unwrap();
+            assert!(replace_with_link(&a, &c).is_err());
+            assert!(c.join("extra.mul").exists());
+
+            // 撤销后是独立的副本，修改不再影响保留的目录
+            copy_back(&b).unwrap();
+            assert!(!is_link(&b));
+            fs::write(b.join("cliloc.enu"), "changed").unwrap();
+            assert_eq!(fs::read_to_string(a.join("cliloc.enu")).unwrap(), "same");
+            assert!(copy_back(&b).is_err());
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// 补丁包中描述改动的文件
+const PATCH_MANIFEST: &str = "patch.json";
+
+/// 更新清单中的一个补丁：把 `from` 版本升级到 `to` 版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchStep {
+    pub from: String,
+    pub to: String,
+    pub url: String,
+    pub sha256: Option<String>,
+    /// minisign 签名（.minisig 文件内容）
+    pub signature: Option<String>,
+}
+
+/// 找到从 `from` 到 `to` 的最短补丁链，没有时返回 None
+pub fn find_chain<'a>(patches: &'a [PatchStep], from: &str, to: &str) -> Option<Vec<&'a PatchStep>> {
+    if from == to {
+        return None;
+    }
+    // 广度优先，记录到达每个版本所用的补丁
+    let mut came_by: HashMap<&str, &PatchStep> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(version) = queue.pop_front() {
+        for step in patches.iter().filter(|p| p.from == version) {
+            if step.to == from || came_by.contains_key(step.to.as_str()) {
+                continue;
+            }
+            came_by.insert(&step.to, step);
+            if step.to == to {
+                let mut chain = vec![step];
+                while chain[0].from != from {
+                    chain.insert(0, came_by[chain[0].from.as_str()]);
+                }
+                return Some(chain);
+            }
+            queue.push_back(&step.to);
+        }
+    }
+    None
+}
+
+/// 补丁包 patch.json 的内容
+#[derive(Debug, Deserialize)]
+struct PatchManifest {
+    files: Vec<PatchFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PatchFile {
+    /// 相对于安装目录的路径
+    path: String,
+    action: PatchAction,
+    /// 应用后的 SHA-256，用于确认结果正确
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PatchAction {
+    /// 补丁包中的 `files/<path>` 整体替换（或新增）
+    Replace,
+    /// 以现有文件为参考解压补丁包中的 `diffs/<path>.zst`（`zstd --patch-from` 生成）
+    Patch,
+    Delete,
+}
+
+/// 把一个补丁包应用到安装目录，返回改动的文件数
+///
+/// 先在内存中生成所有新文件并校验，全部成功后才写入磁盘，
+/// 避免补丁不匹配时留下一半新一半旧的安装。
+pub fn apply_patch_archive(archive_path: &Path, install_dir: &Path) -> Result<usize> {
+    let file = fs::File::open(archive_path)?;
+    let mut archive = zip::ZipArchive::new(file).context("无法打开补丁包")?;
+    let manifest: PatchManifest = serde_json::from_slice(&read_entry(&mut archive, PATCH_MANIFEST)?)
+        .context("补丁包中的 patch.json 无效")?;
+
+    let mut writes: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+    for entry in &manifest.files {
+        let relative = safe_relative_path(&entry.path)?;
+        let target = install_dir.join(&relative);
+        let content = match entry.action {
+            PatchAction::Delete => {
+                writes.push((target, None));
+                continue;
+            }
+            PatchAction::Replace => read_entry(&mut archive, &format!("files/{}", entry.path))?,
+            PatchAction::Patch => {
+                let old = fs::read(&target).with_context(|| format!("找不到需要打补丁的文件 {}", entry.path))?;
+                let diff = read_entry(&mut archive, &format!("diffs/{}.zst", entry.path))?;
+                apply_zstd_patch(&old, &diff).with_context(|| format!("无法应用 {} 的补丁", entry.path))?
+            }
+        };
+        if let Some(expected) = &entry.sha256 {
+            let expected = crate::checksum::normalize_hash(expected).context("无效的 SHA-256 校验值")?;
+            let actual = crate::checksum::sha256_bytes(&content);
+            if actual != expected {
+                anyhow::bail!("补丁结果校验失败: {}（期望 {}，实际 {}）", entry.path, expected, actual);
+            }
+        }
+        writes.push((target, Some(content)));
+    }
+
+    let changed = writes.len();
+    for (target, content) in writes {
+        match content {
+            Some(content) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, content).with_context(|| format!("无法写入 {}", target.display()))?;
+            }
+            None => {
+                if target.exists() {
+                    fs::remove_file(&target).with_context(|| format!("无法删除 {}", target.display()))?;
+                }
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// 解压 `zstd --patch-from=<old>` 生成的补丁
+fn apply_zstd_patch(old: &[u8], patch: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = zstd::stream::read::Decoder::with_ref_prefix(patch, old)?;
+    // --patch-from 会按旧文件大小放大窗口，这里允许到 2GB
+    decoder.window_log_max(31)?;
+    let mut new = Vec::new();
+    decoder.read_to_end(&mut new)?;
+    Ok(new)
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<Vec<u8>> {
+    let mut entry = archive.by_name(name).with_context(|| format!("补丁包中缺少 {}", name))?;
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// 拒绝绝对路径和 `..`，避免补丁写到安装目录之外
+fn safe_relative_path(path: &str) -> Result<PathBuf> {
+    let relative = PathBuf::from(path);
+    let safe = !path.is_empty()
+        && relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !safe {
+        anyhow::bail!("补丁包中的路径无效: {}", path);
+    }
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+    use std::io::Write;
+
+    fn step(from: &str, to: &str) -> PatchStep {
+        PatchStep {
+            from: from.to_string(),
+            to: to.to_string(),
+            url: format!("https://cdn/{from}-{to}.zip"),
+            sha256: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_find_chain() {
+        let patches = [step("1.0", "1.1"), step("1.1", "1.2"), step("1.2", "1.3"), step("1.1", "1.3"), step("0.9", "1.0")];
+        let chain = find_chain(&patches, "1.0", "1.3").unwrap();
+        let hops: Vec<_> = chain.iter().map(|s| (s.from.as_str(), s.to.as_str())).collect();
+        assert_eq!(hops, [("1.0", "1.1"), ("1.1", "1.3")]);
+
+        assert!(find_chain(&patches, "0.8", "1.3").is_none());
+        assert!(find_chain(&patches, "1.3", "1.3").is_none());
+    }
+
+    #[test]
+    fn test_apply_patch_archive() {
+        let root = temp_dir("delta");
+        let install = root.join("OpenUO");
+        fs::create_dir_all(install.join("Data")).unwrap();
+        let old_dll = b"OpenUO.dll version 1 with lots of shared content".to_vec();
+        let new_dll = b"OpenUO.dll version 2 with lots of shared content!".to_vec();
+        fs::write(install.join("OpenUO.dll"), &old_dll).unwrap();
+        fs::write(install.join("Data").join("old.txt"), b"remove me").unwrap();
+
+        let mut encoder = zstd::stream::write::Encoder::with_ref_prefix(Vec::new(), 19, &old_dll).unwrap();
+        encoder.write_all(&new_dll).unwrap();
+        let diff = encoder.finish().unwrap();
+        let manifest = format!(
+            r#"{{"files": [
+                {{"path": "OpenUO.dll", "action": "patch", "sha256": "{}"}},
+                {{"path": "Data/new.txt", "action": "replace"}},
+                {{"path": "Data/old.txt", "action": "delete"}}
+            ]}}"#,
+            crate::checksum::sha256_bytes(&new_dll)
+        );
+
+        let archive_path = root.join("patch.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = zip::write::FileOptions::default();
+        for (name, content) in [
+            ("patch.json", manifest.as_bytes()),
+            ("diffs/OpenUO.dll.zst", diff.as_slice()),
+            ("files/Data/new.txt", b"hello".as_slice()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+
+        assert_eq!(apply_patch_archive(&archive_path, &install).unwrap(), 3);
+        assert_eq!(fs::read(install.join("OpenUO.dll")).unwrap(), new_dll);
+        assert_eq!(fs::read(install.join("Data").join("new.txt")).unwrap(), b"hello");
+        assert!(!install.join("Data").join("old.txt").exists());
+
+        // 再次应用时旧文件已不匹配，校验失败且不写入任何文件
+        assert!(apply_patch_archive(&archive_path, &install).is_err());
+        assert_eq!(fs::read(install.join("Data").join("new.txt")).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_rejects_unsafe_paths() {
+        assert!(safe_relative_path("Data/map0.mul").is_ok());
+        assert!(safe_relative_path("../evil.dll").is_err());
+        assert!(safe_relative_path("/etc/passwd").is_err());
+        assert!(safe_relative_path("").is_err());
+    }
+}
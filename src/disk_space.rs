@@ -0,0 +1,163 @@
+use anyhow::Result;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 检查空间时额外保留的余量，避免把磁盘写满
+const MARGIN: u64 = 64 * 1024 * 1024;
+/// 不知道解压后的大小时，按压缩包大小的倍数估算
+pub const UNPACK_FACTOR: u64 = 3;
+
+/// 磁盘空间不足，下载或安装不会开始
+///
+/// 与网络错误不同，换一个更新源也无济于事。
+#[derive(Debug)]
+pub struct InsufficientSpace {
+    pub path: PathBuf,
+    pub required: u64,
+    pub available: u64,
+}
+
+impl fmt::Display for InsufficientSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "磁盘空间不足: {} 需要 {}，可用 {}，请清理磁盘后重试",
+            self.path.display(),
+            format_mb(self.required),
+            format_mb(self.available)
+        )
+    }
+}
+
+impl std::error::Error for InsufficientSpace {}
+
+fn format_mb(bytes: u64) -> String {
+    format!("{:.0} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// 检查各目录所在磁盘的剩余空间，同一磁盘上的需求合并计算
+///
+/// 无法获取剩余空间时不阻止下载，由后续的写入报告错误。
+pub fn ensure(needs: &[(&Path, u64)]) -> Result<()> {
+    match find_shortfall(needs, volume_id, crate::system_info::free_disk_space) {
+        Some(shortfall) => Err(shortfall.into()),
+        None => Ok(()),
+    }
+}
+
+fn find_shortfall(
+    needs: &[(&Path, u64)],
+    volume: impl Fn(&Path) -> Option<String>,
+    free: impl Fn(&Path) -> Option<u64>,
+) -> Option<InsufficientSpace> {
+    let mut volumes: Vec<(Option<String>, &Path, u64)> = Vec::new();
+    for &(path, bytes) in needs {
+        let id = volume(path);
+        match volumes.iter_mut().find(|(v, _, _)| id.is_some() && *v == id) {
+            Some((_, _, total)) => *total += bytes,
+            None => volumes.push((id, path, bytes)),
+        }
+    }
+    volumes.into_iter().find_map(|(_, path, bytes)| below(path, bytes + MARGIN, free(path)?))
+}
+
+fn below(path: &Path, required: u64, available: u64) -> Option<InsufficientSpace> {
+    (available < required).then(|| InsufficientSpace {
+        path: path.to_path_buf(),
+        required,
+        available,
+    })
+}
+
+/// 启动前检查：路径所在磁盘的剩余空间低于 `minimum` 时返回不足的情况
+///
+/// `minimum` 本身就是给缓存和日志预留的空间，不再加余量；无法获取剩余空间时不阻止启动。
+pub fn check_free(path: &Path, minimum: u64) -> Option<InsufficientSpace> {
+    below(path, minimum, crate::system_info::free_disk_space(path)?)
+}
+
+/// 路径所在的卷，路径尚不存在时使用最近的已存在的上级目录
+#[cfg(unix)]
+fn volume_id(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    Some(fs::metadata(existing).ok()?.dev().to_string())
+}
+
+#[cfg(windows)]
+fn volume_id(path: &Path) -> Option<String> {
+    let absolute = std::path::absolute(path).ok()?;
+    match absolute.components().next()? {
+        std::path::Component::Prefix(prefix) => Some(prefix.as_os_str().to_string_lossy().to_lowercase()),
+        _ => None,
+    }
+}
+
+/// 目录中所有文件的总大小，目录不存在时为 0
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_same_volume_needs_are_added() {
+        let (tmp, install) = (Path::new("/tmp"), Path::new("/home/me/OpenUO"));
+        let needs = [(tmp, 600 * MB), (install, 1200 * MB)];
+
+        // 同一磁盘：需要 1800 MB 加余量
+        let same = |_: &Path| Some("disk".to_string());
+        let shortfall = find_shortfall(&needs, same, |_| Some(1500 * MB)).unwrap();
+        assert_eq!(shortfall.required, 1800 * MB + MARGIN);
+        assert!(find_shortfall(&needs, same, |_| Some(2000 * MB)).is_none());
+
+        // 不同磁盘：分别检查
+        let split = |p: &Path| Some(p.to_string_lossy().into_owned());
+        assert!(find_shortfall(&needs, split, |_| Some(1500 * MB)).is_none());
+        let shortfall = find_shortfall(&needs, split, |p| Some(if p == tmp { 2000 * MB } else { 1000 * MB })).unwrap();
+        assert_eq!(shortfall.path, install);
+    }
+
+    #[test]
+    fn test_check_free_has_no_margin() {
+        let path = Path::new("/tmp");
+        assert!(below(path, 500 * MB, 500 * MB).is_none());
+        let shortfall = below(path, 500 * MB, 499 * MB).unwrap();
+        assert_eq!((shortfall.required, shortfall.available), (500 * MB, 499 * MB));
+        assert!(check_free(path, 0).is_none());
+    }
+
+    #[test]
+    fn test_unknown_free_space_does_not_block() {
+        let needs = [(Path::new("/tmp"), 600 * MB)];
+        assert!(find_shortfall(&needs, |_| None, |_| None).is_none());
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = temp_dir("dirsize");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a"), b"abcd").unwrap();
+        fs::write(dir.join("sub").join("b"), b"xy").unwrap();
+        assert_eq!(dir_size(&dir), 6);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(dir_size(&dir), 0);
+    }
+}
@@ -0,0 +1,348 @@
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, OnceLock};
+use tokio::runtime::Runtime;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// 下载过程中发给 UI 的事件
+#[derive(Debug)]
+pub enum DownloadEvent {
+    Progress { received: u64, total: u64 },
+    /// 当前更新源失败，改用下一个镜像
+    Fallback { failed: String, next: String, error: String },
+    /// 增量补丁失败，改为下载完整安装包
+    DeltaFailed(String),
+    /// 临时的网络错误，稍后自动重试（`attempt` 为即将进行的第几次）
+    Retrying { attempt: u32, max_attempts: u32, error: String },
+    /// 用户取消，已下载的临时文件已清理
+    Cancelled,
+    Finished(Result<DownloadOutcome, String>),
+}
+
+/// 下载任务完成后的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadOutcome {
+    /// OpenUO 已安装，附带版本号
+    OpenUO(String),
+    /// Launcher 已替换并启动了新版本，当前进程应退出
+    LauncherUpdated(String),
+    /// 游戏数据包已解压到 UO 目录，附带版本号
+    GameData(String),
+    /// 附加包已解压到 UO 目录，附带名称和版本号
+    Addon { name: String, version: String },
+    /// 已按文件清单校验 OpenUO，附带版本号和重新下载的文件
+    Verified { version: String, repaired: Vec<String> },
+    /// 指定版本已并存安装到 OpenUO/<tag>，附带标签和版本号
+    SideBySide { tag: String, version: String },
+}
+
+/// 用户取消下载时返回的错误，不作为失败报告
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("下载已取消")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// 任务所处的阶段，见 `DownloadContext::uninterruptible` 和 `DownloadTask::stop_for_exit`
+const PHASE_RUNNING: u8 = 0;
+const PHASE_FINISHING: u8 = 1;
+const PHASE_STOPPED: u8 = 2;
+
+/// 所有下载共用的运行时，第一次下载时创建；创建失败时下次下载再试
+fn runtime() -> Result<&'static Runtime> {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("openuo-download")
+        .enable_all()
+        .build()
+        .context("无法创建下载运行时")?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// 下载任务内部使用：发送事件、检查是否已取消或暂停
+#[derive(Clone)]
+pub struct DownloadContext {
+    tx: mpsc::Sender<DownloadEvent>,
+    cancel: CancellationToken,
+    paused: watch::Receiver<bool>,
+    phase: Arc<AtomicU8>,
+}
+
+impl DownloadContext {
+    pub fn emit(&self, event: DownloadEvent) {
+        let _ = self.tx.send(event);
+    }
+
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+
+    /// 取消时立即返回，不等待 `fut` 完成
+    pub async fn cancellable<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::select! {
+            _ = self.cancel.cancelled() => Err(Cancelled.into()),
+            result = fut => result,
+        }
+    }
+
+    /// 暂停时返回，用于在读取数据的同时监听暂停
+    pub async fn paused(&self) {
+        let mut paused = self.paused.clone();
+        // 发送端随任务一起存在，出错时视为永不暂停
+        if paused.wait_for(|p| *p).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// 暂停期间等待继续，取消时返回错误
+    pub async fn wait_while_paused(&self) -> Result<()> {
+        let mut paused = self.paused.clone();
+        self.cancellable(async {
+            let _ = paused.wait_for(|p| !*p).await;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 在阻塞线程池中执行（请求元数据、校验、解压等），可被取消
+    ///
+    /// 取消只是不再等待结果，已经开始的阻塞操作会在后台执行完，
+    /// 因此不可中断的步骤（替换文件）应通过 `uninterruptible` 调用。
+    pub async fn blocking<T: Send + 'static>(&self, f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+        self.cancellable(async {
+            tokio::task::spawn_blocking(f).await?
+        })
+        .await
+    }
+
+    /// 在阻塞线程池中执行不可中断的步骤（解压、清空安装目录、替换可执行文件），不响应取消
+    ///
+    /// 执行期间 `DownloadTask::is_finishing` 为真，关闭窗口时等待它完成；
+    /// Launcher 已经在退出时不再开始，返回 `Cancelled`。
+    pub async fn uninterruptible<T: Send + 'static>(&self, f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+        if self
+            .phase
+            .compare_exchange(PHASE_RUNNING, PHASE_FINISHING, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(Cancelled.into());
+        }
+        let result = tokio::task::spawn_blocking(f).await;
+        self.phase.store(PHASE_RUNNING, Ordering::SeqCst);
+        result?
+    }
+}
+
+/// 正在进行的下载任务，UI 每帧读取事件
+pub struct DownloadTask {
+    rx: mpsc::Receiver<DownloadEvent>,
+    cancel: CancellationToken,
+    paused: watch::Sender<bool>,
+    phase: Arc<AtomicU8>,
+}
+
+impl DownloadTask {
+    pub fn events(&self) -> Vec<DownloadEvent> {
+        self.rx.try_iter().collect()
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// 暂停后断开连接，继续时从断点重新请求
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// 正在执行不可中断的步骤
+    pub fn is_finishing(&self) -> bool {
+        self.phase.load(Ordering::SeqCst) == PHASE_FINISHING
+    }
+
+    /// Launcher 退出前调用：暂停下载，之后不再开始不可中断的步骤
+    ///
+    /// 正在执行不可中断的步骤时返回 false，这时不能退出。
+    pub fn stop_for_exit(&self) -> bool {
+        match self
+            .phase
+            .compare_exchange(PHASE_RUNNING, PHASE_STOPPED, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            Ok(_) | Err(PHASE_STOPPED) => {
+                self.set_paused(true);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// 在下载运行时中启动任务，结束时一定会发送 `Finished` 或 `Cancelled`
+///
+/// 无法创建运行时时返回错误，任务不会启动。
+pub fn spawn<F, Fut>(job: F) -> Result<DownloadTask>
+where
+    F: FnOnce(DownloadContext) -> Fut,
+    Fut: Future<Output = Result<DownloadOutcome>> + Send + 'static,
+{
+    let runtime = runtime()?;
+    let (tx, rx) = mpsc::channel();
+    let cancel = CancellationToken::new();
+    let (paused, paused_rx) = watch::channel(false);
+    let phase = Arc::new(AtomicU8::new(PHASE_RUNNING));
+    let ctx = DownloadContext {
+        tx: tx.clone(),
+        cancel: cancel.clone(),
+        paused: paused_rx,
+        phase: phase.clone(),
+    };
+    let fut = job(ctx);
+    runtime.spawn(async move {
+        let event = match fut.await {
+            Ok(outcome) => DownloadEvent::Finished(Ok(outcome)),
+            Err(e) if e.is::<Cancelled>() => DownloadEvent::Cancelled,
+            Err(e) => DownloadEvent::Finished(Err(format!("{e:#}"))),
+        };
+        let _ = tx.send(event);
+    });
+    Ok(DownloadTask { rx, cancel, paused, phase })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn wait_finished(task: &DownloadTask) -> DownloadEvent {
+        loop {
+            match task.rx.recv_timeout(Duration::from_secs(5)).expect("任务没有结束") {
+                DownloadEvent::Progress { .. } => continue,
+                event => return event,
+            }
+        }
+    }
+
+    #[test]
+    fn test_finished() {
+        let task = spawn(|ctx| async move {
+            ctx.emit(DownloadEvent::Progress { received: 1, total: 2 });
+            Ok(DownloadOutcome::OpenUO("1.0".to_string()))
+        })
+        .unwrap();
+        assert!(matches!(
+            wait_finished(&task),
+            DownloadEvent::Finished(Ok(DownloadOutcome::OpenUO(v))) if v == "1.0"
+        ));
+    }
+
+    #[test]
+    fn test_cancel_interrupts_blocking_step() {
+        let (started_tx, started_rx) = mpsc::channel();
+        let task = spawn(|ctx| async move {
+            ctx.blocking(move || {
+                let _ = started_tx.send(());
+                std::thread::sleep(Duration::from_secs(10));
+                Ok(())
+            })
+            .await?;
+            Ok(DownloadOutcome::OpenUO("never".to_string()))
+        })
+        .unwrap();
+        started_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        task.cancel();
+        assert!(task.is_cancelling());
+        assert!(matches!(wait_finished(&task), DownloadEvent::Cancelled));
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let (reached_tx, reached_rx) = mpsc::channel();
+        let task = spawn(|ctx| async move {
+            ctx.paused().await;
+            let _ = reached_tx.send(());
+            ctx.wait_while_paused().await?;
+            Ok(DownloadOutcome::OpenUO("resumed".to_string()))
+        })
+        .unwrap();
+        task.set_paused(true);
+        assert!(task.is_paused());
+        reached_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(task.rx.recv_timeout(Duration::from_millis(100)).is_err());
+        task.set_paused(false);
+        assert!(matches!(
+            wait_finished(&task),
+            DownloadEvent::Finished(Ok(DownloadOutcome::OpenUO(v))) if v == "resumed"
+        ));
+    }
+
+    #[test]
+    fn test_cancel_while_paused() {
+        let task = spawn(|ctx| async move {
+            ctx.paused().await;
+            ctx.wait_while_paused().await?;
+            Ok(DownloadOutcome::OpenUO("never".to_string()))
+        })
+        .unwrap();
+        task.set_paused(true);
+        task.cancel();
+        assert!(matches!(wait_finished(&task), DownloadEvent::Cancelled));
+    }
+
+    #[test]
+    fn test_exit_waits_for_uninterruptible_step() {
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let task = spawn(|ctx| async move {
+            ctx.uninterruptible(move || {
+                let _ = started_tx.send(());
+                let _ = release_rx.recv_timeout(Duration::from_secs(5));
+                Ok(())
+            })
+            .await?;
+            // 退出后不再开始新的不可中断步骤
+            ctx.paused().await;
+            ctx.uninterruptible(|| Ok(())).await?;
+            Ok(DownloadOutcome::OpenUO("never".to_string()))
+        })
+        .unwrap();
+        started_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(task.is_finishing());
+        assert!(!task.stop_for_exit());
+        release_tx.send(()).unwrap();
+        while task.is_finishing() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(task.stop_for_exit());
+        assert!(task.is_paused());
+        assert!(matches!(wait_finished(&task), DownloadEvent::Cancelled));
+    }
+
+    #[test]
+    fn test_error_is_reported() {
+        let task = spawn(|_| async { anyhow::bail!("boom") }).unwrap();
+        assert!(matches!(wait_finished(&task), DownloadEvent::Finished(Err(e)) if e == "boom"));
+    }
+}
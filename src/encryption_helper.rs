@@ -61,6 +61,7 @@ mod tests {
         assert_eq!(suggest_encryption_from_version("6.0.0"), 1);   // 加密
         assert_eq!(suggest_encryption_from_version("1.26.0"), 1);  // 加密
         assert_eq!(suggest_encryption_from_version("1.25.0"), 0);  // 不加密
-        assert_eq!(suggest_encryption_from_version("5.0.0"), 0);   // 不加密
+        assert_eq!(suggest_encryption_from_version("5.0.0"), 1);   // 加密
+        assert_eq!(suggest_encryption_from_version("0.9.0"), 0);   // 不加密
     }
 }
@@ -0,0 +1,170 @@
+use std::io;
+use std::path::Path;
+
+/// 需要给出详细说明的常见失败类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// 找不到可用的显卡适配器（Launcher 本身无法显示）
+    NoGpuAdapter,
+    /// OpenUO 可执行文件不存在
+    OpenUOMissing,
+    /// 解压或写入时没有权限
+    PermissionDenied,
+    /// 更新源返回 404
+    UpdateNotFound,
+}
+
+/// 对话框中提供的一键操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remedy {
+    /// 重新下载 OpenUO
+    Redownload,
+    /// 重试 Launcher 更新
+    RetryLauncherUpdate,
+    /// 选择其他 OpenUO 安装目录并重新下载
+    ChangeInstallDir,
+    /// 打开 OpenUO 安装目录
+    OpenInstallDir,
+    /// 打开 Launcher 所在目录（update_source.json、launcher.log）
+    OpenLauncherDir,
+}
+
+impl FailureKind {
+    pub fn title_key(self) -> &'static str {
+        match self {
+            FailureKind::NoGpuAdapter => "error_help.no_gpu_title",
+            FailureKind::OpenUOMissing => "error_help.openuo_missing_title",
+            FailureKind::PermissionDenied => "error_help.permission_title",
+            FailureKind::UpdateNotFound => "error_help.not_found_title",
+        }
+    }
+
+    pub fn explanation_key(self) -> &'static str {
+        match self {
+            FailureKind::NoGpuAdapter => "error_help.no_gpu_explanation",
+            FailureKind::OpenUOMissing => "error_help.openuo_missing_explanation",
+            FailureKind::PermissionDenied => "error_help.permission_explanation",
+            FailureKind::UpdateNotFound => "error_help.not_found_explanation",
+        }
+    }
+
+    pub fn cause_key(self) -> &'static str {
+        match self {
+            FailureKind::NoGpuAdapter => "error_help.no_gpu_cause",
+            FailureKind::OpenUOMissing => "error_help.openuo_missing_cause",
+            FailureKind::PermissionDenied => "error_help.permission_cause",
+            FailureKind::UpdateNotFound => "error_help.not_found_cause",
+        }
+    }
+
+    /// 可用的一键操作，`launcher_update` 表示失败发生在更新 Launcher 时
+    pub fn remedies(self, launcher_update: bool) -> Vec<Remedy> {
+        match (self, launcher_update) {
+            (FailureKind::NoGpuAdapter, _) => vec![],
+            (FailureKind::OpenUOMissing, _) => vec![Remedy::Redownload, Remedy::OpenInstallDir],
+            (FailureKind::PermissionDenied, false) => vec![Remedy::ChangeInstallDir, Remedy::OpenInstallDir],
+            (FailureKind::PermissionDenied, true) => vec![Remedy::RetryLauncherUpdate, Remedy::OpenLauncherDir],
+            (FailureKind::UpdateNotFound, false) => vec![Remedy::Redownload, Remedy::OpenLauncherDir],
+            (FailureKind::UpdateNotFound, true) => vec![Remedy::RetryLauncherUpdate, Remedy::OpenLauncherDir],
+        }
+    }
+}
+
+impl Remedy {
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Remedy::Redownload => "error_help.redownload",
+            Remedy::RetryLauncherUpdate => "error_help.retry_launcher_update",
+            Remedy::ChangeInstallDir => "error_help.change_install_dir",
+            Remedy::OpenInstallDir => "error_help.open_install_dir",
+            Remedy::OpenLauncherDir => "error_help.open_launcher_dir",
+        }
+    }
+}
+
+/// 等待用户处理的错误
+#[derive(Debug, Clone)]
+pub struct ErrorReport {
+    pub kind: FailureKind,
+    /// 原始错误信息
+    pub detail: String,
+    pub launcher_update: bool,
+}
+
+/// 根据错误链中的具体错误类型判断
+pub fn classify(err: &anyhow::Error) -> Option<FailureKind> {
+    for cause in err.chain() {
+        if let Some(io_err) = cause.downcast_ref::<io::Error>()
+            && io_err.kind() == io::ErrorKind::PermissionDenied
+        {
+            return Some(FailureKind::PermissionDenied);
+        }
+        if let Some(http_err) = cause.downcast_ref::<reqwest::Error>()
+            && http_err.status() == Some(reqwest::StatusCode::NOT_FOUND)
+        {
+            return Some(FailureKind::UpdateNotFound);
+        }
+    }
+    classify_message(&format!("{err:#}"))
+}
+
+/// 根据错误文本判断（用于通过通道传回 UI 的错误字符串）
+pub fn classify_message(message: &str) -> Option<FailureKind> {
+    let lower = message.to_lowercase();
+    if lower.contains("permission denied")
+        || lower.contains("access is denied")
+        || lower.contains("os error 13")
+        || lower.contains("os error 5)")
+        || lower.contains("拒绝访问")
+    {
+        Some(FailureKind::PermissionDenied)
+    } else if lower.contains("404") && lower.contains("not found") {
+        Some(FailureKind::UpdateNotFound)
+    } else if lower.contains("no compatible gpu adapter") {
+        Some(FailureKind::NoGpuAdapter)
+    } else {
+        None
+    }
+}
+
+/// 在系统文件管理器中打开目录
+pub fn open_folder(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    std::process::Command::new(program).arg(path).spawn().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_message() {
+        assert_eq!(
+            classify_message("Permission denied (os error 13)"),
+            Some(FailureKind::PermissionDenied)
+        );
+        assert_eq!(
+            classify_message("拒绝访问。 (os error 5)"),
+            Some(FailureKind::PermissionDenied)
+        );
+        assert_eq!(
+            classify_message("HTTP status client error (404 Not Found) for url (https://example.com/x.zip)"),
+            Some(FailureKind::UpdateNotFound)
+        );
+        assert_eq!(classify_message("operation timed out"), None);
+        assert_eq!(classify_message("downloaded 404 bytes"), None);
+    }
+
+    #[test]
+    fn test_classify_error_chain() {
+        let err = anyhow::Error::from(io::Error::from(io::ErrorKind::PermissionDenied)).context("解压失败");
+        assert_eq!(classify(&err), Some(FailureKind::PermissionDenied));
+        assert_eq!(classify(&anyhow::anyhow!("something else")), None);
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use std::sync::mpsc;
+
+use crate::download::{DownloadEvent, DownloadTask};
+use crate::github::UpdateEvent;
+
+/// 后台任务发给 UI 的事件
+#[derive(Debug)]
+pub enum PumpEvent {
+    Download(DownloadEvent),
+    Update(UpdateEvent),
+}
+
+/// 持有正在进行的下载任务和更新检查，UI 每帧从这里统一取出事件
+///
+/// 任务结束（`Finished` / `Cancelled` / `Done`）或通道断开后自动释放，
+/// UI 只需要处理事件本身，不再各自管理通道的生命周期。
+#[derive(Default)]
+pub struct EventPump {
+    download: Option<DownloadTask>,
+    update_rx: Option<mpsc::Receiver<UpdateEvent>>,
+}
+
+impl EventPump {
+    /// 已有下载任务时不启动新的，返回 false；任务无法启动时返回错误
+    pub fn start_download(&mut self, task: impl FnOnce() -> Result<DownloadTask>) -> Result<bool> {
+        if self.download.is_some() {
+            return Ok(false);
+        }
+        self.download = Some(task()?);
+        Ok(true)
+    }
+
+    pub fn download(&self) -> Option<&DownloadTask> {
+        self.download.as_ref()
+    }
+
+    pub fn is_downloading(&self) -> bool {
+        self.download.is_some()
+    }
+
+    /// 新的检查替换尚未完成的检查
+    pub fn start_update_check(&mut self, rx: mpsc::Receiver<UpdateEvent>) {
+        self.update_rx = Some(rx);
+    }
+
+    /// 取出目前收到的所有事件，下载事件在前
+    pub fn poll(&mut self) -> Vec<PumpEvent> {
+        let mut events = Vec::new();
+        if let Some(task) = &self.download {
+            for event in task.events() {
+                let finished = matches!(event, DownloadEvent::Finished(_) | DownloadEvent::Cancelled);
+                events.push(PumpEvent::Download(event));
+                if finished {
+                    self.download = None;
+                    break;
+                }
+            }
+        }
+        if let Some(rx) = &self.update_rx {
+            loop {
+                match rx.try_recv() {
+                    Ok(event) => {
+                        let done = matches!(event, UpdateEvent::Done);
+                        events.push(PumpEvent::Update(event));
+                        if done {
+                            self.update_rx = None;
+                            break;
+                        }
+                    }
+                    Err(mpsc::TryRecvError::Empty) => break,
+                    // 检查线程异常退出时不会发送 Done
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        self.update_rx = None;
+                        break;
+                    }
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::{self, DownloadOutcome};
+    use std::time::{Duration, Instant};
+
+    /// 下载任务在后台运行，轮询直到满足条件
+    fn poll_until(pump: &mut EventPump, done: impl Fn(&EventPump) -> bool) -> Vec<PumpEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while !done(pump) {
+            assert!(Instant::now() < deadline, "任务没有结束");
+            events.extend(pump.poll());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        events
+    }
+
+    #[test]
+    fn test_download_released_when_finished() {
+        let mut pump = EventPump::default();
+        assert!(pump.start_download(|| {
+            download::spawn(|ctx| async move {
+                ctx.emit(DownloadEvent::Progress { received: 1, total: 2 });
+                Ok(DownloadOutcome::OpenUO("1.0".to_string()))
+            })
+        })
+        .unwrap());
+        // 进行中时不会启动第二个任务
+        assert!(!pump.start_download(|| unreachable!()).unwrap());
+
+        let events = poll_until(&mut pump, |p| !p.is_downloading());
+        assert!(matches!(events[0], PumpEvent::Download(DownloadEvent::Progress { received: 1, total: 2 })));
+        assert!(matches!(
+            events.last(),
+            Some(PumpEvent::Download(DownloadEvent::Finished(Ok(DownloadOutcome::OpenUO(v))))) if v == "1.0"
+        ));
+        assert!(pump.poll().is_empty());
+    }
+
+    #[test]
+    fn test_update_check_released_when_done_or_disconnected() {
+        let mut pump = EventPump::default();
+        let (tx, rx) = mpsc::channel();
+        pump.start_update_check(rx);
+        tx.send(UpdateEvent::OpenUO(Ok("1.0".to_string()))).unwrap();
+        assert_eq!(pump.poll().len(), 1);
+
+        tx.send(UpdateEvent::Done).unwrap();
+        // Done 之后的事件属于已结束的检查，不再转发
+        tx.send(UpdateEvent::Launcher(Ok("2.0".to_string()))).unwrap();
+        let events = pump.poll();
+        assert!(matches!(events[..], [PumpEvent::Update(UpdateEvent::Done)]));
+        assert!(pump.update_rx.is_none());
+
+        let (tx, rx) = mpsc::channel::<UpdateEvent>();
+        pump.start_update_check(rx);
+        drop(tx);
+        assert!(pump.poll().is_empty());
+        assert!(pump.update_rx.is_none());
+    }
+}
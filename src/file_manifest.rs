@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 逐文件更新清单：列出安装目录中的每个文件及其校验值
+///
+/// ```json
+/// {
+///   "base_url": "https://cdn.example.com/openuo/v1.2.0/linux-x64/",
+///   "files": [{ "path": "OpenUO.dll", "sha256": "...", "size": 123456 }]
+/// }
+/// ```
+///
+/// `base_url` 省略时使用清单所在的目录，单个文件也可以用 `url` 指定完整地址。
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileManifest {
+    #[serde(default)]
+    pub base_url: Option<String>,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManifestFile {
+    /// 相对于安装目录的路径
+    pub path: String,
+    pub sha256: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// 需要下载的文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingFile {
+    pub relative: PathBuf,
+    pub url: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl FileManifest {
+    pub fn parse(body: &str) -> Result<Self> {
+        let manifest: FileManifest = serde_json::from_str(body).context("逐文件更新清单格式无效")?;
+        for file in &manifest.files {
+            crate::checksum::normalize_hash(&file.sha256)
+                .with_context(|| format!("{} 的 SHA-256 校验值无效", file.path))?;
+        }
+        Ok(manifest)
+    }
+
+    /// 与安装目录比较，返回缺失或内容不同的文件；清单中没有的本地文件保留不动
+    pub fn changed_files(&self, manifest_url: &str, install_dir: &Path) -> Result<Vec<PendingFile>> {
+        let base = self.base_url(manifest_url)?;
+        let mut pending = Vec::new();
+        for file in &self.files {
+            let relative = crate::archive::safe_relative_path(&file.path)
+                .with_context(|| format!("逐文件更新清单中的路径无效: {}", file.path))?;
+            let sha256 = crate::checksum::normalize_hash(&file.sha256).unwrap_or_default();
+            if is_up_to_date(&install_dir.join(&relative), &sha256, file.size) {
+                continue;
+            }
+            let url = match &file.url {
+                Some(url) => url.clone(),
+                None => base.join(&file.path.replace('\\', "/"))?.to_string(),
+            };
+            pending.push(PendingFile { relative, url, sha256, size: file.size });
+        }
+        Ok(pending)
+    }
+
+    fn base_url(&self, manifest_url: &str) -> Result<reqwest::Url> {
+        let manifest_url = reqwest::Url::parse(manifest_url).context("逐文件更新清单的地址无效")?;
+        match &self.base_url {
+            // 没有结尾的 / 时 join 会替换最后一段
+            Some(base) if base.ends_with('/') => Ok(manifest_url.join(base)?),
+            Some(base) => Ok(manifest_url.join(&format!("{base}/"))?),
+            None => Ok(manifest_url),
+        }
+    }
+}
+
+/// 大小不同时不必计算校验值
+fn is_up_to_date(path: &Path, sha256: &str, size: u64) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    if size > 0 && meta.len() != size {
+        return false;
+    }
+    crate::checksum::sha256_file(path).is_ok_and(|actual| actual == sha256)
+}
+
+/// 把已下载并校验过的文件从暂存目录移到安装目录
+pub fn install_staged(staging: &Path, install_dir: &Path, files: &[PendingFile]) -> Result<()> {
+    for file in files {
+        let src = staging.join(&file.relative);
+        let dest = install_dir.join(&file.relative);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // 暂存目录可能在另一个磁盘上，无法直接改名时复制
+        if fs::rename(&src, &dest).is_err() {
+            fs::copy(&src, &dest).with_context(|| format!("无法写入 {}", dest.display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    fn manifest(base_url: Option<&str>) -> FileManifest {
+        let body = serde_json::json!({
+            "base_url": base_url,
+            "files": [
+                { "path": "OpenUO.dll", "sha256": HELLO_SHA256, "size": 5 },
+                { "path": "Data\\art.mul", "sha256": HELLO_SHA256.to_uppercase() },
+                { "path": "new.txt", "sha256": HELLO_SHA256, "url": "https://other.example/new.txt" }
+            ]
+        });
+        FileManifest::parse(&body.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let dir = temp_dir("files");
+        fs::create_dir_all(dir.join("Data")).unwrap();
+        fs::write(dir.join("OpenUO.dll"), b"hello").unwrap();
+        fs::write(dir.join("Data").join("art.mul"), b"hellp").unwrap();
+
+        let pending = manifest(None).changed_files("https://cdn.example/openuo/files.json", &dir).unwrap();
+        let urls: Vec<_> = pending.iter().map(|p| p.url.as_str()).collect();
+        assert_eq!(urls, ["https://cdn.example/openuo/Data/art.mul", "https://other.example/new.txt"]);
+        assert_eq!(pending[0].relative, PathBuf::from("Data").join("art.mul"));
+        assert_eq!(pending[0].sha256, HELLO_SHA256);
+    }
+
+    #[test]
+    fn test_base_url() {
+        let url = "https://cdn.example/openuo/files.json";
+        assert_eq!(manifest(Some("v2")).base_url(url).unwrap().as_str(), "https://cdn.example/openuo/v2/");
+        assert_eq!(
+            manifest(Some("https://files.example/v2/")).base_url(url).unwrap().as_str(),
+            "https://files.example/v2/"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unsafe_paths_and_bad_hashes() {
+        let body = serde_json::json!({ "files": [{ "path": "../evil.dll", "sha256": HELLO_SHA256 }] });
+        let manifest = FileManifest::parse(&body.to_string()).unwrap();
+        assert!(manifest.changed_files("https://cdn.example/files.json", Path::new("/nonexistent")).is_err());
+
+        let body = serde_json::json!({ "files": [{ "path": "a.dll", "sha256": "abc" }] });
+        assert!(FileManifest::parse(&body.to_string()).is_err());
+    }
+}
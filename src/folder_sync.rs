@@ -0,0 +1,342 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::config::{base_dir, profiles_dir};
+
+/// 同步目录中存放 Launcher 数据的子目录
+const REMOTE_DIR: &str = "OpenUO Launcher";
+/// 上次同步时每个文件的 SHA-256，用来判断是哪一边修改了文件
+const STATE_FILENAME: &str = ".folder_sync";
+/// 写入完成前的临时文件，同步客户端和下一次同步都不会把它当作内容
+const TMP_SUFFIX: &str = ".sync-tmp";
+/// 档案中只属于这台电脑的字段，不写入同步目录：保存的密码只能用本机的密钥解密，路径和窗口位置因电脑而异
+const LOCAL_ONLY_KEYS: &[&str] = &[
+    "password",
+    "PasswordKeyId",
+    "ultimaonlinedirectory",
+    "profilespath",
+    "window_position",
+    "window_size",
+    "is_win_maximized",
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// 更换同步目录后重新开始
+    folder: PathBuf,
+    /// `<内容>/<相对路径>` -> 写入同步目录的内容的 SHA-256
+    files: BTreeMap<String, String>,
+}
+
+/// 一次同步的结果，路径为 `<内容>/<相对路径>`，例如 `Profiles/Settings/xxx.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// 从同步目录取回的文件
+    pub pulled: Vec<String>,
+    /// 写入同步目录的文件数
+    pub pushed: usize,
+    /// 两边都修改过的文件，保持不动，等待选择保留哪一边
+    pub conflicts: Vec<String>,
+    /// 本机或同步目录中整个不存在、因此跳过的内容
+    pub skipped: Vec<String>,
+}
+
+/// 冲突时保留哪一边
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Local,
+    Synced,
+}
+
+/// 同步的内容：Launcher 的档案和 OpenUO 的游戏内设置（宏、界面位置）
+fn areas() -> Vec<(&'static str, PathBuf)> {
+    vec![("Profiles", profiles_dir()), ("GameProfiles", crate::profile_backup::game_profiles_dir())]
+}
+
+fn is_profile_json(key: &str) -> bool {
+    key.starts_with("Profiles/") && key.ends_with(".json")
+}
+
+/// 写入同步目录的内容：档案去掉只属于本机的字段
+fn synced_view(key: &str, bytes: Vec<u8>) -> Vec<u8> {
+    if !is_profile_json(key) {
+        return bytes;
+    }
+    match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut map)) => {
+            for k in LOCAL_ONLY_KEYS {
+                map.remove(*k);
+            }
+            serde_json::to_vec_pretty(&map).unwrap_or(bytes)
+        }
+        _ => bytes,
+    }
+}
+
+/// 取回到本机的内容：保留本机档案中只属于本机的字段
+fn merge_local(key: &str, synced: Vec<u8>, local: Option<Vec<u8>>) -> Vec<u8> {
+    if !is_profile_json(key) {
+        return synced;
+    }
+    let parsed = (
+        serde_json::from_slice::<serde_json::Value>(&synced),
+        local.map(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes)),
+    );
+    let (Ok(serde_json::Value::Object(mut map)), Some(Ok(serde_json::Value::Object(local)))) = parsed else {
+        return synced;
+    };
+    for k in LOCAL_ONLY_KEYS {
+        if let Some(value) = local.get(*k) {
+            map.insert(k.to_string(), value.clone());
+        }
+    }
+    serde_json::to_vec_pretty(&map).unwrap_or(synced)
+}
+
+fn view_hash(key: &str, path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    Some(crate::checksum::sha256_bytes(&synced_view(key, bytes)))
+}
+
+/// 目录中所有文件的相对路径（统一用 / 分隔）和内容的 SHA-256；目录不存在时为空
+fn list(name: &str, root: &Path, dir: &Path, files: &mut BTreeMap<String, String>) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            list(name, root, &path, files)?;
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.ends_with(TMP_SUFFIX) || file_name.ends_with(".tmp") {
+            continue;
+        }
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let relative = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+        let hash = view_hash(&format!("{name}/{relative}"), &path).with_context(|| format!("无法读取 {}", path.display()))?;
+        files.insert(relative, hash);
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.file_name().context("无效的文件路径")?.to_os_string();
+    tmp.push(TMP_SUFFIX);
+    let tmp = path.with_file_name(tmp);
+    fs::write(&tmp, bytes).with_context(|| format!("无法写入 {}", path.display()))?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(anyhow::Error::from(e).context(format!("无法删除 {}", path.display()))),
+        _ => Ok(()),
+    }
+}
+
+/// 用本机的文件覆盖同步目录中的（本机没有时删除）
+fn push(key: &str, local: &Path, remote: &Path) -> Result<()> {
+    match fs::read(local) {
+        Ok(bytes) => write_file(remote, &synced_view(key, bytes)),
+        Err(_) => remove_file(remote),
+    }
+}
+
+/// 用同步目录中的文件覆盖本机的（同步目录中没有时删除）
+fn pull(key: &str, local: &Path, remote: &Path) -> Result<()> {
+    match fs::read(remote) {
+        Ok(bytes) => write_file(local, &merge_local(key, bytes, fs::read(local).ok())),
+        Err(_) => remove_file(local),
+    }
+}
+
+fn sync_areas(areas: &[(&str, PathBuf)], remote: &Path, state: &mut BTreeMap<String, String>) -> Result<SyncReport> {
+    let mut report = SyncReport::default();
+    for (name, local_root) in areas {
+        let remote_root = remote.join(name);
+        let prefix = format!("{name}/");
+        // OpenUO 还没安装、同步客户端还没下载完等情况下，不能当作删除了全部文件
+        let known = state.keys().any(|key| key.starts_with(&prefix));
+        if known && (!local_root.is_dir() || !remote_root.is_dir()) {
+            report.skipped.push(name.to_string());
+            continue;
+        }
+        let (mut local, mut synced) = (BTreeMap::new(), BTreeMap::new());
+        list(name, local_root, local_root, &mut local)?;
+        list(name, &remote_root, &remote_root, &mut synced)?;
+        let known: Vec<String> = state.keys().filter_map(|key| key.strip_prefix(&prefix)).map(str::to_string).collect();
+        let paths: BTreeSet<String> = local.keys().chain(synced.keys()).cloned().chain(known).collect();
+        for relative in paths {
+            let key = format!("{prefix}{relative}");
+            let (l, r) = (local.get(&relative), synced.get(&relative));
+            let base = state.get(&key);
+            let (local_path, remote_path) = (local_root.join(&relative), remote_root.join(&relative));
+            let result = if l == r {
+                r.cloned()
+            } else if l == base {
+                // 只有同步目录中的修改过（包括删除）
+                pull(&key, &local_path, &remote_path)?;
+                report.pulled.push(key.clone());
+                r.cloned()
+            } else if r == base {
+                push(&key, &local_path, &remote_path)?;
+                report.pushed += 1;
+                l.cloned()
+            } else {
+                report.conflicts.push(key);
+                continue;
+            };
+            match result {
+                Some(hash) => state.insert(key, hash),
+                None => state.remove(&key),
+            };
+        }
+    }
+    Ok(report)
+}
+
+fn resolve_in(areas: &[(&str, PathBuf)], remote: &Path, state: &mut BTreeMap<String, String>, key: &str, keep: Keep) -> Result<()> {
+    let (name, relative) = key.split_once('/').context("无效的同步路径")?;
+    let (_, local_root) = areas.iter().find(|(n, _)| *n == name).context("未知的同步内容")?;
+    let (local_path, remote_path) = (local_root.join(relative), remote.join(name).join(relative));
+    match keep {
+        Keep::Local => push(key, &local_path, &remote_path)?,
+        Keep::Synced => pull(key, &local_path, &remote_path)?,
+    }
+    match view_hash(key, &local_path) {
+        Some(hash) => state.insert(key.to_string(), hash),
+        None => state.remove(key),
+    };
+    Ok(())
+}
+
+fn state_path() -> PathBuf {
+    base_dir().join(STATE_FILENAME)
+}
+
+/// 同步目录对应的状态；更换了同步目录时从空状态开始
+fn load_state(folder: &Path) -> SyncState {
+    fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<SyncState>(&content).ok())
+        .filter(|state| state.folder == folder)
+        .unwrap_or_else(|| SyncState { folder: folder.to_path_buf(), files: BTreeMap::new() })
+}
+
+fn save_state(state: &SyncState) -> Result<()> {
+    write_file(&state_path(), serde_json::to_string_pretty(state)?.as_bytes())
+}
+
+fn remote_dir(folder: &Path) -> Result<PathBuf> {
+    // 网络磁盘或外接磁盘没有连接时，目录不存在，不能当作其他电脑删除了所有文件
+    if !folder.is_dir() {
+        anyhow::bail!("同步目录不可用: {}", folder.display());
+    }
+    let remote = folder.join(REMOTE_DIR);
+    fs::create_dir_all(&remote)?;
+    Ok(remote)
+}
+
+/// 与同步目录双向同步档案和游戏内设置：只有一边修改过的文件复制到另一边，两边都修改过的列为冲突
+pub fn sync(folder: &Path) -> Result<SyncReport> {
+    let remote = remote_dir(folder)?;
+    let mut state = load_state(folder);
+    let report = sync_areas(&areas(), &remote, &mut state.files);
+    // 中途失败时已经复制的文件也要记录
+    save_state(&state)?;
+    report
+}
+
+/// 解决冲突：用保留的一边覆盖另一边
+pub fn resolve(folder: &Path, key: &str, keep: Keep) -> Result<()> {
+    let remote = remote_dir(folder)?;
+    let mut state = load_state(folder);
+    resolve_in(&areas(), &remote, &mut state.files, key, keep)?;
+    save_state(&state)
+}
+
+/// 在后台线程同步
+pub fn spawn(folder: PathBuf) -> mpsc::Receiver<Result<SyncReport, String>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(sync(&folder).map_err(|e| format!("{e:#}")));
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_sync_between_computers() {
+        let root = temp_dir("folder-sync");
+        let remote = root.join("Dropbox").join(REMOTE_DIR);
+        let (desktop, laptop) = (root.join("desktop"), root.join("laptop"));
+        let areas_of = |dir: &Path| vec![("Profiles", dir.join("Profiles")), ("GameProfiles", dir.join("Data"))];
+        let (desktop_areas, laptop_areas) = (areas_of(&desktop), areas_of(&laptop));
+        let (mut desktop_state, mut laptop_state) = (BTreeMap::new(), BTreeMap::new());
+        let settings = |dir: &Path| dir.join("Profiles").join("Settings").join("a.json");
+        let read_json = |path: &Path| serde_json::from_slice::<serde_json::Value>(&fs::read(path).unwrap()).unwrap();
+
+        fs::create_dir_all(settings(&desktop).parent().unwrap()).unwrap();
+        fs::write(settings(&desktop), r#"{"username": "lord", "password": "desktop-key", "fps": 60}"#).unwrap();
+        fs::create_dir_all(desktop.join("Data").join("lord")).unwrap();
+        fs::write(desktop.join("Data").join("lord").join("macros.xml"), "v1").unwrap();
+        let report = sync_areas(&desktop_areas, &remote, &mut desktop_state).unwrap();
+        assert_eq!(report.pushed, 2);
+        // 密码只属于本机
+        assert!(read_json(&remote.join("Profiles").join("Settings").join("a.json")).get("password").is_none());
+
+        fs::create_dir_all(laptop.join("Profiles")).unwrap();
+        fs::create_dir_all(laptop.join("Data")).unwrap();
+        let report = sync_areas(&laptop_areas, &remote, &mut laptop_state).unwrap();
+        assert_eq!(report.pulled, ["Profiles/Settings/a.json", "GameProfiles/lord/macros.xml"]);
+        assert_eq!(fs::read_to_string(laptop.join("Data").join("lord").join("macros.xml")).unwrap(), "v1");
+
+        // 笔记本修改后，台式机取回修改并保留自己的密码
+        let mut json = read_json(&settings(&laptop));
+        json["fps"] = 144.into();
+        json["password"] = "laptop-key".into();
+        fs::write(settings(&laptop), serde_json::to_vec(&json).unwrap()).unwrap();
+        assert_eq!(sync_areas(&laptop_areas, &remote, &mut laptop_state).unwrap().pushed, 1);
+        let report = sync_areas(&desktop_areas, &remote, &mut desktop_state).unwrap();
+        assert_eq!(report.pulled, ["Profiles/Settings/a.json"]);
+        let json = read_json(&settings(&desktop));
+        assert_eq!((json["fps"].as_i64(), json["password"].as_str()), (Some(144), Some("desktop-key")));
+        // 只修改了本机字段时没有需要同步的内容
+        assert_eq!(sync_areas(&desktop_areas, &remote, &mut desktop_state).unwrap(), SyncReport::default());
+
+        // 两边都修改过的文件保持不动，直到选择保留哪一边
+        fs::write(desktop.join("Data").join("lord").join("macros.xml"), "desktop").unwrap();
+        fs::write(laptop.join("Data").join("lord").join("macros.xml"), "laptop").unwrap();
+        sync_areas(&laptop_areas, &remote, &mut laptop_state).unwrap();
+        let report = sync_areas(&desktop_areas, &remote, &mut desktop_state).unwrap();
+        assert_eq!(report.conflicts, ["GameProfiles/lord/macros.xml"]);
+        assert_eq!(fs::read_to_string(desktop.join("Data").join("lord").join("macros.xml")).unwrap(), "desktop");
+        resolve_in(&desktop_areas, &remote, &mut desktop_state, "GameProfiles/lord/macros.xml", Keep::Synced).unwrap();
+        assert_eq!(fs::read_to_string(desktop.join("Data").join("lord").join("macros.xml")).unwrap(), "laptop");
+        assert_eq!(sync_areas(&desktop_areas, &remote, &mut desktop_state).unwrap(), SyncReport::default());
+
+        // 删除会同步到另一边；整个目录不存在时跳过，不当作删除
+        fs::remove_file(settings(&laptop)).unwrap();
+        sync_areas(&laptop_areas, &remote, &mut laptop_state).unwrap();
+        fs::remove_dir_all(desktop.join("Data")).unwrap();
+        let report = sync_areas(&desktop_areas, &remote, &mut desktop_state).unwrap();
+        assert_eq!(report.skipped, ["GameProfiles"]);
+        assert!(!settings(&desktop).exists());
+        assert!(remote.join("GameProfiles").join("lord").join("macros.xml").exists());
+    }
+}
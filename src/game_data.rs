@@ -0,0 +1,102 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+use crate::tls_trust::TlsTrust;
+
+/// 记录已安装的游戏数据包版本，放在 UO 目录中
+pub const DATA_VERSION_FILE: &str = ".openuo_data_version";
+
+/// 更新清单中声明的游戏数据包（UO 的 art、map 等数据文件），已选好当前平台
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataPackage {
+    pub version: String,
+    pub url: String,
+    pub sha256: Option<String>,
+    /// minisign 签名，即 .minisig 文件的内容
+    pub signature: Option<String>,
+    /// 获取更新清单的更新源的证书信任设置，下载时沿用
+    pub tls: TlsTrust,
+}
+
+/// 更新清单中声明的可选附加包，例如被服务器从数据包中去掉的音乐和音效，由玩家选择安装
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Addon {
+    /// 只含字母、数字、- 和 _，用于版本文件名
+    pub id: String,
+    pub name: String,
+    pub package: DataPackage,
+}
+
+/// 附加包的 id 可以用在文件名中
+pub fn valid_addon_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn read_version(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// UO 目录中已安装的数据包版本，没有通过 Launcher 安装过时为 None
+pub fn installed_version(uo_dir: &Path) -> Option<String> {
+    read_version(&uo_dir.join(DATA_VERSION_FILE))
+}
+
+/// UO 目录中没有这个版本的数据包
+pub fn needs_install(package: &DataPackage, uo_dir: &Path) -> bool {
+    installed_version(uo_dir).as_deref() != Some(package.version.as_str())
+}
+
+pub fn write_installed_version(uo_dir: &Path, version: &str) -> Result<()> {
+    fs::write(uo_dir.join(DATA_VERSION_FILE), version)?;
+    Ok(())
+}
+
+/// 每个附加包有自己的版本文件，与数据包互不影响
+fn addon_version_file(id: &str) -> String {
+    format!(".openuo_addon_{}_version", id)
+}
+
+pub fn installed_addon_version(uo_dir: &Path, id: &str) -> Option<String> {
+    read_version(&uo_dir.join(addon_version_file(id)))
+}
+
+pub fn write_installed_addon_version(uo_dir: &Path, id: &str, version: &str) -> Result<()> {
+    fs::write(uo_dir.join(addon_version_file(id)), version)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_needs_install() {
+        let dir = temp_dir("data");
+        let package = DataPackage {
+            version: "7.0.102".to_string(),
+            url: "https://cdn.example/uo-data.zip".to_string(),
+            sha256: None,
+            signature: None,
+            tls: TlsTrust::default(),
+        };
+        assert!(needs_install(&package, &dir));
+        write_installed_version(&dir, "7.0.98").unwrap();
+        assert_eq!(installed_version(&dir).as_deref(), Some("7.0.98"));
+        assert!(needs_install(&package, &dir));
+        write_installed_version(&dir, "7.0.102\n").unwrap();
+        assert!(!needs_install(&package, &dir));
+
+        // 附加包的版本单独记录
+        assert_eq!(installed_addon_version(&dir, "music"), None);
+        write_installed_addon_version(&dir, "music", "2").unwrap();
+        assert_eq!(installed_addon_version(&dir, "music").as_deref(), Some("2"));
+        assert_eq!(installed_version(&dir).as_deref(), Some("7.0.102"));
+        assert!(valid_addon_id("sound-fx_2"));
+        assert!(!valid_addon_id("../music") && !valid_addon_id(""));
+    }
+}
@@ -1,18 +1,39 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
-use std::io::{Read, Write};
-use std::path::PathBuf;
-use std::sync::mpsc;
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicU16, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, mpsc};
 use std::time::Duration;
 
-use crate::config::open_uo_dir;
+use crate::config::{open_uo_binary_in, open_uo_dir};
+use crate::delta_update::{self, PatchStep};
+use crate::disk_space::{self, InsufficientSpace};
+use crate::download::{Cancelled, DownloadContext, DownloadEvent, DownloadOutcome};
+use crate::file_manifest::{FileManifest, PendingFile};
+use crate::game_data::{self, Addon, DataPackage};
+use crate::operation_lock::{self, Operation};
+use crate::process::GameRunning;
+use crate::release_cache;
+use crate::release_chain::{self, ChainRelease, ReleaseChain};
+use crate::shard_events::ShardEvent;
+use crate::shard_rules::ShardRules;
+use crate::staff_mode::StaffManifest;
+use crate::vote::VoteSite;
+use crate::tls_trust::{self, TlsTrust};
 
 const OPEN_UO_RELEASE_URL: &str =
     "https://api.github.com/repos/openuo-online/OpenUO/releases/latest";
 const LAUNCHER_RELEASE_URL: &str =
     "https://api.github.com/repos/openuo-online/OpenUO-Launcher/releases/latest";
 const OPEN_UO_VERSION_FILE: &str = ".open_uo_version";
+/// 安装时间（Unix 时间戳），与版本记录一起写入
+const OPEN_UO_INSTALLED_FILE: &str = ".open_uo_installed";
+/// Release 中可能附带的 sha256sum 格式校验文件
+const CHECKSUM_ASSET_NAMES: [&str; 4] = ["checksums.txt", "SHA256SUMS", "SHA256SUMS.txt", "sha256sums.txt"];
 
 // 自定义更新源配置文件
 const UPDATE_SOURCE_CONFIG: &str = "update_source.json";
@@ -27,19 +48,294 @@ pub struct UpdateSourceConfig {
     /// 是否使用 GitHub API 格式（false 则使用简化格式）
     #[serde(default = "default_true")]
     pub use_github_format: bool,
+    /// 更新信息的格式，设置后优先于 `use_github_format`
+    #[serde(default)]
+    pub format: Option<ReleaseFormat>,
+    /// OpenUO 的备用更新源，按顺序在主源失败后尝试
+    #[serde(default)]
+    pub openuo_mirrors: Vec<MirrorEntry>,
+    /// Launcher 的备用更新源
+    #[serde(default)]
+    pub launcher_mirrors: Vec<MirrorEntry>,
+    /// 校验更新签名的 minisign 公钥，配置后拒绝安装没有签名或签名无效的文件
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// 主源（openuo_url、launcher_url）的证书信任设置
+    #[serde(flatten)]
+    pub tls: TlsTrust,
+}
+
+/// 镜像可以只写 URL（格式与主源相同），也可以单独指定格式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum MirrorEntry {
+    Url(String),
+    Detailed {
+        url: String,
+        use_github_format: Option<bool>,
+        #[serde(default)]
+        format: Option<ReleaseFormat>,
+        #[serde(flatten)]
+        tls: TlsTrust,
+    },
+}
+
+impl UpdateSourceConfig {
+    fn release_format(&self) -> ReleaseFormat {
+        self.format.unwrap_or(ReleaseFormat::from_github_flag(self.use_github_format))
+    }
+}
+
+/// 更新信息的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseFormat {
+    /// GitHub Releases API，例如 `https://api.github.com/repos/{owner}/{repo}/releases/latest`
+    Github,
+    /// Gitea / Forgejo Releases API，例如 `https://git.example/api/v1/repos/{owner}/{repo}/releases/latest`，
+    /// 返回的字段与 GitHub 相同
+    Gitea,
+    /// GitLab Releases API，例如 `https://gitlab.example/api/v4/projects/{id}/releases/permalink/latest`
+    Gitlab,
+    /// 简化格式，见 `SimpleRelease`
+    Simple,
+}
+
+impl ReleaseFormat {
+    fn from_github_flag(github: bool) -> Self {
+        if github { ReleaseFormat::Github } else { ReleaseFormat::Simple }
+    }
+
+    /// 最新版本地址的后缀，去掉后得到版本列表的地址
+    fn latest_suffix(self) -> Option<&'static str> {
+        match self {
+            ReleaseFormat::Github | ReleaseFormat::Gitea => Some("/latest"),
+            ReleaseFormat::Gitlab => Some("/permalink/latest"),
+            ReleaseFormat::Simple => None,
+        }
+    }
+}
+
+/// 一个具体的更新源（主源或镜像）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateSource {
+    pub url: String,
+    pub format: ReleaseFormat,
+    pub tls: TlsTrust,
 }
 
 fn default_true() -> bool {
     true
 }
 
-/// 简化的更新信息格式（用于自定义 CDN）
+/// GitLab Releases API 返回的 release，转换为 `GithubRelease` 使用
+#[derive(Debug, Deserialize)]
+struct GitlabRelease {
+    tag_name: String,
+    name: Option<String>,
+    description: Option<String>,
+    released_at: Option<String>,
+    commit: Option<GitlabCommit>,
+    assets: GitlabAssets,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabCommit {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabAssets {
+    /// 发布时附加的链接（GitLab 不直接托管 release 文件，资产都以链接的形式出现）
+    #[serde(default)]
+    links: Vec<GitlabLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabLink {
+    name: String,
+    url: String,
+    /// 稳定的下载地址，`url` 指向的文件替换后也不变
+    direct_asset_url: Option<String>,
+}
+
+impl From<GitlabRelease> for GithubRelease {
+    fn from(release: GitlabRelease) -> Self {
+        GithubRelease {
+            name: release.name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| release.tag_name.clone()),
+            tag_name: release.tag_name,
+            assets: release
+                .assets
+                .links
+                .into_iter()
+                .map(|link| GithubAsset {
+                    name: link.name,
+                    browser_download_url: link.direct_asset_url.unwrap_or(link.url),
+                    size: 0,
+                    digest: None,
+                    signature: None,
+                })
+                .collect(),
+            body: release.description,
+            published_at: release.released_at,
+            target_commitish: release.commit.map(|c| c.id),
+            required_version: None,
+            patches: Vec::new(),
+            files_url: None,
+            data_package: None,
+            addons: Vec::new(),
+            events: Vec::new(),
+            votes: Vec::new(),
+            rules: None,
+            staff: None,
+            tls: TlsTrust::default(),
+        }
+    }
+}
+
+/// 简化的更新信息格式（用于自定义 CDN），顶层字段描述最新版本
 #[derive(Debug, Clone, Deserialize)]
 pub struct SimpleRelease {
+    #[serde(flatten)]
+    pub latest: SimpleVersion,
+    /// 服务器要求的最低版本（可选，未填写时要求最新版本）
+    #[serde(default)]
+    pub required_version: Option<String>,
+    /// 从旧版本升级的增量补丁（可选）
+    #[serde(default)]
+    pub patches: Vec<ManifestPatch>,
+    /// 游戏数据包（可选），解压到档案的 UO 目录
+    #[serde(default)]
+    pub data_package: Option<ManifestDataPackage>,
+    /// 附加包（可选），例如音乐和音效，由玩家选择安装到档案的 UO 目录
+    #[serde(default)]
+    pub addons: Vec<ManifestAddon>,
+    /// 即将开始的服务器活动（可选），显示在“开始游戏”页
+    #[serde(default)]
+    pub events: Vec<ShardEvent>,
+    /// 投票网站（可选），显示为带冷却计时的“投票”按钮
+    #[serde(default)]
+    pub votes: Vec<VoteSite>,
+    /// 服务器规则（可选），第一次启动档案前需要玩家确认
+    #[serde(default)]
+    pub rules: Option<ShardRules>,
+    /// 员工模式（可选）：测试服地址和额外的启动参数预设
+    #[serde(default)]
+    pub staff: Option<StaffManifest>,
+    /// 更早的版本（可选，从新到旧），用于在版本列表中选择安装
+    #[serde(default)]
+    pub versions: Vec<SimpleVersion>,
+}
+
+/// 简化格式中的一个版本
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimpleVersion {
     /// 版本号/标签
     pub version: String,
     /// 下载 URL（可以是对象或字符串）
     pub download_url: DownloadUrls,
+    /// 资产的 SHA-256（可选，格式与 download_url 相同）
+    #[serde(default)]
+    pub sha256: Option<DownloadUrls>,
+    /// 资产的 minisign 签名，即 .minisig 文件的内容（可选，格式与 download_url 相同）
+    #[serde(default)]
+    pub signature: Option<DownloadUrls>,
+    /// 发布说明（可选，Markdown）
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// 发布时间（可选，例如 "2024-05-01T10:00:00Z"）
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// 逐文件更新清单的地址（可选，格式与 download_url 相同），见 `file_manifest::FileManifest`；
+    /// 也用于校验和修复这个版本的安装
+    #[serde(default)]
+    pub files_url: Option<DownloadUrls>,
+}
+
+impl SimpleVersion {
+    /// 转换为 GithubRelease，只保留当前平台的资产
+    fn into_release(self) -> Result<GithubRelease> {
+        let download_url = self
+            .download_url
+            .for_current_platform()
+            .context("当前平台没有可用的下载链接")?;
+        let digest = self
+            .sha256
+            .and_then(DownloadUrls::for_current_platform)
+            .map(|hash| format!("sha256:{}", hash.trim()));
+        Ok(GithubRelease {
+            tag_name: self.version.clone(),
+            name: self.version,
+            assets: vec![GithubAsset {
+                name: get_platform_asset_name(),
+                browser_download_url: download_url,
+                size: 0,
+                digest,
+                signature: self.signature.and_then(DownloadUrls::for_current_platform),
+            }],
+            body: self.notes,
+            published_at: self.published_at,
+            target_commitish: None,
+            required_version: None,
+            patches: Vec::new(),
+            files_url: self.files_url.and_then(DownloadUrls::for_current_platform),
+            data_package: None,
+            addons: Vec::new(),
+            events: Vec::new(),
+            votes: Vec::new(),
+            rules: None,
+            staff: None,
+            tls: TlsTrust::default(),
+        })
+    }
+}
+
+/// 简化格式中的增量补丁，url、sha256 和 signature 的格式与 download_url 相同
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestPatch {
+    pub from: String,
+    pub to: String,
+    pub url: DownloadUrls,
+    #[serde(default)]
+    pub sha256: Option<DownloadUrls>,
+    #[serde(default)]
+    pub signature: Option<DownloadUrls>,
+}
+
+/// 简化格式中的游戏数据包（UO 的 art、map 等文件），url、sha256 和 signature 的格式与 download_url 相同
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestDataPackage {
+    pub version: String,
+    pub url: DownloadUrls,
+    #[serde(default)]
+    pub sha256: Option<DownloadUrls>,
+    #[serde(default)]
+    pub signature: Option<DownloadUrls>,
+}
+
+/// 简化格式中的附加包，除 id 和 name 外与游戏数据包相同
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestAddon {
+    /// 只含字母、数字、- 和 _，例如 "music"
+    pub id: String,
+    /// 显示的名称（可选，默认为 id）
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub package: ManifestDataPackage,
+}
+
+impl ManifestDataPackage {
+    /// 选好当前平台，没有当前平台的下载链接时为 None
+    fn for_current_platform(self, tls: &TlsTrust) -> Option<DataPackage> {
+        Some(DataPackage {
+            url: self.url.for_current_platform()?,
+            sha256: self.sha256.and_then(DownloadUrls::for_current_platform),
+            signature: self.signature.and_then(DownloadUrls::for_current_platform),
+            version: self.version,
+            tls: tls.clone(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -60,7 +356,34 @@ pub enum DownloadUrls {
     },
 }
 
-fn get_platform_asset_name() -> String {
+impl DownloadUrls {
+    /// 取当前平台对应的值
+    // 只会用到当前平台对应的字段
+    #[allow(unused_variables)]
+    fn for_current_platform(self) -> Option<String> {
+        match self {
+            DownloadUrls::Single(value) => Some(value),
+            DownloadUrls::Multiple { osx_arm64, osx_x64, linux_x64, win_x64 } => {
+                #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+                return osx_arm64;
+
+                #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+                return osx_x64;
+
+                #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+                return linux_x64;
+
+                #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+                return win_x64;
+
+                #[allow(unreachable_code)]
+                None
+            }
+        }
+    }
+}
+
+pub fn get_platform_asset_name() -> String {
     #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
     return "osx-arm64.zip".to_string();
     
@@ -89,6 +412,12 @@ pub struct GithubAsset {
     pub name: String,
     pub browser_download_url: String,
     pub size: u64,
+    /// GitHub 提供的校验值，例如 "sha256:..."
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// 仅来自简化格式的 minisign 签名；GitHub Release 使用附带的 `<资产名>.minisig`
+    #[serde(skip)]
+    pub signature: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -99,22 +428,79 @@ pub struct GithubRelease {
     pub body: Option<String>,
     pub published_at: Option<String>,
     pub target_commitish: Option<String>,
+    /// 仅来自简化格式的 required_version
+    #[serde(skip)]
+    pub required_version: Option<String>,
+    /// 仅来自简化格式的增量补丁（已选好当前平台）
+    #[serde(skip)]
+    pub patches: Vec<PatchStep>,
+    /// 仅来自简化格式的逐文件更新清单地址（已选好当前平台），每个版本可以有自己的清单
+    #[serde(skip)]
+    pub files_url: Option<String>,
+    /// 仅来自简化格式的游戏数据包（已选好当前平台）
+    #[serde(skip)]
+    pub data_package: Option<DataPackage>,
+    /// 仅来自简化格式的附加包（已选好当前平台）
+    #[serde(skip)]
+    pub addons: Vec<Addon>,
+    /// 仅来自简化格式的服务器活动
+    #[serde(skip)]
+    pub events: Vec<ShardEvent>,
+    /// 仅来自简化格式的投票网站
+    #[serde(skip)]
+    pub votes: Vec<VoteSite>,
+    /// 仅来自简化格式的服务器规则
+    #[serde(skip)]
+    pub rules: Option<ShardRules>,
+    /// 仅来自简化格式的员工模式设置
+    #[serde(skip)]
+    pub staff: Option<StaffManifest>,
+    /// 获取这个 release 的更新源的证书信任设置，下载资产时沿用
+    #[serde(skip)]
+    pub tls: TlsTrust,
 }
 
-pub enum DownloadEvent {
-    Progress { received: u64, total: u64 },
-    Finished(Result<String, String>),
-}
-
+#[derive(Debug)]
 pub enum UpdateEvent {
     OpenUO(Result<String, String>),
     Launcher(Result<String, String>),
+    /// 更新清单中声明的最低 OpenUO 版本（紧跟在 OpenUO 结果之后发送）
+    RequiredOpenUO(String),
+    /// 最新 OpenUO / Launcher 的发布说明（Markdown，紧跟在对应结果之后发送，没有说明时不发送）
+    OpenUONotes(String),
+    LauncherNotes(String),
+    /// 已安装的 OpenUO 落后不止一个版本时，中间跳过的版本（在 OpenUO 结果和发布说明之后发送）
+    OpenUOChain(ReleaseChain),
+    /// 更新清单中声明的游戏数据包（在 OpenUO 结果之后发送）
+    DataPackage(DataPackage),
+    /// 更新清单中声明的附加包（在 OpenUO 结果之后发送，没有时为空）
+    Addons(Vec<Addon>),
+    /// 更新清单中声明的服务器活动（在 OpenUO 结果之后发送，没有活动时为空）
+    ShardEvents(Vec<ShardEvent>),
+    /// 更新清单中声明的投票网站（在 OpenUO 结果之后发送，没有时为空）
+    VoteSites(Vec<VoteSite>),
+    /// 更新清单中声明的服务器规则（在 OpenUO 结果之后发送，没有时为 None）
+    ShardRules(Option<ShardRules>),
+    /// 更新清单中的员工模式设置（在 OpenUO 结果之后发送，没有时为默认值）
+    StaffMode(StaffManifest),
+    /// 最新 OpenUO / Launcher 的发布时间（Unix 时间戳，紧跟在对应结果之后发送，没有时不发送）
+    Published { open_uo: bool, at: i64 },
+    /// 从引导地址更新 update_source.json 的结果（最先发送，没有设置引导地址时不发送）；true 表示配置有变化
+    Bootstrap(Result<bool, String>),
+    /// 更新源使用了自定义的证书信任设置，UI 需要提醒用户
+    CustomTrust { url: String, ca_cert: Option<String>, pin: Option<String> },
+    /// 更新源被限流（在对应的 OpenUO / Launcher 结果之前发送）；`cached` 为 false 时不再发送结果
+    RateLimited { open_uo: bool, retry_after: Duration, cached: bool },
     Done,
 }
 
+pub fn update_source_config_path() -> PathBuf {
+    crate::config::base_dir().join(UPDATE_SOURCE_CONFIG)
+}
+
 /// 加载自定义更新源配置
 fn load_update_source_config() -> Option<UpdateSourceConfig> {
-    let config_path = crate::config::base_dir().join(UPDATE_SOURCE_CONFIG);
+    let config_path = update_source_config_path();
     if !config_path.exists() {
         return None;
     }
@@ -139,321 +525,1627 @@ fn load_update_source_config() -> Option<UpdateSourceConfig> {
     }
 }
 
-/// 获取 OpenUO 更新 URL
-fn get_openuo_update_url() -> String {
-    load_update_source_config()
-        .and_then(|c| c.openuo_url)
-        .unwrap_or_else(|| OPEN_UO_RELEASE_URL.to_string())
+/// 获取 OpenUO 的更新源列表（主源在前）
+fn get_openuo_update_sources() -> Vec<UpdateSource> {
+    match load_update_source_config() {
+        Some(c) => build_sources(c.openuo_url.clone(), OPEN_UO_RELEASE_URL, c.release_format(), &c.tls, &c.openuo_mirrors),
+        None => build_sources(None, OPEN_UO_RELEASE_URL, ReleaseFormat::Github, &TlsTrust::default(), &[]),
+    }
 }
 
-/// 获取 Launcher 更新 URL
-fn get_launcher_update_url() -> String {
-    load_update_source_config()
-        .and_then(|c| c.launcher_url)
-        .unwrap_or_else(|| LAUNCHER_RELEASE_URL.to_string())
+/// 获取 Launcher 的更新源列表（主源在前）
+fn get_launcher_update_sources() -> Vec<UpdateSource> {
+    match load_update_source_config() {
+        Some(c) => build_sources(c.launcher_url.clone(), LAUNCHER_RELEASE_URL, c.release_format(), &c.tls, &c.launcher_mirrors),
+        None => build_sources(None, LAUNCHER_RELEASE_URL, ReleaseFormat::Github, &TlsTrust::default(), &[]),
+    }
 }
 
-/// 是否使用 GitHub API 格式
-fn use_github_format() -> bool {
-    load_update_source_config()
-        .map(|c| c.use_github_format)
-        .unwrap_or(true)
+/// 顶层的证书信任设置只用于自定义的主源，默认的 GitHub 地址和镜像不受影响
+fn build_sources(
+    primary: Option<String>,
+    default_url: &str,
+    format: ReleaseFormat,
+    tls: &TlsTrust,
+    mirrors: &[MirrorEntry],
+) -> Vec<UpdateSource> {
+    let primary = match primary {
+        Some(url) => UpdateSource { tls: tls.clone().for_url(&url), url, format },
+        None => UpdateSource { url: default_url.to_string(), format, tls: TlsTrust::default() },
+    };
+    let mut sources = vec![primary];
+    for mirror in mirrors {
+        let source = match mirror {
+            MirrorEntry::Url(url) => UpdateSource { url: url.clone(), format, tls: TlsTrust::default() },
+            MirrorEntry::Detailed { url, use_github_format, format: mirror_format, tls } => UpdateSource {
+                url: url.clone(),
+                format: mirror_format.or(use_github_format.map(ReleaseFormat::from_github_flag)).unwrap_or(format),
+                tls: tls.clone().for_url(url),
+            },
+        };
+        if !source.url.trim().is_empty() && !sources.iter().any(|s| s.url == source.url) {
+            sources.push(source);
+        }
+    }
+    sources
 }
 
-pub fn fetch_latest_release(url: &str) -> Result<GithubRelease> {
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("OpenUO-Launcher")
-        .timeout(Duration::from_secs(8))
-        .build()?;
-    
-    if use_github_format() {
-        // GitHub API 格式
-        let resp = client
-            .get(url)
-            .header("Accept", "application/vnd.github+json")
-            .send()?
-            .error_for_status()?
-            .json::<GithubRelease>()?;
-        Ok(resp)
-    } else {
-        // 简化格式，转换为 GithubRelease
-        let resp = client
-            .get(url)
-            .send()?
-            .error_for_status()?
-            .json::<SimpleRelease>()?;
-        
-        // 转换为 GithubRelease 格式
-        let platform_name = get_platform_asset_name();
-        let download_url = match resp.download_url {
-            DownloadUrls::Single(url) => url,
-            DownloadUrls::Multiple { osx_arm64, osx_x64, linux_x64, win_x64 } => {
-                #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-                let url = osx_arm64;
-                
-                #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-                let url = osx_x64;
-                
-                #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-                let url = linux_x64;
-                
-                #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
-                let url = win_x64;
-                
-                url.context("当前平台没有可用的下载链接")?
+/// 依次尝试各个更新源，直到某个成功为止
+///
+/// 每次切换到下一个源之前调用 `on_fallback(失败的源, 下一个源, 错误)`。
+fn with_fallback<T>(
+    sources: &[UpdateSource],
+    mut attempt: impl FnMut(&UpdateSource) -> Result<T>,
+    mut on_fallback: impl FnMut(&UpdateSource, &UpdateSource, &anyhow::Error),
+) -> Result<T> {
+    let mut last_error = None;
+    for (i, source) in sources.iter().enumerate() {
+        match attempt(source) {
+            Ok(value) => {
+                tracing::info!("使用更新源: {}", source.url);
+                return Ok(value);
             }
-        };
-        
-        Ok(GithubRelease {
-            tag_name: resp.version.clone(),
-            name: resp.version,
-            assets: vec![GithubAsset {
-                name: platform_name,
-                browser_download_url: download_url,
-                size: 0,
-            }],
-            body: None,
-            published_at: None,
-            target_commitish: None,
-        })
+            Err(e) => {
+                tracing::warn!("更新源 {} 失败: {:#}", source.url, e);
+                if let Some(next) = sources.get(i + 1) {
+                    on_fallback(source, next, &e);
+                }
+                last_error = Some(e);
+            }
+        }
     }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("没有可用的更新源")))
 }
 
-pub fn download_and_unpack_open_uo_with_progress<F: Fn(DownloadEvent) + Send + 'static>(
-    progress: F,
-) -> Result<String> {
-    let progress_cb = |evt: DownloadEvent| {
-        progress(evt);
-    };
+/// 与 `with_fallback` 相同，用于下载任务；用户取消时不再尝试下一个更新源
+async fn with_fallback_async<T, Fut: Future<Output = Result<T>>>(
+    sources: &[UpdateSource],
+    mut attempt: impl FnMut(UpdateSource) -> Fut,
+    mut on_fallback: impl FnMut(&UpdateSource, &UpdateSource, &anyhow::Error),
+) -> Result<T> {
+    let mut last_error = None;
+    for (i, source) in sources.iter().enumerate() {
+        match attempt(source.clone()).await {
+            Ok(value) => {
+                tracing::info!("使用更新源: {}", source.url);
+                return Ok(value);
+            }
+            // 取消或磁盘空间不足时换更新源也没有用
+            Err(e) if e.is::<Cancelled>() || e.is::<InsufficientSpace>() => return Err(e),
+            Err(e) => {
+                tracing::warn!("更新源 {} 失败: {:#}", source.url, e);
+                if let Some(next) = sources.get(i + 1) {
+                    on_fallback(source, next, &e);
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("没有可用的更新源")))
+}
 
-    let url = get_openuo_update_url();
-    let release = fetch_latest_release(&url)?;
-    
-    // 根据当前平台选择正确的资产
-    let platform_name = get_platform_asset_name();
-    let asset = release
-        .assets
-        .iter()
-        .find(|a| a.name == platform_name)
-        .cloned()
-        .context(format!("未找到平台 {} 的资产", platform_name))?;
+/// 读取 GitHub 令牌的环境变量，优先于 Launcher 设置
+const GITHUB_TOKEN_ENV_VARS: [&str; 2] = ["OPENUO_GITHUB_TOKEN", "GITHUB_TOKEN"];
 
-    let tmp = std::env::temp_dir().join(&asset.name);
-    download_asset(&asset.browser_download_url, &tmp, |received, total| {
-        progress_cb(DownloadEvent::Progress { received, total });
-    })?;
+/// GitHub API 令牌，未认证的请求在共享 IP 下很快会被限流
+///
+/// Debug 输出时隐藏内容，不会出现在日志和诊断信息中。
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GithubToken(String);
 
-    let target_dir = open_uo_dir();
-    fs::create_dir_all(&target_dir)?;
-    extract_zip(&tmp, &target_dir)?;
-    fs::remove_file(&tmp).ok();
+impl GithubToken {
+    pub fn new(token: &str) -> Self {
+        Self(token.trim().to_string())
+    }
 
-    // 使用发布时间作为版本标识
-    let version = get_version_string(&release);
-    write_open_uo_version(&version, &target_dir)?;
-    Ok(version)
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
-pub fn download_launcher_update<F: Fn(DownloadEvent) + Send + 'static>(
-    progress: F,
-) -> Result<String> {
-    let progress_cb = |evt: DownloadEvent| {
-        progress(evt);
-    };
+impl fmt::Debug for GithubToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            f.write_str("GithubToken(<empty>)")
+        } else {
+            f.write_str("GithubToken(***)")
+        }
+    }
+}
 
-    let url = get_launcher_update_url();
-    let release = fetch_latest_release(&url)?;
-    
-    // 根据当前平台选择正确的可执行文件
-    let launcher_name = get_launcher_asset_name();
-    let asset = release
-        .assets
+/// Launcher 设置中的令牌
+static GITHUB_TOKEN: RwLock<Option<GithubToken>> = RwLock::new(None);
+
+pub fn set_github_token(token: &GithubToken) {
+    if let Ok(mut current) = GITHUB_TOKEN.write() {
+        *current = (!token.is_empty()).then(|| token.clone());
+    }
+}
+
+/// 是否已通过环境变量提供令牌（此时忽略设置中的令牌）
+pub fn github_token_from_env() -> bool {
+    env_github_token().is_some()
+}
+
+fn env_github_token() -> Option<GithubToken> {
+    GITHUB_TOKEN_ENV_VARS
         .iter()
-        .find(|a| a.name == launcher_name)
-        .cloned()
-        .context(format!("未找到平台 {} 的 Launcher", launcher_name))?;
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| GithubToken::new(&value))
+        .find(|token| !token.is_empty())
+}
 
-    // 下载到临时文件
-    let tmp = std::env::temp_dir().join(&asset.name);
-    download_asset(&asset.browser_download_url, &tmp, |received, total| {
-        progress_cb(DownloadEvent::Progress { received, total });
-    })?;
+fn github_token() -> Option<GithubToken> {
+    env_github_token().or_else(|| GITHUB_TOKEN.read().ok().and_then(|t| t.clone()))
+}
 
-    // 设置执行权限（Unix 系统）
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let perms = fs::Permissions::from_mode(0o755);
-        fs::set_permissions(&tmp, perms)?;
+/// 只有发往 GitHub API 的请求才带上令牌，不能泄露给镜像源
+fn is_github_api(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some("api.github.com"))
+}
+
+fn authorize(request: reqwest::blocking::RequestBuilder, url: &str) -> reqwest::blocking::RequestBuilder {
+    if !is_github_api(url) {
+        return request;
     }
-    
-    // 获取当前可执行文件路径（在替换前）
-    let current_exe = std::env::current_exe()?;
-    
-    // 使用 self_replace 替换当前可执行文件
-    // 这个库会自动处理跨平台的替换逻辑
-    self_replace::self_replace(&tmp)?;
-    
-    // 删除临时文件
-    fs::remove_file(&tmp).ok();
-    
-    let version = get_version_string(&release);
-    
-    // 启动新版本程序
-    #[cfg(target_os = "macos")]
-    {
-        // macOS 使用 open 命令
-        std::process::Command::new("open")
-            .arg(&current_exe)
-            .spawn()
-            .ok();
+    let Some(token) = github_token() else {
+        return request;
+    };
+    let Ok(mut value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token.as_str())) else {
+        tracing::warn!("GitHub 令牌包含无效字符，已忽略");
+        return request;
+    };
+    value.set_sensitive(true);
+    request.header(reqwest::header::AUTHORIZATION, value)
+}
+
+/// 获取更新信息等小文件用的客户端，返回需要检查的证书指纹
+///
+/// 阻塞客户端的 timeout 限制的是等待响应头和每次读取的时间，响应体须用 `read_body` 读取，
+/// 而不是 `text()`（后者把整个响应体作为一次等待）。
+fn blocking_client(tls: &TlsTrust, url: &str) -> Result<(reqwest::blocking::Client, Option<String>)> {
+    let builder = reqwest::blocking::Client::builder()
+        .user_agent("OpenUO-Launcher")
+        .connect_timeout(connect_timeout())
+        .timeout(read_timeout());
+    let (builder, pin) = tls.apply_blocking(builder, url)?;
+    Ok((builder.build()?, pin))
+}
+
+/// 获取最新的 release，临时的网络错误会自动重试
+pub fn fetch_latest_release(source: &UpdateSource) -> Result<GithubRelease> {
+    crate::retry::blocking(&format!("获取更新信息 {}", source.url), || {
+        fetch_and_parse(source, |body| parse_release(source, body))
+    })
+}
+
+/// 获取更新信息并用 `parse` 解析，解析成功后才写入缓存
+fn fetch_and_parse<T>(source: &UpdateSource, parse: impl Fn(&str) -> Result<T>) -> Result<T> {
+    let url = source.url.as_str();
+    let (client, pin) = blocking_client(&source.tls, url)?;
+
+    // 带上 ETag 做条件请求，未变化时使用本地缓存的响应
+    let cached = release_cache::get(url);
+    let mut request = authorize(client.get(url), url);
+    if source.format == ReleaseFormat::Github {
+        request = request.header("Accept", "application/vnd.github+json");
     }
-    
-    #[cfg(target_os = "windows")]
-    {
-        // Windows 下使用 cmd /c start 来启动
-        // 使用 start "" "path" 格式，空字符串是窗口标题
-        let exe_str = current_exe.to_string_lossy().to_string();
-        std::process::Command::new("cmd")
-            .args(&["/C", "start", "", &exe_str])
-            .spawn()
-            .ok();
+    if let Some(etag) = cached.as_ref().and_then(|c| c.etag.as_deref()) {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
     }
-    
-    #[cfg(target_os = "linux")]
+    let resp = request.send()?;
+    tls_trust::verify_pin(pin.as_deref(), resp.extensions().get())?;
+    note_server_date(resp.headers());
+    if let Some(retry_after) = rate_limit_wait(resp.status(), resp.headers(), crate::clock::now_unix()) {
+        return Err(RateLimited {
+            url: url.to_string(),
+            retry_after,
+        }
+        .into());
+    }
+    if resp.status() == reqwest::StatusCode::NOT_MODIFIED
+        && let Some(cached) = cached
     {
-        // Linux 直接启动
-        std::process::Command::new(&current_exe)
-            .spawn()
-            .ok();
+        tracing::debug!("更新信息未变化，使用缓存: {}", url);
+        // 更新获取时间，作为上次检查更新的时间
+        release_cache::store(url, cached.etag, &cached.body);
+        return parse(&cached.body);
     }
-    
-    // 返回特殊标记，告诉 UI 需要退出程序
-    Ok(format!("UPDATE_AND_RESTART:{}", version))
+    let resp = resp.error_for_status()?;
+    let etag = resp
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let body = read_body(resp)?;
+    let parsed = parse(&body)?;
+    release_cache::store(url, etag, &body);
+    Ok(parsed)
 }
 
-fn get_launcher_asset_name() -> String {
-    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-    return "OpenUO-Launcher-macos-arm64".to_string();
-    
-    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-    return "OpenUO-Launcher-macos-x64".to_string();
-    
-    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-    return "OpenUO-Launcher-linux-x64".to_string();
-    
-    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
-    return "OpenUO-Launcher-windows-x64.exe".to_string();
-    
-    #[cfg(not(any(
-        all(target_os = "macos", target_arch = "aarch64"),
-        all(target_os = "macos", target_arch = "x86_64"),
-        all(target_os = "linux", target_arch = "x86_64"),
-        all(target_os = "windows", target_arch = "x86_64")
-    )))]
-    {
-        panic!("不支持的平台");
+/// 用服务器的 Date 头估算本机时钟偏差
+fn note_server_date(headers: &reqwest::header::HeaderMap) {
+    if let Some(date) = headers.get(reqwest::header::DATE).and_then(|v| v.to_str().ok()) {
+        crate::clock::record_server_date(date);
     }
 }
 
-fn download_asset(url: &str, dest: &PathBuf, progress: impl Fn(u64, u64)) -> Result<()> {
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("OpenUO-Launcher")
-        .timeout(Duration::from_secs(8))
-        .build()?;
-    let mut resp = client.get(url).send()?.error_for_status()?;
-    let mut file = fs::File::create(dest)?;
-    let total = resp
-        .headers()
-        .get(reqwest::header::CONTENT_LENGTH)
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(0);
+/// 更新源限流（GitHub 未认证的请求每小时只有 60 次）
+///
+/// 限流解除前重试没有用，因此不算临时错误。
+#[derive(Debug)]
+pub struct RateLimited {
+    pub url: String,
+    /// 距离限流解除的时间
+    pub retry_after: Duration,
+}
 
-    let mut received = 0u64;
-    let mut buffer = [0u8; 16 * 1024];
-    loop {
-        let n = resp.read(&mut buffer)?;
-        if n == 0 {
-            break;
-        }
-        file.write_all(&buffer[..n])?;
-        received += n as u64;
-        progress(received, total);
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 请求过于频繁，已被限流，{} 秒后可重试", self.url, self.retry_after.as_secs())
     }
-    Ok(())
 }
 
-fn extract_zip(zip_path: &PathBuf, target_dir: &PathBuf) -> Result<()> {
-    let file = fs::File::open(zip_path)?;
-    let mut archive = zip::ZipArchive::new(file)?;
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let mut outpath = target_dir.clone();
-        outpath.push(file.mangled_name());
+impl std::error::Error for RateLimited {}
 
-        if (*file.name()).ends_with('/') {
-            fs::create_dir_all(&outpath)?;
-        } else {
-            if let Some(parent) = outpath.parent() {
-                fs::create_dir_all(parent)?;
+/// 没有给出解除时间的限流（例如 GitHub 的二级限流）至少等待一分钟
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// 响应是限流时返回需要等待的时间，`now` 是当前 Unix 时间戳
+///
+/// 优先使用 `Retry-After`，其次是 `X-RateLimit-Remaining: 0` 时的 `X-RateLimit-Reset`。
+/// 没有这些头的 403 是真正的拒绝访问，没有这些头的 429 仍按临时错误重试。
+fn rate_limit_wait(status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap, now: i64) -> Option<Duration> {
+    if status != reqwest::StatusCode::FORBIDDEN && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+    if let Some(secs) = header("retry-after").and_then(|v| v.parse::<u64>().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+    if header("x-ratelimit-remaining") != Some("0") {
+        return None;
+    }
+    let wait = match header("x-ratelimit-reset").and_then(|v| v.parse::<i64>().ok()) {
+        Some(reset) => Duration::from_secs(reset.saturating_sub(now).max(0) as u64),
+        None => DEFAULT_RATE_LIMIT_WAIT,
+    };
+    Some(wait)
+}
+
+/// 所有更新源都失败时（例如暂时离线或被限流）使用上次缓存的更新信息
+///
+/// 有更新源被限流时调用 `on_rate_limit`，参数为限流解除的时间和是否有缓存可用。
+fn fetch_latest_or_cached(sources: &[UpdateSource], on_rate_limit: impl FnOnce(Duration, bool)) -> Result<GithubRelease> {
+    let mut rate_limit = None;
+    let fetch = |source: &UpdateSource| {
+        fetch_latest_release(source).inspect_err(|e| {
+            if let Some(limited) = e.downcast_ref::<RateLimited>() {
+                rate_limit = Some(limited.retry_after);
             }
-            let mut outfile = fs::File::create(&outpath)?;
-            std::io::copy(&mut file, &mut outfile)?;
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Some(mode) = file.unix_mode() {
-                    fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
-                }
+        })
+    };
+    with_fallback(sources, fetch, |_, _, _| {}).or_else(|e| {
+        let cached = sources
+            .iter()
+            .find_map(|s| parse_release(s, &release_cache::get(&s.url)?.body).ok());
+        if let Some(retry_after) = rate_limit {
+            on_rate_limit(retry_after, cached.is_some());
+        }
+        match cached {
+            Some(release) => {
+                tracing::warn!("无法获取更新信息，使用缓存: {:#}", e);
+                Ok(release)
             }
+            None => Err(e),
         }
-    }
-    Ok(())
+    })
 }
 
-fn write_open_uo_version(tag: &str, dir: &PathBuf) -> Result<()> {
-    let path = dir.join(OPEN_UO_VERSION_FILE);
-    fs::write(path, tag)?;
-    Ok(())
+/// 缓存中的最新版本信息
+pub struct CachedRelease {
+    pub version: String,
+    pub required_version: Option<String>,
+    pub fetched_at: i64,
 }
 
-pub fn read_open_uo_version_file() -> Option<String> {
-    let path = open_uo_dir().join(OPEN_UO_VERSION_FILE);
-    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+/// 从缓存读取最近一次获取的最新版本，不访问网络
+pub fn cached_latest_release(open_uo: bool) -> Option<CachedRelease> {
+    let sources = if open_uo {
+        get_openuo_update_sources()
+    } else {
+        get_launcher_update_sources()
+    };
+    sources
+        .iter()
+        .filter_map(|s| {
+            let cached = release_cache::get(&s.url)?;
+            let release = parse_release(s, &cached.body).ok()?;
+            Some(CachedRelease {
+                version: get_version_string(&release),
+                required_version: release.required_version,
+                fetched_at: cached.fetched_at,
+            })
+        })
+        .max_by_key(|r| r.fetched_at)
 }
 
-pub fn detect_open_uo_version() -> Option<String> {
-    let exe = crate::config::open_uo_binary_path();
-    if !exe.exists() {
-        return None;
-    }
-    if let Some(ver) = read_open_uo_version_file() {
-        return Some(ver);
-    }
-    Some("已安装 (版本未知)".to_string())
+fn parse_release(source: &UpdateSource, body: &str) -> Result<GithubRelease> {
+    let mut release = match source.format {
+        // Gitea 的字段与 GitHub 相同
+        ReleaseFormat::Github | ReleaseFormat::Gitea => serde_json::from_str::<GithubRelease>(body)?,
+        ReleaseFormat::Gitlab => serde_json::from_str::<GitlabRelease>(body)?.into(),
+        ReleaseFormat::Simple => {
+            // 简化格式，转换为 GithubRelease 格式
+            let resp = serde_json::from_str::<SimpleRelease>(body)?;
+            let mut release = resp.latest.into_release()?;
+            release.required_version = resp.required_version;
+            release.patches = resp
+                .patches
+                .into_iter()
+                .filter_map(|p| {
+                    Some(PatchStep {
+                        url: p.url.for_current_platform()?,
+                        sha256: p.sha256.and_then(DownloadUrls::for_current_platform),
+                        signature: p.signature.and_then(DownloadUrls::for_current_platform),
+                        from: p.from,
+                        to: p.to,
+                    })
+                })
+                .collect();
+            release.data_package = resp.data_package.and_then(|p| p.for_current_platform(&source.tls));
+            release.addons = resp
+                .addons
+                .into_iter()
+                .filter(|a| {
+                    let valid = game_data::valid_addon_id(&a.id);
+                    if !valid {
+                        tracing::warn!("忽略 id 无效的附加包: {:?}", a.id);
+                    }
+                    valid
+                })
+                .filter_map(|a| {
+                    Some(Addon {
+                        name: a.name.unwrap_or_else(|| a.id.clone()),
+                        package: a.package.for_current_platform(&source.tls)?,
+                        id: a.id,
+                    })
+                })
+                .collect();
+            release.events = resp.events;
+            release.votes = resp.votes;
+            release.rules = resp.rules;
+            release.staff = resp.staff;
+            release
+        }
+    };
+    release.tls = source.tls.clone();
+    Ok(release)
 }
 
-pub fn trigger_update_check_impl(open_uo: bool, launcher: bool) -> mpsc::Receiver<UpdateEvent> {
-    let (tx, rx) = mpsc::channel();
-    std::thread::spawn(move || {
-        if open_uo {
-            let url = get_openuo_update_url();
-            let res = fetch_latest_release(&url)
-                .map(|r| get_version_string(&r))
-                .map_err(|e| format!("{e:#}"));
-            let _ = tx.send(UpdateEvent::OpenUO(res));
+/// 简化格式中的全部版本（从新到旧），跳过没有当前平台下载链接的旧版本
+fn parse_simple_versions(source: &UpdateSource, body: &str) -> Result<Vec<GithubRelease>> {
+    let latest = parse_release(source, body)?;
+    let resp = serde_json::from_str::<SimpleRelease>(body)?;
+    let mut releases = vec![latest];
+    for version in resp.versions {
+        if releases.iter().any(|r| r.tag_name == version.version) {
+            continue;
         }
-        if launcher {
-            let url = get_launcher_update_url();
-            let res = fetch_latest_release(&url)
-                .map(|r| get_version_string(&r))
-                .map_err(|e| format!("{e:#}"));
-            let _ = tx.send(UpdateEvent::Launcher(res));
+        let name = version.version.clone();
+        match version.into_release() {
+            Ok(mut release) => {
+                release.tls = source.tls.clone();
+                releases.push(release);
+            }
+            Err(e) => tracing::debug!("跳过版本 {}: {:#}", name, e),
         }
-        let _ = tx.send(UpdateEvent::Done);
-    });
-    rx
+    }
+    Ok(releases)
+}
+
+/// 获取指定标签的 release，未指定时获取最新版本
+///
+/// 简化格式在 `versions` 中查找，没有列出的版本返回错误。
+fn fetch_release(source: &UpdateSource, tag: Option<&str>) -> Result<GithubRelease> {
+    let Some(tag) = tag else {
+        return fetch_latest_release(source);
+    };
+    if source.format == ReleaseFormat::Simple {
+        return fetch_release_list(source)?
+            .into_iter()
+            .find(|r| r.tag_name == tag)
+            .with_context(|| format!("更新源 {} 没有列出版本 {}", source.url, tag));
+    }
+    let url = release_api_url(source.format, &source.url, Some(tag)).context("更新源不支持按版本获取")?;
+    fetch_latest_release(&UpdateSource { url, format: source.format, tls: source.tls.clone() })
+}
+
+/// 由最新版本的地址得到指定版本（`tag`）或版本列表（`tag` 为 None）的地址
+///
+/// GitHub / Gitea 把 `.../releases/latest` 换成 `.../releases/tags/{tag}`，
+/// GitLab 把 `.../releases/permalink/latest` 换成 `.../releases/{tag}`。
+fn release_api_url(format: ReleaseFormat, latest_url: &str, tag: Option<&str>) -> Option<String> {
+    let base = latest_url.trim_end_matches('/').strip_suffix(format.latest_suffix()?)?;
+    let Some(tag) = tag else {
+        return Some(base.to_string());
+    };
+    let mut url = reqwest::Url::parse(base).ok()?;
+    {
+        // 标签按路径段编码，GitLab 的标签可以包含 `/`
+        let mut segments = url.path_segments_mut().ok()?;
+        if format != ReleaseFormat::Gitlab {
+            segments.push("tags");
+        }
+        segments.push(tag);
+    }
+    Some(url.into())
+}
+
+/// 获取 OpenUO 的历史版本列表（从新到旧）
+///
+/// 依次尝试各个更新源；简化格式的更新源返回最新版本和 `versions` 中列出的版本。
+pub fn fetch_open_uo_releases() -> Result<Vec<GithubRelease>> {
+    with_fallback(&get_openuo_update_sources(), fetch_release_list, |_, _, _| {})
+}
+
+fn fetch_release_list(source: &UpdateSource) -> Result<Vec<GithubRelease>> {
+    if source.format == ReleaseFormat::Simple {
+        return crate::retry::blocking(&format!("获取版本列表 {}", source.url), || {
+            fetch_and_parse(source, |body| parse_simple_versions(source, body))
+        });
+    }
+    let url = release_api_url(source.format, &source.url, None).context("更新源不支持获取版本列表")?;
+    let url = url.as_str();
+    let (client, pin) = blocking_client(&source.tls, url)?;
+    let mut request = authorize(client.get(url), url);
+    request = match source.format {
+        ReleaseFormat::Github => request.query(&[("per_page", "50")]).header("Accept", "application/vnd.github+json"),
+        // Gitea 用 limit 表示每页数量
+        ReleaseFormat::Gitea => request.query(&[("limit", "50")]),
+        _ => request.query(&[("per_page", "50")]),
+    };
+    let resp = request.send()?;
+    tls_trust::verify_pin(pin.as_deref(), resp.extensions().get())?;
+    note_server_date(resp.headers());
+    let body = read_body(resp.error_for_status()?)?;
+    let mut releases: Vec<GithubRelease> = match source.format {
+        ReleaseFormat::Gitlab => serde_json::from_str::<Vec<GitlabRelease>>(&body)?
+            .into_iter()
+            .map(GithubRelease::from)
+            .collect(),
+        _ => serde_json::from_str(&body)?,
+    };
+    for release in &mut releases {
+        release.tls = source.tls.clone();
+    }
+    Ok(releases)
+}
+
+/// 下载并安装 OpenUO，`tag` 为空时安装最新版本
+pub async fn download_and_unpack_open_uo(tag: Option<String>, ctx: DownloadContext) -> Result<DownloadOutcome> {
+    // 安装最新版本时优先尝试增量补丁
+    if tag.is_none() {
+        match try_delta_update(&ctx).await {
+            Ok(Some(version)) => return Ok(DownloadOutcome::OpenUO(version)),
+            Ok(None) => {}
+            Err(e) if e.is::<Cancelled>() || e.is::<GameRunning>() => return Err(e),
+            Err(e) => {
+                tracing::warn!("增量更新失败，改为完整下载: {:#}", e);
+                ctx.emit(DownloadEvent::DeltaFailed(format!("{e:#}")));
+            }
+        }
+        match try_file_update(&ctx).await {
+            Ok(Some(version)) => return Ok(DownloadOutcome::OpenUO(version)),
+            Ok(None) => {}
+            Err(e) if e.is::<Cancelled>() || e.is::<InsufficientSpace>() || e.is::<GameRunning>() => return Err(e),
+            Err(e) => {
+                tracing::warn!("逐文件更新失败，改为完整下载: {:#}", e);
+                ctx.emit(DownloadEvent::DeltaFailed(format!("{e:#}")));
+            }
+        }
+    }
+
+    // 根据当前平台选择正确的资产
+    let platform_name = get_platform_asset_name();
+    let (release, tmp) = with_fallback_async(
+        &get_openuo_update_sources(),
+        |source| download_release_asset(&ctx, source, tag.as_deref(), &platform_name, preflight_open_uo, false),
+        |failed, next, e| ctx.emit(fallback_event(failed, next, e)),
+    )
+    .await?;
+
+    // 开始解压后不再响应取消，避免留下只解压了一半的目录
+    if let Err(e) = ctx.check_cancelled() {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    let version = ctx.uninterruptible(move || install_open_uo(&release, &tmp)).await?;
+    Ok(DownloadOutcome::OpenUO(version))
+}
+
+fn install_open_uo(release: &GithubRelease, archive: &Path) -> Result<String> {
+    // 使用发布时间作为版本标识
+    let version = get_version_string(release);
+    let result = install_open_uo_archive(archive, &version);
+    fs::remove_file(archive).ok();
+    result.map(|_| version)
+}
+
+/// 重新安装最新版本的 OpenUO：先完整下载，再清空安装目录（保留角色设置、并存安装的版本和 `protected` 中的目录）后解压
+///
+/// 下载失败时原来的安装保持不变。
+pub async fn reinstall_open_uo(ctx: DownloadContext, protected: Vec<PathBuf>) -> Result<DownloadOutcome> {
+    let platform_name = get_platform_asset_name();
+    let (release, tmp) = with_fallback_async(
+        &get_openuo_update_sources(),
+        |source| download_release_asset(&ctx, source, None, &platform_name, preflight_open_uo, false),
+        |failed, next, e| ctx.emit(fallback_event(failed, next, e)),
+    )
+    .await?;
+
+    if let Err(e) = ctx.check_cancelled() {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    let version = ctx.uninterruptible(move || {
+        let cleaned = {
+            let _guard = operation_lock::acquire(Operation::Install);
+            let dir = open_uo_dir();
+            crate::process::ensure_not_running(&open_uo_binary_in(&dir))
+                .map_err(anyhow::Error::from)
+                .and_then(|()| crate::install_dir::clean(&dir, &protected))
+        };
+        match cleaned {
+            Ok(removed) => tracing::info!("Removed {} entries before reinstalling OpenUO", removed),
+            Err(e) => {
+                fs::remove_file(&tmp).ok();
+                return Err(e);
+            }
+        }
+        install_open_uo(&release, &tmp)
+    })
+    .await?;
+    Ok(DownloadOutcome::OpenUO(version))
+}
+
+/// 把安装包解压到 OpenUO 目录并记录版本，不删除安装包（也用于从本地文件安装）
+pub fn install_open_uo_archive(archive: &Path, version: &str) -> Result<()> {
+    install_open_uo_archive_into(archive, version, &open_uo_dir())
+}
+
+fn install_open_uo_archive_into(archive: &Path, version: &str, target_dir: &Path) -> Result<()> {
+    // 等待正在进行的 profile 保存完成，安装期间 UI 会推迟保存
+    let _guard = operation_lock::acquire(Operation::Install);
+    crate::process::ensure_not_running(&open_uo_binary_in(target_dir))?;
+    fs::create_dir_all(target_dir)?;
+    ensure_unpack_space(archive, target_dir)?;
+    crate::archive::extract(archive, target_dir)?;
+
+    // 修正可执行位并去掉下载标记，避免首次启动失败
+    match crate::permissions::normalize_install(target_dir) {
+        Ok(fixed) => tracing::info!("Normalized permissions of {} files", fixed),
+        Err(e) => tracing::warn!("Failed to normalize permissions: {}", e),
+    }
+
+    write_open_uo_version(version, target_dir)
+}
+
+/// 把指定版本的 OpenUO 完整安装到 OpenUO/<tag>，与主安装并存，供固定了版本的档案使用
+pub async fn download_side_by_side(tag: String, ctx: DownloadContext) -> Result<DownloadOutcome> {
+    let target_dir = {
+        let tag = tag.clone();
+        ctx.blocking(move || crate::client_versions::prepare_dir(&tag)).await?
+    };
+    let platform_name = get_platform_asset_name();
+    let (release, tmp) = with_fallback_async(
+        &get_openuo_update_sources(),
+        |source| download_release_asset(&ctx, source, Some(&tag), &platform_name, preflight_side_by_side, false),
+        |failed, next, e| ctx.emit(fallback_event(failed, next, e)),
+    )
+    .await?;
+
+    if let Err(e) = ctx.check_cancelled() {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    let version = get_version_string(&release);
+    let (path, installed) = (tmp.clone(), version.clone());
+    let result = ctx.uninterruptible(move || install_open_uo_archive_into(&path, &installed, &target_dir)).await;
+    fs::remove_file(&tmp).ok();
+    result?;
+    Ok(DownloadOutcome::SideBySide { tag, version })
+}
+
+pub async fn download_launcher_update(ctx: DownloadContext) -> Result<DownloadOutcome> {
+    // 根据当前平台选择正确的可执行文件，下载到临时文件
+    let launcher_name = get_launcher_asset_name();
+    let (release, tmp) = with_fallback_async(
+        &get_launcher_update_sources(),
+        |source| download_release_asset(&ctx, source, None, &launcher_name, preflight_launcher, true),
+        |failed, next, e| ctx.emit(fallback_event(failed, next, e)),
+    )
+    .await?;
+
+    // 替换可执行文件的过程不可中断
+    if let Err(e) = ctx.check_cancelled() {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    let version = ctx.uninterruptible(move || replace_and_restart(&release, &tmp)).await?;
+    Ok(DownloadOutcome::LauncherUpdated(version))
+}
+
+/// 用下载的新版本替换当前可执行文件并启动它，返回新版本号
+fn replace_and_restart(release: &GithubRelease, tmp: &Path) -> Result<String> {
+    // 设置执行权限（Unix 系统）
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let perms = fs::Permissions::from_mode(0o755);
+        fs::set_permissions(tmp, perms)?;
+    }
+    // 去掉下载标记，避免新版本启动时被 SmartScreen / Gatekeeper 拦截
+    crate::permissions::strip_download_marks(tmp);
+    
+    let current_exe = std::env::current_exe()?;
+
+    // 保留旧版本为 .bak，新版本没能启动时自动恢复
+    let backup = crate::self_update::replace(tmp, &current_exe)?;
+    fs::remove_file(tmp).ok();
+
+    let version = get_version_string(release);
+    // 新版本启动后显示一次这个版本的发布说明
+    if let Some(notes) = release.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        let whats_new = crate::self_update::WhatsNew { version: version.clone(), notes: notes.to_string() };
+        if let Err(e) = crate::self_update::save_whats_new(&current_exe, &whats_new) {
+            tracing::warn!("无法保存发布说明: {:#}", e);
+        }
+    }
+
+    if let Err(e) = crate::self_update::start_and_wait(&current_exe, crate::self_update::STARTUP_TIMEOUT) {
+        crate::self_update::discard_whats_new(&current_exe);
+        crate::self_update::rollback(&backup)?;
+        return Err(e.context("新版本 Launcher 启动失败，已恢复旧版本"));
+    }
+
+    Ok(version)
+}
+
+/// 下载游戏数据包，校验后解压到 UO 目录并记录版本
+pub async fn download_game_data(package: DataPackage, uo_dir: PathBuf, ctx: DownloadContext) -> Result<DownloadOutcome> {
+    let version = package.version.clone();
+    install_package(package, uo_dir, ctx, "游戏数据包", game_data::write_installed_version).await?;
+    Ok(DownloadOutcome::GameData(version))
+}
+
+/// 下载附加包，与游戏数据包一样解压到 UO 目录，版本单独记录
+pub async fn download_addon(addon: Addon, uo_dir: PathBuf, ctx: DownloadContext) -> Result<DownloadOutcome> {
+    let (id, name, version) = (addon.id.clone(), addon.name.clone(), addon.package.version.clone());
+    let record = move |dir: &Path, version: &str| game_data::write_installed_addon_version(dir, &id, version);
+    install_package(addon.package, uo_dir, ctx, "附加包", record).await?;
+    Ok(DownloadOutcome::Addon { name, version })
+}
+
+/// 下载数据包，校验后解压到 UO 目录，由 `record` 记录安装的版本
+async fn install_package(
+    package: DataPackage,
+    uo_dir: PathBuf,
+    ctx: DownloadContext,
+    kind: &'static str,
+    record: impl FnOnce(&Path, &str) -> Result<()> + Send + 'static,
+) -> Result<()> {
+    match remote_size(&ctx, &package.url, &package.tls).await {
+        Some(size) => {
+            let dir = uo_dir.clone();
+            ctx.blocking(move || disk_space::ensure(&[(&std::env::temp_dir(), size), (&dir, size * disk_space::UNPACK_FACTOR)]))
+                .await?
+        }
+        None => tracing::info!("无法获取{}的大小，跳过下载前的空间检查", kind),
+    }
+
+    let tmp = std::env::temp_dir().join(format!("openuo-data-{}", uuid::Uuid::new_v4()));
+    if let Err(e) = download_asset(&ctx, &package.url, &tmp, &package.tls).await {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    // 开始解压后不再响应取消
+    if let Err(e) = ctx.check_cancelled() {
+        fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
+    let path = tmp.clone();
+    let result = ctx.uninterruptible(move || {
+        match &package.sha256 {
+            Some(hash) => crate::checksum::verify(&path, hash)?,
+            None => tracing::warn!("{} {} 没有校验值，跳过校验", kind, package.version),
+        }
+        if let Some(key) = update_public_key()? {
+            let signature = package.signature.as_deref().with_context(|| format!("{}没有签名，已拒绝安装", kind))?;
+            crate::signature::verify_file(&path, &key, signature)?;
+        }
+        let _guard = operation_lock::acquire(Operation::Install);
+        fs::create_dir_all(&uo_dir)?;
+        disk_space::ensure(&[(&uo_dir, crate::archive::unpacked_size(&path)?)])?;
+        crate::archive::extract(&path, &uo_dir)?;
+        record(&uo_dir, &package.version)
+    })
+    .await;
+    fs::remove_file(&tmp).ok();
+    result?;
+    Ok(())
+}
+
+/// 检查更新时记录的、从 `local` 到 `latest` 之间跳过的版本
+fn recorded_skipped(local: &str, latest: &str) -> Vec<String> {
+    release_chain::recorded(local, latest)
+        .map(|chain| chain.skipped().iter().map(|r| r.version.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// 用补丁链把已安装的 OpenUO 升级到最新版本，没有可用的补丁链时返回 None
+async fn try_delta_update(ctx: &DownloadContext) -> Result<Option<String>> {
+    let Some(local) = read_open_uo_version_file() else {
+        return Ok(None);
+    };
+    if !crate::config::open_uo_binary_path().exists() {
+        return Ok(None);
+    }
+    // 获取失败时交给完整下载流程报告错误
+    let release = match ctx
+        .blocking(|| with_fallback(&get_openuo_update_sources(), fetch_latest_release, |_, _, _| {}))
+        .await
+    {
+        Ok(release) => release,
+        Err(e) if e.is::<Cancelled>() => return Err(e),
+        Err(_) => return Ok(None),
+    };
+    let latest = get_version_string(&release);
+    let skipped = recorded_skipped(&local, &latest);
+    let Some(chain) = delta_update::find_chain(&release.patches, &local, &latest) else {
+        if !skipped.is_empty() {
+            tracing::info!("没有从 {} 到 {} 的补丁链（中间跳过了 {} 个版本）", local, latest, skipped.len());
+        }
+        return Ok(None);
+    };
+    tracing::info!("使用 {} 个增量补丁从 {} 升级到 {}", chain.len(), local, latest);
+    if !skipped.is_empty() {
+        tracing::info!("跳过的版本: {}", skipped.join(", "));
+    }
+    let key = update_public_key()?;
+
+    let target_dir = open_uo_dir();
+    for step in chain {
+        // 两个补丁之间可以取消，已应用的补丁会保留
+        ctx.check_cancelled()?;
+        let tmp = std::env::temp_dir().join(format!("openuo-patch-{}.zip", uuid::Uuid::new_v4()));
+        let result = match download_asset(ctx, &step.url, &tmp, &release.tls).await {
+            Ok(()) => {
+                let (tmp, target_dir, sha256) = (tmp.clone(), target_dir.clone(), step.sha256.clone());
+                let (key, signature) = (key.clone(), step.signature.clone());
+                ctx.uninterruptible(move || {
+                    let _guard = operation_lock::acquire(Operation::Install);
+                    crate::process::ensure_not_running(&open_uo_binary_in(&target_dir))?;
+                    if let Some(hash) = &sha256 {
+                        crate::checksum::verify(&tmp, hash)?;
+                    }
+                    if let Some(key) = &key {
+                        let signature = signature.context("补丁没有签名，已拒绝应用")?;
+                        crate::signature::verify_file(&tmp, key, &signature)?;
+                    }
+                    delta_update::apply_patch_archive(&tmp, &target_dir)
+                })
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        fs::remove_file(&tmp).ok();
+        let changed = match result {
+            Err(e) if e.is::<Cancelled>() => return Err(e),
+            result => result.with_context(|| format!("补丁 {} -> {}", step.from, step.to))?,
+        };
+        tracing::info!("补丁 {} -> {} 修改了 {} 个文件", step.from, step.to, changed);
+        // 每一步完成后都记录版本，中途失败时下次可以从这里继续
+        write_open_uo_version(&step.to, &target_dir)?;
+    }
+
+    let _guard = operation_lock::acquire(Operation::Install);
+    if let Err(e) = crate::permissions::normalize_install(&target_dir) {
+        tracing::warn!("Failed to normalize permissions: {}", e);
+    }
+    Ok(Some(latest))
+}
+
+/// 按逐文件更新清单只下载缺失或有变化的文件，更新源没有提供清单时返回 None
+///
+/// 所有文件下载并校验完成后才写入安装目录，中途失败或取消时安装保持不变。
+async fn try_file_update(ctx: &DownloadContext) -> Result<Option<String>> {
+    let Some(local) = read_open_uo_version_file() else {
+        return Ok(None);
+    };
+    if !crate::config::open_uo_binary_path().exists() {
+        return Ok(None);
+    }
+    let release = match ctx
+        .blocking(|| with_fallback(&get_openuo_update_sources(), fetch_latest_release, |_, _, _| {}))
+        .await
+    {
+        Ok(release) => release,
+        Err(e) if e.is::<Cancelled>() => return Err(e),
+        Err(_) => return Ok(None),
+    };
+    let Some(files_url) = release.files_url.clone() else {
+        return Ok(None);
+    };
+    let latest = get_version_string(&release);
+    let pending = fetch_pending_files(ctx, files_url, &release.tls).await?;
+    tracing::info!(
+        "逐文件更新到 {}：需要下载 {} 个文件（{} 字节）",
+        latest,
+        pending.len(),
+        pending.iter().map(|f| f.size).sum::<u64>()
+    );
+    let skipped = recorded_skipped(&local, &latest);
+    if !skipped.is_empty() {
+        tracing::info!("逐文件更新一次跳过 {} 个版本: {}", skipped.len(), skipped.join(", "));
+    }
+    install_files(ctx, pending, &release.tls, &latest).await?;
+    Ok(Some(latest))
+}
+
+/// 按已安装版本的文件清单校验 OpenUO，只重新下载缺失或损坏的文件
+///
+/// 清单由更新源在打包时生成（简化格式中对应版本的 `files_url`）。
+pub async fn verify_and_repair_open_uo(ctx: DownloadContext) -> Result<DownloadOutcome> {
+    let local = read_open_uo_version_file().context("OpenUO 未安装或没有版本记录，无法校验")?;
+    let tag = local.clone();
+    let release = ctx
+        .blocking(move || with_fallback(&get_openuo_update_sources(), |source| fetch_release(source, Some(&tag)), |_, _, _| {}))
+        .await?;
+    let files_url = release
+        .files_url
+        .clone()
+        .with_context(|| format!("更新源没有提供 {} 的文件清单，无法校验", local))?;
+    let pending = fetch_pending_files(&ctx, files_url, &release.tls).await?;
+    let repaired: Vec<String> = pending.iter().map(|f| f.relative.display().to_string()).collect();
+    if pending.is_empty() {
+        tracing::info!("OpenUO {} 的所有文件校验通过", local);
+    } else {
+        tracing::info!("OpenUO {} 有 {} 个文件缺失或损坏: {}", local, repaired.len(), repaired.join(", "));
+        install_files(&ctx, pending, &release.tls, &local).await?;
+    }
+    Ok(DownloadOutcome::Verified { version: local, repaired })
+}
+
+/// 下载并校验逐文件更新清单，与安装目录比较后返回缺失或有变化的文件
+async fn fetch_pending_files(ctx: &DownloadContext, files_url: String, tls: &TlsTrust) -> Result<Vec<PendingFile>> {
+    let (url, tls, dir) = (files_url, tls.clone(), open_uo_dir());
+    ctx.blocking(move || {
+        let body = fetch_text(&url, &tls).context("无法下载逐文件更新清单")?;
+        if let Some(key) = update_public_key()? {
+            let signature = fetch_text(&format!("{url}.minisig"), &tls).context("逐文件更新清单没有签名，已拒绝使用")?;
+            crate::signature::verify_bytes(body.as_bytes(), &key, &signature, &url)?;
+        }
+        FileManifest::parse(&body)?.changed_files(&url, &dir)
+    })
+    .await
+}
+
+/// 把文件下载到暂存目录，全部校验通过后才写入安装目录并记录版本，中途失败或取消时安装保持不变
+async fn install_files(ctx: &DownloadContext, pending: Vec<PendingFile>, tls: &TlsTrust, version: &str) -> Result<()> {
+    let total: u64 = pending.iter().map(|f| f.size).sum();
+    let target_dir = open_uo_dir();
+    let staging = std::env::temp_dir().join(format!("openuo-files-{}", uuid::Uuid::new_v4()));
+    let (staging_dir, dir) = (staging.clone(), target_dir.clone());
+    let result = match ctx
+        .blocking(move || disk_space::ensure(&[(&staging_dir, total), (&dir, total)]))
+        .await
+    {
+        Ok(()) => download_files(ctx, &pending, &staging, tls, total).await,
+        Err(e) => Err(e),
+    };
+    let result = match result {
+        Ok(()) => {
+            let (staging, version) = (staging.clone(), version.to_string());
+            ctx.uninterruptible(move || {
+                let _guard = operation_lock::acquire(Operation::Install);
+                crate::process::ensure_not_running(&open_uo_binary_in(&target_dir))?;
+                crate::file_manifest::install_staged(&staging, &target_dir, &pending)?;
+                if let Err(e) = crate::permissions::normalize_install(&target_dir) {
+                    tracing::warn!("Failed to normalize permissions: {}", e);
+                }
+                write_open_uo_version(&version, &target_dir)
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    fs::remove_dir_all(&staging).ok();
+    result
+}
+
+/// 依次下载文件到暂存目录并校验，进度按所有文件合计
+async fn download_files(
+    ctx: &DownloadContext,
+    files: &[PendingFile],
+    staging: &Path,
+    tls: &TlsTrust,
+    total: u64,
+) -> Result<()> {
+    let mut done = 0;
+    for file in files {
+        ctx.check_cancelled()?;
+        let dest = staging.join(&file.relative);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::File::create(&dest)?;
+        let client = DownloadClient::new(tls, &file.url)?;
+        fetch_into(ctx, &client, &file.url, &dest, 0, None, |received, file_total| {
+            // 清单中可以不写大小，这时总大小随下载增长
+            let total = total.max(done + file_total);
+            ctx.emit(DownloadEvent::Progress { received: done + received, total });
+        })
+        .await
+        .with_context(|| format!("无法下载 {}", file.relative.display()))?;
+        let (path, sha256) = (dest.clone(), file.sha256.clone());
+        ctx.blocking(move || crate::checksum::verify(&path, &sha256)).await?;
+        done += fs::metadata(&dest)?.len();
+    }
+    Ok(())
+}
+
+/// 从一个更新源获取 release（`tag` 为空时为最新版本），下载并校验指定资产，返回 release 和临时文件路径
+///
+/// 下载前用 `preflight(下载大小)` 检查磁盘空间。
+async fn download_release_asset(
+    ctx: &DownloadContext,
+    source: UpdateSource,
+    tag: Option<&str>,
+    asset_name: &str,
+    preflight: fn(u64) -> Result<()>,
+    require_verification: bool,
+) -> Result<(GithubRelease, std::path::PathBuf)> {
+    let tag = tag.map(str::to_string);
+    let release = ctx.blocking(move || fetch_release(&source, tag.as_deref())).await?;
+    let asset = find_asset(&release, asset_name)
+        .cloned()
+        .context(format!("未找到平台资产 {}", asset_name))?;
+
+    // 简化格式不提供大小，向服务器询问
+    let size = match asset.size {
+        0 => remote_size(ctx, &asset.browser_download_url, &release.tls).await,
+        size => Some(size),
+    };
+    match size {
+        Some(size) => ctx.blocking(move || preflight(size)).await?,
+        None => tracing::info!("无法获取 {} 的大小，跳过下载前的空间检查", asset.name),
+    }
+
+    let tmp = std::env::temp_dir().join(&asset.name);
+    let result = match download_asset(ctx, &asset.browser_download_url, &tmp, &release.tls).await {
+        Ok(()) => {
+            let path = tmp.clone();
+            ctx.blocking(move || verify_download(&release, &asset, &path, require_verification).map(|_| release)).await
+        }
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        fs::remove_file(&tmp).ok();
+        fs::remove_file(resume_marker(&tmp)).ok();
+    }
+    Ok((result?, tmp))
+}
+
+/// 按名称查找资产；安装包也接受同名的其他压缩格式，例如 `linux-x64.tar.gz`
+fn find_asset<'a>(release: &'a GithubRelease, asset_name: &str) -> Option<&'a GithubAsset> {
+    release.assets.iter().find(|a| a.name == asset_name).or_else(|| {
+        let stem = crate::archive::strip_extension(asset_name)?;
+        release
+            .assets
+            .iter()
+            .find(|a| crate::archive::strip_extension(&a.name) == Some(stem))
+    })
+}
+
+/// 用 HEAD 请求获取文件大小，服务器没有返回时为 None
+async fn remote_size(ctx: &DownloadContext, url: &str, tls: &TlsTrust) -> Option<u64> {
+    let client = DownloadClient::new(tls, url).ok()?;
+    let resp = ctx.cancellable(client.send(client.http.head(url))).await.ok()?;
+    resp.headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|size| *size > 0)
+}
+
+/// 临时目录放下载的压缩包，安装目录放解压后的文件（扣除会被覆盖的旧版本）
+fn preflight_open_uo(download_size: u64) -> Result<()> {
+    let target_dir = open_uo_dir();
+    let unpacked = (download_size * disk_space::UNPACK_FACTOR).saturating_sub(disk_space::dir_size(&target_dir));
+    disk_space::ensure(&[(&std::env::temp_dir(), download_size), (&target_dir, unpacked)])
+}
+
+/// 并存安装的版本解压到新的目录，需要完整的解压空间
+fn preflight_side_by_side(download_size: u64) -> Result<()> {
+    disk_space::ensure(&[(&std::env::temp_dir(), download_size), (&open_uo_dir(), download_size * disk_space::UNPACK_FACTOR)])
+}
+
+/// 新的可执行文件先下载到临时目录，替换时再复制到当前可执行文件旁边
+fn preflight_launcher(download_size: u64) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let exe_dir = exe.parent().context("无法获取 Launcher 所在目录")?;
+    disk_space::ensure(&[(&std::env::temp_dir(), download_size), (exe_dir, download_size)])
+}
+
+/// 解压前按压缩包实际记录的大小再检查一次
+fn ensure_unpack_space(archive: &Path, target_dir: &Path) -> Result<()> {
+    let unpacked = crate::archive::unpacked_size(archive)?;
+    disk_space::ensure(&[(target_dir, unpacked.saturating_sub(disk_space::dir_size(target_dir)))])
+}
+
+fn fallback_event(failed: &UpdateSource, next: &UpdateSource, error: &anyhow::Error) -> DownloadEvent {
+    DownloadEvent::Fallback {
+        failed: failed.url.clone(),
+        next: next.url.clone(),
+        error: format!("{error:#}"),
+    }
+}
+
+fn get_launcher_asset_name() -> String {
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    return "OpenUO-Launcher-macos-arm64".to_string();
+    
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    return "OpenUO-Launcher-macos-x64".to_string();
+    
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    return "OpenUO-Launcher-linux-x64".to_string();
+    
+    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+    return "OpenUO-Launcher-windows-x64.exe".to_string();
+    
+    #[cfg(not(any(
+        all(target_os = "macos", target_arch = "aarch64"),
+        all(target_os = "macos", target_arch = "x86_64"),
+        all(target_os = "linux", target_arch = "x86_64"),
+        all(target_os = "windows", target_arch = "x86_64")
+    )))]
+    {
+        panic!("不支持的平台");
+    }
+}
+
+/// 分段下载时每段的最小大小，小文件不值得开多个连接
+const MIN_SEGMENT_SIZE: u64 = 4 * 1024 * 1024;
+/// 分段下载的最大连接数
+pub const MAX_DOWNLOAD_CONNECTIONS: u8 = 8;
+
+/// 下载使用的连接数，1 为不分段
+static DOWNLOAD_CONNECTIONS: AtomicU8 = AtomicU8::new(1);
+
+pub fn set_download_connections(connections: u8) {
+    DOWNLOAD_CONNECTIONS.store(connections.clamp(1, MAX_DOWNLOAD_CONNECTIONS), Ordering::Relaxed);
+}
+
+/// 默认的连接超时和读取超时（秒）
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u16 = 8;
+pub const DEFAULT_READ_TIMEOUT_SECS: u16 = 30;
+/// 设置中允许的最大超时（秒）
+pub const MAX_TIMEOUT_SECS: u16 = 600;
+
+static CONNECT_TIMEOUT_SECS: AtomicU16 = AtomicU16::new(DEFAULT_CONNECT_TIMEOUT_SECS);
+static READ_TIMEOUT_SECS: AtomicU16 = AtomicU16::new(DEFAULT_READ_TIMEOUT_SECS);
+
+/// 建立连接和两次收到数据之间最多等待的秒数，0 为默认值；不限制整个请求的时长
+pub fn set_network_timeouts(connect_secs: u16, read_secs: u16) {
+    let clamp = |secs: u16, default: u16| if secs == 0 { default } else { secs.min(MAX_TIMEOUT_SECS) };
+    CONNECT_TIMEOUT_SECS.store(clamp(connect_secs, DEFAULT_CONNECT_TIMEOUT_SECS), Ordering::Relaxed);
+    READ_TIMEOUT_SECS.store(clamp(read_secs, DEFAULT_READ_TIMEOUT_SECS), Ordering::Relaxed);
+}
+
+fn connect_timeout() -> Duration {
+    Duration::from_secs(CONNECT_TIMEOUT_SECS.load(Ordering::Relaxed) as u64)
+}
+
+fn read_timeout() -> Duration {
+    Duration::from_secs(READ_TIMEOUT_SECS.load(Ordering::Relaxed) as u64)
+}
+
+/// 下载用的客户端和需要检查的证书指纹
+#[derive(Clone)]
+struct DownloadClient {
+    http: reqwest::Client,
+    pin: Option<String>,
+}
+
+impl DownloadClient {
+    fn new(tls: &TlsTrust, url: &str) -> Result<Self> {
+        // 大文件下载时间不可预估，只限制连接和单次读取的等待时间
+        let builder = reqwest::Client::builder()
+            .user_agent("OpenUO-Launcher")
+            .connect_timeout(connect_timeout())
+            .read_timeout(read_timeout());
+        let (builder, pin) = tls.apply(builder, url)?;
+        Ok(Self { http: builder.build()?, pin })
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let resp = request.send().await?;
+        tls_trust::verify_pin(self.pin.as_deref(), resp.extensions().get())?;
+        Ok(resp.error_for_status()?)
+    }
+}
+
+/// 下载到 `dest`，临时的网络错误会自动重试（单连接下载从断点继续）
+async fn download_asset(ctx: &DownloadContext, url: &str, dest: &Path, tls: &TlsTrust) -> Result<()> {
+    let client = DownloadClient::new(tls, url)?;
+    crate::retry::download(ctx, &format!("下载 {}", url), || download_asset_once(ctx, &client, url, dest)).await
+}
+
+async fn download_asset_once(ctx: &DownloadContext, client: &DownloadClient, url: &str, dest: &Path) -> Result<()> {
+    // 上次关闭 Launcher 时留下的同一地址的部分文件，用单连接从断点继续
+    let marker = resume_marker(dest);
+    let resume_from = resumable_len(dest, &marker, url);
+    let connections = DOWNLOAD_CONNECTIONS.load(Ordering::Relaxed) as u64;
+    if connections > 1 && resume_from == 0 {
+        match probe_range_support(ctx, client, url).await {
+            Ok(Some((final_url, total))) => {
+                let ranges = split_ranges(total, connections, MIN_SEGMENT_SIZE);
+                if ranges.len() > 1 {
+                    tracing::info!("分 {} 段下载 {} ({} 字节)", ranges.len(), url, total);
+                    // 分段下载的文件预先分配了大小，中断后无法从长度判断断点
+                    fs::remove_file(&marker).ok();
+                    return download_segmented(ctx, client, &final_url, dest, total, ranges).await;
+                }
+            }
+            Ok(None) => tracing::info!("{} 不支持分段下载，使用单连接", url),
+            Err(e) if e.is::<Cancelled>() => return Err(e),
+            Err(e) => tracing::warn!("检测分段下载失败，使用单连接: {:#}", e),
+        }
+    }
+
+    if resume_from > 0 {
+        tracing::info!("从 {} 字节处继续下载 {}", resume_from, url);
+    } else {
+        fs::File::create(dest)?;
+        fs::write(&marker, url)?;
+    }
+    fetch_into(ctx, client, url, dest, 0, None, |received, total| {
+        ctx.emit(DownloadEvent::Progress { received, total });
+    })
+    .await?;
+    fs::remove_file(&marker).ok();
+    Ok(())
+}
+
+/// 记录部分文件对应的下载地址，地址不同（例如新版本）时不能继续
+fn resume_marker(dest: &Path) -> PathBuf {
+    let mut path = dest.as_os_str().to_owned();
+    path.push(".resume");
+    PathBuf::from(path)
+}
+
+/// 可以继续的部分文件的长度，没有时为 0
+fn resumable_len(dest: &Path, marker: &Path, url: &str) -> u64 {
+    if fs::read_to_string(marker).ok().as_deref() != Some(url) {
+        return 0;
+    }
+    fs::metadata(dest).map(|m| m.len()).unwrap_or(0)
+}
+
+/// 把从 `start` 开始的数据（`end` 为空时直到文件末尾）写入 `dest` 中对应的位置，进度回调参数为（已写入字节数，总大小）
+///
+/// 暂停时断开连接，继续后用 Range 从断点重新请求；
+/// 不分段下载时从 `dest` 已有的长度开始（上次关闭 Launcher 时留下的部分文件），服务器不支持 Range 则从头开始。
+async fn fetch_into(
+    ctx: &DownloadContext,
+    client: &DownloadClient,
+    url: &str,
+    dest: &Path,
+    start: u64,
+    end: Option<u64>,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<()> {
+    let mut file = fs::OpenOptions::new().write(true).open(dest)?;
+    let mut pos = match end {
+        Some(_) => start,
+        None => file.metadata()?.len().max(start),
+    };
+    loop {
+        ctx.wait_while_paused().await?;
+        let range = match end {
+            Some(end) => Some(format!("bytes={pos}-{end}")),
+            None if pos > 0 => Some(format!("bytes={pos}-")),
+            None => None,
+        };
+        let mut request = client.http.get(url);
+        if let Some(range) = &range {
+            request = request.header(reqwest::header::RANGE, range);
+        }
+        let mut resp = ctx.cancellable(client.send(request)).await?;
+        let mut total = resp
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+        if range.is_some() {
+            if resp.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+                total = resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_content_range_total)
+                    .unwrap_or(0);
+            } else if end.is_none() {
+                tracing::warn!("{} 不支持断点续传，从头开始下载", url);
+                pos = start;
+                file.set_len(start)?;
+            } else {
+                anyhow::bail!("服务器没有返回请求的分段 {}-{}", start, end.unwrap_or_default());
+            }
+        }
+
+        file.seek(SeekFrom::Start(pos))?;
+        let paused = loop {
+            let chunk = tokio::select! {
+                _ = ctx.paused() => break true,
+                chunk = ctx.cancellable(async { Ok(resp.chunk().await?) }) => chunk?,
+            };
+            let Some(chunk) = chunk else {
+                break false;
+            };
+            if let Some(end) = end
+                && pos + chunk.len() as u64 > end + 1
+            {
+                anyhow::bail!("分段 {}-{} 返回了多余的数据", start, end);
+            }
+            file.write_all(&chunk)?;
+            pos += chunk.len() as u64;
+            on_progress(pos - start, total);
+        };
+        if !paused {
+            break;
+        }
+        tracing::info!("下载已暂停: {}", url);
+    }
+    if let Some(end) = end
+        && pos != end + 1
+    {
+        anyhow::bail!("分段 {}-{} 下载不完整", start, end);
+    }
+    Ok(())
+}
+
+/// 请求第一个字节，服务器支持 Range 时返回跳转后的地址和文件大小
+async fn probe_range_support(
+    ctx: &DownloadContext,
+    client: &DownloadClient,
+    url: &str,
+) -> Result<Option<(String, u64)>> {
+    let resp = ctx
+        .cancellable(client.send(client.http.get(url).header(reqwest::header::RANGE, "bytes=0-0")))
+        .await?;
+    if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Ok(None);
+    }
+    let total = resp
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_content_range_total);
+    Ok(total.map(|total| (resp.url().to_string(), total)))
+}
+
+/// 解析 `Content-Range: bytes 0-0/12345` 中的总大小
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    let (unit, rest) = value.trim().split_once(' ')?;
+    if !unit.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    rest.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// 把 `[0, total)` 切成最多 `segments` 段，每段不小于 `min_size`，返回闭区间
+fn split_ranges(total: u64, segments: u64, min_size: u64) -> Vec<(u64, u64)> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let count = segments.min(total / min_size.max(1)).max(1);
+    let size = total.div_ceil(count);
+    (0..count)
+        .map(|i| (i * size, ((i + 1) * size).min(total) - 1))
+        .filter(|(start, end)| start <= end)
+        .collect()
+}
+
+/// 多个连接同时下载各自的区间，直接写入文件中对应的位置
+async fn download_segmented(
+    ctx: &DownloadContext,
+    client: &DownloadClient,
+    url: &str,
+    dest: &Path,
+    total: u64,
+    ranges: Vec<(u64, u64)>,
+) -> Result<()> {
+    fs::File::create(dest)?.set_len(total)?;
+    let received = Arc::new(AtomicU64::new(0));
+    let mut segments = tokio::task::JoinSet::new();
+    for (start, end) in ranges {
+        let (ctx, client, url, dest, received) =
+            (ctx.clone(), client.clone(), url.to_string(), dest.to_path_buf(), received.clone());
+        segments.spawn(async move { download_range(&ctx, &client, &url, &dest, (start, end), total, &received).await });
+    }
+    // 任意一段失败时丢弃 JoinSet，其余分段随之中止
+    while let Some(result) = segments.join_next().await {
+        result??;
+    }
+    Ok(())
+}
+
+async fn download_range(
+    ctx: &DownloadContext,
+    client: &DownloadClient,
+    url: &str,
+    dest: &Path,
+    (start, end): (u64, u64),
+    total: u64,
+    received: &AtomicU64,
+) -> Result<()> {
+    let mut reported = 0;
+    fetch_into(ctx, client, url, dest, start, Some(end), |done, _| {
+        // 各分段的进度合并后上报
+        let delta = done - reported;
+        reported = done;
+        let all = received.fetch_add(delta, Ordering::Relaxed) + delta;
+        ctx.emit(DownloadEvent::Progress { received: all, total });
+    })
+    .await
+}
+
+/// 校验下载的资产，release 没有提供校验值时跳过
+///
+/// 校验值优先取资产自身的 digest（GitHub API 或简化格式中的 sha256），
+/// 其次取 release 中附带的 checksums 文件。
+///
+/// `required` 为 true 时（替换 Launcher 自身），既没有 SHA-256 校验值也没有签名的资产会被拒绝。
+fn verify_download(release: &GithubRelease, asset: &GithubAsset, path: &Path, required: bool) -> Result<()> {
+    let expected = match asset.digest.as_deref().and_then(crate::checksum::normalize_hash) {
+        Some(hash) => Some(hash),
+        None => fetch_checksum_from_release(release, &asset.name)?,
+    };
+    let hashed = match expected {
+        Some(expected) => {
+            crate::checksum::verify(path, &expected)?;
+            tracing::info!("{} SHA-256 校验通过", asset.name);
+            true
+        }
+        None => {
+            tracing::warn!("{} 没有提供 SHA-256 校验值，跳过校验", asset.name);
+            false
+        }
+    };
+    let signed = verify_signature(release, asset, path)?;
+    if required && !hashed && !signed {
+        anyhow::bail!("{} 没有 SHA-256 校验值或签名，已拒绝安装", asset.name);
+    }
+    Ok(())
+}
+
+/// 配置了签名公钥时校验资产的 minisign 签名，没有签名或签名无效都视为错误；返回是否校验了签名
+fn verify_signature(release: &GithubRelease, asset: &GithubAsset, path: &Path) -> Result<bool> {
+    let Some(key) = update_public_key()? else {
+        return Ok(false);
+    };
+    let signature = match &asset.signature {
+        Some(signature) => signature.clone(),
+        None => fetch_signature_from_release(release, &asset.name)?
+            .with_context(|| format!("{} 没有签名，已拒绝安装", asset.name))?,
+    };
+    crate::signature::verify_file(path, &key, &signature)?;
+    tracing::info!("{} 签名校验通过", asset.name);
+    Ok(true)
+}
+
+/// 更新签名公钥，见 `signature::trusted_key`
+fn update_public_key() -> Result<Option<minisign_verify::PublicKey>> {
+    let configured = load_update_source_config().and_then(|c| c.public_key);
+    crate::signature::trusted_key(configured.as_deref())
+}
+
+/// 下载 release 中附带的 `<资产名>.minisig`
+fn fetch_signature_from_release(release: &GithubRelease, asset_name: &str) -> Result<Option<String>> {
+    let signature_name = format!("{asset_name}.minisig");
+    let Some(signature) = release.assets.iter().find(|a| a.name == signature_name) else {
+        return Ok(None);
+    };
+    fetch_text(&signature.browser_download_url, &release.tls)
+        .with_context(|| format!("无法下载签名文件 {}", signature.name))
+        .map(Some)
+}
+
+pub fn fetch_text(url: &str, tls: &TlsTrust) -> Result<String> {
+    let (client, pin) = blocking_client(tls, url)?;
+    let resp = client.get(url).send()?;
+    tls_trust::verify_pin(pin.as_deref(), resp.extensions().get())?;
+    read_body(resp.error_for_status()?)
+}
+
+/// 按块读取响应体，慢速网络上只要数据还在持续到达就不会超时
+fn read_body(mut resp: reqwest::blocking::Response) -> Result<String> {
+    let mut body = String::new();
+    resp.read_to_string(&mut body)?;
+    Ok(body)
+}
+
+/// release 中附带了校验文件却找不到对应条目时视为错误
+fn fetch_checksum_from_release(release: &GithubRelease, asset_name: &str) -> Result<Option<String>> {
+    let Some(checksums) = release
+        .assets
+        .iter()
+        .find(|a| CHECKSUM_ASSET_NAMES.iter().any(|n| a.name.eq_ignore_ascii_case(n)))
+    else {
+        return Ok(None);
+    };
+    let content = fetch_text(&checksums.browser_download_url, &release.tls).context("无法下载校验文件")?;
+    crate::checksum::find_in_checksums(&content, asset_name)
+        .map(Some)
+        .with_context(|| format!("{} 中没有 {} 的校验值", checksums.name, asset_name))
+}
+
+fn write_open_uo_version(tag: &str, dir: &Path) -> Result<()> {
+    let path = dir.join(OPEN_UO_VERSION_FILE);
+    fs::write(path, tag)?;
+    fs::write(dir.join(OPEN_UO_INSTALLED_FILE), crate::clock::now_unix().to_string())?;
+    Ok(())
+}
+
+/// OpenUO 的安装时间（Unix 时间戳）；旧版本安装的没有记录，使用版本记录的修改时间
+pub fn read_open_uo_installed_at_in(dir: &Path) -> Option<i64> {
+    if let Some(at) = fs::read_to_string(dir.join(OPEN_UO_INSTALLED_FILE)).ok().and_then(|s| s.trim().parse().ok()) {
+        return Some(at);
+    }
+    let modified = fs::metadata(dir.join(OPEN_UO_VERSION_FILE)).ok()?.modified().ok()?;
+    Some(modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64)
+}
+
+pub fn read_open_uo_version_file() -> Option<String> {
+    read_open_uo_version_in(&open_uo_dir())
+}
+
+/// 指定 OpenUO 目录中记录的版本（也用于并存安装的版本）
+pub fn read_open_uo_version_in(dir: &Path) -> Option<String> {
+    fs::read_to_string(dir.join(OPEN_UO_VERSION_FILE)).ok().map(|s| s.trim().to_string())
+}
+
+pub fn detect_open_uo_version() -> Option<String> {
+    let exe = crate::config::open_uo_binary_path();
+    if !exe.exists() {
+        return None;
+    }
+    if let Some(ver) = read_open_uo_version_file() {
+        return Some(ver);
+    }
+    Some("已安装 (版本未知)".to_string())
+}
+
+/// 获取 OpenUO 的最新版本和更新清单中声明的最低版本
+pub fn fetch_open_uo_requirements() -> Result<(String, Option<String>)> {
+    let release = with_fallback(&get_openuo_update_sources(), fetch_latest_release, |_, _, _| {})?;
+    Ok((get_version_string(&release), release.required_version))
+}
+
+pub fn trigger_update_check_impl(open_uo: bool, launcher: bool) -> mpsc::Receiver<UpdateEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        // 先更新配置，本次检查就使用新的更新源
+        if crate::bootstrap::current().is_some() {
+            let _ = tx.send(UpdateEvent::Bootstrap(crate::bootstrap::refresh().map_err(|e| format!("{e:#}"))));
+        }
+        let mut sources = Vec::new();
+        if open_uo {
+            sources.extend(get_openuo_update_sources());
+        }
+        if launcher {
+            sources.extend(get_launcher_update_sources());
+        }
+        for source in sources.into_iter().filter(|s| s.tls.is_custom()) {
+            let _ = tx.send(UpdateEvent::CustomTrust {
+                ca_cert: source.tls.ca_path().map(|p| p.display().to_string()),
+                pin: source.tls.pin_sha256.clone(),
+                url: source.url,
+            });
+        }
+        if open_uo {
+            let mut rate_limited = false;
+            let sources = get_openuo_update_sources();
+            let result = fetch_latest_or_cached(&sources, |retry_after, cached| {
+                rate_limited = !cached;
+                let _ = tx.send(UpdateEvent::RateLimited { open_uo: true, retry_after, cached });
+            });
+            match result {
+                Ok(release) => {
+                    let latest = get_version_string(&release);
+                    let _ = tx.send(UpdateEvent::OpenUO(Ok(latest.clone())));
+                    if let Some(at) = release.published_at.as_deref().and_then(crate::clock::parse_rfc3339) {
+                        let _ = tx.send(UpdateEvent::Published { open_uo: true, at });
+                    }
+                    if let Some(required) = release.required_version {
+                        let _ = tx.send(UpdateEvent::RequiredOpenUO(required));
+                    }
+                    if let Some(package) = release.data_package {
+                        let _ = tx.send(UpdateEvent::DataPackage(package));
+                    }
+                    let _ = tx.send(UpdateEvent::Addons(release.addons));
+                    let _ = tx.send(UpdateEvent::ShardEvents(release.events));
+                    let _ = tx.send(UpdateEvent::VoteSites(release.votes));
+                    let _ = tx.send(UpdateEvent::ShardRules(release.rules));
+                    let _ = tx.send(UpdateEvent::StaffMode(release.staff.unwrap_or_default()));
+                    if let Some(notes) = release.body.filter(|b| !b.trim().is_empty()) {
+                        let _ = tx.send(UpdateEvent::OpenUONotes(notes));
+                    }
+                    if let Some(chain) = fetch_release_chain(&latest) {
+                        release_chain::record(&chain);
+                        let _ = tx.send(UpdateEvent::OpenUOChain(chain));
+                    }
+                }
+                // 限流且没有缓存时 RateLimited 事件已经说明了原因
+                Err(_) if rate_limited => {}
+                Err(e) => {
+                    let _ = tx.send(UpdateEvent::OpenUO(Err(format!("{e:#}"))));
+                }
+            }
+        }
+        if launcher {
+            let mut rate_limited = false;
+            let sources = get_launcher_update_sources();
+            let result = fetch_latest_or_cached(&sources, |retry_after, cached| {
+                rate_limited = !cached;
+                let _ = tx.send(UpdateEvent::RateLimited { open_uo: false, retry_after, cached });
+            });
+            match result {
+                Ok(release) => {
+                    let _ = tx.send(UpdateEvent::Launcher(Ok(get_version_string(&release))));
+                    if let Some(at) = release.published_at.as_deref().and_then(crate::clock::parse_rfc3339) {
+                        let _ = tx.send(UpdateEvent::Published { open_uo: false, at });
+                    }
+                    if let Some(notes) = release.body.filter(|b| !b.trim().is_empty()) {
+                        let _ = tx.send(UpdateEvent::LauncherNotes(notes));
+                    }
+                }
+                // 限流且没有缓存时 RateLimited 事件已经说明了原因
+                Err(_) if rate_limited => {}
+                Err(e) => {
+                    let _ = tx.send(UpdateEvent::Launcher(Err(format!("{e:#}"))));
+                }
+            }
+        }
+        let _ = tx.send(UpdateEvent::Done);
+    });
+    rx
+}
+
+/// 获取已安装版本到最新版本之间的 release，已是最新或无法确定时返回 None
+fn fetch_release_chain(latest: &str) -> Option<ReleaseChain> {
+    let installed = read_open_uo_version_file().filter(|v| v != latest)?;
+    let releases = fetch_open_uo_releases()
+        .inspect_err(|e| tracing::warn!("无法获取版本列表: {:#}", e))
+        .ok()?;
+    let list: Vec<ChainRelease> = releases
+        .into_iter()
+        .map(|release| ChainRelease {
+            version: get_version_string(&release),
+            published_at: release.published_at,
+            notes: release.body,
+        })
+        .collect();
+    ReleaseChain::build(&list, &installed, latest)
 }
 
 // 从 release 中提取版本字符串
@@ -461,3 +2153,392 @@ fn get_version_string(release: &GithubRelease) -> String {
     // 直接使用 release 的 name 字段作为版本号
     release.name.clone()
 }
+
+// 按数字逐段比较版本号，remote 比 local 新时返回 true
+pub fn version_newer(remote: &str, local: &str) -> bool {
+    let parse = |s: &str| -> Vec<u32> {
+        s.split(['.', '-'])
+            .filter_map(|p| p.parse::<u32>().ok())
+            .collect()
+    };
+    let r = parse(remote);
+    let l = parse(local);
+    let len = r.len().max(l.len());
+    for i in 0..len {
+        let rv = *r.get(i).unwrap_or(&0);
+        let lv = *l.get(i).unwrap_or(&0);
+        if rv != lv {
+            return rv > lv;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_split_ranges() {
+        const MB: u64 = 1024 * 1024;
+        assert_eq!(split_ranges(10, 4, 1), vec![(0, 2), (3, 5), (6, 8), (9, 9)]);
+        assert_eq!(split_ranges(100 * MB, 4, 4 * MB).len(), 4);
+        // 文件太小时不分段
+        assert_eq!(split_ranges(6 * MB, 4, 4 * MB), vec![(0, 6 * MB - 1)]);
+        assert_eq!(split_ranges(12 * MB, 8, 4 * MB).len(), 3);
+        assert!(split_ranges(0, 4, 1).is_empty());
+
+        let ranges = split_ranges(1_000_003, 7, 1);
+        assert_eq!(ranges.first().unwrap().0, 0);
+        assert_eq!(ranges.last().unwrap().1, 1_000_002);
+        assert!(ranges.windows(2).all(|w| w[0].1 + 1 == w[1].0));
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(parse_content_range_total("bytes 0-0/12345"), Some(12345));
+        assert_eq!(parse_content_range_total("bytes 0-0/*"), None);
+        assert_eq!(parse_content_range_total("items 0-0/5"), None);
+    }
+
+    #[test]
+    fn test_resumable_len() {
+        let dir = temp_dir("resume");
+        let dest = dir.join("OpenUO.zip");
+        let marker = resume_marker(&dest);
+        assert_eq!(marker.file_name(), Some(std::ffi::OsStr::new(&format!("{}.resume", dest.file_name().unwrap().to_string_lossy()))));
+        fs::write(&dest, b"12345").unwrap();
+        assert_eq!(resumable_len(&dest, &marker, "https://a/v1.zip"), 0);
+        fs::write(&marker, "https://a/v1.zip").unwrap();
+        assert_eq!(resumable_len(&dest, &marker, "https://a/v1.zip"), 5);
+        // 新版本的地址不同，从头下载
+        assert_eq!(resumable_len(&dest, &marker, "https://a/v2.zip"), 0);
+        fs::remove_file(&dest).unwrap();
+        assert_eq!(resumable_len(&dest, &marker, "https://a/v1.zip"), 0);
+    }
+
+    #[test]
+    fn test_build_sources() {
+        let mirrors = vec![
+            MirrorEntry::Url("https://mirror-a/latest.json".to_string()),
+            MirrorEntry::Detailed {
+                url: "https://api.example/releases/latest".to_string(),
+                use_github_format: Some(true),
+                format: None,
+                tls: TlsTrust::default(),
+            },
+            MirrorEntry::Url("https://cdn/latest.json".to_string()),
+            MirrorEntry::Url(" ".to_string()),
+            MirrorEntry::Detailed {
+                url: "https://git.example/api/v1/repos/shard/openuo/releases/latest".to_string(),
+                use_github_format: Some(true),
+                format: Some(ReleaseFormat::Gitea),
+                tls: TlsTrust::default(),
+            },
+        ];
+        let tls = TlsTrust::default();
+        let sources =
+            build_sources(Some("https://cdn/latest.json".to_string()), OPEN_UO_RELEASE_URL, ReleaseFormat::Simple, &tls, &mirrors);
+        let urls: Vec<_> = sources.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://cdn/latest.json",
+                "https://mirror-a/latest.json",
+                "https://api.example/releases/latest",
+                "https://git.example/api/v1/repos/shard/openuo/releases/latest"
+            ]
+        );
+        assert_eq!(sources[1].format, ReleaseFormat::Simple);
+        assert_eq!(sources[2].format, ReleaseFormat::Github);
+        // format 优先于 use_github_format
+        assert_eq!(sources[3].format, ReleaseFormat::Gitea);
+
+        let sources = build_sources(None, OPEN_UO_RELEASE_URL, ReleaseFormat::Github, &tls, &[]);
+        assert_eq!(
+            sources,
+            [UpdateSource { url: OPEN_UO_RELEASE_URL.to_string(), format: ReleaseFormat::Github, tls: TlsTrust::default() }]
+        );
+    }
+
+    #[test]
+    fn test_source_tls_config() {
+        let config: UpdateSourceConfig = serde_json::from_str(
+            r#"{
+                "openuo_url": "https://updates.shard.example/openuo.json",
+                "use_github_format": false,
+                "ca_cert": "shard-ca.pem",
+                "openuo_mirrors": [
+                    "https://mirror.example/openuo.json",
+                    { "url": "https://backup.shard.example/openuo.json", "pin_sha256": "AB:CD" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let sources = build_sources(config.openuo_url, OPEN_UO_RELEASE_URL, ReleaseFormat::Simple, &config.tls, &config.openuo_mirrors);
+        assert_eq!(sources[0].tls.ca_cert.as_deref(), Some("shard-ca.pem"));
+        assert_eq!(sources[0].tls.host.as_deref(), Some("updates.shard.example"));
+        assert!(!sources[1].tls.is_custom());
+        assert_eq!(sources[2].tls.pin_sha256.as_deref(), Some("AB:CD"));
+        assert_eq!(sources[2].tls.host.as_deref(), Some("backup.shard.example"));
+
+        // 没有自定义主源时，顶层设置不作用于默认的 GitHub 地址
+        let sources = build_sources(None, LAUNCHER_RELEASE_URL, ReleaseFormat::Github, &config.tls, &[]);
+        assert!(!sources[0].tls.is_custom());
+    }
+
+    #[test]
+    fn test_release_api_url() {
+        use ReleaseFormat::*;
+        assert_eq!(
+            release_api_url(Github, OPEN_UO_RELEASE_URL, Some("v1.2.0")).as_deref(),
+            Some("https://api.github.com/repos/openuo-online/OpenUO/releases/tags/v1.2.0")
+        );
+        assert_eq!(
+            release_api_url(Github, "https://api.github.com/repos/a/b/releases/latest/", None).as_deref(),
+            Some("https://api.github.com/repos/a/b/releases")
+        );
+        assert_eq!(
+            release_api_url(Gitea, "https://git.example/api/v1/repos/a/b/releases/latest", Some("v1")).as_deref(),
+            Some("https://git.example/api/v1/repos/a/b/releases/tags/v1")
+        );
+        let gitlab = "https://gitlab.example/api/v4/projects/shard%2Fopenuo/releases/permalink/latest";
+        assert_eq!(
+            release_api_url(Gitlab, gitlab, Some("stable/v1")).as_deref(),
+            Some("https://gitlab.example/api/v4/projects/shard%2Fopenuo/releases/stable%2Fv1")
+        );
+        assert_eq!(
+            release_api_url(Gitlab, gitlab, None).as_deref(),
+            Some("https://gitlab.example/api/v4/projects/shard%2Fopenuo/releases")
+        );
+        assert_eq!(release_api_url(Gitlab, "https://gitlab.example/api/v4/projects/1/releases/latest", None), None);
+        assert_eq!(release_api_url(Simple, "https://cdn/latest.json", Some("v1")), None);
+    }
+
+    #[test]
+    fn test_parse_simple_versions() {
+        let source = UpdateSource {
+            url: "https://cdn.example/openuo/latest.json".to_string(),
+            format: ReleaseFormat::Simple,
+            tls: TlsTrust::default(),
+        };
+        let body = r#"{
+            "version": "v1.2.0",
+            "download_url": "https://cdn.example/openuo/v1.2.0.zip",
+            "sha256": "AA11",
+            "required_version": "v1.1.0",
+            "data_package": { "version": "7.0.102", "url": "https://cdn.example/uo-data.zip", "sha256": { "osx-arm64": "cc33" } },
+            "addons": [
+                { "id": "music", "name": "Music", "version": "2", "url": "https://cdn.example/music.zip" },
+                { "id": "../sound", "version": "1", "url": "https://cdn.example/sound.zip" }
+            ],
+            "events": [{ "title": "Siege of Vesper", "starts_at": "2024-06-01T18:00:00Z", "url": "https://shard.example/events/siege" }],
+            "votes": [{ "name": "Top 200", "url": "https://top200.example/vote/1", "cooldown_minutes": 720 }],
+            "rules": { "version": "3", "title": "Shard rules", "text": "- No multi-boxing in PvP" },
+            "staff": { "test_center": "tc.cdn.example:2594", "presets": [{ "name": "GM", "args": "-gm" }] },
+            "versions": [
+                { "version": "v1.2.0", "download_url": "https://cdn.example/openuo/duplicate.zip" },
+                {
+                    "version": "v1.1.0",
+                    "download_url": "https://cdn.example/openuo/v1.1.0.zip",
+                    "sha256": "bb22",
+                    "notes": "Older build",
+                    "published_at": "2024-04-01T00:00:00Z",
+                    "files_url": "https://cdn.example/openuo/v1.1.0/files.json"
+                },
+                { "version": "v1.0.0", "download_url": { "osx-arm64": null } }
+            ]
+        }"#;
+        let releases = parse_simple_versions(&source, body).unwrap();
+        let tags: Vec<_> = releases.iter().map(|r| r.tag_name.as_str()).collect();
+        assert_eq!(tags, ["v1.2.0", "v1.1.0"]);
+        assert_eq!(releases[0].required_version.as_deref(), Some("v1.1.0"));
+        assert_eq!(releases[0].assets[0].browser_download_url, "https://cdn.example/openuo/v1.2.0.zip");
+        let package = releases[0].data_package.as_ref().unwrap();
+        assert_eq!((package.version.as_str(), package.url.as_str()), ("7.0.102", "https://cdn.example/uo-data.zip"));
+        assert!(releases[1].data_package.is_none());
+        // id 不能用作文件名的附加包被忽略
+        let addons: Vec<_> = releases[0].addons.iter().map(|a| (a.id.as_str(), a.name.as_str(), a.package.version.as_str())).collect();
+        assert_eq!(addons, [("music", "Music", "2")]);
+        assert_eq!(releases[0].events[0].starts(), Some(1_717_264_800));
+        assert!(releases[1].events.is_empty());
+        assert_eq!(releases[0].votes[0].name, "Top 200");
+        assert_eq!(releases[0].rules.as_ref().map(|r| r.version.as_str()), Some("3"));
+        assert!(releases[1].rules.is_none());
+        let staff = releases[0].staff.as_ref().unwrap();
+        assert!(!staff.enabled);
+        assert_eq!(staff.presets[0].args, "-gm");
+        assert_eq!(releases[1].assets[0].digest.as_deref(), Some("sha256:bb22"));
+        assert_eq!(releases[1].body.as_deref(), Some("Older build"));
+        assert_eq!(releases[1].published_at.as_deref(), Some("2024-04-01T00:00:00Z"));
+        // 每个版本有自己的文件清单，用于校验已安装的旧版本
+        assert_eq!(releases[0].files_url, None);
+        assert_eq!(releases[1].files_url.as_deref(), Some("https://cdn.example/openuo/v1.1.0/files.json"));
+
+        // 只有最新版本的旧格式仍然可用
+        let latest = parse_release(&source, r#"{ "version": "v1.2.0", "download_url": "https://cdn.example/a.zip" }"#).unwrap();
+        assert_eq!(latest.tag_name, "v1.2.0");
+    }
+
+    #[test]
+    fn test_parse_gitlab_release() {
+        let source = UpdateSource {
+            url: "https://gitlab.example/api/v4/projects/7/releases/permalink/latest".to_string(),
+            format: ReleaseFormat::Gitlab,
+            tls: TlsTrust::default(),
+        };
+        let body = r#"{
+            "tag_name": "v1.3.0",
+            "name": "",
+            "description": "Fixes",
+            "released_at": "2024-05-01T10:00:00Z",
+            "commit": { "id": "abc123" },
+            "assets": {
+                "count": 2,
+                "sources": [{ "format": "zip", "url": "https://gitlab.example/shard/openuo/-/archive/v1.3.0.zip" }],
+                "links": [
+                    { "name": "linux-x64.zip", "url": "https://cdn.example/linux-x64.zip", "direct_asset_url": "https://gitlab.example/shard/openuo/-/releases/v1.3.0/downloads/linux-x64.zip" },
+                    { "name": "checksums.txt", "url": "https://cdn.example/checksums.txt" }
+                ]
+            }
+        }"#;
+        let release = parse_release(&source, body).unwrap();
+        assert_eq!(release.name, "v1.3.0");
+        assert_eq!(release.body.as_deref(), Some("Fixes"));
+        assert_eq!(release.target_commitish.as_deref(), Some("abc123"));
+        let urls: Vec<_> = release.assets.iter().map(|a| a.browser_download_url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://gitlab.example/shard/openuo/-/releases/v1.3.0/downloads/linux-x64.zip",
+                "https://cdn.example/checksums.txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_fallback() {
+        let sources = build_sources(
+            Some("a".to_string()),
+            "",
+            ReleaseFormat::Simple,
+            &TlsTrust::default(),
+            &[MirrorEntry::Url("b".to_string()), MirrorEntry::Url("c".to_string())],
+        );
+        let mut fallbacks = Vec::new();
+        let used = with_fallback(
+            &sources,
+            |s| if s.url == "b" { Ok(s.url.clone()) } else { anyhow::bail!("down") },
+            |failed, next, _| fallbacks.push((failed.url.clone(), next.url.clone())),
+        )
+        .unwrap();
+        assert_eq!(used, "b");
+        assert_eq!(fallbacks, [("a".to_string(), "b".to_string())]);
+
+        let err = with_fallback(&sources, |_| -> Result<()> { anyhow::bail!("down") }, |_, _, _| {});
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        use reqwest::StatusCode;
+        use reqwest::header::{HeaderMap, HeaderValue};
+        let headers = |pairs: &[(&'static str, &str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(*name, HeaderValue::from_str(value).unwrap());
+            }
+            map
+        };
+        let now = 1_700_000_000;
+
+        let exhausted = headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "1700000600")]);
+        assert_eq!(rate_limit_wait(StatusCode::FORBIDDEN, &exhausted, now), Some(Duration::from_secs(600)));
+        // 已经过了解除时间
+        assert_eq!(rate_limit_wait(StatusCode::FORBIDDEN, &exhausted, now + 700), Some(Duration::ZERO));
+        // Retry-After 优先
+        let retry_after = headers(&[("retry-after", "90"), ("x-ratelimit-remaining", "0")]);
+        assert_eq!(rate_limit_wait(StatusCode::TOO_MANY_REQUESTS, &retry_after, now), Some(Duration::from_secs(90)));
+        let no_reset = headers(&[("x-ratelimit-remaining", "0")]);
+        assert_eq!(rate_limit_wait(StatusCode::FORBIDDEN, &no_reset, now), Some(DEFAULT_RATE_LIMIT_WAIT));
+
+        // 真正的拒绝访问、还有剩余次数、成功的响应都不是限流
+        assert_eq!(rate_limit_wait(StatusCode::FORBIDDEN, &HeaderMap::new(), now), None);
+        let remaining = headers(&[("x-ratelimit-remaining", "12")]);
+        assert_eq!(rate_limit_wait(StatusCode::FORBIDDEN, &remaining, now), None);
+        assert_eq!(rate_limit_wait(StatusCode::OK, &exhausted, now), None);
+    }
+
+    #[test]
+    fn test_github_token_only_for_api() {
+        assert!(is_github_api("https://api.github.com/repos/openuo-online/OpenUO/releases/latest"));
+        assert!(!is_github_api("http://api.github.com/repos/a/b/releases/latest"));
+        assert!(!is_github_api("https://mirror.example.com/api.github.com/releases/latest"));
+        assert!(!is_github_api("https://github.com/openuo-online/OpenUO/releases/download/v1/a.zip"));
+        assert!(!is_github_api("not a url"));
+    }
+
+    #[test]
+    fn test_github_token_redacted() {
+        let token = GithubToken::new("  ghp_secret\n");
+        assert_eq!(token.as_str(), "ghp_secret");
+        assert_eq!(format!("{:?}", token), "GithubToken(***)");
+        assert_eq!(format!("{:?}", GithubToken::default()), "GithubToken(<empty>)");
+    }
+
+    #[test]
+    fn test_launcher_update_requires_verification() {
+        let dir = temp_dir("verify");
+        let path = dir.join("launcher");
+        fs::write(&path, b"launcher").unwrap();
+        let mut asset = GithubAsset {
+            name: "launcher".to_string(),
+            browser_download_url: String::new(),
+            size: 8,
+            digest: None,
+            signature: None,
+        };
+        let release = GithubRelease {
+            tag_name: "v2.0".to_string(),
+            name: "v2.0".to_string(),
+            assets: vec![asset.clone()],
+            body: None,
+            published_at: None,
+            target_commitish: None,
+            required_version: None,
+            patches: Vec::new(),
+            files_url: None,
+            data_package: None,
+            addons: Vec::new(),
+            events: Vec::new(),
+            votes: Vec::new(),
+            rules: None,
+            staff: None,
+            tls: TlsTrust::default(),
+        };
+        // OpenUO 安装包没有校验值时只记录警告，Launcher 自身的更新则拒绝
+        assert!(verify_download(&release, &asset, &path, false).is_ok());
+        assert!(verify_download(&release, &asset, &path, true).is_err());
+
+        asset.digest = Some(format!("sha256:{}", crate::checksum::sha256_bytes(b"launcher")));
+        assert!(verify_download(&release, &asset, &path, true).is_ok());
+        asset.digest = Some(format!("sha256:{}", crate::checksum::sha256_bytes(b"tampered")));
+        assert!(verify_download(&release, &asset, &path, true).is_err());
+    }
+
+    #[test]
+    fn test_installed_at() {
+        let dir = temp_dir("installed");
+        assert_eq!(read_open_uo_installed_at_in(&dir), None);
+        // 旧版本只写了版本记录
+        fs::write(dir.join(OPEN_UO_VERSION_FILE), "v1.0").unwrap();
+        let legacy = read_open_uo_installed_at_in(&dir).unwrap();
+        assert!((crate::clock::now_unix() - legacy).abs() < 60);
+
+        write_open_uo_version("v1.1", &dir).unwrap();
+        fs::write(dir.join(OPEN_UO_INSTALLED_FILE), "1700000000").unwrap();
+        assert_eq!(read_open_uo_installed_at_in(&dir), Some(1_700_000_000));
+        assert_eq!(read_open_uo_version_in(&dir).as_deref(), Some("v1.1"));
+    }
+}
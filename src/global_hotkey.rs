@@ -0,0 +1,196 @@
+use anyhow::Result;
+use std::fmt;
+use std::sync::mpsc;
+
+/// 当前平台是否支持全局快捷键
+pub const SUPPORTED: bool = cfg!(target_os = "windows");
+
+/// 系统级快捷键，例如 Ctrl+Alt+U；同时按住 Shift 时启动当前档案
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub win: bool,
+    /// 大写字母、数字或 F1-F24
+    pub key: String,
+}
+
+/// 按下快捷键后要做的事
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum HotkeyAction {
+    /// 恢复并激活 Launcher 窗口
+    Show,
+    /// 恢复窗口并启动当前档案
+    Launch,
+}
+
+impl Hotkey {
+    pub fn parse(text: &str) -> Result<Hotkey> {
+        let mut hotkey = Hotkey { ctrl: false, alt: false, win: false, key: String::new() };
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = parts.split_last() else {
+            anyhow::bail!("快捷键为空");
+        };
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => hotkey.ctrl = true,
+                "alt" => hotkey.alt = true,
+                "win" | "super" | "meta" => hotkey.win = true,
+                "shift" => anyhow::bail!("Shift 用于启动当前档案，不能用在快捷键中"),
+                _ => anyhow::bail!("无法识别的修饰键: {}", modifier),
+            }
+        }
+        if !(hotkey.ctrl || hotkey.alt || hotkey.win) {
+            anyhow::bail!("快捷键至少需要 Ctrl、Alt 或 Win 中的一个");
+        }
+        let key = key.to_uppercase();
+        let valid = match key.strip_prefix('F') {
+            Some(n) if !n.is_empty() => n.parse::<u8>().is_ok_and(|n| (1..=24).contains(&n)),
+            _ => key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()),
+        };
+        if !valid {
+            anyhow::bail!("无法识别的按键: {}", key);
+        }
+        hotkey.key = key;
+        Ok(hotkey)
+    }
+
+    /// Windows 虚拟键码：字母和数字与 ASCII 相同，F1 为 0x70
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn virtual_key(&self) -> u32 {
+        match self.key.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+            Some(n) => 0x70 + n - 1,
+            None => self.key.as_bytes()[0] as u32,
+        }
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [(self.ctrl, "Ctrl"), (self.alt, "Alt"), (self.win, "Win")] {
+            if on {
+                write!(f, "{}+", name)?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+/// 已注册的快捷键，drop 时注销
+pub struct Registration {
+    rx: mpsc::Receiver<HotkeyAction>,
+    #[cfg(target_os = "windows")]
+    thread_id: u32,
+    #[cfg(target_os = "windows")]
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Registration {
+    pub fn try_recv(&self) -> Option<HotkeyAction> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// 在后台线程注册快捷键和启动用的 Shift 组合；快捷键已被其他程序占用时返回错误
+#[cfg(target_os = "windows")]
+pub fn register(hotkey: &Hotkey) -> Result<Registration> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, RegisterHotKey, UnregisterHotKey,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetMessageW, MSG, PM_NOREMOVE, PeekMessageW, WM_HOTKEY, WM_USER};
+
+    const SHOW_ID: i32 = 1;
+    const LAUNCH_ID: i32 = 2;
+
+    let mut modifiers = MOD_NOREPEAT;
+    for (on, modifier) in [(hotkey.ctrl, MOD_CONTROL), (hotkey.alt, MOD_ALT), (hotkey.win, MOD_WIN)] {
+        if on {
+            modifiers |= modifier;
+        }
+    }
+    let vk = hotkey.virtual_key();
+    let name = hotkey.to_string();
+    let (tx, rx) = mpsc::channel();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        let mut msg = MSG::default();
+        // 先建立消息队列，注销时才能收到 WM_QUIT
+        unsafe {
+            let _ = PeekMessageW(&mut msg, HWND::default(), WM_USER, WM_USER, PM_NOREMOVE);
+        }
+        let registered = unsafe { RegisterHotKey(HWND::default(), SHOW_ID, modifiers, vk) }
+            .map_err(|e| anyhow::anyhow!("无法注册快捷键 {}，可能已被其他程序占用: {}", name, e));
+        if let Err(e) = registered {
+            let _ = ready_tx.send(Err(e));
+            return;
+        }
+        // 启动用的组合注册失败时只保留显示窗口
+        if let Err(e) = unsafe { RegisterHotKey(HWND::default(), LAUNCH_ID, modifiers | MOD_SHIFT, vk) } {
+            tracing::warn!("Failed to register Shift+{}: {}", name, e);
+        }
+        let _ = ready_tx.send(Ok(unsafe { GetCurrentThreadId() }));
+        // 收到 WM_QUIT 时 GetMessageW 返回 0
+        while unsafe { GetMessageW(&mut msg, HWND::default(), 0, 0) }.as_bool() {
+            if msg.message != WM_HOTKEY {
+                continue;
+            }
+            let action = if msg.wParam.0 as i32 == LAUNCH_ID { HotkeyAction::Launch } else { HotkeyAction::Show };
+            if tx.send(action).is_err() {
+                break;
+            }
+        }
+        unsafe {
+            let _ = UnregisterHotKey(HWND::default(), SHOW_ID);
+            let _ = UnregisterHotKey(HWND::default(), LAUNCH_ID);
+        }
+    });
+    let thread_id = ready_rx.recv().map_err(|_| anyhow::anyhow!("快捷键线程意外退出"))??;
+    Ok(Registration { rx, thread_id, thread: Some(thread) })
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn register(hotkey: &Hotkey) -> Result<Registration> {
+    anyhow::bail!("当前平台不支持全局快捷键 {}", hotkey)
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for Registration {
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::{LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        // 等待注销完成，立即重新注册同一组合时才不会冲突
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let hotkey = Hotkey::parse("ctrl + alt + u").unwrap();
+        assert_eq!(hotkey.to_string(), "Ctrl+Alt+U");
+        assert_eq!(hotkey.virtual_key(), b'U' as u32);
+        let hotkey = Hotkey::parse("Win+F9").unwrap();
+        assert_eq!(hotkey.to_string(), "Win+F9");
+        assert_eq!(hotkey.virtual_key(), 0x78);
+        assert_eq!(Hotkey::parse("Control+7").unwrap().to_string(), "Ctrl+7");
+
+        // 单独的 F 是字母键
+        assert_eq!(Hotkey::parse("Ctrl+f").unwrap().key, "F");
+
+        for text in ["", "U", "Shift+U", "Ctrl+Shift+U", "Ctrl+Space", "Ctrl+F25", "Hyper+U", "Ctrl+é"] {
+            assert!(Hotkey::parse(text).is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use std::path::Path;
+use std::process::Command;
+
+/// Windows 按程序保存显卡偏好的注册表项（“设置 > 显示 > 图形”中的设置）
+#[cfg(target_os = "windows")]
+const USER_GPU_PREFERENCES: &str = r"HKCU\Software\Microsoft\DirectX\UserGpuPreferences";
+/// 2 表示高性能（独立显卡）
+#[cfg(target_os = "windows")]
+const HIGH_PERFORMANCE: &str = "GpuPreference=2;";
+
+/// 双显卡笔记本上让游戏使用独立显卡，在 spawn 之前调用
+///
+/// Linux 上通过 PRIME 环境变量，Windows 上写入当前用户的显卡偏好；macOS 由系统自动切换，不做处理。
+pub fn prefer_dedicated(cmd: &mut Command, exe: &Path) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        let _ = exe;
+        for (key, value) in prime_env(Path::new("/proc/driver/nvidia/version").exists()) {
+            cmd.env(key, value);
+        }
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let _ = cmd;
+        set_windows_preference(exe)?;
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    let _ = (cmd, exe);
+    Ok(())
+}
+
+/// PRIME 渲染卸载的环境变量：NVIDIA 专有驱动和 Mesa（AMD / nouveau）使用不同的变量
+///
+/// 没有 NVIDIA 驱动时不能设置 `__GLX_VENDOR_LIBRARY_NAME=nvidia`，否则 OpenGL 无法初始化。
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn prime_env(nvidia: bool) -> &'static [(&'static str, &'static str)] {
+    if nvidia {
+        &[
+            ("__NV_PRIME_RENDER_OFFLOAD", "1"),
+            ("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+            ("__VK_LAYER_NV_optimus", "NVIDIA_only"),
+        ]
+    } else {
+        &[("DRI_PRIME", "1")]
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_windows_preference(exe: &Path) -> Result<()> {
+    use anyhow::Context;
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    // 注册表中的值名是完整路径
+    let exe = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+    let exe = exe.to_string_lossy();
+    let exe = exe.strip_prefix(r"\\?\").unwrap_or(&exe);
+    let status = Command::new("reg")
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .args(["add", USER_GPU_PREFERENCES, "/v", exe, "/t", "REG_SZ", "/d", HIGH_PERFORMANCE, "/f"])
+        .status()
+        .context("无法运行 reg 命令")?;
+    if !status.success() {
+        anyhow::bail!("无法写入显卡偏好设置（{}）", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prime_env() {
+        assert!(prime_env(true).contains(&("__NV_PRIME_RENDER_OFFLOAD", "1")));
+        assert!(prime_env(true).iter().all(|(key, _)| *key != "DRI_PRIME"));
+        assert_eq!(prime_env(false), [("DRI_PRIME", "1")]);
+    }
+}
@@ -0,0 +1,280 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::process::Child;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::config::launch_history_path;
+
+/// 历史记录最多保留的条数
+const MAX_RECORDS: usize = 500;
+
+/// 启动结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LaunchOutcome {
+    /// 游戏仍在运行
+    Running,
+    /// 正常退出（退出码 0）
+    Exited,
+    /// 异常退出（非 0 退出码或被信号终止）
+    Crashed,
+    /// 启动失败（进程未能创建）
+    Failed,
+    /// Launcher 在游戏结束前退出，结果未知
+    Unknown,
+}
+
+/// 单次启动记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchRecord {
+    pub id: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    /// 启动时间（Unix 时间戳，秒）
+    pub started_at: i64,
+    pub outcome: LaunchOutcome,
+    pub exit_code: Option<i32>,
+    pub duration_secs: Option<u64>,
+    pub error: Option<String>,
+    /// 玩家在游戏结束后填写的笔记
+    pub note: Option<String>,
+}
+
+impl Default for LaunchRecord {
+    fn default() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            profile_id: String::new(),
+            profile_name: String::new(),
+            started_at: crate::clock::now_unix(),
+            outcome: LaunchOutcome::Unknown,
+            exit_code: None,
+            duration_secs: None,
+            error: None,
+            note: None,
+        }
+    }
+}
+
+/// 持久化的启动历史
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchHistory {
+    pub records: Vec<LaunchRecord>,
+}
+
+impl LaunchHistory {
+    pub fn load() -> Self {
+        let mut history = fs::read_to_string(launch_history_path())
+            .ok()
+            .and_then(|raw| serde_json::from_str::<LaunchHistory>(&raw).ok())
+            .unwrap_or_default();
+
+        // 上次 Launcher 退出时仍在运行的记录无法得知结果
+        for record in &mut history.records {
+            if record.outcome == LaunchOutcome::Running {
+                record.outcome = LaunchOutcome::Unknown;
+            }
+        }
+        history
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = launch_history_path();
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, record: LaunchRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    /// 记录游戏进程结束
+    pub fn finish(&mut self, record_id: &str, exit_code: Option<i32>, duration: Duration) {
+        if let Some(record) = self.records.iter_mut().find(|r| r.id == record_id) {
+            record.outcome = if exit_code == Some(0) {
+                LaunchOutcome::Exited
+            } else {
+                LaunchOutcome::Crashed
+            };
+            record.exit_code = exit_code;
+            record.duration_secs = Some(duration.as_secs());
+        }
+    }
+
+    /// 记录外部启动的游戏进程结束，退出码未知，时长从附加监视时开始计算
+    pub fn finish_external(&mut self, record_id: &str, duration: Duration) {
+        if let Some(record) = self.records.iter_mut().find(|r| r.id == record_id) {
+            record.outcome = LaunchOutcome::Unknown;
+            record.duration_secs = Some(duration.as_secs());
+        }
+    }
+
+    /// 为记录附加说明（例如被看门狗结束）
+    pub fn annotate(&mut self, record_id: &str, note: &str) {
+        if let Some(record) = self.records.iter_mut().find(|r| r.id == record_id) {
+            record.error = Some(note.to_string());
+        }
+    }
+
+    /// 保存本次游戏的笔记，空白内容视为删除
+    pub fn set_note(&mut self, record_id: &str, note: &str) {
+        if let Some(record) = self.records.iter_mut().find(|r| r.id == record_id) {
+            let note = note.trim();
+            record.note = (!note.is_empty()).then(|| note.to_string());
+        }
+    }
+
+    pub fn get(&self, record_id: &str) -> Option<&LaunchRecord> {
+        self.records.iter().find(|r| r.id == record_id)
+    }
+
+    /// 按 profile 过滤，最新的记录在前
+    pub fn filtered<'a>(&'a self, profile_id: Option<&'a str>) -> impl Iterator<Item = &'a LaunchRecord> {
+        self.records
+            .iter()
+            .rev()
+            .filter(move |r| profile_id.is_none_or(|id| r.profile_id == id))
+    }
+}
+
+/// 游戏进程事件
+pub enum GameEvent {
+    Exited {
+        record_id: String,
+        exit_code: Option<i32>,
+        duration: Duration,
+    },
+    /// 看门狗检测到掉线，进程已被要求结束
+    Disconnected {
+        record_id: String,
+        profile_id: String,
+    },
+    /// A/B 对比中检测到已进入游戏
+    Connected {
+        record_id: String,
+    },
+    /// 在 Launcher 之外启动的游戏进程结束
+    Ended {
+        pid: u32,
+    },
+}
+
+/// 已启动的游戏进程
+pub struct LaunchedGame {
+    pub record_id: String,
+    pub pid: u32,
+    pub control: Arc<SessionControl>,
+}
+
+/// 与等待游戏进程的线程共享的状态
+#[derive(Debug, Default)]
+pub struct SessionControl {
+    kill: AtomicBool,
+    exited: AtomicBool,
+}
+
+impl SessionControl {
+    /// 要求结束游戏进程
+    pub fn request_kill(&self) {
+        self.kill.store(true, Ordering::SeqCst);
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited.load(Ordering::SeqCst)
+    }
+}
+
+/// 在后台线程等待游戏进程结束
+pub fn watch_game_process(mut child: Child, record_id: String, tx: mpsc::Sender<GameEvent>) -> Arc<SessionControl> {
+    let control = Arc::new(SessionControl::default());
+    let thread_control = control.clone();
+    let started = Instant::now();
+    let pid = child.id();
+    std::thread::spawn(move || {
+        let status = loop {
+            if thread_control.kill.swap(false, Ordering::SeqCst) {
+                let _ = child.kill();
+            }
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) => std::thread::sleep(Duration::from_millis(500)),
+                Err(_) => break None,
+            }
+        };
+        crate::child_group::release(pid);
+        thread_control.exited.store(true, Ordering::SeqCst);
+        let _ = tx.send(GameEvent::Exited {
+            record_id,
+            exit_code: status.and_then(|s| s.code()),
+            duration: started.elapsed(),
+        });
+    });
+    control
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(profile_id: &str) -> LaunchRecord {
+        LaunchRecord {
+            profile_id: profile_id.to_string(),
+            outcome: LaunchOutcome::Running,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_finish_sets_outcome() {
+        let mut history = LaunchHistory::default();
+        let ok = record("a");
+        let crashed = record("a");
+        let (ok_id, crashed_id) = (ok.id.clone(), crashed.id.clone());
+        history.push(ok);
+        history.push(crashed);
+
+        history.finish(&ok_id, Some(0), Duration::from_secs(90));
+        history.finish(&crashed_id, Some(-1), Duration::from_secs(5));
+
+        assert_eq!(history.records[0].outcome, LaunchOutcome::Exited);
+        assert_eq!(history.records[0].duration_secs, Some(90));
+        assert_eq!(history.records[1].outcome, LaunchOutcome::Crashed);
+        assert_eq!(history.records[1].exit_code, Some(-1));
+    }
+
+    #[test]
+    fn test_set_note() {
+        let mut history = LaunchHistory::default();
+        let r = record("a");
+        let id = r.id.clone();
+        history.push(r);
+
+        history.set_note(&id, "  Mining in Minoc, lag spikes at 21:00 \n");
+        assert_eq!(history.get(&id).unwrap().note.as_deref(), Some("Mining in Minoc, lag spikes at 21:00"));
+        history.set_note(&id, "   ");
+        assert_eq!(history.get(&id).unwrap().note, None);
+    }
+
+    #[test]
+    fn test_filter_and_cap() {
+        let mut history = LaunchHistory::default();
+        for i in 0..MAX_RECORDS + 10 {
+            history.push(record(if i % 2 == 0 { "a" } else { "b" }));
+        }
+        assert_eq!(history.records.len(), MAX_RECORDS);
+        assert_eq!(history.filtered(Some("a")).count(), MAX_RECORDS / 2);
+        assert_eq!(history.filtered(None).count(), MAX_RECORDS);
+    }
+}
@@ -0,0 +1,183 @@
+use std::borrow::Cow;
+
+use crate::i18n::t;
+
+/// CLDR 复数类别（只考虑整数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plural {
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+impl Plural {
+    fn suffix(self) -> &'static str {
+        match self {
+            Plural::One => "one",
+            Plural::Few => "few",
+            Plural::Many => "many",
+            Plural::Other => "other",
+        }
+    }
+}
+
+/// 按语言的复数规则给出 `n` 的类别，未列出的语言按英语处理
+pub fn plural_category(locale: &str, n: u64) -> Plural {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+    match language {
+        // 中日韩不区分单复数
+        "zh" | "ja" | "ko" => Plural::Other,
+        // 俄语等东斯拉夫语：1、21 为 one；2-4、22-24 为 few；其余为 many
+        "ru" | "uk" | "be" => {
+            let (n10, n100) = (n % 10, n % 100);
+            if n10 == 1 && n100 != 11 {
+                Plural::One
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                Plural::Few
+            } else {
+                Plural::Many
+            }
+        }
+        // 巴西葡萄牙语把 0 也算作单数（葡萄牙的葡萄牙语与英语相同）
+        "pt" if locale != "pt-PT" => {
+            if n <= 1 {
+                Plural::One
+            } else {
+                Plural::Other
+            }
+        }
+        _ => {
+            if n == 1 {
+                Plural::One
+            } else {
+                Plural::Other
+            }
+        }
+    }
+}
+
+/// 翻译 `key.one` / `key.few` / `key.many` / `key.other` 中与 `n` 对应的一条，并填入 `%{count}`
+///
+/// 当前语言缺少对应类别时使用 `key.other`。
+pub fn count(key: &str, n: u64) -> String {
+    count_in(&crate::i18n::current_locale(), key, n)
+}
+
+fn count_in(locale: &str, key: &str, n: u64) -> String {
+    let category = plural_category(locale, n);
+    let template = crate::_rust_i18n_try_translate(locale, format!("{key}.{}", category.suffix()))
+        .or_else(|| crate::_rust_i18n_try_translate(locale, format!("{key}.other")))
+        .unwrap_or_else(|| Cow::Owned(format!("{key}.other")));
+    rust_i18n::replace_patterns(&template, &["count"], &[n.to_string()])
+}
+
+/// 时长，例如 "1 hour 5 minutes"、"3 minutes 12 seconds"，只显示最大的两个单位
+pub fn duration(secs: u64) -> String {
+    duration_in(&crate::i18n::current_locale(), secs)
+}
+
+fn duration_in(locale: &str, secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    let (major, minor) = if hours > 0 {
+        (count_in(locale, "humanize.hours", hours), (minutes > 0).then(|| count_in(locale, "humanize.minutes", minutes)))
+    } else if minutes > 0 {
+        (count_in(locale, "humanize.minutes", minutes), (seconds > 0).then(|| count_in(locale, "humanize.seconds", seconds)))
+    } else {
+        (count_in(locale, "humanize.seconds", seconds), None)
+    };
+    match minor {
+        Some(minor) => t!("humanize.duration_pair", locale = locale, major = major, minor = minor).to_string(),
+        None => major,
+    }
+}
+
+/// 过去的时间点，例如 "5 minutes ago"
+pub fn ago(secs: u64) -> String {
+    ago_in(&crate::i18n::current_locale(), secs)
+}
+
+fn ago_in(locale: &str, secs: u64) -> String {
+    match secs {
+        0..60 => t!("humanize.just_now", locale = locale).to_string(),
+        60..3600 => count_in(locale, "humanize.minutes_ago", secs / 60),
+        3600..86400 => count_in(locale, "humanize.hours_ago", secs / 3600),
+        _ => count_in(locale, "humanize.days_ago", secs / 86400),
+    }
+}
+
+/// 字节数，例如 "512 KB"、"45.2 MB"，小数点按语言显示
+pub fn bytes(n: u64) -> String {
+    bytes_in(&crate::i18n::current_locale(), n)
+}
+
+fn bytes_in(locale: &str, n: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    let value = n as f64;
+    let (number, unit) = if value >= GB {
+        (format!("{:.1}", value / GB), "humanize.gb")
+    } else if value >= MB {
+        (format!("{:.1}", value / MB), "humanize.mb")
+    } else if value >= KB {
+        (format!("{:.0}", value / KB), "humanize.kb")
+    } else {
+        return count_in(locale, "humanize.bytes", n);
+    };
+    let number = number.replace('.', &t!("humanize.decimal_separator", locale = locale));
+    t!(unit, locale = locale, size = number).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plural_category() {
+        assert_eq!(plural_category("en", 1), Plural::One);
+        assert_eq!(plural_category("en", 0), Plural::Other);
+        assert_eq!(plural_category("zh-CN", 1), Plural::Other);
+        assert_eq!(plural_category("pt-BR", 0), Plural::One);
+        assert_eq!(plural_category("pt-PT", 0), Plural::Other);
+        let russian: Vec<Plural> = [1, 2, 5, 11, 12, 21, 22, 25, 111, 112].iter().map(|n| plural_category("ru", *n)).collect();
+        use Plural::*;
+        assert_eq!(russian, [One, Few, Many, Many, Many, One, Few, Many, Many, Many]);
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(count_in("en", "humanize.minutes", 1), "1 minute");
+        assert_eq!(count_in("en", "humanize.minutes", 5), "5 minutes");
+        assert_eq!(count_in("ru", "humanize.minutes", 1), "1 минута");
+        assert_eq!(count_in("ru", "humanize.minutes", 3), "3 минуты");
+        assert_eq!(count_in("ru", "humanize.minutes", 11), "11 минут");
+        assert_eq!(count_in("zh-CN", "humanize.minutes", 2), "2 分钟");
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration_in("en", 42), "42 seconds");
+        assert_eq!(duration_in("en", 192), "3 minutes 12 seconds");
+        assert_eq!(duration_in("en", 3900), "1 hour 5 minutes");
+        assert_eq!(duration_in("en", 7200), "2 hours");
+        assert_eq!(duration_in("ja", 3900), "1時間5分");
+    }
+
+    #[test]
+    fn test_ago() {
+        assert_eq!(ago_in("en", 5), "just now");
+        assert_eq!(ago_in("en", 60), "1 minute ago");
+        assert_eq!(ago_in("en", 7300), "2 hours ago");
+        assert_eq!(ago_in("ru", 22 * 86400), "22 дня назад");
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(bytes_in("en", 1), "1 byte");
+        assert_eq!(bytes_in("en", 512 * 1024), "512 KB");
+        assert_eq!(bytes_in("en", 47_395_635), "45.2 MB");
+        assert_eq!(bytes_in("ru", 47_395_635), "45,2 МБ");
+        assert_eq!(bytes_in("pt-BR", 3 * 1024 * 1024 * 1024), "3,0 GB");
+    }
+}
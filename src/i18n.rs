@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 // 国际化配置
 rust_i18n::i18n!("locales", fallback = "en");
@@ -62,9 +63,17 @@ pub fn current_locale() -> String {
     rust_i18n::locale().to_string()
 }
 
+/// 每次切换语言加一，缓存了翻译文本的地方比较这个值来判断是否需要刷新
+static LOCALE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub fn locale_generation() -> u64 {
+    LOCALE_GENERATION.load(Ordering::Relaxed)
+}
+
 /// 设置语言
 pub fn set_locale(locale: &str) {
     rust_i18n::set_locale(locale);
+    LOCALE_GENERATION.fetch_add(1, Ordering::Relaxed);
 }
 
 /// 根据系统语言自动初始化
@@ -184,3 +193,36 @@ fn detect_windows_locale() -> Option<String> {
 
 // 重新导出 t! 宏，方便使用
 pub use rust_i18n::t;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_languages_have_locale_files() {
+        let locales = rust_i18n::available_locales!();
+        let languages = available_languages();
+        assert!(languages.iter().any(|lang| lang.code == default_language()));
+        for lang in languages {
+            assert!(locales.contains(&lang.code.as_str()), "{} 没有翻译文件", lang.code);
+            assert_eq!(lang.file, format!("{}.yml", lang.code));
+            // 主界面至少要有翻译，其余缺失的键回退到英文
+            if lang.code != "en" {
+                assert_ne!(
+                    t!("main.launch", locale = &lang.code),
+                    t!("main.launch", locale = "en"),
+                    "{} 缺少主界面的翻译",
+                    lang.code
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_match_system_locale() {
+        let available = available_languages();
+        assert_eq!(match_system_locale("pt-PT", &available, "en"), "pt-BR");
+        assert_eq!(match_system_locale("ko-KR", &available, "en"), "ko");
+        assert_eq!(match_system_locale("fr-FR", &available, "en"), "en");
+    }
+}
@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{Ime, WindowEvent};
+use winit::window::Window;
+
+/// 修正 egui-winit 0.27 的输入法（IME）处理
+///
+/// - 光标为 None 的 Preedit 会被忽略：取消输入（Esc、切换窗口）时输入框中残留未确认的文字，
+///   部分 Linux 输入法隐藏光标时预编辑文字也不显示。
+/// - 输入期间的按键由输入法处理，交给 egui 会同时修改输入框中已有的文字（Backspace、方向键）。
+/// - 候选窗口按整个输入框定位，长输入框中会遮住正在输入的文字，改为跟随文本光标。
+#[derive(Debug, Default)]
+pub struct ImeState {
+    /// 是否有未确认的预编辑文字
+    composing: bool,
+    /// 上次设置的候选窗口位置（物理像素）
+    cursor_area: Option<egui::Rect>,
+}
+
+impl ImeState {
+    /// 返回应当交给 egui-winit 的事件，None 表示丢弃
+    pub fn filter<'a>(&mut self, event: &'a WindowEvent) -> Option<Cow<'a, WindowEvent>> {
+        let clear = || Cow::Owned(WindowEvent::Ime(Ime::Preedit(String::new(), Some((0, 0)))));
+        match event {
+            WindowEvent::Ime(Ime::Preedit(text, _)) if text.is_empty() => {
+                // 确认前也会收到空的 Preedit，清除预编辑文字不影响随后的 Commit
+                std::mem::take(&mut self.composing).then(clear)
+            }
+            WindowEvent::Ime(Ime::Preedit(text, cursor)) => {
+                self.composing = true;
+                match cursor {
+                    Some(_) => Some(Cow::Borrowed(event)),
+                    None => Some(Cow::Owned(WindowEvent::Ime(Ime::Preedit(
+                        text.clone(),
+                        Some((text.len(), text.len())),
+                    )))),
+                }
+            }
+            WindowEvent::Ime(Ime::Commit(_)) => {
+                self.composing = false;
+                Some(Cow::Borrowed(event))
+            }
+            WindowEvent::Ime(Ime::Disabled) if self.composing => {
+                self.composing = false;
+                Some(clear())
+            }
+            WindowEvent::KeyboardInput { .. } if self.composing => None,
+            _ => Some(Cow::Borrowed(event)),
+        }
+    }
+
+    /// 把候选窗口移到文本光标处，在 `handle_platform_output` 之后调用
+    pub fn update_cursor_area(&mut self, ctx: &egui::Context, window: &Window, ime: Option<egui::output::IMEOutput>) {
+        let Some(ime) = ime else {
+            self.cursor_area = None;
+            return;
+        };
+        let area = egui_winit::pixels_per_point(ctx, window) * ime.cursor_rect;
+        // egui-winit 在输入框位置变化或有输入时会重新设置为整个输入框，需要再次覆盖
+        if self.cursor_area != Some(area) || ctx.input(|i| !i.events.is_empty()) {
+            self.cursor_area = Some(area);
+            window.set_ime_cursor_area(
+                PhysicalPosition::new(area.min.x, area.min.y),
+                PhysicalSize::new(area.width().max(1.0), area.height()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preedit(text: &str, cursor: Option<(usize, usize)>) -> WindowEvent {
+        WindowEvent::Ime(Ime::Preedit(text.to_string(), cursor))
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut ime = ImeState::default();
+        let pass = |ime: &mut ImeState, event: WindowEvent| ime.filter(&event).map(Cow::into_owned);
+
+        // 不在输入时空的 Preedit 没有需要清除的文字
+        assert_eq!(pass(&mut ime, preedit("", None)), None);
+        assert_eq!(pass(&mut ime, preedit("ni", Some((2, 2)))), Some(preedit("ni", Some((2, 2)))));
+        // 隐藏光标的预编辑文字放在末尾显示
+        assert_eq!(pass(&mut ime, preedit("你好", None)), Some(preedit("你好", Some((6, 6)))));
+        assert!(ime.composing);
+
+        // 确认：先清除预编辑文字，再插入
+        assert_eq!(pass(&mut ime, preedit("", None)), Some(preedit("", Some((0, 0)))));
+        let commit = WindowEvent::Ime(Ime::Commit("你好".to_string()));
+        assert_eq!(pass(&mut ime, commit.clone()), Some(commit));
+
+        // 输入中失去焦点
+        pass(&mut ime, preedit("de", Some((2, 2))));
+        assert_eq!(pass(&mut ime, WindowEvent::Ime(Ime::Disabled)), Some(preedit("", Some((0, 0)))));
+        assert!(!ime.composing);
+        assert_eq!(pass(&mut ime, WindowEvent::Ime(Ime::Disabled)), Some(WindowEvent::Ime(Ime::Disabled)));
+    }
+}
@@ -0,0 +1,251 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::cloud_sync::RelocateEvent;
+use crate::config::{ProfileConfig, open_uo_binary_in};
+use crate::operation_lock::{self, Operation};
+
+/// 默认的 OpenUO 安装目录：Launcher 旁边的 OpenUO
+pub fn default_dir() -> PathBuf {
+    crate::config::base_dir().join("OpenUO")
+}
+
+/// 更换安装目录时要做的事
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plan {
+    /// 新目录中已经安装了 OpenUO，直接使用，原来的安装保持不动
+    UseExisting,
+    /// 把原来的安装移动到新目录
+    Move,
+    /// 原来的目录中没有安装，只需要切换
+    Switch,
+}
+
+/// 检查新的安装目录是否可用，并决定如何处理原来的安装
+pub fn plan(current: &Path, target: &Path) -> Result<Plan> {
+    if !target.is_absolute() {
+        anyhow::bail!("安装目录必须是绝对路径: {}", target.display());
+    }
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let (current, target) = (canonical(current), canonical(target));
+    if current == target {
+        anyhow::bail!("{} 已经是当前的安装目录", target.display());
+    }
+    if target.starts_with(&current) || current.starts_with(&target) {
+        anyhow::bail!("新的安装目录不能与当前的安装目录互相包含");
+    }
+    if target.is_file() {
+        anyhow::bail!("{} 是一个文件", target.display());
+    }
+    let has_files = |dir: &Path| fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some());
+    if has_files(&target) {
+        if open_uo_binary_in(&target).is_file() {
+            return Ok(Plan::UseExisting);
+        }
+        anyhow::bail!("{} 不为空，请选择空目录或已安装 OpenUO 的目录", target.display());
+    }
+    check_writable(&target)?;
+    Ok(if has_files(&current) { Plan::Move } else { Plan::Switch })
+}
+
+fn check_writable(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("无法创建目录 {}", dir.display()))?;
+    let probe = dir.join(".openuo_write_test");
+    fs::write(&probe, b"").with_context(|| format!("没有 {} 的写入权限", dir.display()))?;
+    fs::remove_file(&probe).ok();
+    Ok(())
+}
+
+/// 在后台线程把安装从 `src` 移动到 `dest`（空目录）
+///
+/// 同一磁盘上直接重命名；跨磁盘时先完整复制，成功后再删除原目录，复制失败时原安装保持不变。
+pub fn spawn_move(src: PathBuf, dest: PathBuf) -> mpsc::Receiver<RelocateEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        // 移动期间不能安装更新或保存档案
+        let _guard = operation_lock::acquire(Operation::Install);
+        let result = move_install(&src, &dest, |copied, total| {
+            let _ = tx.send(RelocateEvent::Progress { copied, total });
+        });
+        let _ = tx.send(match result {
+            Ok(()) => RelocateEvent::Done(dest),
+            Err(e) => RelocateEvent::Failed(format!("{e:#}")),
+        });
+    });
+    rx
+}
+
+fn move_install(src: &Path, dest: &Path, mut progress: impl FnMut(u64, u64)) -> Result<()> {
+    // 目标是 plan 检查过的空目录，先删除才能重命名
+    fs::remove_dir(dest).ok();
+    if fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    let total = crate::disk_space::dir_size(src);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+        crate::disk_space::ensure(&[(parent, total)])?;
+    }
+    let mut copied = 0;
+    let result = crate::cloud_sync::copy_dir(src, dest, &mut |bytes| {
+        copied += bytes;
+        progress(copied, total);
+    });
+    if let Err(e) = result {
+        fs::remove_dir_all(dest).ok();
+        return Err(e.context("无法复制 OpenUO 安装"));
+    }
+    if let Err(e) = fs::remove_dir_all(src) {
+        tracing::warn!("Failed to remove old install {}: {}", src.display(), e);
+    }
+    Ok(())
+}
+
+/// 清空安装目录时不能删除的目录：各档案的 UO 目录、补丁包以及 Launcher 所在的目录
+///
+/// 安装目录可以选择任何已经有 OpenUO 的目录，其中可能放着这些目录。
+pub fn protected_dirs(profiles: &[ProfileConfig]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = profiles
+        .iter()
+        .map(|p| p.settings.ultima_online_directory.trim())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    dirs.push(crate::config::mods_dir());
+    dirs.push(crate::config::base_dir());
+    dirs
+}
+
+/// 清空安装目录，只保留 Data/Profiles（游戏内的宏和设置）、并存安装的版本以及包含 `protected` 中目录的条目，返回删除的条目数
+///
+/// 安装目录本身在 `protected` 中时拒绝清空。
+pub fn clean(dir: &Path, protected: &[PathBuf]) -> Result<usize> {
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let dir = canonical(dir);
+    let protected: Vec<PathBuf> = protected.iter().map(|p| canonical(p)).collect();
+    if protected.contains(&dir) {
+        anyhow::bail!("{} 中有 UO 数据或 Launcher 的文件，不能清空", dir.display());
+    }
+    // 条目本身或其中的文件受保护时保留
+    let is_protected = |path: &Path| protected.iter().any(|p| p.starts_with(path));
+    let keep: Vec<PathBuf> = crate::client_versions::installed_in(&dir).into_iter().map(|v| v.dir).collect();
+    let mut removed = 0;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(0);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if keep.contains(&path) {
+            continue;
+        }
+        if entry.file_name() == "Data" && path.is_dir() && !protected.contains(&path) {
+            for data in fs::read_dir(&path)?.flatten() {
+                if data.file_name() != "Profiles" && !is_protected(&data.path()) {
+                    remove_entry(&data.path())?;
+                    removed += 1;
+                }
+            }
+            continue;
+        }
+        if is_protected(&path) {
+            continue;
+        }
+        remove_entry(&path)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+fn remove_entry(path: &Path) -> Result<()> {
+    let result = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+    result.with_context(|| format!("无法删除 {}，游戏可能正在运行", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_plan_and_move() {
+        let base = temp_dir("install-dir");
+        let current = base.join("ssd").join("OpenUO");
+        let binary = open_uo_binary_in(&current);
+        fs::create_dir_all(current.join("Data")).unwrap();
+        fs::write(&binary, "exe").unwrap();
+        fs::write(current.join("Data").join("settings.xml"), "x").unwrap();
+
+        assert!(plan(&current, Path::new("relative/OpenUO")).is_err());
+        assert!(plan(&current, &current).is_err());
+        assert!(plan(&current, &current.join("nested")).is_err());
+        let occupied = base.join("hdd").join("Music");
+        fs::create_dir_all(&occupied).unwrap();
+        fs::write(occupied.join("song.mp3"), "").unwrap();
+        assert!(plan(&current, &occupied).is_err());
+
+        let target = base.join("hdd").join("Games").join("OpenUO");
+        assert_eq!(plan(&current, &target).unwrap(), Plan::Move);
+        move_install(&current, &target, |_, _| {}).unwrap();
+        assert!(!current.exists());
+        assert_eq!(fs::read_to_string(target.join("Data").join("settings.xml")).unwrap(), "x");
+
+        // 搬回原处时原目录已空；已经安装了 OpenUO 的目录直接使用
+        assert_eq!(plan(&current, &target).unwrap(), Plan::UseExisting);
+        assert_eq!(plan(&target, &current).unwrap(), Plan::Move);
+        let empty = base.join("empty");
+        assert_eq!(plan(&empty, &base.join("other")).unwrap(), Plan::Switch);
+    }
+
+    #[test]
+    fn test_clean() {
+        let dir = temp_dir("clean");
+        let profiles = dir.join("Data").join("Profiles").join("lord");
+        fs::create_dir_all(&profiles).unwrap();
+        fs::write(profiles.join("macros.xml"), "keep").unwrap();
+        fs::create_dir_all(dir.join("Data").join("Client")).unwrap();
+        fs::write(dir.join("Data").join("settings.json"), "").unwrap();
+        fs::create_dir_all(dir.join("lib")).unwrap();
+        fs::write(dir.join("lib").join("broken.dll"), "").unwrap();
+        fs::write(open_uo_binary_in(&dir), "exe").unwrap();
+        // 并存安装的版本
+        let pinned = dir.join("v1.0.0");
+        fs::create_dir_all(&pinned).unwrap();
+        fs::write(open_uo_binary_in(&pinned), "old").unwrap();
+        fs::write(pinned.join(".open_uo_version"), "v1.0.0").unwrap();
+
+        assert_eq!(clean(&dir, &[]).unwrap(), 4);
+        assert_eq!(fs::read_to_string(profiles.join("macros.xml")).unwrap(), "keep");
+        assert!(!dir.join("Data").join("Client").exists());
+        assert!(!dir.join("lib").exists());
+        assert!(!open_uo_binary_in(&dir).exists());
+        assert!(open_uo_binary_in(&pinned).exists());
+    }
+
+    #[test]
+    fn test_clean_keeps_protected_dirs() {
+        let dir = temp_dir("clean-protected");
+        // OpenUO 装在了 UO 目录旁边，UO 数据和补丁包都在安装目录中
+        let uo = dir.join("Ultima Online");
+        fs::create_dir_all(&uo).unwrap();
+        fs::write(uo.join("client.exe"), "uo").unwrap();
+        let mods = dir.join("Data").join("Mods");
+        fs::create_dir_all(&mods).unwrap();
+        fs::write(mods.join("pack.zip"), "mod").unwrap();
+        fs::create_dir_all(dir.join("Data").join("Client")).unwrap();
+        fs::write(open_uo_binary_in(&dir), "exe").unwrap();
+
+        assert_eq!(clean(&dir, &[uo.clone(), mods.clone()]).unwrap(), 2);
+        assert_eq!(fs::read_to_string(uo.join("client.exe")).unwrap(), "uo");
+        assert_eq!(fs::read_to_string(mods.join("pack.zip")).unwrap(), "mod");
+        assert!(!dir.join("Data").join("Client").exists());
+        assert!(!open_uo_binary_in(&dir).exists());
+
+        // 安装目录就是 UO 目录时什么都不删除
+        fs::write(open_uo_binary_in(&uo), "exe").unwrap();
+        assert!(clean(&uo, std::slice::from_ref(&uo)).is_err());
+        assert!(open_uo_binary_in(&uo).exists());
+        assert!(uo.join("client.exe").exists());
+    }
+}
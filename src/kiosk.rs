@@ -0,0 +1,43 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// 放在 Launcher 旁边即开启锁定模式，内容不限
+pub const KIOSK_FILE: &str = ".kiosk";
+
+/// 启动时确定，运行期间不能关闭
+static ACTIVE: OnceLock<bool> = OnceLock::new();
+
+/// 锁定模式（网吧、LAN party）：档案只能启动，不能编辑、复制或删除，不能修改更新源和安装，也不显示账号
+///
+/// 在 `.launcher_settings` 中设置 `"kiosk": true`，或在 Launcher 旁边放一个 `.kiosk` 文件；
+/// 界面中没有解除的入口，需要管理员去掉设置或删除文件。
+fn enabled(dir: &Path, setting: bool) -> bool {
+    setting || dir.join(KIOSK_FILE).exists()
+}
+
+pub fn init(dir: &Path, setting: bool) {
+    let active = enabled(dir, setting);
+    if active {
+        tracing::info!("Kiosk mode enabled");
+    }
+    let _ = ACTIVE.set(active);
+}
+
+pub fn active() -> bool {
+    ACTIVE.get().copied().unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_enabled() {
+        let dir = temp_dir("kiosk");
+        assert!(!enabled(&dir, false));
+        assert!(enabled(&dir, true));
+        std::fs::write(dir.join(KIOSK_FILE), "").unwrap();
+        assert!(enabled(&dir, false));
+    }
+}
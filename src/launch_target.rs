@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::ProfileConfig;
+
+/// Profile 启动的目标程序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LaunchTarget {
+    /// 启动器管理的 OpenUO
+    #[default]
+    OpenUO,
+    /// 原版 client.exe（或 Razor/UOSteam 等加载器）
+    ClassicClient,
+    /// 任意可执行文件
+    Custom,
+}
+
+impl LaunchTarget {
+    pub const ALL: [LaunchTarget; 3] = [
+        LaunchTarget::OpenUO,
+        LaunchTarget::ClassicClient,
+        LaunchTarget::Custom,
+    ];
+
+    /// 对应的翻译 key
+    pub fn label_key(self) -> &'static str {
+        match self {
+            LaunchTarget::OpenUO => "launch_target.openuo",
+            LaunchTarget::ClassicClient => "launch_target.classic",
+            LaunchTarget::Custom => "launch_target.custom",
+        }
+    }
+}
+
+/// 点击“启动”后的二次确认方式，防止误点直接自动登录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LaunchConfirmation {
+    /// 单击即启动
+    #[default]
+    None,
+    /// 弹出确认对话框
+    Dialog,
+    /// 需要双击启动按钮
+    DoubleClick,
+}
+
+impl LaunchConfirmation {
+    pub const ALL: [LaunchConfirmation; 3] = [
+        LaunchConfirmation::None,
+        LaunchConfirmation::Dialog,
+        LaunchConfirmation::DoubleClick,
+    ];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            LaunchConfirmation::None => "launch_confirm.none",
+            LaunchConfirmation::Dialog => "launch_confirm.dialog",
+            LaunchConfirmation::DoubleClick => "launch_confirm.double_click",
+        }
+    }
+}
+
+/// 参数模板中可用的占位符
+pub const PLACEHOLDERS: &str = "{uo_dir} {settings} {ip} {port} {username} {character}";
+
+/// 解析要启动的可执行文件
+///
+/// 经典客户端未指定路径时默认使用 UO 目录下的 client.exe；相对路径也相对于 UO 目录。
+pub fn resolve_executable(profile: &ProfileConfig) -> Option<PathBuf> {
+    let path = Path::new(profile.index.executable_path.trim());
+    let uo_dir = Path::new(&profile.settings.ultima_online_directory);
+    if !path.as_os_str().is_empty() {
+        if path.is_relative() && !uo_dir.as_os_str().is_empty() {
+            return Some(uo_dir.join(path));
+        }
+        return Some(path.to_path_buf());
+    }
+    match profile.index.launch_target {
+        LaunchTarget::ClassicClient if !uo_dir.as_os_str().is_empty() => Some(uo_dir.join("client.exe")),
+        _ => None,
+    }
+}
+
+/// 展开参数模板
+///
+/// 先按空白拆分（双引号内的空白保留），再替换占位符，
+/// 这样包含空格的路径也只会成为一个参数。
+pub fn expand_args(template: &str, profile: &ProfileConfig, settings_path: &Path) -> Vec<String> {
+    let settings_path = settings_path.to_string_lossy();
+    let port = profile.settings.port.to_string();
+    let replacements = [
+        ("{uo_dir}", profile.settings.ultima_online_directory.as_str()),
+        ("{settings}", settings_path.as_ref()),
+        ("{ip}", profile.settings.ip.as_str()),
+        ("{port}", port.as_str()),
+        ("{username}", profile.settings.username.as_str()),
+        ("{character}", profile.index.last_character_name.as_str()),
+    ];
+
+    split_args(template)
+        .into_iter()
+        .map(|arg| {
+            replacements
+                .iter()
+                .fold(arg, |acc, (key, value)| acc.replace(key, value))
+        })
+        .collect()
+}
+
+fn split_args(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+
+    for c in template.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_token {
+                    args.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(split_args(""), Vec::<String>::new());
+        assert_eq!(split_args("  -a  b "), vec!["-a", "b"]);
+        assert_eq!(
+            split_args(r#"--client "C:\Program Files\UO\client.exe" -x"#),
+            vec!["--client", r"C:\Program Files\UO\client.exe", "-x"]
+        );
+        assert_eq!(split_args(r#"-empty """#), vec!["-empty", ""]);
+    }
+
+    #[test]
+    fn test_expand_args() {
+        let mut profile = ProfileConfig::default();
+        profile.settings.ultima_online_directory = "/games/Ultima Online".to_string();
+        profile.settings.ip = "shard.example.com".to_string();
+        profile.settings.port = 2593;
+
+        let args = expand_args("--uo {uo_dir} {ip},{port}", &profile, Path::new("/tmp/s.json"));
+        assert_eq!(args, vec!["--uo", "/games/Ultima Online", "shard.example.com,2593"]);
+    }
+
+    #[test]
+    fn test_resolve_executable() {
+        let mut profile = ProfileConfig::default();
+        profile.index.launch_target = LaunchTarget::ClassicClient;
+        profile.settings.ultima_online_directory = "/uo".to_string();
+        assert_eq!(resolve_executable(&profile), Some(PathBuf::from("/uo/client.exe")));
+
+        profile.index.executable_path = "/razor/Razor.exe".to_string();
+        assert_eq!(resolve_executable(&profile), Some(PathBuf::from("/razor/Razor.exe")));
+        profile.index.executable_path = "Razor/Razor.exe".to_string();
+        assert_eq!(resolve_executable(&profile), Some(PathBuf::from("/uo/Razor/Razor.exe")));
+        profile.settings.ultima_online_directory.clear();
+        assert_eq!(resolve_executable(&profile), Some(PathBuf::from("Razor/Razor.exe")));
+
+        profile.index.launch_target = LaunchTarget::Custom;
+        profile.index.executable_path.clear();
+        assert_eq!(resolve_executable(&profile), None);
+    }
+}
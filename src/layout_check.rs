@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use egui::{Color32, Rect, Response, Stroke, Ui};
+
+/// 设置这个环境变量后，超出所在区域的控件会被标红，方便检查新语言的翻译是否过长
+const ENV_VAR: &str = "OPENUO_LAYOUT_CHECK";
+
+/// 小于这个宽度的超出视为取整误差
+const TOLERANCE: f32 = 0.5;
+
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| std::env::var_os(ENV_VAR).is_some_and(|value| !value.is_empty() && value != "0"))
+}
+
+/// 控件超出 `bounds` 右侧或下方的像素数
+fn overflow(widget: Rect, bounds: Rect) -> Option<f32> {
+    let px = (widget.max.x - bounds.max.x).max(widget.max.y - bounds.max.y);
+    (px > TOLERANCE).then_some(px)
+}
+
+/// 检查一行控件是否超出 `bounds`，超出的控件画红框并标注超出的宽度，每种语言每个控件只记一次日志
+///
+/// `bounds` 是这一行应当容纳的区域，通常是调用 `ui.horizontal` 之前的 `ui.max_rect()`。
+pub fn check(ui: &Ui, bounds: Rect, widgets: &[(&str, &Response)]) {
+    if !enabled() {
+        return;
+    }
+    static REPORTED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+    let painter = ui.ctx().debug_painter();
+    for (name, response) in widgets {
+        let Some(px) = overflow(response.rect, bounds) else {
+            continue;
+        };
+        painter.rect_stroke(response.rect, 2.0, Stroke::new(2.0, Color32::RED));
+        painter.debug_text(
+            response.rect.left_bottom(),
+            egui::Align2::LEFT_TOP,
+            Color32::RED,
+            format!("{} +{:.0}px", name, px),
+        );
+
+        let key = format!("{}:{}", crate::i18n::current_locale(), name);
+        let mut reported = REPORTED.lock().unwrap_or_else(|e| e.into_inner());
+        if reported.get_or_insert_with(HashSet::new).insert(key) {
+            tracing::warn!(
+                "布局检查：{} 超出 {:.0}px（语言 {}）",
+                name,
+                px,
+                crate::i18n::current_locale()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{pos2, vec2};
+
+    #[test]
+    fn test_overflow() {
+        let bounds = Rect::from_min_size(pos2(10.0, 10.0), vec2(400.0, 30.0));
+        let inside = Rect::from_min_size(pos2(300.0, 12.0), vec2(110.0, 24.0));
+        assert_eq!(overflow(inside, bounds), None);
+        // 取整误差不算超出
+        assert_eq!(overflow(inside.translate(vec2(0.3, 0.0)), bounds), None);
+        let wide = Rect::from_min_size(pos2(380.0, 12.0), vec2(60.0, 24.0));
+        assert_eq!(overflow(wide, bounds), Some(30.0));
+        let wrapped = Rect::from_min_size(pos2(20.0, 30.0), vec2(60.0, 24.0));
+        assert_eq!(overflow(wrapped, bounds), Some(14.0));
+    }
+}
@@ -0,0 +1,287 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::ProfileConfig;
+
+// 从原来的 C# Launcher（以及使用相同 Profiles 目录结构的社区 Launcher）迁移：
+// 档案索引在 Profiles/*.json，OpenUO 设置在 Profiles/Settings/ 下，密码用 .NET 的机器名加密，
+// 客户端安装在 Launcher 目录下的 OpenUO 中。
+
+/// 旧 Launcher 的档案索引，只读取迁移需要的字段
+#[derive(Debug, Deserialize)]
+struct LegacyIndex {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "SettingsFile", default)]
+    settings_file: String,
+    #[serde(rename = "FileName", default)]
+    file_name: String,
+    #[serde(rename = "LastCharacterName", default)]
+    last_character_name: String,
+    #[serde(rename = "AdditionalArgs", default)]
+    additional_args: String,
+}
+
+/// 找到的旧 Launcher 目录
+#[derive(Debug, Clone)]
+pub struct LegacyInstall {
+    pub dir: PathBuf,
+    /// 档案索引文件
+    pub profiles: Vec<PathBuf>,
+    /// 旧 Launcher 安装的 OpenUO 目录
+    pub client_dir: Option<PathBuf>,
+}
+
+/// 迁移的结果
+#[derive(Debug, Default)]
+pub struct MigrationSummary {
+    pub profiles: Vec<ProfileConfig>,
+    /// 密码无法解密、需要重新输入的档案
+    pub password_lost: Vec<String>,
+    /// 无法读取的档案（文件名和原因）
+    pub failed: Vec<(String, String)>,
+}
+
+/// 在 Launcher 旁边和系统的应用数据目录中查找旧 Launcher
+pub fn detect() -> Vec<LegacyInstall> {
+    let own = crate::config::base_dir();
+    let own = own.canonicalize().unwrap_or(own);
+    let mut roots: Vec<PathBuf> = own.parent().map(Path::to_path_buf).into_iter().collect();
+    for var in ["LOCALAPPDATA", "APPDATA"] {
+        roots.extend(std::env::var_os(var).map(PathBuf::from));
+    }
+    let mut found: Vec<LegacyInstall> = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            if !looks_like_launcher_dir(&name) || dir.canonicalize().is_ok_and(|d| d == own) {
+                continue;
+            }
+            if let Some(install) = inspect(&dir)
+                && !found.iter().any(|f| f.dir == install.dir)
+            {
+                found.push(install);
+            }
+        }
+    }
+    found
+}
+
+/// 目录名同时包含 “uo” 和 “launcher”，例如 OpenUO-Launcher、ClassicUOLauncher
+fn looks_like_launcher_dir(name: &str) -> bool {
+    name.contains("launcher") && name.contains("uo")
+}
+
+/// 检查目录是否为旧 Launcher（用户也可以手动选择目录）
+pub fn inspect(dir: &Path) -> Option<LegacyInstall> {
+    let profiles: Vec<PathBuf> = fs::read_dir(dir.join("Profiles"))
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")))
+        .filter(|p| read_index(p).is_ok())
+        .collect();
+    if profiles.is_empty() {
+        return None;
+    }
+    let binary = crate::config::open_uo_binary_path();
+    let binary = binary.file_name()?;
+    let client_dir = Some(dir.join("OpenUO")).filter(|d| d.join(binary).is_file());
+    Some(LegacyInstall {
+        dir: dir.to_path_buf(),
+        profiles,
+        client_dir,
+    })
+}
+
+fn read_index(path: &Path) -> Result<LegacyIndex> {
+    let raw = fs::read_to_string(path).with_context(|| format!("无法读取 {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("{} 不是档案文件", path.display()))
+}
+
+/// 旧档案的设置文件：SettingsFile 可能是完整路径、带或不带扩展名的文件名
+fn resolve_settings(dir: &Path, index: &LegacyIndex) -> Option<PathBuf> {
+    let settings_dir = dir.join("Profiles").join("Settings");
+    let mut candidates = Vec::new();
+    for name in [&index.settings_file, &index.file_name].into_iter().filter(|n| !n.is_empty()) {
+        let path = Path::new(name);
+        if path.is_absolute() {
+            candidates.push(path.to_path_buf());
+        }
+        candidates.push(settings_dir.join(format!("{}.json", name)));
+        candidates.push(settings_dir.join(name));
+        candidates.push(dir.join("Profiles").join(name));
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+/// 导入旧 Launcher 的全部档案，密码用本机的密钥重新加密
+///
+/// `existing` 为已有档案的名称，重名的档案加上后缀。
+pub fn migrate(install: &LegacyInstall, existing: &[String]) -> MigrationSummary {
+    migrate_with_keys(install, existing, &legacy_keys(&hostname()))
+}
+
+fn migrate_with_keys(install: &LegacyInstall, existing: &[String], keys: &[String]) -> MigrationSummary {
+    let mut summary = MigrationSummary::default();
+    let mut names: Vec<String> = existing.to_vec();
+    for path in &install.profiles {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        match migrate_profile(&install.dir, path, keys) {
+            Ok((mut profile, password_ok)) => {
+                profile.index.name = unique_name(&profile.index.name, &names);
+                names.push(profile.index.name.clone());
+                if !password_ok {
+                    summary.password_lost.push(profile.index.name.clone());
+                }
+                summary.profiles.push(profile);
+            }
+            Err(e) => summary.failed.push((file, format!("{e:#}"))),
+        }
+    }
+    summary
+}
+
+/// 返回档案以及密码是否成功迁移（没有保存密码时也算成功）
+fn migrate_profile(dir: &Path, path: &Path, keys: &[String]) -> Result<(ProfileConfig, bool)> {
+    let index = read_index(path)?;
+    let mut profile = match resolve_settings(dir, &index) {
+        Some(settings) => crate::settings_import::import(&settings)?.profile,
+        None => crate::config::new_profile(&index.name),
+    };
+    profile.index.name = index.name;
+    profile.index.last_character_name = index.last_character_name;
+    profile.index.additional_args = index.additional_args;
+    let encrypted = std::mem::take(&mut profile.settings.password);
+    let password = reencrypt(&encrypted, keys);
+    let password_ok = encrypted.is_empty() || password.is_some();
+    profile.settings.password = password.unwrap_or_default();
+    Ok((profile, password_ok))
+}
+
+fn hostname() -> String {
+    hostname::get()
+        .ok()
+        .and_then(|name| name.into_string().ok())
+        .unwrap_or_default()
+}
+
+/// 旧 Launcher 可能使用的密钥，按可能性排列
+///
+/// .NET 的 Environment.MachineName 在 Windows 上是大写的 NetBIOS 名（最多 15 个字符），
+/// 其他系统上是第一个 `.` 之前的主机名；最后是本 Launcher 使用的完整主机名。
+pub fn legacy_keys(hostname: &str) -> Vec<String> {
+    let short = hostname.split('.').next().unwrap_or(hostname);
+    let netbios: String = short.to_uppercase().chars().take(15).collect();
+    let ordered = if cfg!(target_os = "windows") {
+        [netbios, short.to_string(), hostname.to_string()]
+    } else {
+        [short.to_string(), netbios, hostname.to_string()]
+    };
+    let mut keys: Vec<String> = Vec::new();
+    for key in ordered {
+        if !key.is_empty() && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// 用旧 Launcher 的密钥解密后再用本机的密钥加密，无法解密时返回 None
+fn reencrypt(encrypted: &str, keys: &[String]) -> Option<String> {
+    if encrypted.is_empty() {
+        return None;
+    }
+    keys.iter()
+        .map(|key| crate::crypter::decrypt_with_key(encrypted, key))
+        .find(|plain| plausible_password(plain))
+        .map(|plain| crate::crypter::encrypt(&plain))
+}
+
+/// 密钥不对时解密结果通常包含控制字符或无效的 UTF-8
+fn plausible_password(plain: &str) -> bool {
+    !plain.is_empty() && !plain.chars().any(|c| c.is_control() || c == char::REPLACEMENT_CHARACTER)
+}
+
+fn unique_name(name: &str, existing: &[String]) -> String {
+    if !existing.iter().any(|n| n == name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|i| format!("{} ({})", name, i))
+        .find(|candidate| !existing.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_legacy_keys() {
+        let keys = legacy_keys("gaming-laptop-2024.local");
+        assert_eq!(keys.len(), 3);
+        assert!(keys.contains(&"GAMING-LAPTOP-2".to_string()));
+        assert!(keys.contains(&"gaming-laptop-2024".to_string()));
+        assert_eq!(keys.last().map(String::as_str), Some("gaming-laptop-2024.local"));
+        // 重复的密钥只保留一个
+        assert_eq!(legacy_keys("PC"), ["PC"]);
+    }
+
+    #[test]
+    fn test_migrate_install() {
+        let dir = temp_dir("legacy");
+        let settings_dir = dir.join("Profiles").join("Settings");
+        fs::create_dir_all(&settings_dir).unwrap();
+        // 用旧机器名加密的密码
+        let old_key = "OLD-PC";
+        let mut encrypted = String::from("1-");
+        for (i, byte) in "hunter2".bytes().enumerate() {
+            encrypted.push_str(&format!("{:02X}", byte ^ old_key.as_bytes()[i % old_key.len()]));
+        }
+        fs::write(
+            dir.join("Profiles").join("a.json"),
+            r#"{"Name":"Main","SettingsFile":"a","FileName":"a","LastCharacterName":"Bob","AdditionalArgs":"-skiplogin"}"#,
+        )
+        .unwrap();
+        fs::write(
+            settings_dir.join("a.json"),
+            format!(r#"{{"username":"bob","password":"{}","ip":"127.0.0.1","port":2593}}"#, encrypted),
+        )
+        .unwrap();
+        fs::write(dir.join("Profiles").join("notes.json"), "[]").unwrap();
+
+        let install = inspect(&dir).unwrap();
+        assert_eq!(install.profiles.len(), 1);
+        assert!(install.client_dir.is_none());
+
+        let summary = migrate_with_keys(&install, &["Main".to_string()], &[]);
+        assert!(summary.failed.is_empty());
+        let profile = &summary.profiles[0];
+        assert_eq!(profile.index.name, "Main (2)");
+        assert_eq!(profile.index.last_character_name, "Bob");
+        assert_eq!(profile.index.additional_args, "-skiplogin");
+        assert_eq!(profile.settings.username, "bob");
+        // 没有能解密的密钥时，密码需要重新输入
+        assert_eq!(summary.password_lost, ["Main (2)"]);
+        assert!(profile.settings.password.is_empty());
+
+        let (profile, password_ok) = migrate_profile(&dir, &install.profiles[0], &["OLD-PC".to_string()]).unwrap();
+        assert!(password_ok);
+        assert_eq!(crate::crypter::decrypt(&profile.settings.password), "hunter2");
+    }
+
+    #[test]
+    fn test_looks_like_launcher_dir() {
+        assert!(looks_like_launcher_dir("openuo-launcher"));
+        assert!(looks_like_launcher_dir("classicuolauncher"));
+        assert!(!looks_like_launcher_dir("steam"));
+    }
+}
@@ -4,27 +4,100 @@
 // 初始化 i18n（必须在最前面）
 rust_i18n::i18n!("locales", fallback = "en");
 
+mod ab_compare;
+mod activity_log;
+mod archive;
+mod bootstrap;
+mod checksum;
+mod child_group;
+mod client_versions;
+mod clock;
+mod cloud_sync;
+mod collation;
 mod config;
 mod crypter;
+mod data_dedup;
+mod delta_update;
+mod disk_space;
+mod download;
 mod encryption_helper;
+mod error_help;
+mod event_pump;
+mod file_manifest;
+mod folder_sync;
+mod game_data;
 mod github;
+mod global_hotkey;
+mod gpu_preference;
+mod history;
+mod humanize;
 mod i18n;
+mod ime;
+mod install_dir;
+mod kiosk;
+mod launch_target;
+mod layout_check;
+mod legacy_migration;
+mod mod_packs;
+mod network_check;
+mod notification;
+mod offline_install;
+mod operation_lock;
+mod password_rekey;
+mod pending_download;
+mod permissions;
+mod playtime;
+mod power;
+mod process;
+mod process_tuning;
+mod profile_backup;
+mod profile_bundle;
 mod profile_editor;
+mod profile_lint;
+mod release_cache;
+mod release_chain;
+mod render_driver;
+mod release_notes;
+mod requirements;
+mod retry;
+mod scheduler;
+mod self_update;
+mod server_list;
+mod session_password;
+mod settings_export;
+mod settings_import;
+mod shard_events;
+mod shard_rules;
+mod signature;
+mod splash;
+mod staff_mode;
+mod status_bar;
+mod storage;
+mod status_report;
 mod system_info;
+#[cfg(test)]
+mod test_dir;
+mod tls_trust;
+mod translucency;
 mod ui;
+mod uo_data;
+mod update_policy;
+mod url_scheme;
 mod version_reader;
+mod vote;
+mod watchdog;
 
 use anyhow::{Context, Result};
 use egui_wgpu::{Renderer, ScreenDescriptor};
 use egui_winit::{pixels_per_point, State as EguiWinitState};
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::info;
 use winit::dpi::LogicalSize;
 use winit::event::{Event, WindowEvent};
-use winit::event_loop::EventLoop;
+use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
 
-use config::load_config_from_disk;
 use ui::LauncherUi;
 
 #[cfg(target_os = "windows")]
@@ -48,6 +121,7 @@ fn set_windows_app_id() {
 fn init_tracing() {
     let _ = tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
         .try_init();
 }
 
@@ -85,21 +159,74 @@ fn get_primary_screen_size() -> (u32, u32) {
     }
 }
 
+/// 发布版本没有控制台窗口，命令行模式下输出到启动它的终端
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
+
 fn main() -> Result<()> {
     init_tracing();
+    // 必须在创建任何线程之前获取本地时区
+    clock::init_local_offset();
     #[cfg(target_os = "windows")]
     set_windows_app_id();
     
     // 加载保存的语言设置
     let launcher_settings = config::load_launcher_settings();
+    config::set_open_uo_dir_override(launcher_settings.openuo_dir.clone());
+    github::set_download_connections(launcher_settings.download_connections);
+    github::set_github_token(&launcher_settings.github_token);
+    bootstrap::set(launcher_settings.bootstrap.clone());
+    kiosk::init(&config::base_dir(), launcher_settings.kiosk);
+    retry::set_max_attempts(launcher_settings.retry_attempts);
+    github::set_network_timeouts(launcher_settings.connect_timeout_secs, launcher_settings.read_timeout_secs);
     
     // 初始化国际化（优先使用保存的语言）
     i18n::init_locale_with_saved(launcher_settings.language);
+
+    // 命令行检查档案，输出报告后退出，不创建窗口
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|arg| arg == "--lint-profile") {
+        let path = args.get(pos + 1).context("--lint-profile 需要指定档案文件路径")?;
+        attach_parent_console();
+        let clean = profile_lint::run_cli(std::path::Path::new(path))?;
+        std::process::exit(if clean { 0 } else { 1 });
+    }
+    // 输出版本和最近启动状态（--json 为 JSON 格式），供外部监控工具使用
+    if args.iter().any(|arg| arg == "--status") {
+        attach_parent_console();
+        return status_report::run_cli(args.iter().any(|arg| arg == "--json"));
+    }
     
-    pollster::block_on(run())
+    let result = pollster::block_on(run(launcher_settings.translucent_window));
+    child_group::terminate_all();
+    if let Err(e) = &result
+        && error_help::classify(e) == Some(error_help::FailureKind::NoGpuAdapter)
+    {
+        // 窗口还无法显示，使用系统对话框说明原因
+        let kind = error_help::FailureKind::NoGpuAdapter;
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Error)
+            .set_title(i18n::t!(kind.title_key()).as_ref())
+            .set_description(format!(
+                "{}\n\n{}\n\n{e:#}",
+                i18n::t!(kind.explanation_key()),
+                i18n::t!(kind.cause_key())
+            ))
+            .set_buttons(rfd::MessageButtons::Ok)
+            .show();
+    }
+    result
 }
 
-async fn run() -> Result<()> {
+async fn run(translucent: bool) -> Result<()> {
+    // 读取配置和初始化显卡同时进行，加载完成前显示启动画面
+    let ui_rx = splash::spawn_loader();
     let event_loop = EventLoop::new().context("Failed to create event loop")?;
     
     // 加载窗口图标
@@ -109,7 +236,8 @@ async fn run() -> Result<()> {
         .with_title("OpenUO Launcher")
         .with_inner_size(LogicalSize::new(960.0, 600.0))
         .with_min_inner_size(LogicalSize::new(720.0, 480.0))
-        .with_window_icon(window_icon.clone());
+        .with_window_icon(window_icon.clone())
+        .with_transparent(translucent);
     
     // Windows: create hidden first to avoid white flash and help taskbar icon apply
     #[cfg(target_os = "windows")]
@@ -176,6 +304,13 @@ async fn run() -> Result<()> {
     }
 
     let adapter = adapter.context("No compatible GPU adapter found")?;
+    let adapter_info = adapter.get_info();
+    let gpu_info = requirements::GpuInfo {
+        name: adapter_info.name.clone(),
+        backend: format!("{:?}", adapter_info.backend),
+        software: adapter_info.device_type == wgpu::DeviceType::Cpu,
+        full_feature_level: adapter.get_downlevel_capabilities().is_webgpu_compliant(),
+    };
 
     let (device, queue) = adapter
         .request_device(
@@ -196,6 +331,16 @@ async fn run() -> Result<()> {
         .find(|format| format.is_srgb())
         .unwrap_or(caps.formats[0]);
 
+    // 合成器不支持透明时退回不透明窗口
+    let translucent_alpha = if translucent { translucency::pick_alpha_mode(&caps.alpha_modes) } else { None };
+    if translucent && translucent_alpha.is_none() {
+        info!("Compositor does not support transparency, using an opaque window");
+    }
+    let translucent = translucent_alpha.is_some();
+    if translucent {
+        translucency::enable_blur(&window);
+    }
+
     let size = window.inner_size();
     let mut config = wgpu::SurfaceConfiguration {
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -203,7 +348,7 @@ async fn run() -> Result<()> {
         width: size.width.max(1),
         height: size.height.max(1),
         present_mode: caps.present_modes[0],
-        alpha_mode: caps.alpha_modes[0],
+        alpha_mode: translucent_alpha.unwrap_or(caps.alpha_modes[0]),
         view_formats: vec![],
         desired_maximum_frame_latency: 2,
     };
@@ -219,16 +364,18 @@ async fn run() -> Result<()> {
         None,
     );
     let mut egui_renderer = Renderer::new(&device, surface_format, None, 1);
+    let mut ime = ime::ImeState::default();
+    let mut viewport_info = egui::ViewportInfo::default();
 
-    let loaded_config = load_config_from_disk();
-    let mut ui = LauncherUi::new(loaded_config);
+    let mut ui: Option<LauncherUi> = None;
+    let mut update_confirmed = false;
+    let mut gpu_info = Some(gpu_info);
+    let mut last_frame = Instant::now();
 
     // 获取屏幕信息
     let scale_factor = window.scale_factor();
     let (screen_width, screen_height) = get_primary_screen_size();
     
-    ui.set_screen_info(screen_width, screen_height, scale_factor);
-    
     // Windows: show window after resources/icons are ready to avoid white flash and help taskbar icon display
     #[cfg(target_os = "windows")]
     {
@@ -248,13 +395,26 @@ async fn run() -> Result<()> {
 
     event_loop.run(move |event, target| match event {
         Event::WindowEvent { event, window_id } if window_id == window.id() => {
-            let response = egui_state.on_window_event(&window, &event);
+            let Some(ime_event) = ime.filter(&event) else {
+                return;
+            };
+            let response = egui_state.on_window_event(&window, &ime_event);
             if response.consumed {
                 return;
             }
 
             match event {
-                WindowEvent::CloseRequested => target.exit(),
+                // 有下载或安装在进行时先确认，退出前保存状态
+                WindowEvent::CloseRequested => match &mut ui {
+                    Some(ui) => {
+                        if ui.request_close() && ui.shutdown() {
+                            target.exit();
+                        } else {
+                            window.request_redraw();
+                        }
+                    }
+                    None => target.exit(),
+                },
                 WindowEvent::Resized(new_size) => {
                     if new_size.width > 0 && new_size.height > 0 {
                         config.width = new_size.width;
@@ -264,6 +424,10 @@ async fn run() -> Result<()> {
                     }
                 }
                 WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                    if let Some(ui) = &mut ui {
+                        let monitor = window.current_monitor().and_then(|m| m.name());
+                        ui.set_screen_info(screen_width, screen_height, scale_factor, monitor);
+                    }
                     let new_size = window.inner_size();
                     egui_ctx.set_pixels_per_point(scale_factor as f32);
                     config.width = new_size.width.max(1);
@@ -272,14 +436,44 @@ async fn run() -> Result<()> {
                     window.request_redraw();
                 }
                 WindowEvent::RedrawRequested => {
+                    last_frame = Instant::now();
                     let input = egui_state.take_egui_input(&window);
 
+                    if ui.is_none()
+                        && let Ok(mut loaded) = ui_rx.try_recv()
+                    {
+                        let monitor = window.current_monitor().and_then(|m| m.name());
+                        loaded.set_screen_info(screen_width, screen_height, scale_factor, monitor);
+                        if let Some(info) = gpu_info.take() {
+                            loaded.set_gpu_info(info);
+                        }
+                        loaded.set_translucent(translucent);
+                        ui = Some(loaded);
+                    }
+
                     let full_output = egui_ctx.run(input, |ctx| {
                         ctx.request_repaint();
-                        ui.ui(ctx);
+                        match &mut ui {
+                            Some(ui) => ui.ui(ctx),
+                            None => splash::show(ctx),
+                        }
                     });
 
+                    // 确认关闭对话框中选择了退出
+                    if let Some(ui) = &mut ui
+                        && std::mem::take(&mut ui.close_confirmed)
+                        && ui.shutdown()
+                    {
+                        target.exit();
+                        return;
+                    }
+
+                    let ime_output = full_output.platform_output.ime;
                     egui_state.handle_platform_output(&window, full_output.platform_output);
+                    ime.update_cursor_area(&egui_ctx, &window, ime_output);
+                    // 界面发出的窗口命令，例如全局快捷键恢复窗口
+                    let commands = full_output.viewport_output[&egui::ViewportId::ROOT].commands.clone();
+                    egui_winit::process_viewport_commands(&egui_ctx, &mut viewport_info, commands, &window, false, &mut false);
 
                     let screen_descriptor = ScreenDescriptor {
                         size_in_pixels: [config.width, config.height],
@@ -335,12 +529,7 @@ async fn run() -> Result<()> {
                                     view: &view,
                                     resolve_target: None,
                                     ops: wgpu::Operations {
-                                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                                            r: 0.1,
-                                            g: 0.1,
-                                            b: 0.1,
-                                            a: 1.0,
-                                        }),
+                                        load: wgpu::LoadOp::Clear(translucency::clear_color(translucent)),
                                         store: wgpu::StoreOp::Store,
                                     },
                                 })],
@@ -355,6 +544,12 @@ async fn run() -> Result<()> {
                     queue.submit(std::iter::once(encoder.finish()));
                     surface_tex.present();
 
+                    // 主界面显示出来后，通知等待中的旧版本更新成功
+                    if !update_confirmed && ui.is_some() {
+                        update_confirmed = true;
+                        self_update::confirm_started();
+                    }
+
                     for id in &full_output.textures_delta.free {
                         egui_renderer.free_texture(id);
                     }
@@ -362,6 +557,7 @@ async fn run() -> Result<()> {
                     if full_output.viewport_output[&egui::ViewportId::ROOT]
                         .repaint_delay
                         .is_zero()
+                        && power::frame_interval().is_none()
                     {
                         window.request_redraw();
                     }
@@ -369,9 +565,16 @@ async fn run() -> Result<()> {
                 _ => {}
             }
         }
-        Event::AboutToWait => {
-            window.request_redraw();
-        }
+        Event::AboutToWait => match power::frame_interval() {
+            // 省电时限制帧率：输入照常处理，画面在下一帧更新
+            Some(interval) if last_frame.elapsed() < interval => {
+                target.set_control_flow(ControlFlow::WaitUntil(last_frame + interval));
+            }
+            _ => {
+                target.set_control_flow(ControlFlow::Wait);
+                window.request_redraw();
+            }
+        },
         _ => {}
     })?;
 
@@ -445,6 +648,17 @@ fn install_cjk_font(ctx: &egui::Context) {
         "/usr/share/fonts/truetype/arphic/uming.ttc",
     ];
 
+    // 中文字体大多不含韩文，韩文字体放在其后作为补充（Noto CJK 已经包含）
+    #[cfg(target_os = "windows")]
+    let hangul_candidates = ["C:\\Windows\\Fonts\\malgun.ttf"];
+    #[cfg(target_os = "macos")]
+    let hangul_candidates = ["/System/Library/Fonts/AppleSDGothicNeo.ttc"];
+    #[cfg(target_os = "linux")]
+    let hangul_candidates = [
+        "/usr/share/fonts/truetype/nanum/NanumGothic.ttf",
+        "/usr/share/fonts/nanum/NanumGothic.ttf",
+    ];
+
     let font_id = "cjk-fallback";
     let loaded = candidates
         .iter()
@@ -464,8 +678,20 @@ fn install_cjk_font(ctx: &egui::Context) {
             .entry(egui::FontFamily::Monospace)
             .or_default()
             .insert(0, font_id.to_string());
-        ctx.set_fonts(fonts);
     } else {
         tracing::warn!("{}", i18n::t!("log.font_not_found"));
     }
+
+    let hangul_id = "hangul-fallback";
+    if let Some(data) = hangul_candidates.iter().find_map(|path| fs::read(path).ok()) {
+        fonts
+            .font_data
+            .insert(hangul_id.to_string(), egui::FontData::from_owned(data));
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            let list = fonts.families.entry(family).or_default();
+            let at = list.iter().position(|id| id == font_id).map_or(0, |i| i + 1);
+            list.insert(at, hangul_id.to_string());
+        }
+    }
+    ctx.set_fonts(fonts);
 }
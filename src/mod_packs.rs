@@ -0,0 +1,423 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::operation_lock::{self, Operation};
+
+/// 补丁包中可选的信息文件，例如 `{"name": "Britain Decorations", "version": "3"}`
+pub const PACK_INFO_FILE: &str = "openuo-mod.json";
+/// UO 目录中记录已启用的补丁包和被覆盖文件的备份
+const STATE_DIR: &str = ".openuo-mods";
+const STATE_FILE: &str = "applied.json";
+const ORIGINALS_DIR: &str = "originals";
+
+/// 补丁包的名称和版本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackInfo {
+    pub name: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+}
+
+fn default_version() -> String {
+    "1".to_string()
+}
+
+/// 已导入 Mods 目录的补丁包（服务器发布的 art、gump、地图补丁）
+#[derive(Debug, Clone)]
+pub struct Pack {
+    pub info: PackInfo,
+    pub dir: PathBuf,
+    /// 相对路径（统一用 / 分隔），不含信息文件
+    pub files: Vec<String>,
+}
+
+/// 已应用到 UO 目录的补丁包
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedPack {
+    pub name: String,
+    pub version: String,
+    files: Vec<AppliedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct AppliedFile {
+    /// UO 目录中的相对路径
+    path: String,
+    /// 写入的文件的 SHA-256，停用时用来判断文件是否被其他更新替换过
+    sha256: String,
+    /// 覆盖前已有原文件，原文件备份在 .openuo-mods/originals
+    backed_up: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    packs: Vec<AppliedPack>,
+}
+
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            list_files(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn read_pack(dir: &Path) -> Result<Pack> {
+    let info: PackInfo = serde_json::from_str(&fs::read_to_string(dir.join(PACK_INFO_FILE))?)
+        .with_context(|| format!("{} 格式无效", PACK_INFO_FILE))?;
+    let mut files = Vec::new();
+    list_files(dir, dir, &mut files)?;
+    files.retain(|f| f != PACK_INFO_FILE);
+    files.sort();
+    Ok(Pack { info, dir: dir.to_path_buf(), files })
+}
+
+/// Mods 目录中的补丁包，按名称排序
+pub fn library() -> Vec<Pack> {
+    library_in(&crate::config::mods_dir())
+}
+
+fn library_in(dir: &Path) -> Vec<Pack> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<Pack> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir() && !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| read_pack(&entry.path()).ok())
+        .collect();
+    packs.sort_by_key(|p| p.info.name.to_lowercase());
+    packs
+}
+
+/// 名称用作目录名，去掉路径分隔符等不能出现在文件名中的字符
+fn dir_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    cleaned.trim().trim_start_matches('.').to_string()
+}
+
+/// 导入补丁包压缩包；已有同名补丁包时替换为新版本，返回导入的名称和版本
+///
+/// 没有信息文件时名称取自文件名，版本为 1。
+pub fn import(archive: &Path) -> Result<PackInfo> {
+    import_into(&crate::config::mods_dir(), archive)
+}
+
+fn import_into(library: &Path, archive: &Path) -> Result<PackInfo> {
+    let staging = library.join(format!(".import-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&staging)?;
+    let result = (|| {
+        crate::archive::extract(archive, &staging).with_context(|| format!("无法解压 {}", archive.display()))?;
+        let info_path = staging.join(PACK_INFO_FILE);
+        if !info_path.exists() {
+            let file_name = archive.file_name().unwrap_or_default().to_string_lossy();
+            let name = crate::archive::strip_extension(&file_name).unwrap_or(&file_name).to_string();
+            fs::write(&info_path, serde_json::to_string_pretty(&PackInfo { name, version: default_version() })?)?;
+        }
+        let pack = read_pack(&staging)?;
+        if pack.files.is_empty() {
+            anyhow::bail!("{} 中没有文件", archive.display());
+        }
+        let name = dir_name(&pack.info.name);
+        if name.is_empty() {
+            anyhow::bail!("无效的补丁包名称: {:?}", pack.info.name);
+        }
+        let target = library.join(name);
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(&staging, &target)?;
+        Ok(pack.info)
+    })();
+    if result.is_err() {
+        fs::remove_dir_all(&staging).ok();
+    }
+    result
+}
+
+/// 从 Mods 目录删除补丁包；已经应用到 UO 目录的文件不受影响，仍可以停用
+pub fn remove(pack: &Pack) -> Result<()> {
+    fs::remove_dir_all(&pack.dir).with_context(|| format!("无法删除 {}", pack.dir.display()))
+}
+
+fn state_path(uo_dir: &Path) -> PathBuf {
+    uo_dir.join(STATE_DIR).join(STATE_FILE)
+}
+
+fn load_state(uo_dir: &Path) -> State {
+    fs::read_to_string(state_path(uo_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(uo_dir: &Path, state: &State) -> Result<()> {
+    let path = state_path(uo_dir);
+    fs::create_dir_all(path.parent().unwrap_or(uo_dir))?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// 已应用到 UO 目录的补丁包
+pub fn applied(uo_dir: &Path) -> Vec<AppliedPack> {
+    load_state(uo_dir).packs
+}
+
+/// 补丁包不能只应用到这个档案的原因：UO 目录本身是链接，或者其他档案也在用它
+///
+/// 补丁包直接覆盖 UO 目录中的文件，共用目录的所有档案都会用到这些文件。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SharedDir {
+    /// 合并重复数据时创建的符号链接或 junction
+    Link,
+    /// 使用同一个目录（解析链接后）的其他档案
+    Profiles(Vec<String>),
+}
+
+/// `others` 为其他档案的名称和 UO 目录，目录不共用时返回 None
+pub fn shared_dir(uo_dir: &Path, others: &[(String, PathBuf)]) -> Option<SharedDir> {
+    if fs::symlink_metadata(uo_dir).is_ok_and(|m| m.file_type().is_symlink()) {
+        return Some(SharedDir::Link);
+    }
+    let canonical = uo_dir.canonicalize().ok()?;
+    let profiles: Vec<String> = others
+        .iter()
+        .filter(|(_, dir)| dir.canonicalize().is_ok_and(|other| other == canonical))
+        .map(|(name, _)| name.clone())
+        .collect();
+    (!profiles.is_empty()).then_some(SharedDir::Profiles(profiles))
+}
+
+/// UO 目录中的目标文件；已有只是大小写不同的文件时覆盖它，避免 Linux 上同时存在两份
+fn resolve_target(uo_dir: &Path, relative: &str) -> Result<PathBuf> {
+    let safe = crate::archive::safe_relative_path(relative).with_context(|| format!("无效的文件路径: {}", relative))?;
+    let target = uo_dir.join(&safe);
+    if target.exists() {
+        return Ok(target);
+    }
+    let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+        return Ok(target);
+    };
+    let name = name.to_string_lossy().to_lowercase();
+    let existing = fs::read_dir(parent)
+        .ok()
+        .and_then(|entries| entries.flatten().find(|e| e.file_name().to_string_lossy().to_lowercase() == name))
+        .map(|e| e.path());
+    Ok(existing.unwrap_or(target))
+}
+
+fn backup_path(uo_dir: &Path, relative: &str) -> PathBuf {
+    uo_dir.join(STATE_DIR).join(ORIGINALS_DIR).join(relative)
+}
+
+/// 把补丁包应用到 UO 目录，原文件先移到备份目录；已启用旧版本时先停用旧版本
+///
+/// 任何一个文件失败时恢复已经写入的文件。与其他已启用补丁包修改同一文件时拒绝启用。
+pub fn enable(uo_dir: &Path, pack: &Pack) -> Result<()> {
+    let mut state = load_state(uo_dir);
+    if state.packs.iter().any(|p| p.name == pack.info.name) {
+        disable(uo_dir, &pack.info.name)?;
+        state = load_state(uo_dir);
+    }
+    let conflicts: Vec<String> = state
+        .packs
+        .iter()
+        .flat_map(|p| p.files.iter().filter(|f| pack.files.iter().any(|n| n.eq_ignore_ascii_case(&f.path))).map(move |f| format!("{} ({})", f.path, p.name)))
+        .collect();
+    if !conflicts.is_empty() {
+        anyhow::bail!("与已启用的补丁包修改了相同的文件: {}", conflicts.join(", "));
+    }
+
+    let mut written: Vec<AppliedFile> = Vec::new();
+    let result = (|| {
+        for relative in &pack.files {
+            let target = resolve_target(uo_dir, relative)?;
+            let path = target.strip_prefix(uo_dir).unwrap_or(&target).to_string_lossy().replace('\\', "/");
+            let backed_up = target.exists();
+            if backed_up {
+                let backup = backup_path(uo_dir, &path);
+                fs::create_dir_all(backup.parent().unwrap_or(uo_dir))?;
+                fs::rename(&target, &backup).with_context(|| format!("无法备份 {}，游戏可能正在使用", target.display()))?;
+            }
+            // 先记录，复制失败时也能恢复备份
+            written.push(AppliedFile { path, sha256: String::new(), backed_up });
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(pack.dir.join(relative), &target).with_context(|| format!("无法写入 {}", target.display()))?;
+            if let Some(file) = written.last_mut() {
+                file.sha256 = crate::checksum::sha256_file(&target)?;
+            }
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        for file in written.iter().rev() {
+            restore(uo_dir, file);
+        }
+        return Err(e);
+    }
+    state.packs.push(AppliedPack { name: pack.info.name.clone(), version: pack.info.version.clone(), files: written });
+    save_state(uo_dir, &state)
+}
+
+/// 删除补丁包写入的文件并恢复原文件
+fn restore(uo_dir: &Path, file: &AppliedFile) {
+    let target = uo_dir.join(&file.path);
+    if let Err(e) = fs::remove_file(&target)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!("Failed to remove {}: {}", target.display(), e);
+        return;
+    }
+    if file.backed_up
+        && let Err(e) = fs::rename(backup_path(uo_dir, &file.path), &target)
+    {
+        tracing::warn!("Failed to restore {}: {}", target.display(), e);
+    }
+}
+
+/// 停用补丁包，恢复原文件；返回启用后又被其他更新替换过、因此保留不动的文件
+pub fn disable(uo_dir: &Path, name: &str) -> Result<Vec<String>> {
+    let mut state = load_state(uo_dir);
+    let Some(index) = state.packs.iter().position(|p| p.name == name) else {
+        anyhow::bail!("补丁包 {} 没有启用", name);
+    };
+    let pack = state.packs.remove(index);
+    let mut kept = Vec::new();
+    for file in &pack.files {
+        let target = uo_dir.join(&file.path);
+        let unchanged = crate::checksum::sha256_file(&target).is_ok_and(|hash| hash == file.sha256);
+        if unchanged || !target.exists() {
+            restore(uo_dir, file);
+        } else {
+            // 游戏数据包等更新已经替换了这个文件，原文件的备份已经过时
+            fs::remove_file(backup_path(uo_dir, &file.path)).ok();
+            kept.push(file.path.clone());
+        }
+    }
+    save_state(uo_dir, &state)?;
+    Ok(kept)
+}
+
+/// 补丁包管理的后台任务
+pub enum ModTask {
+    Import(PathBuf),
+    Remove(Pack),
+    Enable { uo_dir: PathBuf, pack: Pack },
+    Disable { uo_dir: PathBuf, name: String },
+}
+
+/// 后台任务的结果
+pub enum ModEvent {
+    Imported(PackInfo),
+    Removed(String),
+    Enabled(String),
+    /// 停用的补丁包和保留不动的文件
+    Disabled { name: String, kept: Vec<String> },
+    Failed(String),
+}
+
+/// 在后台线程执行，写入 UO 目录时与安装更新互斥
+pub fn spawn(task: ModTask) -> mpsc::Receiver<ModEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = match task {
+            ModTask::Import(path) => import(&path).map(ModEvent::Imported),
+            ModTask::Remove(pack) => remove(&pack).map(|()| ModEvent::Removed(pack.info.name)),
+            ModTask::Enable { uo_dir, pack } => {
+                let _guard = operation_lock::acquire(Operation::Install);
+                enable(&uo_dir, &pack).map(|()| ModEvent::Enabled(pack.info.name))
+            }
+            ModTask::Disable { uo_dir, name } => {
+                let _guard = operation_lock::acquire(Operation::Install);
+                disable(&uo_dir, &name).map(|kept| ModEvent::Disabled { name, kept })
+            }
+        };
+        let _ = tx.send(result.unwrap_or_else(|e| ModEvent::Failed(format!("{e:#}"))));
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    fn write_pack(library: &Path, name: &str, files: &[(&str, &str)]) -> Pack {
+        let dir = library.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(PACK_INFO_FILE), format!(r#"{{"name": "{name}", "version": "2"}}"#)).unwrap();
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        read_pack(&dir).unwrap()
+    }
+
+    #[test]
+    fn test_enable_and_disable() {
+        let root = temp_dir("mods");
+        let (library, uo) = (root.join("Mods"), root.join("UO"));
+        fs::create_dir_all(&uo).unwrap();
+        fs::write(uo.join("ArtLegacyMUL.uop"), "original art").unwrap();
+        fs::write(uo.join("hues.mul"), "original hues").unwrap();
+
+        let deco = write_pack(&library, "Deco", &[("artlegacymul.uop", "deco art"), ("hues.mul", "deco hues"), ("gumps/new.mul", "new")]);
+        assert_eq!(library_in(&library).len(), 1);
+        enable(&uo, &deco).unwrap();
+        // 只是大小写不同的文件被覆盖，不会多出一份
+        assert_eq!(fs::read_to_string(uo.join("ArtLegacyMUL.uop")).unwrap(), "deco art");
+        assert!(!uo.join("artlegacymul.uop").exists() || cfg!(any(windows, target_os = "macos")));
+        assert_eq!(fs::read_to_string(uo.join("gumps/new.mul")).unwrap(), "new");
+        assert_eq!(applied(&uo)[0].version, "2");
+
+        // 修改相同文件的补丁包不能同时启用
+        let other = write_pack(&library, "Other", &[("hues.mul", "other hues")]);
+        assert!(enable(&uo, &other).is_err());
+        assert_eq!(fs::read_to_string(uo.join("hues.mul")).unwrap(), "deco hues");
+
+        // 数据包更新替换过的文件保留不动
+        fs::write(uo.join("hues.mul"), "updated hues").unwrap();
+        assert_eq!(disable(&uo, "Deco").unwrap(), ["hues.mul"]);
+        assert_eq!(fs::read_to_string(uo.join("ArtLegacyMUL.uop")).unwrap(), "original art");
+        assert_eq!(fs::read_to_string(uo.join("hues.mul")).unwrap(), "updated hues");
+        assert!(!uo.join("gumps/new.mul").exists());
+        assert!(applied(&uo).is_empty());
+        assert!(disable(&uo, "Deco").is_err());
+
+        enable(&uo, &other).unwrap();
+        assert_eq!(fs::read_to_string(uo.join("hues.mul")).unwrap(), "other hues");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shared_dir() {
+        let root = temp_dir("mods");
+        let (uo, own, link) = (root.join("UO"), root.join("Own"), root.join("Linked"));
+        fs::create_dir_all(&uo).unwrap();
+        fs::create_dir_all(&own).unwrap();
+        std::os::unix::fs::symlink(&uo, &link).unwrap();
+
+        assert_eq!(shared_dir(&own, &[("Main".to_string(), uo.clone())]), None);
+        assert_eq!(shared_dir(&link, &[]), Some(SharedDir::Link));
+        // 链接到这个目录的档案也算共用
+        let others = [("Alt".to_string(), link.clone()), ("Other".to_string(), own.clone())];
+        assert_eq!(shared_dir(&uo, &others), Some(SharedDir::Profiles(vec!["Alt".to_string()])));
+    }
+}
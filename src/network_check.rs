@@ -0,0 +1,153 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 每个地址的连接超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 启动前的网络要求（按 profile 配置），例如只能通过 VPN 访问的私服
+///
+/// 不满足时不启动，避免客户端卡在登录界面。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkRequirement {
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    /// 必须能连接的地址（host:port）；为空时检查档案的登录服务器
+    #[serde(rename = "Endpoint", skip_serializing_if = "String::is_empty")]
+    pub endpoint: String,
+    /// 必须处于连接状态的网络接口（例如 wg0、utun3 或 Windows 中的适配器名称）；为空时不检查
+    #[serde(rename = "Interface", skip_serializing_if = "String::is_empty")]
+    pub interface: String,
+}
+
+impl NetworkRequirement {
+    /// 要连接的地址，没有填写时使用登录服务器
+    pub fn endpoint_or(&self, host: &str, port: u16) -> String {
+        match self.endpoint.trim() {
+            "" => format!("{}:{}", host.trim(), port),
+            endpoint => endpoint.to_string(),
+        }
+    }
+}
+
+/// 在后台线程检查，`endpoint` 为要连接的地址
+pub fn spawn(requirement: NetworkRequirement, endpoint: String) -> mpsc::Receiver<Result<(), String>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(check(&requirement.interface, &endpoint).map_err(|e| format!("{e:#}")));
+    });
+    rx
+}
+
+/// 先检查网络接口，再连接地址
+fn check(interface: &str, endpoint: &str) -> Result<()> {
+    let interface = interface.trim();
+    if !interface.is_empty() {
+        match interface_up(interface) {
+            Some(true) => {}
+            Some(false) => bail!("网络接口 {} 没有连接", interface),
+            None => bail!("找不到网络接口 {}", interface),
+        }
+    }
+    let addrs: Vec<_> = endpoint
+        .to_socket_addrs()
+        .with_context(|| format!("无法解析地址 {}", endpoint))?
+        .collect();
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) => Err(e).with_context(|| format!("无法连接 {}", endpoint)),
+        None => bail!("无法解析地址 {}", endpoint),
+    }
+}
+
+/// 接口存在时返回是否已启用并连接，找不到时为 None
+#[cfg(unix)]
+fn interface_up(name: &str) -> Option<bool> {
+    let mut addrs: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut addrs) } != 0 {
+        return None;
+    }
+    let mut found = None;
+    let mut cursor = addrs;
+    while !cursor.is_null() {
+        let ifa = unsafe { &*cursor };
+        cursor = ifa.ifa_next;
+        if ifa.ifa_name.is_null() || unsafe { std::ffi::CStr::from_ptr(ifa.ifa_name) }.to_bytes() != name.as_bytes() {
+            continue;
+        }
+        let flags = ifa.ifa_flags as libc::c_int;
+        let up = flags & libc::IFF_UP != 0 && flags & libc::IFF_RUNNING != 0;
+        found = Some(found.unwrap_or(false) || up);
+    }
+    unsafe { libc::freeifaddrs(addrs) };
+    found
+}
+
+/// 按适配器的名称（“网络连接”中显示的名称）查找，不区分大小写
+#[cfg(windows)]
+fn interface_up(name: &str) -> Option<bool> {
+    use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST, GetAdaptersAddresses, IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+    use windows::Win32::Networking::WinSock::AF_UNSPEC;
+
+    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    let mut size = 16 * 1024u32;
+    // 用 u64 保证结构体对齐；适配器变多时按返回的大小重试
+    let mut buffer: Vec<u64> = Vec::new();
+    for _ in 0..3 {
+        buffer.resize((size as usize).div_ceil(8), 0);
+        let first = buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH;
+        let result = unsafe { GetAdaptersAddresses(AF_UNSPEC.0 as u32, flags, None, Some(first), &mut size) };
+        if result == ERROR_BUFFER_OVERFLOW.0 {
+            continue;
+        }
+        if result != NO_ERROR.0 {
+            return None;
+        }
+        let mut cursor = first as *const IP_ADAPTER_ADDRESSES_LH;
+        while !cursor.is_null() {
+            let adapter = unsafe { &*cursor };
+            cursor = adapter.Next;
+            let friendly = unsafe { adapter.FriendlyName.to_string() }.unwrap_or_default();
+            if friendly.eq_ignore_ascii_case(name) {
+                return Some(adapter.OperStatus == IfOperStatusUp);
+            }
+        }
+        return None;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+        assert!(check("", &endpoint).is_ok());
+        drop(listener);
+        assert!(check("", &endpoint).is_err());
+
+        assert!(check("", "not an address").is_err());
+        assert!(check("openuo-no-such-interface", &endpoint).is_err());
+
+        let requirement = NetworkRequirement { enabled: true, ..Default::default() };
+        assert_eq!(requirement.endpoint_or("play.example.com ", 2593), "play.example.com:2593");
+        let requirement = NetworkRequirement { endpoint: "10.8.0.1:2593".to_string(), ..requirement };
+        assert_eq!(requirement.endpoint_or("play.example.com", 2593), "10.8.0.1:2593");
+    }
+}
@@ -0,0 +1,56 @@
+/// 在后台显示系统通知，失败时只记录日志
+pub fn show(title: String, body: String) {
+    std::thread::spawn(move || {
+        if let Err(e) = show_notification(&title, &body) {
+            tracing::warn!("Failed to show notification: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn show_notification(title: &str, body: &str) -> std::io::Result<()> {
+    std::process::Command::new("notify-send")
+        .args(["--app-name=OpenUO Launcher", title, body])
+        .status()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn show_notification(title: &str, body: &str) -> std::io::Result<()> {
+    let script = format!("display notification {} with title {}", applescript_string(body), applescript_string(title));
+    std::process::Command::new("osascript").args(["-e", &script]).status().map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 通过 PowerShell 显示 Windows 的 toast 通知，文本经环境变量传入以免转义问题
+#[cfg(target_os = "windows")]
+fn show_notification(title: &str, body: &str) -> std::io::Result<()> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    const SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+$template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+$texts = $template.GetElementsByTagName('text')
+$texts.Item(0).AppendChild($template.CreateTextNode($env:OPENUO_NOTIFY_TITLE)) | Out-Null
+$texts.Item(1).AppendChild($template.CreateTextNode($env:OPENUO_NOTIFY_BODY)) | Out-Null
+$toast = [Windows.UI.Notifications.ToastNotification]::new($template)
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('OpenUO Launcher').Show($toast)
+"#;
+    std::process::Command::new("powershell")
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .env("OPENUO_NOTIFY_TITLE", title)
+        .env("OPENUO_NOTIFY_BODY", body)
+        .status()
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn show_notification(_title: &str, _body: &str) -> std::io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{self, ArchiveFormat};
+use crate::download::{DownloadContext, DownloadOutcome};
+
+/// 安装包中可选的版本信息文件，例如 `{"version": "v1.2.0", "platform": "linux-x64"}`
+pub const METADATA_FILE: &str = "openuo-release.json";
+
+#[derive(Debug, Deserialize)]
+struct BundledRelease {
+    version: String,
+    /// 安装包对应的平台，与 release 资产名相同（不含扩展名）
+    #[serde(default)]
+    platform: Option<String>,
+}
+
+/// 检查通过的本地安装包
+#[derive(Debug, Clone)]
+pub struct LocalPackage {
+    pub path: PathBuf,
+    pub version: String,
+    /// 安装包中没有版本信息文件，版本号取自文件名
+    pub version_from_file_name: bool,
+}
+
+/// 检查本地的 OpenUO 安装包（局域网聚会、无法联网的电脑），只读取 zip 目录，不解压
+pub fn inspect(path: &Path) -> Result<LocalPackage> {
+    if ArchiveFormat::detect(path)? != ArchiveFormat::Zip {
+        anyhow::bail!("只支持 zip 格式的安装包: {}", path.display());
+    }
+    let mut zip = zip::ZipArchive::new(fs::File::open(path)?).with_context(|| format!("无法读取 {}", path.display()))?;
+    let names: Vec<String> = zip.file_names().map(str::to_string).collect();
+    let metadata = match zip.by_name(METADATA_FILE) {
+        Ok(mut file) => {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            Some(serde_json::from_str::<BundledRelease>(&content).with_context(|| format!("{} 格式无效", METADATA_FILE))?)
+        }
+        Err(zip::result::ZipError::FileNotFound) => None,
+        Err(e) => return Err(e.into()),
+    };
+    let executable = crate::config::open_uo_binary_path();
+    let executable = executable.file_name().unwrap_or_default().to_string_lossy();
+    let platform = crate::github::get_platform_asset_name();
+    let platform = archive::strip_extension(&platform).unwrap_or(&platform);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let (version, version_from_file_name) = check_package(&names, metadata, &file_name, &executable, platform)?;
+    Ok(LocalPackage {
+        path: path.to_path_buf(),
+        version,
+        version_from_file_name,
+    })
+}
+
+/// 检查安装包的内容，返回版本号以及它是否取自文件名
+fn check_package(
+    names: &[String],
+    metadata: Option<BundledRelease>,
+    file_name: &str,
+    executable: &str,
+    platform: &str,
+) -> Result<(String, bool)> {
+    // 与下载的安装包相同，可执行文件必须在压缩包的根目录
+    if !names.iter().any(|name| name.trim_start_matches("./") == executable) {
+        anyhow::bail!("安装包根目录中没有 {}，可能不是 OpenUO 安装包或不适用于当前平台", executable);
+    }
+    match metadata {
+        Some(BundledRelease { platform: Some(p), .. }) if p != platform => {
+            anyhow::bail!("安装包适用于 {}，当前平台为 {}", p, platform)
+        }
+        Some(release) if !release.version.trim().is_empty() => Ok((release.version.trim().to_string(), false)),
+        _ => {
+            let stem = archive::strip_extension(file_name).unwrap_or(file_name);
+            Ok((stem.to_string(), true))
+        }
+    }
+}
+
+/// 解压本地安装包并记录版本，安装包本身保留
+pub async fn install(package: LocalPackage, ctx: DownloadContext) -> Result<DownloadOutcome> {
+    // 与下载后的安装相同，开始解压后不可取消
+    let version = package.version.clone();
+    ctx.uninterruptible(move || crate::github::install_open_uo_archive(&package.path, &package.version)).await?;
+    Ok(DownloadOutcome::OpenUO(version))
+}
+
+/// 文件名看起来像安装包（用于拖放）
+pub fn looks_like_package(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(ArchiveFormat::from_name)
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_package() {
+        let contents = names(&["OpenUO", "Data/", "Data/settings.json", METADATA_FILE]);
+        let metadata = || Some(BundledRelease { version: "v1.2.0".to_string(), platform: Some("linux-x64".to_string()) });
+        assert_eq!(
+            check_package(&contents, metadata(), "openuo.zip", "OpenUO", "linux-x64").unwrap(),
+            ("v1.2.0".to_string(), false)
+        );
+        // 其他平台的安装包
+        assert!(check_package(&contents, metadata(), "openuo.zip", "OpenUO", "win-x64").is_err());
+        // 可执行文件不在根目录
+        let nested = names(&["OpenUO-v1.2.0/OpenUO"]);
+        assert!(check_package(&nested, metadata(), "openuo.zip", "OpenUO", "linux-x64").is_err());
+        // 没有版本信息时使用文件名
+        assert_eq!(
+            check_package(&names(&["./OpenUO"]), None, "OpenUO-v1.3.0.zip", "OpenUO", "linux-x64").unwrap(),
+            ("OpenUO-v1.3.0".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn test_looks_like_package() {
+        assert!(looks_like_package(Path::new("/tmp/OpenUO-linux-x64.ZIP")));
+        assert!(!looks_like_package(Path::new("/tmp/notes.txt")));
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::{Condvar, Mutex};
+
+/// 会写入 OpenUO 目录或 Profiles 目录的操作，同一时间只允许一个
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// 解压、应用补丁、校验并修正安装目录
+    Install,
+    /// 保存 profile
+    ProfileSave,
+}
+
+impl Operation {
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Operation::Install => "operation_lock.install",
+            Operation::ProfileSave => "operation_lock.profile_save",
+        }
+    }
+}
+
+struct OperationLock {
+    current: Mutex<Option<Operation>>,
+    released: Condvar,
+}
+
+static LOCK: OperationLock = OperationLock {
+    current: Mutex::new(None),
+    released: Condvar::new(),
+};
+
+/// 持有期间其他操作需要等待，drop 时释放
+#[must_use]
+pub struct OperationGuard {
+    lock: &'static OperationLock,
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        *self.lock.current.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.lock.released.notify_all();
+    }
+}
+
+impl OperationLock {
+    fn acquire(&'static self, op: Operation) -> OperationGuard {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(running) = *current {
+            tracing::info!("{:?} 等待 {:?} 完成", op, running);
+            current = self.released.wait(current).unwrap_or_else(|e| e.into_inner());
+        }
+        *current = Some(op);
+        OperationGuard { lock: self }
+    }
+
+    fn try_acquire(&'static self, op: Operation) -> Result<OperationGuard, Operation> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = *current {
+            return Err(running);
+        }
+        *current = Some(op);
+        Ok(OperationGuard { lock: self })
+    }
+
+    fn current(&self) -> Option<Operation> {
+        *self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 后台线程使用：等待其他操作完成后开始
+pub fn acquire(op: Operation) -> OperationGuard {
+    LOCK.acquire(op)
+}
+
+/// UI 线程使用：不等待，被占用时返回正在进行的操作
+pub fn try_acquire(op: Operation) -> Result<OperationGuard, Operation> {
+    LOCK.try_acquire(op)
+}
+
+pub fn current() -> Option<Operation> {
+    LOCK.current()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn new_lock() -> &'static OperationLock {
+        Box::leak(Box::new(OperationLock {
+            current: Mutex::new(None),
+            released: Condvar::new(),
+        }))
+    }
+
+    #[test]
+    fn test_try_acquire_reports_holder() {
+        let lock = new_lock();
+        let guard = lock.try_acquire(Operation::Install).ok().unwrap();
+        assert_eq!(lock.current(), Some(Operation::Install));
+        assert_eq!(lock.try_acquire(Operation::ProfileSave).err(), Some(Operation::Install));
+        drop(guard);
+        assert_eq!(lock.current(), None);
+        assert!(lock.try_acquire(Operation::ProfileSave).is_ok());
+    }
+
+    #[test]
+    fn test_acquire_waits_for_release() {
+        let lock = new_lock();
+        let guard = lock.acquire(Operation::ProfileSave);
+        let (tx, rx) = mpsc::channel();
+        let waiter = std::thread::spawn(move || {
+            let _guard = lock.acquire(Operation::Install);
+            tx.send(()).unwrap();
+        });
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(guard);
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        waiter.join().unwrap();
+        assert_eq!(lock.current(), None);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::config::ProfileConfig;
+
+/// 机器名变化后检查保存的密码的结果，内容为档案的 file_name
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RekeyResult {
+    /// 找到了原来的密钥，已改用当前密钥重新加密
+    pub migrated: Vec<String>,
+    /// 无法解密，密码已清空，需要重新输入
+    pub lost: Vec<String>,
+}
+
+impl RekeyResult {
+    pub fn is_empty(&self) -> bool {
+        self.migrated.is_empty() && self.lost.is_empty()
+    }
+}
+
+/// 检查每个档案保存的密码所用的密钥，与当前机器名不同时重新加密或清空
+///
+/// 没有密钥标识的档案（旧版本保存的）视为用 `last_machine`（上次运行时的机器名）加密；
+/// 上次的机器名也未知时假定没有变化。候选的旧密钥包括上次的机器名和当前机器名的各种形式
+/// （完整主机名、短名称、NetBIOS 名），以及无法获取机器名时使用的 "default"。
+pub fn check(profiles: &mut [ProfileConfig], last_machine: Option<&str>) -> RekeyResult {
+    let current = crate::crypter::key_id();
+    let mut candidates = Vec::new();
+    for name in last_machine.into_iter().map(str::to_string).chain([crate::crypter::calculate_key()]) {
+        candidates.extend(crate::legacy_migration::legacy_keys(&name));
+    }
+    candidates.push("default".to_string());
+
+    let mut result = RekeyResult::default();
+    for profile in profiles.iter_mut().filter(|p| !p.settings.password.is_empty()) {
+        let stored = match profile.index.password_key_id.as_str() {
+            "" => match last_machine {
+                Some(name) => crate::crypter::key_id_for(name),
+                None => continue,
+            },
+            id => id.to_string(),
+        };
+        if stored == current {
+            continue;
+        }
+        let id = profile.index.file_name.clone();
+        match crate::crypter::reencrypt(&profile.settings.password, &stored, &candidates) {
+            Some(password) => {
+                profile.settings.password = password;
+                profile.index.password_key_id = current.clone();
+                result.migrated.push(id);
+            }
+            None => {
+                // 用错误的密钥解密只会得到乱码，不能交给自动登录
+                profile.settings.password.clear();
+                profile.index.password_key_id.clear();
+                result.lost.push(id);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(file_name: &str, password: &str, key_id: &str) -> ProfileConfig {
+        let mut profile = crate::config::new_profile(file_name);
+        profile.index.file_name = file_name.to_string();
+        profile.index.password_key_id = key_id.to_string();
+        profile.settings.password = password.to_string();
+        profile
+    }
+
+    /// 模拟用指定机器名加密的密码
+    fn encrypt_with(plain: &str, key: &str) -> String {
+        let mut encrypted = String::from("1-");
+        for (i, byte) in plain.bytes().enumerate() {
+            encrypted.push_str(&format!("{:02X}", byte ^ key.as_bytes()[i % key.len()]));
+        }
+        encrypted
+    }
+
+    #[test]
+    fn test_check() {
+        let old = "OLD-GAMING-PC";
+        let old_id = crate::crypter::key_id_for(old);
+        let mut profiles = vec![
+            profile("current", &crate::crypter::encrypt("a"), &crate::crypter::key_id()),
+            profile("renamed", &encrypt_with("hunter2", old), &old_id),
+            // 旧版本保存的档案没有密钥标识
+            profile("legacy", &encrypt_with("swordfish", old), ""),
+            profile("unknown", &encrypt_with("secret", "SOMEWHERE-ELSE"), &crate::crypter::key_id_for("SOMEWHERE-ELSE")),
+            profile("empty", "", "deadbeef"),
+        ];
+        let result = check(&mut profiles, Some(old));
+        assert_eq!(result.migrated, ["renamed", "legacy"]);
+        assert_eq!(result.lost, ["unknown"]);
+        assert_eq!(crate::crypter::decrypt(&profiles[1].settings.password), "hunter2");
+        assert_eq!(crate::crypter::decrypt(&profiles[2].settings.password), "swordfish");
+        assert_eq!(profiles[1].index.password_key_id, crate::crypter::key_id());
+        assert!(profiles[3].settings.password.is_empty());
+        assert_eq!(crate::crypter::decrypt(&profiles[0].settings.password), "a");
+
+        // 第一次运行新版本时不知道上次的机器名，假定没有变化
+        let mut profiles = vec![profile("legacy", &encrypt_with("swordfish", old), "")];
+        assert!(check(&mut profiles, None).is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 关闭 Launcher 时还没完成的下载，放在配置目录中
+const PENDING_FILE: &str = ".pending_download";
+
+/// 关闭窗口时正在进行的下载，下次启动时提示继续
+///
+/// 单连接下载的安装包会留在临时目录中，继续时从断点请求，不用重新下载已有的部分。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PendingDownload {
+    /// 主安装的 OpenUO，`tag` 为空时为最新版本
+    OpenUO { tag: Option<String> },
+    Verify,
+    Reinstall,
+    SideBySide { tag: String },
+    GameData,
+    Addon { id: String },
+    Launcher,
+}
+
+pub fn save(pending: &PendingDownload) -> Result<()> {
+    save_in(&crate::config::base_dir(), pending)
+}
+
+/// 读取并删除记录，只提示一次
+pub fn take() -> Option<PendingDownload> {
+    take_in(&crate::config::base_dir())
+}
+
+fn save_in(dir: &Path, pending: &PendingDownload) -> Result<()> {
+    let path = dir.join(PENDING_FILE);
+    fs::write(&path, serde_json::to_string(pending)?).with_context(|| format!("无法写入 {}", path.display()))
+}
+
+fn take_in(dir: &Path) -> Option<PendingDownload> {
+    let path = dir.join(PENDING_FILE);
+    let content = fs::read_to_string(&path).ok()?;
+    fs::remove_file(&path).ok();
+    match serde_json::from_str(&content) {
+        Ok(pending) => Some(pending),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_save_and_take() {
+        let dir = temp_dir("pending");
+        assert_eq!(take_in(&dir), None);
+
+        let pending = PendingDownload::SideBySide { tag: "v1.2.0".to_string() };
+        save_in(&dir, &pending).unwrap();
+        assert_eq!(take_in(&dir), Some(pending));
+        // 只提示一次
+        assert_eq!(take_in(&dir), None);
+
+        fs::write(dir.join(PENDING_FILE), "{").unwrap();
+        assert_eq!(take_in(&dir), None);
+        assert!(!dir.join(PENDING_FILE).exists());
+    }
+}
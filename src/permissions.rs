@@ -0,0 +1,189 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+/// 解压后修正文件权限并去掉下载标记，返回被修改的文件数
+///
+/// zip 包并不总是带有 Unix 权限位（例如在 Windows 上打包），
+/// 而下载的文件在 Windows 上带有 Zone.Identifier、在 macOS 上带有 com.apple.quarantine，
+/// 这些都会导致首次启动时被拦截或莫名失败。
+pub fn normalize_install(dir: &Path) -> Result<usize> {
+    let mut fixed = 0;
+    normalize_dir(dir, &mut fixed)?;
+    Ok(fixed)
+}
+
+fn normalize_dir(dir: &Path, fixed: &mut usize) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            continue;
+        }
+        let changed = if file_type.is_dir() {
+            fix_dir(&path)?
+        } else {
+            fix_file(&path)?
+        };
+        if changed | strip_download_marks(&path) {
+            *fixed += 1;
+        }
+        if file_type.is_dir() {
+            normalize_dir(&path, fixed)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn fix_dir(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)?.permissions().mode();
+    let wanted = mode | 0o755;
+    if wanted == mode {
+        return Ok(false);
+    }
+    fs::set_permissions(path, fs::Permissions::from_mode(wanted))?;
+    Ok(true)
+}
+
+#[cfg(windows)]
+fn fix_dir(_path: &Path) -> Result<bool> {
+    Ok(false)
+}
+
+/// Unix：保证文件可读写，可执行文件和动态库加上执行位
+#[cfg(unix)]
+fn fix_file(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)?.permissions().mode();
+    let mut wanted = mode | 0o644;
+    if needs_exec_bit(path) {
+        wanted |= 0o111;
+    }
+    if wanted == mode {
+        return Ok(false);
+    }
+    fs::set_permissions(path, fs::Permissions::from_mode(wanted))?;
+    Ok(true)
+}
+
+/// Windows：去掉只读属性
+#[cfg(windows)]
+fn fix_file(path: &Path) -> Result<bool> {
+    let mut perms = fs::metadata(path)?.permissions();
+    if !perms.readonly() {
+        return Ok(false);
+    }
+    #[allow(clippy::permissions_set_readonly_false)]
+    perms.set_readonly(false);
+    fs::set_permissions(path, perms)?;
+    Ok(true)
+}
+
+/// 去掉系统给下载文件加的来源标记，返回是否有改动
+///
+/// Windows 上删除 Zone.Identifier 备用数据流（相当于资源管理器中的“解除锁定”），
+/// 避免 SmartScreen 提示或静默拦截；macOS 上删除 com.apple.quarantine 扩展属性，
+/// 避免 Gatekeeper 拒绝运行。只应用于 Launcher 自己下载的文件。
+#[cfg(windows)]
+pub fn strip_download_marks(path: &Path) -> bool {
+    let mut zone = path.as_os_str().to_owned();
+    zone.push(":Zone.Identifier");
+    fs::remove_file(&zone).is_ok()
+}
+
+#[cfg(target_os = "macos")]
+pub fn strip_download_marks(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::removexattr(c_path.as_ptr(), c"com.apple.quarantine".as_ptr(), libc::XATTR_NOFOLLOW) == 0 }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn strip_download_marks(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn needs_exec_bit(path: &Path) -> bool {
+    use std::io::Read;
+
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let mut header = [0u8; 4];
+    let read = fs::File::open(path)
+        .and_then(|mut f| f.read(&mut header))
+        .unwrap_or(0);
+    is_executable_candidate(name, &header[..read])
+}
+
+/// 根据文件名和文件头判断是否应该可执行
+#[cfg_attr(windows, allow(dead_code))]
+fn is_executable_candidate(name: &str, header: &[u8]) -> bool {
+    const MAGICS: [&[u8]; 6] = [
+        b"\x7fELF",
+        b"#!",
+        &[0xFE, 0xED, 0xFA, 0xCE],
+        &[0xFE, 0xED, 0xFA, 0xCF],
+        &[0xCF, 0xFA, 0xED, 0xFE],
+        // Universal binary
+        &[0xCA, 0xFE, 0xBA, 0xBE],
+    ];
+    let lower = name.to_ascii_lowercase();
+    lower.ends_with(".so")
+        || lower.contains(".so.")
+        || lower.ends_with(".dylib")
+        || lower.ends_with(".sh")
+        || MAGICS.iter().any(|magic| header.starts_with(magic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executable_candidates() {
+        assert!(is_executable_candidate("OpenUO", b"\x7fELF"));
+        assert!(is_executable_candidate("OpenUO", &[0xCF, 0xFA, 0xED, 0xFE]));
+        assert!(is_executable_candidate("libSDL2-2.0.so.0", b""));
+        assert!(is_executable_candidate("libFNA3D.dylib", b"data"));
+        assert!(is_executable_candidate("run.sh", b"#!/b"));
+        assert!(!is_executable_candidate("settings.json", b"{\n  "));
+        assert!(!is_executable_candidate("OpenUO.dll", b"MZ\x90\x00"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_normalize_sets_exec_bit() {
+        use crate::test_dir::temp_dir;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("perms");
+        fs::create_dir_all(dir.join("lib")).unwrap();
+        let exe = dir.join("OpenUO");
+        let lib = dir.join("lib").join("libSDL2.so");
+        let data = dir.join("settings.json");
+        fs::write(&exe, b"\x7fELF....").unwrap();
+        fs::write(&lib, b"").unwrap();
+        fs::write(&data, b"{}").unwrap();
+        for path in [&exe, &lib, &data] {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        assert_eq!(normalize_install(&dir).unwrap(), 3);
+        let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&exe), 0o755);
+        assert_eq!(mode(&lib), 0o755);
+        assert_eq!(mode(&data), 0o644);
+
+        // 再次运行不应有改动
+        assert_eq!(normalize_install(&dir).unwrap(), 0);
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::history::LaunchRecord;
+
+/// 某个 profile 某一天的游戏时长汇总
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyPlaytime {
+    /// 本地日期，例如 "2024-05-01"
+    pub date: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    pub sessions: u32,
+    pub seconds: u64,
+}
+
+/// 按（本地日期，profile）汇总游戏时长，跨越零点的会话计入开始的那一天
+///
+/// 没有时长的记录（启动失败、仍在运行）不计入。
+pub fn daily_totals<'a>(records: impl IntoIterator<Item = &'a LaunchRecord>, offset: UtcOffset) -> Vec<DailyPlaytime> {
+    let mut totals: BTreeMap<(String, String), DailyPlaytime> = BTreeMap::new();
+    for record in records {
+        let Some(seconds) = record.duration_secs else {
+            continue;
+        };
+        let Ok(started) = OffsetDateTime::from_unix_timestamp(record.started_at) else {
+            continue;
+        };
+        let local = started.to_offset(offset);
+        let date = format!("{:04}-{:02}-{:02}", local.year(), local.month() as u8, local.day());
+
+        let entry = totals
+            .entry((date.clone(), record.profile_id.clone()))
+            .or_insert_with(|| DailyPlaytime {
+                date,
+                profile_id: record.profile_id.clone(),
+                profile_name: record.profile_name.clone(),
+                sessions: 0,
+                seconds: 0,
+            });
+        entry.sessions += 1;
+        entry.seconds += seconds;
+    }
+    totals.into_values().collect()
+}
+
+pub fn to_csv(rows: &[DailyPlaytime]) -> String {
+    let mut csv = String::from("date,profile_id,profile_name,sessions,seconds,hours\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.2}\n",
+            row.date,
+            csv_field(&row.profile_id),
+            csv_field(&row.profile_name),
+            row.sessions,
+            row.seconds,
+            row.seconds as f64 / 3600.0
+        ));
+    }
+    csv
+}
+
+/// 按扩展名导出为 CSV 或 JSON
+pub fn export(rows: &[DailyPlaytime], path: &Path) -> Result<()> {
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let content = if is_json {
+        serde_json::to_string_pretty(rows)?
+    } else {
+        to_csv(rows)
+    };
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(profile: &str, started_at: i64, duration: Option<u64>) -> LaunchRecord {
+        LaunchRecord {
+            profile_id: profile.to_string(),
+            profile_name: format!("Shard, {}", profile),
+            started_at,
+            duration_secs: duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_daily_totals() {
+        // 2024-05-01 18:30 UTC
+        let day1 = 1_714_588_200;
+        let records = [
+            record("a", day1, Some(3600)),
+            record("a", day1 + 600, Some(1800)),
+            record("b", day1, Some(60)),
+            record("a", day1 + 86_400, Some(120)),
+            record("a", day1, None),
+        ];
+
+        let rows = daily_totals(&records, UtcOffset::UTC);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].date, "2024-05-01");
+        assert_eq!(rows[0].profile_id, "a");
+        assert_eq!(rows[0].sessions, 2);
+        assert_eq!(rows[0].seconds, 5400);
+        assert_eq!(rows[2].date, "2024-05-02");
+
+        // UTC+8 时同一时刻已经是第二天
+        let offset = UtcOffset::from_hms(8, 0, 0).unwrap();
+        assert_eq!(daily_totals(&records[..1], offset)[0].date, "2024-05-02");
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        let rows = daily_totals(&[record("a", 0, Some(5400))], UtcOffset::UTC);
+        let csv = to_csv(&rows);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("1970-01-01,a,\"Shard, a\",1,5400,1.50")
+        );
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
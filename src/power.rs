@@ -0,0 +1,203 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 检查电源和网络状态的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 省电时的帧间隔（约 10 FPS）
+const LOW_POWER_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 当前的帧间隔（毫秒），0 表示不限制
+static FRAME_INTERVAL_MS: AtomicU64 = AtomicU64::new(0);
+
+/// 笔记本的电源和网络状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerState {
+    /// 正在使用电池供电
+    pub on_battery: bool,
+    /// 当前网络按流量计费（手机热点、移动网络等）
+    pub metered: bool,
+}
+
+/// 在后台定期检查电源和网络状态，状态变化时发送（第一次检查的结果总是发送）
+pub fn watch() -> mpsc::Receiver<PowerState> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let state = detect();
+            if last != Some(state) {
+                last = Some(state);
+                if tx.send(state).is_err() {
+                    return;
+                }
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+    rx
+}
+
+pub fn detect() -> PowerState {
+    PowerState {
+        on_battery: on_battery(),
+        metered: metered(),
+    }
+}
+
+/// 降低 Launcher 的帧率，由主循环读取
+pub fn set_low_frame_rate(low: bool) {
+    let ms = if low { LOW_POWER_FRAME_INTERVAL.as_millis() as u64 } else { 0 };
+    FRAME_INTERVAL_MS.store(ms, Ordering::Relaxed);
+}
+
+/// 两帧之间的最短间隔，None 表示不限制
+pub fn frame_interval() -> Option<Duration> {
+    match FRAME_INTERVAL_MS.load(Ordering::Relaxed) {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn on_battery() -> bool {
+    battery_discharging(std::path::Path::new("/sys/class/power_supply"))
+}
+
+/// 有系统电池正在放电；鼠标、手柄等外设的电池（scope 为 Device）不算
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn battery_discharging(root: &std::path::Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return false;
+    };
+    let read = |dir: &std::path::Path, name: &str| std::fs::read_to_string(dir.join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+    entries.flatten().any(|entry| {
+        let dir = entry.path();
+        read(&dir, "type") == "Battery" && read(&dir, "scope") != "Device" && read(&dir, "status") == "Discharging"
+    })
+}
+
+/// 通过 NetworkManager 的 Metered 属性判断，没有 NetworkManager 时视为不计费
+#[cfg(target_os = "linux")]
+fn metered() -> bool {
+    std::process::Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .is_some_and(|out| parse_nm_metered(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// 解析 `busctl get-property` 的输出，例如 `u 1`
+///
+/// NMMetered：1 为计费，3 为推测计费（例如手机热点），2 和 4 为不计费，0 为未知。
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nm_metered(output: &str) -> bool {
+    matches!(output.trim().strip_prefix("u ").map(str::trim), Some("1" | "3"))
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> bool {
+    std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .is_ok_and(|out| parse_pmset(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// 解析 `pmset -g batt` 的第一行，例如 `Now drawing from 'Battery Power'`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> bool {
+    output.lines().next().is_some_and(|line| line.contains("'Battery Power'"))
+}
+
+/// macOS 没有简单的接口获取计费网络状态
+#[cfg(target_os = "macos")]
+fn metered() -> bool {
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn on_battery() -> bool {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // ACLineStatus：0 为电池供电，1 为接通电源，255 为未知
+    unsafe { GetSystemPowerStatus(&mut status) }.is_ok() && status.ACLineStatus == 0
+}
+
+/// 与“设置 > 网络 > 按流量计费的连接”相同，漫游或超出流量限制时也视为计费
+#[cfg(target_os = "windows")]
+fn metered() -> bool {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    // 没有联网时返回错误
+    let Ok(cost) = NetworkInformation::GetInternetConnectionProfile().and_then(|profile| profile.GetConnectionCost()) else {
+        return false;
+    };
+    let cost_type = cost.NetworkCostType().unwrap_or(NetworkCostType::Unknown);
+    cost_type == NetworkCostType::Fixed
+        || cost_type == NetworkCostType::Variable
+        || cost.Roaming().unwrap_or(false)
+        || cost.OverDataLimit().unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn on_battery() -> bool {
+    false
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn metered() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_parse_nm_metered() {
+        assert!(parse_nm_metered("u 1\n"));
+        assert!(parse_nm_metered("u 3"));
+        assert!(!parse_nm_metered("u 4\n"));
+        assert!(!parse_nm_metered("u 0"));
+        assert!(!parse_nm_metered(""));
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        assert!(parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging"));
+        assert!(!parse_pmset("Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged"));
+    }
+
+    #[test]
+    fn test_battery_discharging() {
+        let root = temp_dir("power");
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            for (file, content) in files {
+                std::fs::write(dir.join(file), format!("{}\n", content)).unwrap();
+            }
+        };
+        supply("AC", &[("type", "Mains"), ("online", "1")]);
+        supply("BAT0", &[("type", "Battery"), ("scope", "System"), ("status", "Charging")]);
+        // 无线鼠标的电池
+        supply("hidpp_battery_0", &[("type", "Battery"), ("scope", "Device"), ("status", "Discharging")]);
+        assert!(!battery_discharging(&root));
+
+        supply("BAT0", &[("status", "Discharging")]);
+        assert!(battery_discharging(&root));
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(!battery_discharging(&root));
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::history::GameEvent;
+
+/// 检查外部启动的进程是否仍在运行的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 系统中正在运行的进程
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningProcess {
+    pub pid: u32,
+    /// 可执行文件的完整路径，系统没有提供时为 None（例如 Windows 上只知道文件名）
+    pub path: Option<PathBuf>,
+}
+
+/// 在后台扫描正在运行的 `exe`（例如在 Launcher 之外启动的 OpenUO）
+pub fn scan_in_background(exe: PathBuf) -> mpsc::Receiver<Vec<RunningProcess>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(find_running(&exe));
+    });
+    rx
+}
+
+/// 查找正在运行的 `exe`，不包括 Launcher 自身
+///
+/// 能获取完整路径时比较路径，否则只比较文件名，因此可能包括其他目录中的同名程序。
+pub fn find_running(exe: &Path) -> Vec<RunningProcess> {
+    let Some(name) = exe.file_name() else {
+        return Vec::new();
+    };
+    let target = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+    let own_pid = std::process::id();
+    list_processes(&name.to_string_lossy())
+        .into_iter()
+        .filter(|p| p.pid != own_pid)
+        .filter(|p| match &p.path {
+            Some(path) => path.file_name() == Some(name) && (path == &target || path.canonicalize().is_ok_and(|p| p == target)),
+            None => true,
+        })
+        .collect()
+}
+
+/// 安装前发现 OpenUO 正在运行：Windows 上文件被占用，继续解压会留下损坏的安装
+#[derive(Debug)]
+pub struct GameRunning {
+    pub pids: Vec<u32>,
+}
+
+impl fmt::Display for GameRunning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pids: Vec<String> = self.pids.iter().map(u32::to_string).collect();
+        write!(f, "OpenUO 正在运行（PID {}），请先关闭游戏再安装", pids.join(", "))
+    }
+}
+
+impl std::error::Error for GameRunning {}
+
+/// 确认 `exe` 没有在运行；可执行文件不存在时（全新安装）不检查
+pub fn ensure_not_running(exe: &Path) -> Result<(), GameRunning> {
+    if !exe.exists() {
+        return Ok(());
+    }
+    let pids: Vec<u32> = find_running(exe).into_iter().map(|p| p.pid).collect();
+    if pids.is_empty() { Ok(()) } else { Err(GameRunning { pids }) }
+}
+
+/// 请求结束进程（Unix 上发送 SIGTERM，Windows 上强制结束）
+#[cfg(unix)]
+pub fn terminate(pid: u32) -> std::io::Result<()> {
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn terminate(pid: u32) -> std::io::Result<()> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    let status = std::process::Command::new("taskkill")
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("taskkill 退出码 {}", status)))
+    }
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn terminate(_pid: u32) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// 在后台线程等待外部启动的进程结束（无法得知退出码）
+pub fn watch(pid: u32, tx: mpsc::Sender<GameEvent>) {
+    std::thread::spawn(move || {
+        while is_running(pid) {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let _ = tx.send(GameEvent::Ended { pid });
+    });
+}
+
+#[cfg(unix)]
+pub fn is_running(pid: u32) -> bool {
+    // 信号 0 只检查进程是否存在；没有权限发送信号时进程也是存在的
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_running(pid: u32) -> bool {
+    tasklist(&["/FI", &format!("PID eq {}", pid)]).is_some_and(|output| !parse_tasklist(&output).is_empty())
+}
+
+/// Linux 上读取 /proc，无法读取 exe 链接（其他用户的进程）时退回比较进程名
+#[cfg(target_os = "linux")]
+fn list_processes(name: &str) -> Vec<RunningProcess> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            match std::fs::read_link(entry.path().join("exe")) {
+                Ok(path) => {
+                    // 运行中的程序被更新替换后，链接末尾会带上 " (deleted)"
+                    let path = path.to_string_lossy().trim_end_matches(" (deleted)").to_string();
+                    Some(RunningProcess {
+                        pid,
+                        path: Some(PathBuf::from(path)),
+                    })
+                }
+                Err(_) => {
+                    // comm 最多 15 个字符
+                    let comm = std::fs::read_to_string(entry.path().join("comm")).ok()?;
+                    let truncated: String = name.chars().take(15).collect();
+                    (comm.trim() == truncated).then_some(RunningProcess { pid, path: None })
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn list_processes(_name: &str) -> Vec<RunningProcess> {
+    std::process::Command::new("ps")
+        .args(["-axo", "pid=,comm="])
+        .output()
+        .map(|output| parse_ps(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn list_processes(name: &str) -> Vec<RunningProcess> {
+    tasklist(&["/FI", &format!("IMAGENAME eq {}", name)])
+        .map(|output| parse_tasklist(&output))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pid| RunningProcess { pid, path: None })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn list_processes(_name: &str) -> Vec<RunningProcess> {
+    Vec::new()
+}
+
+#[cfg(target_os = "windows")]
+fn tasklist(filter: &[&str]) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    let output = std::process::Command::new("tasklist")
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .args(filter)
+        .args(["/FO", "CSV", "/NH"])
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 解析 `ps -axo pid=,comm=` 的输出：PID 和可执行文件的完整路径
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps(output: &str) -> Vec<RunningProcess> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, path) = line.trim_start().split_once(char::is_whitespace)?;
+            Some(RunningProcess {
+                pid: pid.parse().ok()?,
+                path: Some(PathBuf::from(path.trim())),
+            })
+        })
+        .collect()
+}
+
+/// 解析 `tasklist /FO CSV /NH` 的输出，例如 `"OpenUO.exe","1234","Console","1","123,456 K"`
+///
+/// 没有匹配的进程时 tasklist 输出一行提示（没有引号），会被忽略。
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_tasklist(output: &str) -> Vec<u32> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.strip_prefix('"')?.split("\",\"");
+            fields.next()?;
+            fields.next()?.parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ps() {
+        let output = "    1 /sbin/launchd\n  812 /Applications/OpenUO/OpenUO.app/Contents/MacOS/OpenUO\n  garbage\n";
+        assert_eq!(
+            parse_ps(output),
+            [
+                RunningProcess {
+                    pid: 1,
+                    path: Some(PathBuf::from("/sbin/launchd"))
+                },
+                RunningProcess {
+                    pid: 812,
+                    path: Some(PathBuf::from("/Applications/OpenUO/OpenUO.app/Contents/MacOS/OpenUO"))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tasklist() {
+        let output = "\r\n\"OpenUO.exe\",\"1234\",\"Console\",\"1\",\"123,456 K\"\r\n\"OpenUO.exe\",\"98\",\"Console\",\"1\",\"1,024 K\"\r\n";
+        assert_eq!(parse_tasklist(output), [1234, 98]);
+        assert!(parse_tasklist("INFO: No tasks are running which match the specified criteria.\r\n").is_empty());
+    }
+
+    #[test]
+    fn test_find_running_and_is_running() {
+        // 测试进程自身不会被当作正在运行的游戏
+        let own = std::env::current_exe().unwrap();
+        assert!(find_running(&own).iter().all(|p| p.pid != std::process::id()));
+        assert!(is_running(std::process::id()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_not_running_and_terminate() {
+        assert!(ensure_not_running(Path::new("/nonexistent/OpenUO")).is_ok());
+
+        let sleep = PathBuf::from("/bin/sleep");
+        let mut child = std::process::Command::new(&sleep).arg("30").spawn().unwrap();
+        let running = ensure_not_running(&sleep).unwrap_err();
+        assert!(running.pids.contains(&child.id()));
+        terminate(child.id()).unwrap();
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::process::Child;
+
+/// 游戏进程的优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ProcessPriority {
+    /// 低于正常，把 CPU 让给直播软件等前台程序
+    BelowNormal,
+    #[default]
+    Normal,
+    /// 高于正常（Unix 上需要相应权限）
+    High,
+}
+
+impl ProcessPriority {
+    pub const ALL: [ProcessPriority; 3] = [ProcessPriority::BelowNormal, ProcessPriority::Normal, ProcessPriority::High];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            ProcessPriority::BelowNormal => "process_tuning.below_normal",
+            ProcessPriority::Normal => "process_tuning.normal",
+            ProcessPriority::High => "process_tuning.high",
+        }
+    }
+
+    /// Unix 上对应的 nice 值
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fn nice(self) -> i32 {
+        match self {
+            ProcessPriority::BelowNormal => 10,
+            ProcessPriority::Normal => 0,
+            ProcessPriority::High => -5,
+        }
+    }
+}
+
+/// 启动后对游戏进程的调整
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessTuning {
+    #[serde(rename = "Priority", default)]
+    pub priority: ProcessPriority,
+    /// 允许使用的 CPU（第 n 位对应 CPU n），0 表示不限制
+    #[serde(rename = "AffinityMask", default)]
+    pub affinity_mask: u64,
+}
+
+/// 本机的逻辑 CPU 数量，CPU 亲和性最多支持 64 个
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get()).min(64)
+}
+
+/// 去掉本机不存在的 CPU；结果为 0 时表示不限制
+fn effective_mask(mask: u64, cpus: usize) -> u64 {
+    let all = if cpus >= 64 { u64::MAX } else { (1u64 << cpus) - 1 };
+    match mask & all {
+        // 选中了全部 CPU 等同于不限制
+        m if m == all => 0,
+        m => m,
+    }
+}
+
+/// 启动后立即调用，调整优先级和 CPU 亲和性
+///
+/// 失败时不影响游戏运行，由调用方记录警告。
+pub fn apply(child: &Child, tuning: &ProcessTuning) -> Result<()> {
+    if tuning.priority != ProcessPriority::Normal {
+        set_priority(child, tuning.priority)?;
+    }
+    let mask = effective_mask(tuning.affinity_mask, cpu_count());
+    if mask != 0 {
+        set_affinity(child, mask)?;
+    }
+    Ok(())
+}
+
+/// 游戏的所有线程（Linux 上优先级和亲和性都是按线程设置的，游戏刚启动时可能已经创建了线程）
+#[cfg(target_os = "linux")]
+fn threads(pid: u32) -> Vec<libc::pid_t> {
+    let tasks: Vec<libc::pid_t> = std::fs::read_dir(format!("/proc/{}/task", pid))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    if tasks.is_empty() { vec![pid as libc::pid_t] } else { tasks }
+}
+
+#[cfg(target_os = "linux")]
+fn set_priority(child: &Child, priority: ProcessPriority) -> Result<()> {
+    for tid in threads(child.id()) {
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, priority.nice()) } != 0 {
+            anyhow::bail!("无法设置进程优先级: {}", std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn set_priority(child: &Child, priority: ProcessPriority) -> Result<()> {
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, child.id() as libc::id_t, priority.nice()) } != 0 {
+        anyhow::bail!("无法设置进程优先级: {}", std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(child: &Child, mask: u64) -> Result<()> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for cpu in (0..64).filter(|cpu| mask & (1 << cpu) != 0) {
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    for tid in threads(child.id()) {
+        if unsafe { libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) } != 0 {
+            anyhow::bail!("无法设置 CPU 亲和性: {}", std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn set_affinity(_child: &Child, _mask: u64) -> Result<()> {
+    anyhow::bail!("macOS 不支持设置 CPU 亲和性")
+}
+
+#[cfg(target_os = "windows")]
+fn set_priority(child: &Child, priority: ProcessPriority) -> Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Threading::{
+        BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, SetPriorityClass,
+    };
+
+    let class = match priority {
+        ProcessPriority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::Normal => NORMAL_PRIORITY_CLASS,
+        ProcessPriority::High => HIGH_PRIORITY_CLASS,
+    };
+    unsafe { SetPriorityClass(HANDLE(child.as_raw_handle()), class) }
+        .map_err(|e| anyhow::anyhow!("无法设置进程优先级: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn set_affinity(child: &Child, mask: u64) -> Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Threading::SetProcessAffinityMask;
+
+    unsafe { SetProcessAffinityMask(HANDLE(child.as_raw_handle()), mask as usize) }
+        .map_err(|e| anyhow::anyhow!("无法设置 CPU 亲和性: {}", e))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn set_priority(_child: &Child, _priority: ProcessPriority) -> Result<()> {
+    anyhow::bail!("当前系统不支持设置进程优先级")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn set_affinity(_child: &Child, _mask: u64) -> Result<()> {
+    anyhow::bail!("当前系统不支持设置 CPU 亲和性")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_mask() {
+        assert_eq!(effective_mask(0b0011, 4), 0b0011);
+        // 全选等同于不限制
+        assert_eq!(effective_mask(0b1111, 4), 0);
+        // 不存在的 CPU 被忽略
+        assert_eq!(effective_mask(0b1_0000, 4), 0);
+        assert_eq!(effective_mask(0b1_0001, 4), 0b0001);
+        assert_eq!(effective_mask(u64::MAX, 64), 0);
+        assert_eq!(effective_mask(1 << 63, 64), 1 << 63);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_apply_lowers_priority() {
+        let mut child = std::process::Command::new("sleep").arg("5").spawn().unwrap();
+        let tuning = ProcessTuning { priority: ProcessPriority::BelowNormal, affinity_mask: 0 };
+        apply(&child, &tuning).unwrap();
+        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, child.id() as libc::id_t) };
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(nice, 10);
+    }
+}
@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::{ProfileConfig, game_profiles_backups_dir, open_uo_dir, profile_backups_dir};
+
+/// 每个档案最多保留的备份数
+pub const MAX_KEEP: usize = 50;
+
+/// 启动前备份 OpenUO 的角色设置（Data/Profiles/<账号>：宏、界面位置等）
+///
+/// 游戏崩溃时偶尔会写坏自己的设置文件，备份后可以恢复到之前某次启动时的状态。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileBackupConfig {
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    /// 保留最近几次启动的备份
+    #[serde(rename = "Keep")]
+    pub keep: usize,
+}
+
+impl Default for ProfileBackupConfig {
+    fn default() -> Self {
+        Self { enabled: false, keep: 5 }
+    }
+}
+
+/// 更新 OpenUO 前备份整个 Data/Profiles（所有账号的宏、界面位置、日志）
+///
+/// 更新后丢失宏是常见的问题，默认开启。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateBackupConfig {
+    pub enabled: bool,
+    pub keep: usize,
+}
+
+impl Default for UpdateBackupConfig {
+    fn default() -> Self {
+        Self { enabled: true, keep: 10 }
+    }
+}
+
+/// 一个备份，文件名为创建时的 Unix 时间戳（`<时间戳>.zip`，旧版本为同名目录）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub created: i64,
+}
+
+/// OpenUO 保存档案账号角色设置的目录（固定了版本时在该版本的目录中），没有设置账号时为 None
+pub fn game_data_dir(profile: &ProfileConfig) -> Option<PathBuf> {
+    let username = profile.settings.username.trim();
+    (!username.is_empty()).then(|| crate::client_versions::dir_for(profile).join("Data").join("Profiles").join(username))
+}
+
+/// 备份档案对应账号的角色设置并删除多余的旧备份；还没有角色设置时返回 None
+pub fn snapshot(profile: &ProfileConfig) -> Result<Option<Snapshot>> {
+    let Some(src) = game_data_dir(profile).filter(|dir| dir.is_dir()) else {
+        return Ok(None);
+    };
+    snapshot_into(
+        &src,
+        &profile_backups_dir(profile),
+        crate::clock::now_unix(),
+        profile.index.profile_backup.keep,
+    )
+}
+
+fn snapshot_into(src: &Path, root: &Path, now: i64, keep: usize) -> Result<Option<Snapshot>> {
+    let snapshot = write_snapshot(src, root, now)?;
+    prune(root, keep.clamp(1, MAX_KEEP));
+    Ok(snapshot)
+}
+
+fn write_snapshot(src: &Path, root: &Path, now: i64) -> Result<Option<Snapshot>> {
+    let dest = root.join(format!("{now}.zip"));
+    // 同一秒内再次启动时内容相同，不必重复备份
+    if dest.exists() || root.join(now.to_string()).exists() {
+        return Ok(None);
+    }
+    // 先写入临时文件，不完整的备份不会出现在列表中
+    fs::create_dir_all(root)?;
+    let tmp = root.join(format!("{now}.zip.tmp"));
+    if let Err(e) = write_zip(src, &tmp) {
+        fs::remove_file(&tmp).ok();
+        return Err(e.context("无法备份角色设置"));
+    }
+    fs::rename(&tmp, &dest)?;
+    Ok(Some(Snapshot { path: dest, created: now }))
+}
+
+/// 把目录打包为 zip，条目名为相对路径（统一用 / 分隔）
+fn write_zip(src: &Path, dest: &Path) -> Result<()> {
+    fn add_dir(zip: &mut zip::ZipWriter<fs::File>, root: &Path, dir: &Path) -> Result<()> {
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let name = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            if entry.file_type()?.is_dir() {
+                zip.add_directory(name, options)?;
+                add_dir(zip, root, &path)?;
+            } else {
+                zip.start_file(name, options)?;
+                zip.write_all(&fs::read(&path).with_context(|| format!("无法读取 {}", path.display()))?)?;
+            }
+        }
+        Ok(())
+    }
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(dest)?);
+    add_dir(&mut zip, src, src)?;
+    zip.finish()?.sync_all()?;
+    Ok(())
+}
+
+/// 档案的全部备份，最新的在前
+pub fn list(profile: &ProfileConfig) -> Vec<Snapshot> {
+    list_in(&profile_backups_dir(profile))
+}
+
+fn list_in(root: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let path = entry.path();
+            let created = match name.strip_suffix(".zip") {
+                Some(stem) if path.is_file() => stem,
+                None if path.is_dir() => &name,
+                _ => return None,
+            };
+            Some(Snapshot { created: created.parse().ok()?, path })
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created));
+    snapshots
+}
+
+fn prune(root: &Path, keep: usize) {
+    for old in list_in(root).into_iter().skip(keep) {
+        let result = if old.path.is_dir() { fs::remove_dir_all(&old.path) } else { fs::remove_file(&old.path) };
+        if let Err(e) = result {
+            tracing::warn!("Failed to remove old backup {}: {}", old.path.display(), e);
+        }
+    }
+}
+
+/// 用备份替换档案对应账号当前的角色设置
+pub fn restore(profile: &ProfileConfig, snapshot: &Snapshot) -> Result<()> {
+    let dest = game_data_dir(profile).context("档案没有设置账号，无法恢复角色设置")?;
+    restore_into(&snapshot.path, &dest)
+}
+
+fn restore_into(snapshot: &Path, dest: &Path) -> Result<()> {
+    let name = dest.file_name().context("角色设置目录无效")?.to_string_lossy().into_owned();
+    // 先完整复制到旁边，再替换当前目录，中途失败时当前的设置保持不变
+    let staging = dest.with_file_name(format!("{name}.restoring"));
+    let old = dest.with_file_name(format!("{name}.old"));
+    fs::remove_dir_all(&staging).ok();
+    let copied = if snapshot.is_dir() {
+        crate::cloud_sync::copy_dir(snapshot, &staging, &mut |_| {})
+    } else {
+        fs::create_dir_all(&staging).map_err(anyhow::Error::from).and_then(|()| crate::archive::extract(snapshot, &staging))
+    };
+    if let Err(e) = copied {
+        fs::remove_dir_all(&staging).ok();
+        return Err(e.context("无法恢复角色设置"));
+    }
+    fs::remove_dir_all(&old).ok();
+    if dest.exists() {
+        fs::rename(dest, &old).with_context(|| format!("无法替换 {}，游戏可能正在运行", dest.display()))?;
+    }
+    fs::rename(&staging, dest)?;
+    fs::remove_dir_all(&old).ok();
+    Ok(())
+}
+
+/// 主安装的 OpenUO 保存所有账号角色设置的目录
+pub fn game_profiles_dir() -> PathBuf {
+    open_uo_dir().join("Data").join("Profiles")
+}
+
+/// 备份整个 Data/Profiles；还没有角色设置时返回 None
+pub fn snapshot_game_profiles(keep: usize) -> Result<Option<Snapshot>> {
+    let src = game_profiles_dir();
+    if !src.is_dir() {
+        return Ok(None);
+    }
+    snapshot_into(&src, &game_profiles_backups_dir(), crate::clock::now_unix(), keep)
+}
+
+/// Data/Profiles 的全部备份，最新的在前
+pub fn list_game_profiles() -> Vec<Snapshot> {
+    list_in(&game_profiles_backups_dir())
+}
+
+/// 用备份替换整个 Data/Profiles；替换前先备份当前内容，恢复错了也可以撤销
+pub fn restore_game_profiles(snapshot: &Snapshot, keep: usize) -> Result<()> {
+    let dest = game_profiles_dir();
+    let root = game_profiles_backups_dir();
+    if dest.is_dir() {
+        write_snapshot(&dest, &root, crate::clock::now_unix())?;
+    }
+    restore_into(&snapshot.path, &dest)?;
+    // 恢复之后再删除旧备份，避免删掉正在恢复的那一个
+    prune(&root, keep.clamp(1, MAX_KEEP));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_snapshot_rotation_and_restore() {
+        let base = temp_dir("backup");
+        let src = base.join("Profiles").join("lord");
+        let root = base.join("Backups").join("profile");
+        fs::create_dir_all(src.join("Shard").join("Char")).unwrap();
+        fs::write(src.join("Shard").join("Char").join("macros.xml"), "v1").unwrap();
+
+        for (now, content) in [(100, "v1"), (200, "v2"), (300, "v3")] {
+            fs::write(src.join("Shard").join("Char").join("macros.xml"), content).unwrap();
+            assert!(snapshot_into(&src, &root, now, 2).unwrap().is_some());
+        }
+        // 同一秒内不重复备份
+        assert!(snapshot_into(&src, &root, 300, 2).unwrap().is_none());
+        let snapshots = list_in(&root);
+        let created: Vec<i64> = snapshots.iter().map(|s| s.created).collect();
+        assert_eq!(created, [300, 200]);
+
+        // 游戏写坏了设置
+        fs::write(src.join("Shard").join("Char").join("macros.xml"), "garbage").unwrap();
+        fs::write(src.join("crash.tmp"), "").unwrap();
+        restore_into(&snapshots[1].path, &src).unwrap();
+        assert_eq!(fs::read_to_string(src.join("Shard").join("Char").join("macros.xml")).unwrap(), "v2");
+        assert!(!src.join("crash.tmp").exists());
+        assert!(!base.join("Profiles").join("lord.restoring").exists());
+
+        // 旧版本的目录备份仍然可以列出和恢复
+        crate::cloud_sync::copy_dir(&src, &root.join("50"), &mut |_| {}).unwrap();
+        assert_eq!(list_in(&root).last().map(|s| s.created), Some(50));
+        assert!(snapshot_into(&src, &root, 400, 2).unwrap().is_some());
+        assert_eq!(list_in(&root).iter().map(|s| s.created).collect::<Vec<_>>(), [400, 300]);
+    }
+}
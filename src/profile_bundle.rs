@@ -0,0 +1,225 @@
+use anyhow::{Context, Result, bail};
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+use std::path::Path;
+
+use crate::config::{OuoSettings, ProfileConfig, ProfileIndex};
+
+/// 档案包的扩展名（内容是 zip）
+pub const BUNDLE_EXTENSION: &str = "ouoprofile";
+
+const PROFILE_ENTRY: &str = "profile.json";
+/// 用口令加密的密码：盐 + nonce + 密文
+const PASSWORD_ENTRY: &str = "password.bin";
+const FORMAT: u32 = 1;
+
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 200_000;
+
+#[derive(Serialize, Deserialize)]
+struct BundleProfile {
+    #[serde(rename = "Format")]
+    format: u32,
+    #[serde(rename = "Index")]
+    index: ProfileIndex,
+    #[serde(rename = "Settings")]
+    settings: OuoSettings,
+}
+
+/// 读取的档案包，导入前可能需要口令解密密码
+pub struct Bundle {
+    profile: ProfileConfig,
+    password: Option<Vec<u8>>,
+}
+
+impl Bundle {
+    pub fn name(&self) -> &str {
+        &self.profile.index.name
+    }
+
+    /// 包中带有用口令加密的密码
+    pub fn has_password(&self) -> bool {
+        self.password.is_some()
+    }
+
+    /// 生成新的档案；`passphrase` 为空时不导入密码，口令错误时返回错误
+    pub fn to_profile(&self, passphrase: Option<&str>) -> Result<ProfileConfig> {
+        let mut profile = self.profile.clone();
+        if let (Some(sealed), Some(passphrase)) = (&self.password, passphrase) {
+            let password = open_password(sealed, passphrase)?;
+            profile.settings.password = crate::crypter::encrypt(&password);
+        }
+        Ok(profile)
+    }
+}
+
+/// 把档案（索引和设置）导出为一个文件，分享给公会成员
+///
+/// 密码默认不导出；给出 `passphrase` 时用口令加密后导出，导入时需要同一个口令。
+/// 本机的密码是用机器名加密的，在别的电脑上无法直接使用。
+pub fn export(profile: &ProfileConfig, dest: &Path, passphrase: Option<&str>) -> Result<()> {
+    let mut index = profile.index.clone();
+    index.password_key_id = String::new();
+    // 服务器规则需要导入的玩家自己确认
+    index.rules_acknowledged = None;
+    let settings = crate::settings_export::standalone_settings(&crate::settings_export::saved_settings(profile), profile, false);
+    let bundle = BundleProfile { format: FORMAT, index, settings };
+
+    let password = match passphrase {
+        Some(passphrase) if !profile.index.session_password.enabled => {
+            let password = crate::crypter::decrypt(&profile.settings.password);
+            (!password.is_empty()).then(|| seal_password(&password, passphrase)).transpose()?
+        }
+        _ => None,
+    };
+
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(fs::File::create(dest).with_context(|| format!("无法创建 {}", dest.display()))?);
+    zip.start_file(PROFILE_ENTRY, options)?;
+    zip.write_all(serde_json::to_string_pretty(&bundle)?.as_bytes())?;
+    if let Some(password) = password {
+        zip.start_file(PASSWORD_ENTRY, options)?;
+        zip.write_all(&password)?;
+    }
+    zip.finish()?.sync_all()?;
+    Ok(())
+}
+
+/// 读取档案包，重新生成档案的 UUID，避免和已有档案（例如分享者自己的）冲突
+///
+/// 启动目标、程序路径、启动参数和进程设置不导入：否则别人分享的档案可以让 Launcher 运行任意程序。
+pub fn read(path: &Path) -> Result<Bundle> {
+    let file = fs::File::open(path).with_context(|| format!("无法读取 {}", path.display()))?;
+    let mut zip = zip::ZipArchive::new(file).with_context(|| format!("{} 不是档案包", path.display()))?;
+
+    let mut raw = String::new();
+    zip.by_name(PROFILE_ENTRY)
+        .with_context(|| format!("{} 中没有 {}", path.display(), PROFILE_ENTRY))?
+        .read_to_string(&mut raw)?;
+    let bundle: BundleProfile = serde_json::from_str(&raw).with_context(|| format!("无法解析 {}", PROFILE_ENTRY))?;
+    if bundle.format > FORMAT {
+        bail!("档案包的格式版本 {} 太新，请更新 Launcher", bundle.format);
+    }
+    let password = match zip.by_name(PASSWORD_ENTRY) {
+        Ok(mut entry) => {
+            let mut sealed = Vec::new();
+            entry.read_to_end(&mut sealed)?;
+            Some(sealed)
+        }
+        Err(_) => None,
+    };
+
+    let mut profile = crate::config::new_profile(&bundle.index.name);
+    let default_uo_dir = profile.settings.ultima_online_directory.clone();
+    let defaults = ProfileIndex::default();
+    profile.index = ProfileIndex {
+        settings_file: profile.index.settings_file,
+        file_name: profile.index.file_name,
+        password_key_id: String::new(),
+        launch_target: defaults.launch_target,
+        executable_path: defaults.executable_path,
+        classic_argument_template: defaults.classic_argument_template,
+        custom_argument_template: defaults.custom_argument_template,
+        legacy_argument_template: defaults.legacy_argument_template,
+        additional_args: defaults.additional_args,
+        process_tuning: defaults.process_tuning,
+        ..bundle.index
+    };
+    profile.settings = bundle.settings;
+    profile.settings.password = String::new();
+    // 分享者电脑上的资源目录在这里通常不存在
+    if !Path::new(&profile.settings.ultima_online_directory).is_dir() {
+        profile.settings.ultima_online_directory = default_uo_dir;
+    }
+    Ok(Bundle { profile, password })
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey> {
+    let mut key = [0u8; 32];
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).context("迭代次数无效")?;
+    ring::pbkdf2::derive(ring::pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| anyhow::anyhow!("无法创建密钥"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn seal_password(password: &str, passphrase: &str) -> Result<Vec<u8>> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| anyhow::anyhow!("无法生成随机数"))?;
+    rng.fill(&mut nonce).map_err(|_| anyhow::anyhow!("无法生成随机数"))?;
+
+    let mut data = password.as_bytes().to_vec();
+    derive_key(passphrase, &salt)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| anyhow::anyhow!("无法加密密码"))?;
+    Ok([&salt[..], &nonce[..], &data[..]].concat())
+}
+
+fn open_password(sealed: &[u8], passphrase: &str) -> Result<String> {
+    if sealed.len() < SALT_LEN + NONCE_LEN {
+        bail!("档案包中的密码已损坏");
+    }
+    let (salt, rest) = sealed.split_at(SALT_LEN);
+    let (nonce, data) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow::anyhow!("档案包中的密码已损坏"))?;
+    let mut data = data.to_vec();
+    let password = derive_key(passphrase, salt)?
+        .open_in_place(nonce, Aad::empty(), &mut data)
+        .map_err(|_| anyhow::anyhow!("口令不正确"))?;
+    Ok(String::from_utf8(password.to_vec())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_export_and_read() {
+        let dir = temp_dir("bundle");
+        let mut profile = ProfileConfig::default();
+        profile.index.name = "Guild".to_string();
+        profile.index.password_key_id = "abc".to_string();
+        profile.settings.ip = "shard.example".to_string();
+        profile.settings.username = "lord".to_string();
+        profile.settings.password = crate::crypter::encrypt("secret");
+        profile.settings.ultima_online_directory = "/no/such/uo".to_string();
+        profile.index.launch_target = crate::launch_target::LaunchTarget::Custom;
+        profile.index.executable_path = "/tmp/evil".to_string();
+        profile.index.custom_argument_template = "--run {password}".to_string();
+        profile.index.additional_args = "-c 'rm -rf ~'".to_string();
+
+        let plain = dir.join("plain.ouoprofile");
+        export(&profile, &plain, None).unwrap();
+        let bundle = read(&plain).unwrap();
+        assert_eq!(bundle.name(), "Guild");
+        assert!(!bundle.has_password());
+        let imported = bundle.to_profile(None).unwrap();
+        assert_ne!(imported.index.file_name, profile.index.file_name);
+        assert_ne!(imported.index.settings_file, profile.index.settings_file);
+        assert!(imported.index.password_key_id.is_empty());
+        assert_eq!(imported.settings.ip, "shard.example");
+        assert!(imported.settings.password.is_empty());
+        assert_ne!(imported.settings.ultima_online_directory, "/no/such/uo");
+        assert_eq!(imported.index.launch_target, crate::launch_target::LaunchTarget::OpenUO);
+        assert!(imported.index.executable_path.is_empty());
+        assert!(imported.index.custom_argument_template.is_empty());
+        assert!(imported.index.additional_args.is_empty());
+
+        let sealed = dir.join("sealed.ouoprofile");
+        export(&profile, &sealed, Some("guild pass")).unwrap();
+        let bundle = read(&sealed).unwrap();
+        assert!(bundle.has_password());
+        assert!(bundle.to_profile(Some("wrong")).is_err());
+        let imported = bundle.to_profile(Some("guild pass")).unwrap();
+        assert_eq!(crate::crypter::decrypt(&imported.settings.password), "secret");
+
+        fs::write(dir.join("bad.ouoprofile"), b"not a zip").unwrap();
+        assert!(read(&dir.join("bad.ouoprofile")).is_err());
+    }
+}
@@ -1,6 +1,26 @@
 use crate::config::ProfileConfig;
 use crate::crypter;
 use crate::i18n::t;
+use crate::client_versions::{self, InstalledVersion};
+use crate::launch_target::{self, LaunchConfirmation, LaunchTarget};
+use crate::process_tuning::{self, ProcessPriority};
+use crate::profile_backup::{self, Snapshot};
+use crate::render_driver::{self, RenderDriver};
+use crate::update_policy::UpdatePolicy;
+use crate::server_list::{self, ServerListReceiver, ShardServer};
+use crate::staff_mode::StaffManifest;
+
+fn pick_file(current: &str) -> Option<String> {
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(dir) = std::path::Path::new(current).parent()
+        && dir.is_dir()
+    {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog
+        .pick_file()
+        .map(|p| p.to_string_lossy().to_string())
+}
 
 fn pick_directory(current: &str) -> Option<String> {
     let mut dialog = rfd::FileDialog::new();
@@ -12,9 +32,34 @@ fn pick_directory(current: &str) -> Option<String> {
         .map(|p| p.to_string_lossy().to_string())
 }
 
+/// 逗号分隔的掉线关键字，去掉空白和空项
+fn parse_patterns(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
 pub struct ProfileEditor {
     pub editor_profile: Option<ProfileConfig>,
     pub editor_index: Option<usize>,
+    server_list_rx: Option<ServerListReceiver>,
+    server_list: Vec<ShardServer>,
+    server_list_error: Option<String>,
+    /// 正在编辑的档案的角色设置备份
+    backups: Vec<Snapshot>,
+    /// 上次恢复备份的结果
+    backup_message: Option<String>,
+    /// 有游戏正在运行，这时恢复的设置会在游戏退出时被覆盖
+    pub game_running: bool,
+    /// 并存安装的 OpenUO 版本，可供档案固定使用
+    client_versions: Vec<InstalledVersion>,
+    /// 员工模式已解锁（服务器清单开放或本机手动解锁）
+    pub staff_unlocked: bool,
+    /// 更新清单中的员工模式设置
+    pub staff_manifest: StaffManifest,
+    /// 正在编辑的掉线关键字（逗号分隔），失去焦点或保存时写回档案
+    disconnect_patterns: String,
 }
 
 impl ProfileEditor {
@@ -22,6 +67,16 @@ impl ProfileEditor {
         Self {
             editor_profile: None,
             editor_index: None,
+            server_list_rx: None,
+            server_list: Vec::new(),
+            server_list_error: None,
+            backups: Vec::new(),
+            backup_message: None,
+            game_running: false,
+            client_versions: Vec::new(),
+            staff_unlocked: false,
+            staff_manifest: StaffManifest::default(),
+            disconnect_patterns: String::new(),
         }
     }
 
@@ -37,11 +92,42 @@ impl ProfileEditor {
         
         self.editor_index = Some(index);
         self.editor_profile = Some(profile);
+        self.server_list_rx = None;
+        self.server_list.clear();
+        self.server_list_error = None;
+        self.backups = self.editor_profile.as_ref().map(profile_backup::list).unwrap_or_default();
+        self.backup_message = None;
+        self.client_versions = client_versions::installed();
+        self.disconnect_patterns = self
+            .editor_profile
+            .as_ref()
+            .map(|p| p.index.watchdog.disconnect_patterns.join(", "))
+            .unwrap_or_default();
     }
 
     pub fn close(&mut self) {
         self.editor_profile = None;
         self.editor_index = None;
+        self.server_list_rx = None;
+    }
+
+    fn poll_server_list(&mut self) {
+        let Some(rx) = &self.server_list_rx else {
+            return;
+        };
+        if let Ok(res) = rx.try_recv() {
+            self.server_list_rx = None;
+            match res {
+                Ok(servers) => {
+                    self.server_list = servers;
+                    self.server_list_error = None;
+                }
+                Err(e) => {
+                    self.server_list.clear();
+                    self.server_list_error = Some(e);
+                }
+            }
+        }
     }
 
     pub fn is_open(&self) -> bool {
@@ -52,11 +138,13 @@ impl ProfileEditor {
         if self.editor_profile.is_none() {
             return None;
         }
+        self.poll_server_list();
 
         let mut open = true;
         let mut result = None;
 
         egui::Window::new(t!("profile_editor.title"))
+            .id(egui::Id::new("profile_editor_window"))
             .open(&mut open)
             .frame(egui::Frame::window(&ctx.style()).fill(egui::Color32::from_rgb(40, 40, 45)))
             .show(ctx, |ui| {
@@ -78,6 +166,64 @@ impl ProfileEditor {
                         ui.add(egui::DragValue::new(&mut profile.settings.port).speed(1));
                     });
 
+                    // 服务器列表：从登录服务器获取真实的服务器名称
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut profile.index.query_server_list, t!("profile_editor.query_server_list").as_ref());
+                        let plain_login = server_list::supports_plain_login(&profile.settings);
+                        let fetching = self.server_list_rx.is_some();
+                        let fetch_btn = egui::Button::new(t!("profile_editor.fetch_servers"))
+                            .fill(egui::Color32::from_rgb(100, 100, 120))
+                            .min_size(egui::vec2(60.0, 20.0));
+                        let response = ui.add_enabled(plain_login && !fetching && !profile.settings.username.is_empty(), fetch_btn);
+                        if !plain_login {
+                            response.on_disabled_hover_text(t!("profile_editor.server_list_encrypted"));
+                        } else if response.clicked() {
+                            self.server_list_rx = Some(server_list::spawn_query(&profile.settings, profile.settings.password.clone()));
+                            self.server_list_error = None;
+                        }
+                        if fetching {
+                            ui.spinner();
+                        }
+                    });
+                    if !self.server_list.is_empty() {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.server"));
+                            egui::ComboBox::from_id_source("server_list_combo")
+                                .selected_text(profile.settings.last_server_name.clone())
+                                .show_ui(ui, |ui| {
+                                    for (pos, server) in self.server_list.iter().enumerate() {
+                                        let num = pos as u16 + 1;
+                                        let selected = profile.settings.last_server_num == num
+                                            && profile.settings.last_server_name == server.name;
+                                        let label = format!("{} ({}%)", server.name, server.percent_full);
+                                        if ui.selectable_label(selected, label).clicked() {
+                                            profile.settings.last_server_num = num;
+                                            profile.settings.last_server_name = server.name.clone();
+                                        }
+                                    }
+                                });
+                        });
+                    }
+                    if let Some(err) = &self.server_list_error {
+                        ui.label(egui::RichText::new(format!("⚠ {}", err)).size(11.0).color(egui::Color32::from_rgb(200, 100, 100)));
+                    }
+
+                    // 只能通过 VPN 访问的私服：启动前检查网络
+                    let network = &mut profile.index.network_requirement;
+                    ui.checkbox(&mut network.enabled, t!("network_check.enabled").as_ref())
+                        .on_hover_text(t!("network_check.enabled_hint"));
+                    if network.enabled {
+                        let server = format!("{}:{}", profile.settings.ip, profile.settings.port);
+                        ui.horizontal(|ui| {
+                            ui.label(t!("network_check.endpoint"));
+                            ui.add(egui::TextEdit::singleline(&mut network.endpoint).hint_text(server));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(t!("network_check.interface"));
+                            ui.add(egui::TextEdit::singleline(&mut network.interface).hint_text(t!("network_check.interface_hint")));
+                        });
+                    }
+
                     ui.separator();
                     ui.label(t!("profile_editor.account_settings"));
 
@@ -93,6 +239,23 @@ impl ProfileEditor {
                         );
                     });
                     ui.checkbox(&mut profile.settings.save_account, t!("profile_editor.save_account").as_ref());
+                    if profile.settings.uses_session_credentials() && !profile.index.session_password.enabled {
+                        ui.label(
+                            egui::RichText::new(format!("⚠ {}", t!("profile_editor.session_credentials_hint")))
+                                .size(11.0)
+                                .color(egui::Color32::from_rgb(255, 210, 120)),
+                        );
+                    }
+                    let session_password = &mut profile.index.session_password;
+                    ui.checkbox(&mut session_password.enabled, t!("profile_editor.session_password").as_ref())
+                        .on_hover_text(t!("profile_editor.session_password_hint"));
+                    if session_password.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.session_password_scrub"));
+                            ui.add(egui::DragValue::new(&mut session_password.scrub_after_secs).clamp_range(0..=3600).suffix(" s"))
+                                .on_hover_text(t!("profile_editor.session_password_scrub_hint"));
+                        });
+                    }
 
                     ui.separator();
                     ui.label(t!("profile_editor.game_settings"));
@@ -112,20 +275,26 @@ impl ProfileEditor {
                     
                     // 显示当前 UO 版本号和加密设置
                     if !profile.settings.ultima_online_directory.is_empty() {
-                        let client_exe = std::path::Path::new(&profile.settings.ultima_online_directory).join("client.exe");
-                        if client_exe.exists() {
-                            if let Some(version) = crate::version_reader::read_pe_version(&client_exe) {
-                                // 显示版本号
-                                ui.label(egui::RichText::new(format!("{}: {}", t!("profile_editor.client_version"), version)).size(11.0).color(egui::Color32::from_rgb(150, 150, 150)));
+                        match crate::config::detect_client_version_from_uo_resources(&profile.settings.ultima_online_directory) {
+                            Some(detected) => {
+                                // 显示版本号，推断出的版本注明来源
+                                let text = if detected.exact {
+                                    format!("{}: {}", t!("profile_editor.client_version"), detected.version)
+                                } else {
+                                    format!("{}: {}", t!("profile_editor.client_version"), t!("profile_editor.client_version_inferred", version = &detected.version))
+                                };
+                                ui.label(egui::RichText::new(text).size(11.0).color(egui::Color32::from_rgb(150, 150, 150)));
                                 
-                                // 自动更新 client_version 字段
-                                if profile.settings.client_version != version {
-                                    profile.settings.client_version = version.clone();
+                                // 自动更新 client_version 字段；推断出的只是最低版本，不覆盖已填写的版本号
+                                if profile.settings.client_version != detected.version
+                                    && (detected.exact || profile.settings.client_version.trim().is_empty())
+                                {
+                                    profile.settings.client_version = detected.version.clone();
                                 }
                                 
                                 // 根据版本号推荐加密类型（如果没有强制禁用加密）
-                                if !profile.settings.force_no_encryption {
-                                    let suggested = crate::encryption_helper::suggest_encryption_from_version(&version);
+                                if !profile.settings.force_no_encryption && profile.settings.client_version == detected.version {
+                                    let suggested = crate::encryption_helper::suggest_encryption_from_version(&detected.version);
                                     if profile.settings.encryption != suggested {
                                         profile.settings.encryption = suggested;
                                     }
@@ -140,11 +309,13 @@ impl ProfileEditor {
                                     t!("profile_editor.encryption_none")
                                 };
                                 ui.label(egui::RichText::new(format!("{}: {}", t!("profile_editor.encryption_status"), encryption_text)).size(11.0).color(egui::Color32::from_rgb(150, 150, 150)));
-                            } else {
+                            }
+                            None if std::path::Path::new(&profile.settings.ultima_online_directory).join("client.exe").exists() => {
                                 ui.label(egui::RichText::new(t!("profile_editor.client_found")).size(11.0).color(egui::Color32::from_rgb(100, 200, 100)));
                             }
-                        } else {
-                            ui.label(egui::RichText::new(t!("profile_editor.client_not_found")).size(11.0).color(egui::Color32::from_rgb(200, 100, 100)));
+                            None => {
+                                ui.label(egui::RichText::new(t!("profile_editor.client_not_found")).size(11.0).color(egui::Color32::from_rgb(200, 100, 100)));
+                            }
                         }
                     }
                     
@@ -161,10 +332,295 @@ impl ProfileEditor {
                         ui.checkbox(&mut profile.settings.auto_login, t!("profile_editor.auto_login").as_ref());
                         ui.checkbox(&mut profile.settings.reconnect, t!("profile_editor.reconnect").as_ref());
                     });
+                    // cliloc 语言文件，只列出 UO 目录中存在的
+                    ui.horizontal(|ui| {
+                        ui.label(t!("profile_editor.cliloc_language"));
+                        let available = crate::uo_data::cliloc_languages(std::path::Path::new(&profile.settings.ultima_online_directory));
+                        let label = |code: &str| match crate::uo_data::cliloc_language_name(code) {
+                            Some(name) => format!("{} ({})", code, name),
+                            None => code.to_string(),
+                        };
+                        let selected_text = match &profile.index.cliloc_language {
+                            Some(code) if !available.contains(code) => t!("profile_editor.cliloc_missing", language = label(code)).to_string(),
+                            Some(code) => label(code),
+                            None => t!("profile_editor.cliloc_auto").to_string(),
+                        };
+                        egui::ComboBox::from_id_source("cliloc_language_combo")
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut profile.index.cliloc_language, None, t!("profile_editor.cliloc_auto"));
+                                for code in &available {
+                                    ui.selectable_value(&mut profile.index.cliloc_language, Some(code.clone()), label(code));
+                                }
+                            })
+                            .response
+                            .on_hover_text(t!("profile_editor.cliloc_language_hint"));
+                    });
+                    // 渲染驱动（force_driver）
+                    ui.horizontal(|ui| {
+                        ui.label(t!("profile_editor.render_driver"));
+                        let current = RenderDriver::from_value(profile.settings.force_driver);
+                        let selected_text = match current {
+                            Some(driver) => t!(driver.label_key()).to_string(),
+                            None => t!("render_driver.unknown", value = profile.settings.force_driver).to_string(),
+                        };
+                        egui::ComboBox::from_id_source("render_driver_combo")
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                for driver in render_driver::supported_drivers() {
+                                    ui.selectable_value(&mut profile.settings.force_driver, driver.value(), t!(driver.label_key()))
+                                        .on_hover_text(t!(driver.hint_key()));
+                                }
+                            })
+                            .response
+                            .on_hover_text(t!("profile_editor.render_driver_hint"));
+                    });
+                    ui.checkbox(&mut profile.index.prefer_dedicated_gpu, t!("profile_editor.prefer_dedicated_gpu").as_ref())
+                        .on_hover_text(t!("profile_editor.prefer_dedicated_gpu_hint"));
+                    // 启动目标
+                    ui.horizontal(|ui| {
+                        ui.label(t!("profile_editor.launch_target"));
+                        egui::ComboBox::from_id_source("launch_target_combo")
+                            .selected_text(t!(profile.index.launch_target.label_key()))
+                            .show_ui(ui, |ui| {
+                                for target in LaunchTarget::ALL {
+                                    ui.selectable_value(&mut profile.index.launch_target, target, t!(target.label_key()));
+                                }
+                            });
+                    });
+                    if profile.index.launch_target == LaunchTarget::OpenUO {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("client_versions.label"));
+                            let selected = match &profile.index.pinned_open_uo {
+                                Some(tag) if self.client_versions.iter().any(|v| v.tag == *tag) => format!("📌 {}", tag),
+                                Some(tag) => format!("📌 {} ({})", tag, t!("client_versions.not_installed")),
+                                None => t!("client_versions.latest").to_string(),
+                            };
+                            egui::ComboBox::from_id_source("pinned_open_uo_combo")
+                                .selected_text(selected)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut profile.index.pinned_open_uo, None, t!("client_versions.latest"));
+                                    for version in &self.client_versions {
+                                        let label = if version.version == version.tag {
+                                            version.tag.clone()
+                                        } else {
+                                            format!("{} ({})", version.tag, version.version)
+                                        };
+                                        ui.selectable_value(&mut profile.index.pinned_open_uo, Some(version.tag.clone()), label);
+                                    }
+                                })
+                                .response
+                                .on_hover_text(t!("client_versions.editor_hint"));
+                        });
+                        // 固定了版本的档案不跟随更新
+                        ui.add_enabled_ui(profile.index.pinned_open_uo.is_none(), |ui| ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.update_policy"));
+                            egui::ComboBox::from_id_source("update_policy_combo")
+                                .selected_text(t!(profile.index.update_policy.label_key()))
+                                .show_ui(ui, |ui| {
+                                    for policy in UpdatePolicy::ALL {
+                                        ui.selectable_value(&mut profile.index.update_policy, policy, t!(policy.label_key()));
+                                    }
+                                })
+                                .response
+                                .on_hover_text(t!("profile_editor.update_policy_hint"));
+                        }));
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.executable_path"));
+                            ui.add(
+                                egui::TextEdit::singleline(&mut profile.index.executable_path)
+                                    .hint_text(t!("profile_editor.executable_hint")),
+                            );
+                            let browse_btn = egui::Button::new(t!("profile_editor.browse"))
+                                .fill(egui::Color32::from_rgb(100, 100, 120))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(browse_btn).clicked()
+                                && let Some(path) = pick_file(&profile.index.executable_path)
+                            {
+                                profile.index.executable_path = path;
+                            }
+                        });
+                        if let Some(template) = profile.index.argument_template_mut() {
+                            ui.horizontal(|ui| {
+                                ui.label(t!("profile_editor.argument_template"));
+                                ui.text_edit_singleline(template)
+                                    .on_hover_text(launch_target::PLACEHOLDERS);
+                            });
+                        }
+                    }
+
                     ui.horizontal(|ui| {
                         ui.label(t!("profile_editor.additional_args"));
                         ui.text_edit_singleline(&mut profile.index.additional_args);
                     });
+
+                    // 员工模式：与玩家设置分开显示
+                    if self.staff_unlocked && profile.index.launch_target == LaunchTarget::OpenUO {
+                        let staff = &mut profile.index.staff;
+                        let manifest = &self.staff_manifest;
+                        ui.group(|ui| {
+                            ui.checkbox(&mut staff.enabled, egui::RichText::new(format!("🛡 {}", t!("staff_mode.title"))).strong())
+                                .on_hover_text(t!("staff_mode.hint"));
+                            if !staff.enabled {
+                                return;
+                            }
+                            match manifest.test_center() {
+                                Some((host, port)) => {
+                                    let address = port.map_or_else(|| host.to_string(), |port| format!("{}:{}", host, port));
+                                    ui.checkbox(&mut staff.test_center, t!("staff_mode.test_center", address = address).as_ref());
+                                }
+                                None => {
+                                    ui.add_enabled(false, egui::Checkbox::new(&mut staff.test_center, t!("staff_mode.test_center_none").as_ref()));
+                                }
+                            }
+                            ui.add_enabled_ui(!staff.test_center || manifest.test_center().is_none(), |ui| {
+                                ui.horizontal(|ui| {
+                                    let mut alternate = staff.alternate_port.is_some();
+                                    if ui.checkbox(&mut alternate, t!("staff_mode.alternate_port").as_ref()).changed() {
+                                        staff.alternate_port = alternate.then_some(profile.settings.port);
+                                    }
+                                    if let Some(port) = &mut staff.alternate_port {
+                                        ui.add(egui::DragValue::new(port).clamp_range(1..=65535));
+                                    }
+                                });
+                            });
+                            ui.checkbox(&mut staff.verbose_logging, t!("staff_mode.verbose_logging").as_ref())
+                                .on_hover_text(t!("staff_mode.verbose_logging_hint"));
+                            for preset in &manifest.presets {
+                                let mut selected = staff.presets.contains(&preset.name);
+                                let response = ui.checkbox(&mut selected, &preset.name).on_hover_text(
+                                    preset.description.as_deref().unwrap_or(preset.args.as_str()),
+                                );
+                                if response.changed() {
+                                    if selected {
+                                        staff.presets.push(preset.name.clone());
+                                    } else {
+                                        staff.presets.retain(|name| *name != preset.name);
+                                    }
+                                }
+                            }
+                        });
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label(t!("profile_editor.launch_confirmation"));
+                        egui::ComboBox::from_id_source("launch_confirmation_combo")
+                            .selected_text(t!(profile.index.launch_confirmation.label_key()))
+                            .show_ui(ui, |ui| {
+                                for mode in LaunchConfirmation::ALL {
+                                    ui.selectable_value(&mut profile.index.launch_confirmation, mode, t!(mode.label_key()));
+                                }
+                            })
+                            .response
+                            .on_hover_text(t!("profile_editor.launch_confirmation_hint"));
+                    });
+
+                    // 游戏进程的优先级和 CPU 亲和性
+                    let tuning = &mut profile.index.process_tuning;
+                    ui.horizontal(|ui| {
+                        ui.label(t!("process_tuning.priority"));
+                        egui::ComboBox::from_id_source("process_priority_combo")
+                            .selected_text(t!(tuning.priority.label_key()))
+                            .show_ui(ui, |ui| {
+                                for priority in ProcessPriority::ALL {
+                                    ui.selectable_value(&mut tuning.priority, priority, t!(priority.label_key()));
+                                }
+                            })
+                            .response
+                            .on_hover_text(t!("process_tuning.priority_hint"));
+                    });
+                    let mut limit_cpus = tuning.affinity_mask != 0;
+                    if ui
+                        .checkbox(&mut limit_cpus, t!("process_tuning.limit_cpus").as_ref())
+                        .on_hover_text(t!("process_tuning.limit_cpus_hint"))
+                        .changed()
+                    {
+                        // 开启时默认只选第一个 CPU，由用户再勾选其他的
+                        tuning.affinity_mask = u64::from(limit_cpus);
+                    }
+                    if limit_cpus {
+                        ui.horizontal_wrapped(|ui| {
+                            for cpu in 0..process_tuning::cpu_count() {
+                                let mut selected = tuning.affinity_mask & (1 << cpu) != 0;
+                                if ui.checkbox(&mut selected, cpu.to_string()).changed() {
+                                    tuning.affinity_mask ^= 1 << cpu;
+                                }
+                            }
+                        });
+                    }
+
+                    // 挂机看门狗
+                    let watchdog = &mut profile.index.watchdog;
+                    ui.checkbox(&mut watchdog.enabled, t!("profile_editor.watchdog").as_ref())
+                        .on_hover_text(t!("profile_editor.watchdog_hint"));
+                    if watchdog.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.watchdog_patterns"));
+                            if ui.text_edit_singleline(&mut self.disconnect_patterns).lost_focus() {
+                                watchdog.disconnect_patterns = parse_patterns(&self.disconnect_patterns);
+                            }
+                        });
+                        ui.checkbox(&mut watchdog.character_left_title, t!("profile_editor.watchdog_character").as_ref());
+                        ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.watchdog_grace"));
+                            ui.add(egui::DragValue::new(&mut watchdog.grace_secs).clamp_range(10..=3600).suffix(" s"));
+                            ui.label(t!("profile_editor.watchdog_delay"));
+                            ui.add(egui::DragValue::new(&mut watchdog.relaunch_delay_secs).clamp_range(0..=600).suffix(" s"));
+                        });
+                    }
+
+                    // 启动前备份角色设置
+                    let backup = &mut profile.index.profile_backup;
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut backup.enabled, t!("profile_backup.enabled").as_ref())
+                            .on_hover_text(t!("profile_backup.enabled_hint"));
+                        if backup.enabled {
+                            ui.label(t!("profile_backup.keep"));
+                            ui.add(egui::DragValue::new(&mut backup.keep).clamp_range(1..=profile_backup::MAX_KEEP));
+                        }
+                    });
+                    if !self.backups.is_empty() {
+                        let mut restore = None;
+                        egui::CollapsingHeader::new(t!("profile_backup.list", count = self.backups.len()))
+                            .id_source("profile_backup_list")
+                            .show(ui, |ui| {
+                                for snapshot in &self.backups {
+                                    ui.horizontal(|ui| {
+                                        ui.label(crate::clock::format_local(snapshot.created));
+                                        let btn = ui
+                                            .add_enabled(!self.game_running, egui::Button::new(t!("profile_backup.restore")))
+                                            .on_disabled_hover_text(t!("profile_backup.restore_running"));
+                                        if btn.clicked() {
+                                            restore = Some(snapshot.clone());
+                                        }
+                                    });
+                                }
+                            });
+                        if let Some(snapshot) = restore {
+                            let time = crate::clock::format_local(snapshot.created);
+                            self.backup_message = Some(match profile_backup::restore(profile, &snapshot) {
+                                Ok(()) => format!("✓ {}", t!("profile_backup.restored", time = time)),
+                                Err(e) => format!("✗ {}: {:#}", t!("profile_backup.restore_failed"), e),
+                            });
+                        }
+                    }
+                    if let Some(message) = &self.backup_message {
+                        ui.label(egui::RichText::new(message).size(11.0));
+                    }
+
+                    // 启动前硬件检查
+                    let check = &mut profile.index.hardware_check;
+                    ui.checkbox(&mut check.enabled, t!("profile_editor.hardware_check").as_ref())
+                        .on_hover_text(t!("profile_editor.hardware_check_hint"));
+                    if check.enabled {
+                        ui.horizontal(|ui| {
+                            ui.label(t!("profile_editor.min_ram"));
+                            ui.add(egui::DragValue::new(&mut check.min_ram_mb).clamp_range(0..=65536).speed(64).suffix(" MB"));
+                            ui.label(t!("profile_editor.min_disk"));
+                            ui.add(egui::DragValue::new(&mut check.min_free_disk_mb).clamp_range(0..=102400).speed(64).suffix(" MB"));
+                        });
+                    }
                 }
 
                 ui.add_space(8.0);
@@ -176,9 +632,10 @@ impl ProfileEditor {
                     .min_size(egui::vec2(80.0, 32.0));
                     
                     if ui.add(save_btn).clicked() {
-                        if let (Some(idx), Some(profile)) =
+                        if let (Some(idx), Some(mut profile)) =
                             (self.editor_index, self.editor_profile.clone())
                         {
+                            profile.index.watchdog.disconnect_patterns = parse_patterns(&self.disconnect_patterns);
                             result = Some((idx, profile));
                         }
                         self.close();
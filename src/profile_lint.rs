@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::config::{OuoSettings, ProfileConfig, ProfileIndex, profile_settings_path};
+use crate::encryption_helper::suggest_encryption_from_version;
+use crate::i18n::t;
+use crate::launch_target::{self, LaunchTarget};
+use crate::update_policy::{self, PolicyDecision};
+
+/// OpenUO 已不再读取的旧设置项
+const DEPRECATED_SETTINGS: [&str; 3] = ["debug", "profiler", "preload_maps"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl LintLevel {
+    /// 报告中使用固定的英文标记，方便服务器管理员搜索
+    fn tag(self) -> &'static str {
+        match self {
+            LintLevel::Info => "INFO",
+            LintLevel::Warning => "WARN",
+            LintLevel::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub level: LintLevel,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(level: LintLevel, message: impl Into<String>) -> Self {
+        Self { level, message: message.into() }
+    }
+}
+
+/// 检查时已知的 OpenUO 版本信息
+#[derive(Debug, Clone, Default)]
+pub struct LintContext {
+    pub local_open_uo: Option<String>,
+    pub latest_open_uo: Option<String>,
+    /// 更新清单中声明的最低版本
+    pub required_open_uo: Option<String>,
+}
+
+/// 一个 profile 的检查结果
+#[derive(Debug, Clone)]
+pub struct ProfileLint {
+    pub name: String,
+    pub file_name: String,
+    pub issues: Vec<LintIssue>,
+}
+
+impl ProfileLint {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.level == LintLevel::Error)
+    }
+}
+
+/// 检查 profile，同时读取磁盘上的设置文件检查旧设置项
+pub fn lint_profile(profile: &ProfileConfig, ctx: &LintContext) -> ProfileLint {
+    let settings_path = profile_settings_path(profile);
+    let mut issues = match fs::read_to_string(&settings_path) {
+        Ok(raw) => check_raw_settings(&raw),
+        Err(_) => vec![LintIssue::new(
+            LintLevel::Error,
+            t!("lint.settings_unreadable", path = settings_path.display()),
+        )],
+    };
+    issues.extend(check(profile, ctx));
+    issues.sort_by_key(|i| std::cmp::Reverse(i.level));
+    ProfileLint {
+        name: profile.index.name.clone(),
+        file_name: profile.index.file_name.clone(),
+        issues,
+    }
+}
+
+/// 检查 Profiles 目录中的一个索引文件
+pub fn lint_file(path: &Path, ctx: &LintContext) -> Result<ProfileLint> {
+    let raw = fs::read_to_string(path).with_context(|| format!("无法读取 {}", path.display()))?;
+    let index: ProfileIndex = serde_json::from_str(&raw).with_context(|| format!("无法解析 {}", path.display()))?;
+    let mut profile = ProfileConfig {
+        index,
+        settings: OuoSettings::default(),
+    };
+    // 设置文件无法解析时由 lint_profile 报告
+    if let Ok(settings) = fs::read_to_string(profile_settings_path(&profile))
+        && let Ok(settings) = serde_json::from_str(&settings)
+    {
+        profile.settings = settings;
+    }
+    Ok(lint_profile(&profile, ctx))
+}
+
+fn check_raw_settings(raw: &str) -> Vec<LintIssue> {
+    let value = match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(value) => value,
+        Err(e) => return vec![LintIssue::new(LintLevel::Error, t!("lint.settings_invalid", error = e))],
+    };
+    let mut issues = Vec::new();
+    if let Err(e) = serde_json::from_value::<OuoSettings>(value.clone()) {
+        issues.push(LintIssue::new(LintLevel::Error, t!("lint.settings_invalid", error = e)));
+    }
+    if let Some(map) = value.as_object() {
+        for key in DEPRECATED_SETTINGS.iter().filter(|key| map.contains_key(**key)) {
+            issues.push(LintIssue::new(LintLevel::Warning, t!("lint.deprecated_setting", key = key)));
+        }
+    }
+    issues
+}
+
+/// 检查 profile 本身的配置
+pub fn check(profile: &ProfileConfig, ctx: &LintContext) -> Vec<LintIssue> {
+    let settings = &profile.settings;
+    let target = profile.index.launch_target;
+    let mut issues = Vec::new();
+    let mut push = |level, message: std::borrow::Cow<'_, str>| issues.push(LintIssue::new(level, message));
+
+    if settings.ip.trim().is_empty() {
+        push(LintLevel::Error, t!("lint.no_server"));
+    }
+    if settings.port == 0 {
+        push(LintLevel::Error, t!("lint.no_port"));
+    }
+
+    let uo_dir = settings.ultima_online_directory.trim();
+    if target != LaunchTarget::Custom {
+        if uo_dir.is_empty() {
+            push(LintLevel::Error, t!("lint.no_uo_dir"));
+        } else if !Path::new(uo_dir).is_dir() {
+            push(LintLevel::Error, t!("lint.uo_dir_missing", path = uo_dir));
+        }
+    }
+
+    if target == LaunchTarget::OpenUO {
+        let version = settings.client_version.trim();
+        if version.is_empty() {
+            push(LintLevel::Error, t!("lint.no_client_version"));
+        } else {
+            let client_exe = Path::new(uo_dir).join("client.exe");
+            if let Some(detected) = crate::version_reader::read_pe_version(&client_exe)
+                && detected != version
+            {
+                push(LintLevel::Warning, t!("lint.client_version_mismatch", configured = version, detected = detected));
+            }
+            let suggested = suggest_encryption_from_version(version);
+            if settings.encryption <= 1 && settings.encryption != suggested && !settings.force_no_encryption {
+                push(LintLevel::Info, t!("lint.encryption_mismatch", version = version, suggested = suggested));
+            }
+        }
+        if settings.encryption > 1 {
+            push(LintLevel::Warning, t!("lint.encryption_legacy", value = settings.encryption));
+        }
+
+        match update_policy::evaluate(
+            profile.index.update_policy,
+            ctx.local_open_uo.as_deref(),
+            ctx.latest_open_uo.as_deref(),
+            ctx.required_open_uo.as_deref(),
+        ) {
+            PolicyDecision::Proceed => {}
+            PolicyDecision::Notify { latest } => push(LintLevel::Info, t!("lint.openuo_update", latest = latest)),
+            PolicyDecision::Outdated { local, required } => {
+                push(LintLevel::Error, t!("lint.openuo_outdated", local = local, required = required))
+            }
+            PolicyDecision::NotInstalled { required } => {
+                push(LintLevel::Error, t!("lint.openuo_not_installed", required = required))
+            }
+            PolicyDecision::Unverified => push(LintLevel::Warning, t!("lint.openuo_unverified")),
+        }
+    } else {
+        match launch_target::resolve_executable(profile) {
+            None => push(LintLevel::Error, t!("lint.no_executable")),
+            Some(exe) if !exe.exists() => push(LintLevel::Error, t!("lint.executable_missing", path = exe.display())),
+            Some(_) => {}
+        }
+    }
+
+    if settings.auto_login && settings.username.trim().is_empty() {
+        push(LintLevel::Warning, t!("lint.auto_login_no_user"));
+    }
+    let watchdog = &profile.index.watchdog;
+    if watchdog.enabled && watchdog.character_left_title && profile.index.last_character_name.trim().is_empty() {
+        push(LintLevel::Warning, t!("lint.watchdog_no_character"));
+    }
+    issues
+}
+
+/// 纯文本报告，方便玩家贴给服务器管理员
+pub fn report(results: &[ProfileLint]) -> String {
+    let mut report = format!(
+        "{}\n{}\n",
+        t!("lint.report_title", version = env!("CARGO_PKG_VERSION")),
+        crate::system_info::system_info_string()
+    );
+    for lint in results {
+        report.push_str(&format!("\n[{}] ({})\n", lint.name, lint.file_name));
+        if lint.issues.is_empty() {
+            report.push_str(&format!("  OK   {}\n", t!("lint.no_issues")));
+        }
+        for issue in &lint.issues {
+            report.push_str(&format!("  {:<5} {}\n", issue.level.tag(), issue.message));
+        }
+    }
+    report
+}
+
+/// `--lint-profile <file>`：检查后输出报告，有错误时返回 false
+pub fn run_cli(path: &Path) -> Result<bool> {
+    let mut ctx = LintContext {
+        local_open_uo: crate::github::detect_open_uo_version(),
+        ..Default::default()
+    };
+    match crate::github::fetch_open_uo_requirements() {
+        Ok((latest, required)) => {
+            ctx.latest_open_uo = Some(latest);
+            ctx.required_open_uo = required;
+        }
+        Err(e) => tracing::warn!("无法获取 OpenUO 版本要求: {:#}", e),
+    }
+    let lint = lint_file(path, &ctx)?;
+    println!("{}", report(std::slice::from_ref(&lint)));
+    Ok(!lint.has_errors())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update_policy::UpdatePolicy;
+
+    fn profile() -> ProfileConfig {
+        let mut profile = crate::config::new_profile("Test");
+        profile.settings.ultima_online_directory = std::env::temp_dir().to_string_lossy().into_owned();
+        profile.settings.client_version = "7.0.15.1".to_string();
+        profile.settings.encryption = 1;
+        profile.settings.username = "player".to_string();
+        profile.index.update_policy = UpdatePolicy::Never;
+        profile
+    }
+
+    fn levels(issues: &[LintIssue]) -> Vec<LintLevel> {
+        issues.iter().map(|i| i.level).collect()
+    }
+
+    #[test]
+    fn test_clean_profile() {
+        assert_eq!(check(&profile(), &LintContext::default()), vec![]);
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let mut p = profile();
+        p.settings.ip.clear();
+        p.settings.ultima_online_directory = "/nonexistent/uo".to_string();
+        p.settings.client_version.clear();
+        p.settings.auto_login = true;
+        p.settings.username.clear();
+        assert_eq!(
+            levels(&check(&p, &LintContext::default())),
+            vec![LintLevel::Error, LintLevel::Error, LintLevel::Error, LintLevel::Warning]
+        );
+    }
+
+    #[test]
+    fn test_encryption_and_policy() {
+        let mut p = profile();
+        p.settings.encryption = 3;
+        p.index.update_policy = UpdatePolicy::Require;
+        let ctx = LintContext {
+            local_open_uo: Some("1.0.0".to_string()),
+            latest_open_uo: Some("1.2.0".to_string()),
+            required_open_uo: Some("1.1.0".to_string()),
+        };
+        assert_eq!(levels(&check(&p, &ctx)), vec![LintLevel::Warning, LintLevel::Error]);
+
+        // 客户端版本建议加密，但设置了不加密
+        let mut p = profile();
+        p.settings.encryption = 0;
+        assert_eq!(levels(&check(&p, &LintContext::default())), vec![LintLevel::Info]);
+    }
+
+    #[test]
+    fn test_raw_settings() {
+        assert!(check_raw_settings(r#"{"ip":"a","port":2593}"#).is_empty());
+        let issues = check_raw_settings(r#"{"ip":"a","debug":true}"#);
+        assert_eq!(levels(&issues), vec![LintLevel::Warning]);
+        assert_eq!(levels(&check_raw_settings("{")), vec![LintLevel::Error]);
+        assert_eq!(levels(&check_raw_settings(r#"{"port":"x"}"#)), vec![LintLevel::Error]);
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::config::release_cache_path;
+
+/// 同时检查 OpenUO 和 Launcher 更新时避免同时写文件
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 缓存的更新信息响应
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub body: String,
+    /// 获取时间（Unix 时间戳）
+    pub fetched_at: i64,
+}
+
+/// 按 URL 保存的更新信息，配合 ETag 避免每次检查都下载完整的 JSON
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReleaseCache {
+    entries: BTreeMap<String, CachedResponse>,
+}
+
+impl ReleaseCache {
+    fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+pub fn get(url: &str) -> Option<CachedResponse> {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    get_from(&release_cache_path(), url)
+}
+
+pub fn store(url: &str, etag: Option<String>, body: &str) {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = store_to(&release_cache_path(), url, etag, body) {
+        tracing::warn!("Failed to save release cache: {}", e);
+    }
+}
+
+fn get_from(path: &Path, url: &str) -> Option<CachedResponse> {
+    ReleaseCache::load(path).entries.remove(url)
+}
+
+fn store_to(path: &Path, url: &str, etag: Option<String>, body: &str) -> Result<()> {
+    let mut cache = ReleaseCache::load(path);
+    cache.entries.insert(
+        url.to_string(),
+        CachedResponse {
+            etag,
+            body: body.to_string(),
+            fetched_at: crate::clock::now_unix(),
+        },
+    );
+    cache.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_store_and_get() {
+        let dir = temp_dir("release-cache");
+        let path = dir.join(".release_cache");
+        assert_eq!(get_from(&path, "https://a"), None);
+
+        store_to(&path, "https://a", Some("\"abc\"".to_string()), "{}").unwrap();
+        store_to(&path, "https://b", None, "[]").unwrap();
+        let cached = get_from(&path, "https://a").unwrap();
+        assert_eq!(cached.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(cached.body, "{}");
+
+        // 同一 URL 覆盖旧的响应
+        store_to(&path, "https://a", Some("\"def\"".to_string()), "{\"x\":1}").unwrap();
+        assert_eq!(get_from(&path, "https://a").unwrap().body, "{\"x\":1}");
+        assert_eq!(get_from(&path, "https://b").unwrap().body, "[]");
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::config::update_chain_path;
+
+/// 版本列表中的一个 release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainRelease {
+    pub version: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// 发布说明（Markdown）
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// 从已安装版本升级到最新版本要经过的 release
+///
+/// 检查更新时记录下来，增量补丁和逐文件更新可以据此知道跳过了哪些版本。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseChain {
+    /// 已安装的版本
+    pub from: String,
+    /// 最新版本
+    pub to: String,
+    /// 从旧到新，不包括已安装的版本，最后一个是最新版本
+    pub releases: Vec<ChainRelease>,
+}
+
+impl ReleaseChain {
+    /// 在版本列表（从新到旧）中找出 `from` 之后到 `to` 为止的 release
+    ///
+    /// 列表中没有已安装的版本（太旧或已被删除）时无法确定跳过了哪些版本，返回 None。
+    pub fn build(list: &[ChainRelease], from: &str, to: &str) -> Option<Self> {
+        let newest = list.iter().position(|r| r.version == to)?;
+        let installed = list.iter().position(|r| r.version == from)?;
+        if installed <= newest {
+            return None;
+        }
+        Some(ReleaseChain {
+            from: from.to_string(),
+            to: to.to_string(),
+            releases: list[newest..installed].iter().rev().cloned().collect(),
+        })
+    }
+
+    /// 跳过的中间版本（从旧到新，不包括最新版本）
+    pub fn skipped(&self) -> &[ChainRelease] {
+        &self.releases[..self.releases.len().saturating_sub(1)]
+    }
+
+    /// 合并所有版本的发布说明，最新的在前
+    pub fn combined_notes(&self) -> String {
+        let sections: Vec<String> = self
+            .releases
+            .iter()
+            .rev()
+            .map(|release| {
+                let mut section = format!("# {}", release.version);
+                if let Some(date) = release.published_at.as_deref().and_then(|d| d.get(..10)) {
+                    section.push_str(&format!("\n\n`{}`", date));
+                }
+                if let Some(notes) = release.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+                    section.push_str("\n\n");
+                    section.push_str(notes);
+                }
+                section
+            })
+            .collect();
+        sections.join("\n\n---\n\n")
+    }
+}
+
+/// 保存检查更新时得到的 release 链，覆盖之前的记录
+pub fn record(chain: &ReleaseChain) {
+    if let Err(e) = save_to(&update_chain_path(), chain) {
+        tracing::warn!("Failed to save update chain: {}", e);
+    }
+}
+
+/// 读取记录的 release 链，只有起止版本都相同时才返回
+pub fn recorded(from: &str, to: &str) -> Option<ReleaseChain> {
+    load_from(&update_chain_path()).filter(|chain| chain.from == from && chain.to == to)
+}
+
+fn save_to(path: &Path, chain: &ReleaseChain) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(chain)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn load_from(path: &Path) -> Option<ReleaseChain> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    fn release(version: &str, notes: Option<&str>) -> ChainRelease {
+        ChainRelease {
+            version: version.to_string(),
+            published_at: None,
+            notes: notes.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_build_chain() {
+        let list = vec![
+            release("v1.4", Some("- new map")),
+            release("v1.3", None),
+            release("v1.2", Some("- fixes")),
+            release("v1.1", None),
+        ];
+        let chain = ReleaseChain::build(&list, "v1.1", "v1.4").unwrap();
+        let versions: Vec<&str> = chain.releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, ["v1.2", "v1.3", "v1.4"]);
+        assert_eq!(chain.skipped().len(), 2);
+        assert_eq!(
+            chain.combined_notes(),
+            "# v1.4\n\n- new map\n\n---\n\n# v1.3\n\n---\n\n# v1.2\n\n- fixes"
+        );
+
+        // 只差一个版本时没有跳过的版本
+        assert!(ReleaseChain::build(&list, "v1.3", "v1.4").unwrap().skipped().is_empty());
+        // 已安装的版本不在列表中，或比最新版本还新
+        assert!(ReleaseChain::build(&list, "v1.0", "v1.4").is_none());
+        assert!(ReleaseChain::build(&list, "v1.4", "v1.2").is_none());
+        assert!(ReleaseChain::build(&list, "v1.4", "v1.4").is_none());
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let dir = temp_dir("chain");
+        let path = dir.join("chain.json");
+        let chain = ReleaseChain::build(&[release("v2", Some("notes")), release("v1", None)], "v1", "v2").unwrap();
+        save_to(&path, &chain).unwrap();
+        assert_eq!(load_from(&path), Some(chain));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(load_from(&path), None);
+    }
+}
@@ -0,0 +1,188 @@
+/// 发布说明中常见的 Markdown 子集：标题、列表、代码块、分隔线，以及粗体、行内代码和链接
+///
+/// 只用于在 Launcher 中显示 release 的说明，不认识的语法按原文显示。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading(u8, Vec<Span>),
+    /// 列表项，数字为缩进层级
+    Bullet(usize, Vec<Span>),
+    Paragraph(Vec<Span>),
+    Code(String),
+    Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Span {
+    Text(String),
+    Strong(String),
+    Code(String),
+    Link { text: String, url: String },
+}
+
+pub fn parse(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+    let mut in_comment = false;
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Some(lines) = &mut code {
+            if trimmed.starts_with("```") {
+                blocks.push(Block::Code(lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+        // GitHub 生成的说明里常有 HTML 注释
+        if in_comment || trimmed.starts_with("<!--") {
+            in_comment = !trimmed.ends_with("-->");
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            code = Some(Vec::new());
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some((level, text)) = heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(level, parse_inline(text)));
+        } else if matches!(trimmed, "---" | "***" | "___") {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+        } else if let Some(text) = bullet(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            let indent = line.len() - line.trim_start().len();
+            blocks.push(Block::Bullet(indent / 2, parse_inline(text)));
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| (level as u8, text.trim()))
+}
+
+fn bullet(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "].iter().find_map(|marker| line.strip_prefix(marker))
+}
+
+fn parse_inline(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let parsed = if rest.starts_with("**") {
+            delimited(rest, "**").map(|(inner, len)| (Span::Strong(inner.to_string()), len))
+        } else if c == '`' {
+            delimited(rest, "`").map(|(inner, len)| (Span::Code(inner.to_string()), len))
+        } else if c == '[' {
+            link(rest)
+        } else {
+            None
+        };
+        match parsed {
+            Some((span, len)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::Text(std::mem::take(&mut plain)));
+                }
+                spans.push(span);
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::Text(plain));
+    }
+    spans
+}
+
+/// 成对的标记之间的内容，以及包括标记在内的长度
+fn delimited<'a>(text: &'a str, marker: &str) -> Option<(&'a str, usize)> {
+    let inner = &text[marker.len()..];
+    let end = inner.find(marker).filter(|end| *end > 0)?;
+    Some((&inner[..end], marker.len() * 2 + end))
+}
+
+fn link(text: &str) -> Option<(Span, usize)> {
+    let close = text.find("](")?;
+    let end = text[close..].find(')')? + close;
+    let label = &text[1..close];
+    let url = &text[close + 2..end];
+    if url.contains(' ') || label.contains('[') {
+        return None;
+    }
+    Some((
+        Span::Link {
+            text: label.to_string(),
+            url: url.to_string(),
+        },
+        end + 1,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Span {
+        Span::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_blocks() {
+        let notes = "## What's Changed\n<!-- Release notes generated\nautomatically -->\n* Fix crash\n  - on macOS\nSome text\ncontinues here\n\n---\n```\ncode  block\n```";
+        assert_eq!(
+            parse(notes),
+            [
+                Block::Heading(2, vec![text("What's Changed")]),
+                Block::Bullet(0, vec![text("Fix crash")]),
+                Block::Bullet(1, vec![text("on macOS")]),
+                Block::Paragraph(vec![text("Some text continues here")]),
+                Block::Rule,
+                Block::Code("code  block".to_string()),
+            ]
+        );
+        assert_eq!(parse("#hashtag"), [Block::Paragraph(vec![text("#hashtag")])]);
+    }
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            parse_inline("**New** `--flag` by [@dev](https://github.com/dev) 中文 ** x"),
+            [
+                Span::Strong("New".to_string()),
+                text(" "),
+                Span::Code("--flag".to_string()),
+                text(" by "),
+                Span::Link {
+                    text: "@dev".to_string(),
+                    url: "https://github.com/dev".to_string()
+                },
+                text(" 中文 ** x"),
+            ]
+        );
+        assert_eq!(parse_inline("[not a link]"), [text("[not a link]")]);
+    }
+}
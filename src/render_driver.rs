@@ -0,0 +1,79 @@
+/// OpenUO 的渲染驱动（settings.json 中的 force_driver）
+///
+/// 数值与 OpenUO 中 FNA3D_FORCE_DRIVER 的映射一致：0 = 自动，1 = OpenGL，2 = Vulkan。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderDriver {
+    /// 由 FNA3D 自动选择（Windows 上为 D3D11）
+    Auto,
+    OpenGL,
+    Vulkan,
+}
+
+impl RenderDriver {
+    pub const ALL: [RenderDriver; 3] = [RenderDriver::Auto, RenderDriver::OpenGL, RenderDriver::Vulkan];
+
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(RenderDriver::Auto),
+            1 => Some(RenderDriver::OpenGL),
+            2 => Some(RenderDriver::Vulkan),
+            _ => None,
+        }
+    }
+
+    pub fn value(self) -> u8 {
+        match self {
+            RenderDriver::Auto => 0,
+            RenderDriver::OpenGL => 1,
+            RenderDriver::Vulkan => 2,
+        }
+    }
+
+    /// 当前平台是否可用（macOS 没有原生 Vulkan）
+    pub fn is_supported(self) -> bool {
+        match self {
+            RenderDriver::Vulkan => !cfg!(target_os = "macos"),
+            _ => true,
+        }
+    }
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            RenderDriver::Auto => "render_driver.auto",
+            RenderDriver::OpenGL => "render_driver.opengl",
+            RenderDriver::Vulkan => "render_driver.vulkan",
+        }
+    }
+
+    /// 说明何时应该选择该驱动
+    pub fn hint_key(self) -> &'static str {
+        match self {
+            RenderDriver::Auto => "render_driver.auto_hint",
+            RenderDriver::OpenGL => "render_driver.opengl_hint",
+            RenderDriver::Vulkan => "render_driver.vulkan_hint",
+        }
+    }
+}
+
+/// 当前平台下拉框中可选的驱动
+pub fn supported_drivers() -> impl Iterator<Item = RenderDriver> {
+    RenderDriver::ALL.into_iter().filter(|d| d.is_supported())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_round_trip() {
+        for driver in RenderDriver::ALL {
+            assert_eq!(RenderDriver::from_value(driver.value()), Some(driver));
+        }
+        assert_eq!(RenderDriver::from_value(7), None);
+    }
+
+    #[test]
+    fn test_auto_always_supported() {
+        assert!(supported_drivers().any(|d| d == RenderDriver::Auto));
+    }
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::i18n::t;
+
+/// Launcher 自身使用的图形适配器信息（启动时由 wgpu 获取）
+#[derive(Debug, Clone, Default)]
+pub struct GpuInfo {
+    pub name: String,
+    pub backend: String,
+    /// 软件渲染（例如 llvmpipe / WARP）
+    pub software: bool,
+    /// 是否满足完整的 WebGPU 功能级别，否则只是降级支持（例如 GLES / 老显卡）
+    pub full_feature_level: bool,
+}
+
+/// 启动前硬件检查的阈值（按 profile 配置）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequirementThresholds {
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    /// 最低可用内存（MB）
+    #[serde(rename = "MinAvailableRamMb")]
+    pub min_ram_mb: u64,
+    /// OpenUO 目录所在磁盘的最低剩余空间（MB），用于缓存和日志
+    #[serde(rename = "MinFreeDiskMb")]
+    pub min_free_disk_mb: u64,
+}
+
+impl Default for RequirementThresholds {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_ram_mb: 2048,
+            min_free_disk_mb: 500,
+        }
+    }
+}
+
+/// 某一时刻的硬件状态，获取失败的项为 None
+#[derive(Debug, Clone, Default)]
+pub struct HardwareSnapshot {
+    pub gpu: Option<GpuInfo>,
+    pub available_ram_mb: Option<u64>,
+    pub free_disk_mb: Option<u64>,
+}
+
+impl HardwareSnapshot {
+    /// 采集当前的内存和磁盘状态
+    pub fn capture(gpu: Option<GpuInfo>, disk_path: &Path) -> Self {
+        Self {
+            gpu,
+            available_ram_mb: crate::system_info::available_memory_mb(),
+            free_disk_mb: crate::system_info::free_disk_space(disk_path).map(|b| b / (1024 * 1024)),
+        }
+    }
+}
+
+/// 检查发现的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementIssue {
+    SoftwareGpu { name: String },
+    LimitedGpu { name: String, backend: String },
+    LowMemory { available_mb: u64, required_mb: u64 },
+    LowDisk { free_mb: u64, required_mb: u64 },
+}
+
+impl RequirementIssue {
+    pub fn message(&self) -> String {
+        match self {
+            RequirementIssue::SoftwareGpu { name } => {
+                t!("requirements.software_gpu", name = name).to_string()
+            }
+            RequirementIssue::LimitedGpu { name, backend } => {
+                t!("requirements.limited_gpu", name = name, backend = backend).to_string()
+            }
+            RequirementIssue::LowMemory { available_mb, required_mb } => {
+                t!("requirements.low_memory", available = available_mb, required = required_mb).to_string()
+            }
+            RequirementIssue::LowDisk { free_mb, required_mb } => {
+                t!("requirements.low_disk", free = free_mb, required = required_mb).to_string()
+            }
+        }
+    }
+
+    /// 显卡问题通常可以通过更换渲染驱动（force_driver）缓解
+    pub fn suggests_driver_change(&self) -> bool {
+        matches!(
+            self,
+            RequirementIssue::SoftwareGpu { .. } | RequirementIssue::LimitedGpu { .. }
+        )
+    }
+}
+
+/// 按阈值检查硬件状态，无法获取的项不报告问题
+pub fn check(snapshot: &HardwareSnapshot, thresholds: &RequirementThresholds) -> Vec<RequirementIssue> {
+    let mut issues = Vec::new();
+    if !thresholds.enabled {
+        return issues;
+    }
+
+    if let Some(gpu) = &snapshot.gpu {
+        if gpu.software {
+            issues.push(RequirementIssue::SoftwareGpu { name: gpu.name.clone() });
+        } else if !gpu.full_feature_level {
+            issues.push(RequirementIssue::LimitedGpu {
+                name: gpu.name.clone(),
+                backend: gpu.backend.clone(),
+            });
+        }
+    }
+
+    if let Some(available_mb) = snapshot.available_ram_mb
+        && available_mb < thresholds.min_ram_mb
+    {
+        issues.push(RequirementIssue::LowMemory {
+            available_mb,
+            required_mb: thresholds.min_ram_mb,
+        });
+    }
+
+    if let Some(free_mb) = snapshot.free_disk_mb
+        && free_mb < thresholds.min_free_disk_mb
+    {
+        issues.push(RequirementIssue::LowDisk {
+            free_mb,
+            required_mb: thresholds.min_free_disk_mb,
+        });
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu(software: bool, full_feature_level: bool) -> GpuInfo {
+        GpuInfo {
+            name: "Test GPU".to_string(),
+            backend: "Gl".to_string(),
+            software,
+            full_feature_level,
+        }
+    }
+
+    #[test]
+    fn test_healthy_system() {
+        let snapshot = HardwareSnapshot {
+            gpu: Some(gpu(false, true)),
+            available_ram_mb: Some(8192),
+            free_disk_mb: Some(20_000),
+        };
+        assert!(check(&snapshot, &RequirementThresholds::default()).is_empty());
+    }
+
+    #[test]
+    fn test_reports_issues() {
+        let snapshot = HardwareSnapshot {
+            gpu: Some(gpu(true, false)),
+            available_ram_mb: Some(1024),
+            free_disk_mb: Some(100),
+        };
+        let issues = check(&snapshot, &RequirementThresholds::default());
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].suggests_driver_change());
+        assert_eq!(
+            issues[1],
+            RequirementIssue::LowMemory { available_mb: 1024, required_mb: 2048 }
+        );
+        assert_eq!(issues[2], RequirementIssue::LowDisk { free_mb: 100, required_mb: 500 });
+
+        let disabled = RequirementThresholds {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(check(&snapshot, &disabled).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_values_are_ignored() {
+        let snapshot = HardwareSnapshot {
+            gpu: Some(gpu(false, false)),
+            ..Default::default()
+        };
+        let issues = check(&snapshot, &RequirementThresholds::default());
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], RequirementIssue::LimitedGpu { .. }));
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use crate::disk_space::InsufficientSpace;
+use crate::download::{Cancelled, DownloadContext, DownloadEvent};
+use crate::github::RateLimited;
+
+/// 默认的最大尝试次数（包括第一次）
+pub const DEFAULT_ATTEMPTS: u8 = 3;
+/// 设置中允许的最大尝试次数
+pub const MAX_ATTEMPTS: u8 = 10;
+/// 第一次重试前的等待时间，之后每次翻倍
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(8);
+
+static ATTEMPTS: AtomicU8 = AtomicU8::new(DEFAULT_ATTEMPTS);
+
+/// 0 表示使用默认值
+pub fn set_max_attempts(attempts: u8) {
+    let attempts = if attempts == 0 { DEFAULT_ATTEMPTS } else { attempts.min(MAX_ATTEMPTS) };
+    ATTEMPTS.store(attempts, Ordering::Relaxed);
+}
+
+pub fn max_attempts() -> u8 {
+    ATTEMPTS.load(Ordering::Relaxed)
+}
+
+/// 第 `attempt` 次失败后（从 1 开始）的等待时间：指数退避，`jitter` 在 [0, 1) 之间，
+/// 实际等待为退避时间的 50%～100%，避免大量客户端同时重试
+fn backoff(attempt: u32, jitter: f64) -> Duration {
+    let exp = BASE_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16));
+    exp.min(MAX_DELAY).mul_f64(0.5 + jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+fn random_jitter() -> f64 {
+    let value = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    (value >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// 只有连接中断、超时、服务器 5xx / 429 这类可能自行恢复的错误才值得重试
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    if error.is::<Cancelled>() || error.is::<InsufficientSpace>() || error.is::<RateLimited>() {
+        return false;
+    }
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return match e.status() {
+                Some(status) => status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+                None => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            use std::io::ErrorKind::*;
+            return matches!(e.kind(), ConnectionReset | ConnectionAborted | TimedOut | UnexpectedEof | BrokenPipe);
+        }
+        false
+    })
+}
+
+/// 在阻塞线程中重试 `f`，`what` 用于日志
+pub fn blocking<T>(what: &str, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let max = max_attempts() as u32;
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(e) if attempt < max && is_transient(&e) => {
+                let delay = backoff(attempt, random_jitter());
+                tracing::warn!("{} 失败（第 {}/{} 次），{:?} 后重试: {:#}", what, attempt, max, delay, e);
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 下载任务中重试 `f`，每次重试前发送 `Retrying` 事件；等待期间可以取消
+pub async fn download<T, Fut: Future<Output = Result<T>>>(
+    ctx: &DownloadContext,
+    what: &str,
+    mut f: impl FnMut() -> Fut,
+) -> Result<T> {
+    let max = max_attempts() as u32;
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Err(e) if attempt < max && is_transient(&e) => {
+                let delay = backoff(attempt, random_jitter());
+                tracing::warn!("{} 失败（第 {}/{} 次），{:?} 后重试: {:#}", what, attempt, max, delay, e);
+                ctx.emit(DownloadEvent::Retrying {
+                    attempt: attempt + 1,
+                    max_attempts: max,
+                    error: format!("{e:#}"),
+                });
+                ctx.cancellable(async {
+                    tokio::time::sleep(delay).await;
+                    Ok(())
+                })
+                .await?;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1, 1.0), BASE_DELAY);
+        assert_eq!(backoff(1, 0.0), BASE_DELAY / 2);
+        assert_eq!(backoff(3, 1.0), BASE_DELAY * 4);
+        // 不超过上限，次数很大时也不溢出
+        assert_eq!(backoff(10, 1.0), MAX_DELAY);
+        assert_eq!(backoff(u32::MAX, 1.0), MAX_DELAY);
+        let jitter = random_jitter();
+        assert!((0.0..1.0).contains(&jitter));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let reset = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        assert!(is_transient(&anyhow::Error::new(reset).context("下载失败")));
+        assert!(!is_transient(&anyhow::Error::new(Cancelled)));
+        assert!(!is_transient(&anyhow::anyhow!("SHA-256 校验失败")));
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert!(!is_transient(&denied.into()));
+        let limited = RateLimited {
+            url: "https://api.github.com/repos/openuo-online/OpenUO/releases/latest".to_string(),
+            retry_after: Duration::from_secs(600),
+        };
+        assert!(!is_transient(&limited.into()));
+    }
+
+    #[test]
+    fn test_blocking_retries_only_transient_errors() {
+        let mut calls = 0;
+        let result = blocking("test", || {
+            calls += 1;
+            if calls < 2 {
+                Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "timeout").into())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        let mut calls = 0;
+        let result: Result<()> = blocking("test", || {
+            calls += 1;
+            anyhow::bail!("格式无效")
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
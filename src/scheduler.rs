@@ -0,0 +1,106 @@
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+
+/// 定时启动方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleMode {
+    /// 在指定的本地时间启动（已过则为第二天）
+    AtTime,
+    /// 倒计时 N 分钟后启动
+    AfterMinutes,
+}
+
+/// 定时启动表单
+#[derive(Debug, Clone)]
+pub struct ScheduleForm {
+    pub mode: ScheduleMode,
+    pub hour: u8,
+    pub minute: u8,
+    pub minutes: u32,
+}
+
+impl Default for ScheduleForm {
+    fn default() -> Self {
+        Self {
+            mode: ScheduleMode::AtTime,
+            hour: 12,
+            minute: 0,
+            minutes: 10,
+        }
+    }
+}
+
+impl ScheduleForm {
+    /// 从现在起需要等待的时间
+    pub fn delay(&self, now_local: OffsetDateTime) -> Duration {
+        match self.mode {
+            ScheduleMode::AtTime => delay_until(now_local, self.hour, self.minute),
+            ScheduleMode::AfterMinutes => Duration::from_secs(u64::from(self.minutes) * 60),
+        }
+    }
+}
+
+/// 已安排的启动
+#[derive(Debug, Clone)]
+pub struct ScheduledLaunch {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub fire_at: Instant,
+}
+
+impl ScheduledLaunch {
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.fire_at.saturating_duration_since(now)
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.fire_at
+    }
+}
+
+/// 距离下一次 `hour:minute`（本地时间）的时长
+pub fn delay_until(now_local: OffsetDateTime, hour: u8, minute: u8) -> Duration {
+    let now_secs = u64::from(now_local.hour()) * 3600 + u64::from(now_local.minute()) * 60 + u64::from(now_local.second());
+    let target_secs = u64::from(hour.min(23)) * 3600 + u64::from(minute.min(59)) * 60;
+    let delay = if target_secs > now_secs {
+        target_secs - now_secs
+    } else {
+        target_secs + 24 * 3600 - now_secs
+    };
+    Duration::from_secs(delay)
+}
+
+/// 格式化倒计时，例如 "1:02:03" 或 "02:03"
+pub fn format_countdown(remaining: Duration) -> String {
+    // 向上取整，避免最后一秒显示 00:00
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_delay_until() {
+        let now = datetime!(2024-05-01 18:30:00 UTC);
+        assert_eq!(delay_until(now, 19, 0), Duration::from_secs(30 * 60));
+        // 已经过了的时间安排到第二天
+        assert_eq!(delay_until(now, 6, 0), Duration::from_secs(11 * 3600 + 30 * 60));
+        assert_eq!(delay_until(now, 18, 30), Duration::from_secs(24 * 3600));
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(Duration::from_secs(123)), "02:03");
+        assert_eq!(format_countdown(Duration::from_secs(3723)), "1:02:03");
+        assert_eq!(format_countdown(Duration::from_millis(500)), "00:01");
+        assert_eq!(format_countdown(Duration::ZERO), "00:00");
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus};
+use std::time::{Duration, Instant};
+
+/// 新版本启动成功前存在的标记文件，新版本显示出主界面后删除它
+const PENDING_FILENAME: &str = ".launcher_update_pending";
+
+/// 标记文件中的令牌通过这个环境变量传给新版本，只有令牌一致时新版本才确认启动
+const TOKEN_ENV: &str = "OPENUO_LAUNCHER_UPDATE_TOKEN";
+
+/// 新版本第一次启动时显示的发布说明，显示后删除
+const WHATS_NEW_FILENAME: &str = ".launcher_whats_new";
+
+/// 等待新版本显示主界面的最长时间，超时视为启动失败
+pub const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 旧版本的备份，例如 `OpenUO-Launcher.exe.bak`
+pub fn backup_path(exe: &Path) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    exe.with_file_name(name)
+}
+
+fn pending_path(exe: &Path) -> PathBuf {
+    exe.with_file_name(PENDING_FILENAME)
+}
+
+/// 刚安装的 Launcher 版本的发布说明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhatsNew {
+    pub version: String,
+    /// Markdown
+    pub notes: String,
+}
+
+fn whats_new_path(exe: &Path) -> PathBuf {
+    exe.with_file_name(WHATS_NEW_FILENAME)
+}
+
+/// 在启动新版本之前调用，新版本启动后显示一次这些说明
+pub fn save_whats_new(exe: &Path, whats_new: &WhatsNew) -> Result<()> {
+    let path = whats_new_path(exe);
+    fs::write(&path, serde_json::to_string(whats_new)?).with_context(|| format!("无法写入 {}", path.display()))
+}
+
+/// 恢复旧版本时删除，旧版本不会显示新版本的说明
+pub fn discard_whats_new(exe: &Path) {
+    fs::remove_file(whats_new_path(exe)).ok();
+}
+
+/// 读取并删除更新后要显示的发布说明，只会返回一次
+pub fn take_whats_new() -> Option<WhatsNew> {
+    let path = whats_new_path(&std::env::current_exe().ok()?);
+    take_whats_new_from(&path)
+}
+
+fn take_whats_new_from(path: &Path) -> Option<WhatsNew> {
+    let raw = fs::read_to_string(path).ok()?;
+    fs::remove_file(path).ok();
+    serde_json::from_str(&raw).ok()
+}
+
+/// 把当前可执行文件备份为 `.bak`，再用 `new_exe` 替换它，返回备份路径
+pub fn replace(new_exe: &Path, exe: &Path) -> Result<PathBuf> {
+    let backup = backup_path(exe);
+    fs::copy(exe, &backup).with_context(|| format!("无法备份当前 Launcher 到 {}", backup.display()))?;
+    self_replace::self_replace(new_exe).context("无法替换 Launcher 可执行文件")?;
+    Ok(backup)
+}
+
+/// 启动替换后的新版本，等待它确认启动成功
+///
+/// 超时或新版本提前退出时结束新版本的进程，调用方随后恢复旧版本，不会留下两个 Launcher。
+pub fn start_and_wait(exe: &Path, timeout: Duration) -> Result<()> {
+    let pending = pending_path(exe);
+    let token = uuid::Uuid::new_v4().to_string();
+    fs::write(&pending, &token).with_context(|| format!("无法写入 {}", pending.display()))?;
+    let mut child = match spawn(exe, &token) {
+        Ok(child) => child,
+        Err(e) => {
+            fs::remove_file(&pending).ok();
+            return Err(e.context("无法启动新版本 Launcher"));
+        }
+    };
+    let result = wait_for_removal(&pending, || child.try_wait().ok().flatten(), timeout);
+    if result.is_err() {
+        if let Err(e) = child.kill()
+            && e.kind() != std::io::ErrorKind::InvalidInput
+        {
+            tracing::warn!("无法结束没有确认启动的新版本 Launcher: {}", e);
+        }
+        child.wait().ok();
+    }
+    result
+}
+
+/// 等待新版本删除标记；`exited` 返回新版本进程的退出状态（还在运行时为 None）
+fn wait_for_removal(pending: &Path, mut exited: impl FnMut() -> Option<ExitStatus>, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    while pending.exists() {
+        if let Some(status) = exited() {
+            fs::remove_file(pending).ok();
+            anyhow::bail!("新版本 Launcher 启动后退出（{}）", status);
+        }
+        if Instant::now() >= deadline {
+            // 先删除标记，之后新版本再确认也会被忽略
+            fs::remove_file(pending).ok();
+            anyhow::bail!("新版本 Launcher 在 {} 秒内没有启动", timeout.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Ok(())
+}
+
+/// 新版本没有启动时用备份恢复旧版本
+pub fn rollback(backup: &Path) -> Result<()> {
+    self_replace::self_replace(backup).with_context(|| format!("无法从 {} 恢复旧版本", backup.display()))?;
+    tracing::warn!("新版本 Launcher 启动失败，已从 {} 恢复旧版本", backup.display());
+    Ok(())
+}
+
+/// 新版本显示出主界面后调用，通知等待中的旧版本更新成功
+///
+/// 只有旧版本启动的这个进程（令牌一致）才确认，其他方式启动的 Launcher 不会删除标记。
+pub fn confirm_started() {
+    let Ok(token) = std::env::var(TOKEN_ENV) else {
+        return;
+    };
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    if confirm_in(&pending_path(&exe), &token) {
+        tracing::info!("Launcher 更新后启动成功");
+    }
+}
+
+fn confirm_in(pending: &Path, token: &str) -> bool {
+    fs::read_to_string(pending).is_ok_and(|content| content.trim() == token) && fs::remove_file(pending).is_ok()
+}
+
+/// 直接启动新版本并保留进程句柄，超时时可以结束它
+fn spawn(exe: &Path, token: &str) -> Result<Child> {
+    Ok(Command::new(exe).env(TOKEN_ENV, token).spawn()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_backup_path() {
+        let exe = Path::new("/opt/launcher/OpenUO-Launcher.exe");
+        assert_eq!(backup_path(exe), Path::new("/opt/launcher/OpenUO-Launcher.exe.bak"));
+        assert_eq!(pending_path(exe), Path::new("/opt/launcher/.launcher_update_pending"));
+    }
+
+    #[test]
+    fn test_whats_new_shown_once() {
+        let dir = temp_dir("whats-new");
+        let exe = dir.join("OpenUO-Launcher");
+        let whats_new = WhatsNew { version: "v1.3.0".to_string(), notes: "- Custom update sources".to_string() };
+        save_whats_new(&exe, &whats_new).unwrap();
+        assert_eq!(take_whats_new_from(&whats_new_path(&exe)), Some(whats_new));
+        assert_eq!(take_whats_new_from(&whats_new_path(&exe)), None);
+    }
+
+    #[test]
+    fn test_wait_for_removal() {
+        let dir = temp_dir("self-update");
+        let pending = dir.join(PENDING_FILENAME);
+
+        // 新版本没有确认时超时，并清理标记
+        fs::write(&pending, "token").unwrap();
+        assert!(wait_for_removal(&pending, || None, Duration::from_millis(300)).is_err());
+        assert!(!pending.exists());
+        // 超时后才确认的新版本被忽略
+        assert!(!confirm_in(&pending, "token"));
+
+        // 其他令牌不能确认
+        fs::write(&pending, "token").unwrap();
+        assert!(!confirm_in(&pending, "other"));
+        assert!(pending.exists());
+
+        let confirm = {
+            let pending = pending.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(100));
+                assert!(confirm_in(&pending, "token"));
+            })
+        };
+        assert!(wait_for_removal(&pending, || None, Duration::from_secs(5)).is_ok());
+        confirm.join().unwrap();
+    }
+}
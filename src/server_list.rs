@@ -0,0 +1,231 @@
+use anyhow::{Context, Result, bail};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::config::OuoSettings;
+
+/// 登录服务器返回的游戏服务器条目（0xA8 包）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardServer {
+    pub index: u16,
+    pub name: String,
+    pub percent_full: u8,
+    pub timezone: i8,
+    pub address: Ipv4Addr,
+}
+
+const PACKET_SEED: u8 = 0xEF;
+const PACKET_ACCOUNT_LOGIN: u8 = 0x80;
+const PACKET_LOGIN_DENIED: u8 = 0x82;
+const PACKET_SERVER_LIST: u8 = 0xA8;
+
+/// 0xA8 包中每个服务器条目的长度
+const SERVER_ENTRY_LEN: usize = 40;
+
+/// 查询超时时间
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 明文握手只适用于未启用客户端加密的配置
+pub fn supports_plain_login(settings: &OuoSettings) -> bool {
+    settings.force_no_encryption || settings.encryption == 0
+}
+
+/// 后台查询的结果通道
+pub type ServerListReceiver = mpsc::Receiver<Result<Vec<ShardServer>, String>>;
+
+/// 在后台线程查询服务器列表，`password` 需为明文
+pub fn spawn_query(settings: &OuoSettings, password: String) -> ServerListReceiver {
+    let (tx, rx) = mpsc::channel();
+    let host = settings.ip.clone();
+    let port = settings.port;
+    let username = settings.username.clone();
+    let client_version = settings.client_version.clone();
+    std::thread::spawn(move || {
+        let res = query_server_list(&host, port, &username, &password, &client_version, QUERY_TIMEOUT)
+            .map_err(|e| format!("{e:#}"));
+        let _ = tx.send(res);
+    });
+    rx
+}
+
+/// 向登录服务器查询服务器列表
+///
+/// 只适用于未加密的登录协议：需要加密的服务器无法用明文握手获取列表。
+pub fn query_server_list(
+    host: &str,
+    port: u16,
+    username: &str,
+    password: &str,
+    client_version: &str,
+    timeout: Duration,
+) -> Result<Vec<ShardServer>> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .with_context(|| format!("无法解析服务器地址 {}:{}", host, port))?
+        .next()
+        .with_context(|| format!("无法解析服务器地址 {}:{}", host, port))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    stream.write_all(&build_seed_packet(0x7F00_0001, client_version))?;
+    stream.write_all(&build_login_packet(username, password))?;
+
+    let mut packet_id = [0u8; 1];
+    stream.read_exact(&mut packet_id)?;
+    match packet_id[0] {
+        PACKET_SERVER_LIST => {
+            let mut len_buf = [0u8; 2];
+            stream.read_exact(&mut len_buf)?;
+            let len = u16::from_be_bytes(len_buf) as usize;
+            if len < 6 {
+                bail!("服务器列表数据包长度无效: {}", len);
+            }
+            let mut body = vec![0u8; len - 3];
+            stream.read_exact(&mut body)?;
+            let mut packet = vec![PACKET_SERVER_LIST];
+            packet.extend_from_slice(&len_buf);
+            packet.extend_from_slice(&body);
+            parse_server_list(&packet)
+        }
+        PACKET_LOGIN_DENIED => {
+            let mut reason = [0u8; 1];
+            stream.read_exact(&mut reason).ok();
+            bail!("登录被拒绝（原因代码 {}）", reason[0])
+        }
+        other => bail!("未知的服务器响应: 0x{:02X}", other),
+    }
+}
+
+/// 构造 0xEF 种子包（包含客户端版本）
+pub fn build_seed_packet(seed: u32, client_version: &str) -> Vec<u8> {
+    let mut parts = client_version
+        .split('.')
+        .map(|p| p.trim().parse::<u32>().unwrap_or(0));
+    let mut packet = Vec::with_capacity(21);
+    packet.push(PACKET_SEED);
+    packet.extend_from_slice(&seed.to_be_bytes());
+    for _ in 0..4 {
+        packet.extend_from_slice(&parts.next().unwrap_or(0).to_be_bytes());
+    }
+    packet
+}
+
+/// 构造 0x80 账号登录包
+pub fn build_login_packet(username: &str, password: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(62);
+    packet.push(PACKET_ACCOUNT_LOGIN);
+    packet.extend_from_slice(&fixed_ascii(username, 30));
+    packet.extend_from_slice(&fixed_ascii(password, 30));
+    packet.push(0xFF);
+    packet
+}
+
+/// 解析 0xA8 服务器列表包
+pub fn parse_server_list(packet: &[u8]) -> Result<Vec<ShardServer>> {
+    if packet.len() < 6 || packet[0] != PACKET_SERVER_LIST {
+        bail!("不是有效的服务器列表数据包");
+    }
+    let count = u16::from_be_bytes([packet[4], packet[5]]) as usize;
+    let entries = &packet[6..];
+    if entries.len() < count * SERVER_ENTRY_LEN {
+        bail!("服务器列表数据包被截断");
+    }
+
+    let servers = entries
+        .chunks_exact(SERVER_ENTRY_LEN)
+        .take(count)
+        .map(|entry| {
+            let name_bytes = &entry[2..34];
+            let name_end = name_bytes.iter().position(|&b| b == 0).unwrap_or(name_bytes.len());
+            ShardServer {
+                index: u16::from_be_bytes([entry[0], entry[1]]),
+                name: String::from_utf8_lossy(&name_bytes[..name_end]).trim().to_string(),
+                percent_full: entry[34],
+                timezone: entry[35] as i8,
+                // IP 以小端序存储
+                address: Ipv4Addr::new(entry[39], entry[38], entry[37], entry[36]),
+            }
+        })
+        .collect();
+    Ok(servers)
+}
+
+/// 在服务器列表中选择要使用的服务器：优先匹配之前保存的名称，否则取第一个
+///
+/// 返回值中的编号从 1 开始，与客户端 `lastservernum` 的含义一致。
+pub fn pick_server<'a>(servers: &'a [ShardServer], preferred_name: &str) -> Option<(u16, &'a ShardServer)> {
+    let position = servers
+        .iter()
+        .position(|s| !preferred_name.is_empty() && s.name.eq_ignore_ascii_case(preferred_name))
+        .unwrap_or(0);
+    servers.get(position).map(|s| (position as u16 + 1, s))
+}
+
+fn fixed_ascii(value: &str, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    for (dst, src) in buf.iter_mut().zip(value.bytes().take(len - 1)) {
+        *dst = src;
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_entry(index: u16, name: &str, ip: [u8; 4]) -> Vec<u8> {
+        let mut entry = index.to_be_bytes().to_vec();
+        entry.extend_from_slice(&fixed_ascii(name, 33)[..32]);
+        entry.push(25);
+        entry.push(0);
+        entry.extend_from_slice(&[ip[3], ip[2], ip[1], ip[0]]);
+        entry
+    }
+
+    #[test]
+    fn test_parse_server_list() {
+        let mut body = vec![0x5D];
+        body.extend_from_slice(&2u16.to_be_bytes());
+        body.extend(server_entry(0, "Britannia", [127, 0, 0, 1]));
+        body.extend(server_entry(1, "Test Center", [10, 0, 0, 2]));
+        let mut packet = vec![PACKET_SERVER_LIST];
+        packet.extend_from_slice(&((body.len() + 3) as u16).to_be_bytes());
+        packet.extend(body);
+
+        let servers = parse_server_list(&packet).unwrap();
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "Britannia");
+        assert_eq!(servers[0].address, Ipv4Addr::new(127, 0, 0, 1));
+        assert_eq!(servers[1].index, 1);
+        assert_eq!(servers[1].name, "Test Center");
+        assert_eq!(servers[1].percent_full, 25);
+
+        assert_eq!(pick_server(&servers, "test center").unwrap().0, 2);
+        assert_eq!(pick_server(&servers, "").unwrap().0, 1);
+        assert!(pick_server(&[], "").is_none());
+    }
+
+    #[test]
+    fn test_truncated_server_list() {
+        let packet = [PACKET_SERVER_LIST, 0, 10, 0x5D, 0, 1, 0, 0, 0, 0];
+        assert!(parse_server_list(&packet).is_err());
+    }
+
+    #[test]
+    fn test_build_packets() {
+        let seed = build_seed_packet(1, "7.0.102.3");
+        assert_eq!(seed.len(), 21);
+        assert_eq!(&seed[5..9], &7u32.to_be_bytes());
+        assert_eq!(&seed[13..17], &102u32.to_be_bytes());
+
+        let login = build_login_packet("admin", "secret");
+        assert_eq!(login.len(), 62);
+        assert_eq!(&login[1..6], b"admin");
+        assert_eq!(login[6], 0);
+        assert_eq!(&login[31..37], b"secret");
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::{ProfileConfig, profile_settings_path};
+
+/// 仅本次启动使用的密码（按 profile 配置）
+///
+/// 每次启动时询问密码，写入设置文件供游戏读取，随后再从文件中清除。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionPasswordConfig {
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    /// 启动后多久从设置文件中清除密码（秒），0 表示游戏退出时才清除
+    #[serde(rename = "ScrubAfterSeconds")]
+    pub scrub_after_secs: u64,
+}
+
+impl Default for SessionPasswordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scrub_after_secs: 30,
+        }
+    }
+}
+
+/// 把（已加密的）密码写入设置文件，只修改 password 字段
+pub fn write_password(settings_path: &Path, encrypted: &str) -> Result<()> {
+    update_password(settings_path, encrypted)
+}
+
+/// 清除设置文件中的密码，返回文件中原来是否有密码
+pub fn scrub_password(settings_path: &Path) -> Result<bool> {
+    if !settings_path.exists() {
+        return Ok(false);
+    }
+    let had_password = read_settings(settings_path)?
+        .get("password")
+        .and_then(|v| v.as_str())
+        .is_some_and(|p| !p.is_empty());
+    if had_password {
+        update_password(settings_path, "")?;
+    }
+    Ok(had_password)
+}
+
+/// 在后台线程中延迟清除
+pub fn schedule_scrub(settings_path: PathBuf, delay: Duration) {
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        scrub_logged(&settings_path);
+    });
+}
+
+/// 启动时清除上次运行遗留的密码（例如游戏运行中关闭了 Launcher）
+pub fn scrub_all(profiles: &[ProfileConfig]) {
+    for profile in profiles.iter().filter(|p| p.index.session_password.enabled) {
+        scrub_logged(&profile_settings_path(profile));
+    }
+}
+
+pub fn scrub_logged(settings_path: &Path) {
+    match scrub_password(settings_path) {
+        Ok(true) => tracing::info!("Scrubbed session password from {}", settings_path.display()),
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Failed to scrub password from {}: {}", settings_path.display(), e),
+    }
+}
+
+fn read_settings(path: &Path) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let raw = fs::read_to_string(path).with_context(|| format!("无法读取 {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("无法解析 {}", path.display()))
+}
+
+fn update_password(path: &Path, value: &str) -> Result<()> {
+    let mut settings = read_settings(path)?;
+    settings.insert("password".to_string(), serde_json::Value::String(value.to_string()));
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&settings)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_write_and_scrub() {
+        let dir = temp_dir("session");
+        let path = dir.join("settings.json");
+        fs::write(&path, r#"{"username":"user","password":"","fps":60}"#).unwrap();
+
+        write_password(&path, "1-2-3").unwrap();
+        let settings = read_settings(&path).unwrap();
+        assert_eq!(settings["password"], "1-2-3");
+        // 其余字段保持不变
+        assert_eq!(settings["username"], "user");
+        assert_eq!(settings["fps"], 60);
+
+        assert!(scrub_password(&path).unwrap());
+        assert_eq!(read_settings(&path).unwrap()["password"], "");
+        assert!(!scrub_password(&path).unwrap());
+        assert!(!scrub_password(&dir.join("missing.json")).unwrap());
+    }
+}
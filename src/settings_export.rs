@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{OuoSettings, ProfileConfig};
+
+/// 导出时的可选内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// 附带 OpenUO 的 Data/Profiles（角色设置、宏等）
+    pub include_profiles: bool,
+    /// 保留加密后的密码（只能在本机解密）
+    pub include_password: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_profiles: true,
+            include_password: false,
+        }
+    }
+}
+
+/// 导出的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub settings_path: PathBuf,
+    /// 复制的角色设置文件数
+    pub profile_files: usize,
+}
+
+/// 把档案导出为不依赖 Launcher 的 OpenUO 设置：`settings.json`，以及可选的 `Data/Profiles`
+///
+/// 放进 OpenUO 目录后直接运行 OpenUO 即可使用，用于迁移或排查问题是否与 Launcher 有关。
+pub fn export(profile: &ProfileConfig, dest_dir: &Path, options: ExportOptions) -> Result<ExportSummary> {
+    let settings = standalone_settings(&saved_settings(profile), profile, options.include_password);
+    let profiles = options
+        .include_profiles
+        .then(|| game_profiles_dir(&crate::client_versions::dir_for(profile), &settings.username));
+    write_bundle(&settings, dest_dir, profiles.as_deref())
+}
+
+/// 游戏运行时会改写设置文件（窗口位置等），优先使用磁盘上的版本
+pub fn saved_settings(profile: &ProfileConfig) -> OuoSettings {
+    fs::read_to_string(crate::config::profile_settings_path(profile))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_else(|| profile.settings.clone())
+}
+
+/// 去掉只对本机 Launcher 有意义的字段
+pub fn standalone_settings(saved: &OuoSettings, profile: &ProfileConfig, include_password: bool) -> OuoSettings {
+    let mut settings = saved.clone();
+    // 仅本次使用的账号不会写入设置文件，导出时补上用户名
+    settings.username = profile.settings.username.clone();
+    settings.password = if include_password && !profile.index.session_password.enabled {
+        profile.settings.password.clone()
+    } else {
+        String::new()
+    };
+    settings.profiles_path = String::new();
+    // 屏幕信息每次启动时由 Launcher 重新计算
+    settings.launcher_screen_width = None;
+    settings.launcher_screen_height = None;
+    settings.launcher_scale_factor = None;
+    settings.launcher_is_hidpi = None;
+    settings
+}
+
+/// OpenUO 按账号保存角色设置；有该账号的目录时只导出这一个账号
+fn game_profiles_dir(open_uo_dir: &Path, username: &str) -> PathBuf {
+    let all = open_uo_dir.join("Data").join("Profiles");
+    let account = all.join(username);
+    if !username.is_empty() && account.is_dir() { account } else { all }
+}
+
+fn write_bundle(settings: &OuoSettings, dest_dir: &Path, profiles: Option<&Path>) -> Result<ExportSummary> {
+    fs::create_dir_all(dest_dir).with_context(|| format!("无法创建目录 {}", dest_dir.display()))?;
+    let settings_path = dest_dir.join("settings.json");
+    if settings_path.exists() {
+        anyhow::bail!("{} 已存在，请选择其他目录", settings_path.display());
+    }
+    fs::write(&settings_path, serde_json::to_string_pretty(settings)?)?;
+
+    let mut profile_files = 0;
+    if let Some(src) = profiles.filter(|p| p.is_dir()) {
+        let all = src.ends_with(Path::new("Data").join("Profiles"));
+        let dest = if all {
+            dest_dir.join("Data").join("Profiles")
+        } else {
+            dest_dir.join("Data").join("Profiles").join(src.file_name().unwrap_or_default())
+        };
+        // 导出到 OpenUO 目录本身时角色设置已经在原处
+        if dest != src {
+            crate::cloud_sync::copy_dir(src, &dest, &mut |_| profile_files += 1)?;
+        }
+    }
+    Ok(ExportSummary { settings_path, profile_files })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    fn profile() -> ProfileConfig {
+        let mut profile = ProfileConfig::default();
+        profile.settings.username = "lord".to_string();
+        profile.settings.password = "1-ABCD".to_string();
+        profile
+    }
+
+    #[test]
+    fn test_standalone_settings() {
+        let profile = profile();
+        let mut saved = profile.settings.clone();
+        saved.username.clear();
+        saved.launcher_screen_width = Some(2560);
+        saved.launcher_scale_factor = Some(2.0);
+
+        let settings = standalone_settings(&saved, &profile, false);
+        assert_eq!(settings.username, "lord");
+        assert!(settings.password.is_empty());
+        assert_eq!(settings.launcher_screen_width, None);
+        assert_eq!(settings.launcher_scale_factor, None);
+
+        assert_eq!(standalone_settings(&saved, &profile, true).password, "1-ABCD");
+        let mut session = profile.clone();
+        session.index.session_password.enabled = true;
+        assert!(standalone_settings(&saved, &session, true).password.is_empty());
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let root = temp_dir("export");
+        let open_uo = root.join("OpenUO");
+        let account = open_uo.join("Data").join("Profiles").join("lord").join("Shard").join("Hero");
+        fs::create_dir_all(&account).unwrap();
+        fs::write(account.join("macros.xml"), b"<macros/>").unwrap();
+        fs::create_dir_all(open_uo.join("Data").join("Profiles").join("other")).unwrap();
+
+        let src = game_profiles_dir(&open_uo, "lord");
+        let dest = root.join("export");
+        let summary = write_bundle(&profile().settings, &dest, Some(&src)).unwrap();
+        assert_eq!(summary.profile_files, 1);
+        assert!(dest.join("Data/Profiles/lord/Shard/Hero/macros.xml").exists());
+        assert!(!dest.join("Data/Profiles/other").exists());
+        let written: OuoSettings = serde_json::from_str(&fs::read_to_string(&summary.settings_path).unwrap()).unwrap();
+        assert_eq!(written.username, "lord");
+
+        // 不覆盖已有的 settings.json
+        assert!(write_bundle(&profile().settings, &dest, None).is_err());
+    }
+}
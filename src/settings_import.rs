@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::config::{OuoSettings, ProfileConfig};
+
+/// 导入的结果
+#[derive(Debug, Clone)]
+pub struct ImportSummary {
+    pub profile: ProfileConfig,
+    /// 类型不对而改用默认值的字段
+    pub skipped: Vec<String>,
+}
+
+/// 从任意 OpenUO 的 settings.json（朋友分享的、旧安装目录中的）创建新档案
+///
+/// Launcher 认识的字段映射到档案设置，不认识的字段原样保留，保存时写回设置文件。
+pub fn import(path: &Path) -> Result<ImportSummary> {
+    let raw = fs::read_to_string(path).with_context(|| format!("无法读取 {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&raw).with_context(|| format!("{} 不是有效的 JSON", path.display()))?;
+    let serde_json::Value::Object(fields) = value else {
+        anyhow::bail!("{} 不是 OpenUO 的设置文件", path.display());
+    };
+    let (settings, skipped) = merge_tolerant(fields);
+
+    let mut profile = crate::config::new_profile(&suggested_name(&settings, path));
+    let default_uo_dir = std::mem::replace(&mut profile.settings, settings).ultima_online_directory;
+    // 文件中没有资源目录时沿用新建档案的默认目录
+    if profile.settings.ultima_online_directory.is_empty() {
+        profile.settings.ultima_online_directory = default_uo_dir;
+    }
+    clear_machine_fields(&mut profile.settings);
+    Ok(ImportSummary { profile, skipped })
+}
+
+/// 逐个字段合并到默认设置，某个字段类型不对时跳过它，而不是整个文件导入失败
+fn merge_tolerant(fields: serde_json::Map<String, serde_json::Value>) -> (OuoSettings, Vec<String>) {
+    let mut merged = match serde_json::to_value(OuoSettings::default()) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let mut skipped = Vec::new();
+    for (key, value) in fields {
+        let previous = merged.insert(key.clone(), value);
+        if serde_json::from_value::<OuoSettings>(serde_json::Value::Object(merged.clone())).is_err() {
+            match previous {
+                Some(previous) => merged.insert(key.clone(), previous),
+                None => merged.remove(&key),
+            };
+            skipped.push(key);
+        }
+    }
+    let settings = serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default();
+    (settings, skipped)
+}
+
+/// 屏幕信息每次启动时由 Launcher 重新计算，角色设置目录使用 OpenUO 的默认位置
+fn clear_machine_fields(settings: &mut OuoSettings) {
+    settings.profiles_path = String::new();
+    settings.launcher_screen_width = None;
+    settings.launcher_screen_height = None;
+    settings.launcher_scale_factor = None;
+    settings.launcher_is_hidpi = None;
+}
+
+/// 优先用“账号@服务器”命名，否则用文件所在的目录名
+fn suggested_name(settings: &OuoSettings, path: &Path) -> String {
+    if !settings.username.is_empty() && !settings.ip.is_empty() {
+        return format!("{}@{}", settings.username, settings.ip);
+    }
+    path.parent()
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| settings.ip.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_merge_keeps_unknown_and_skips_invalid() {
+        let fields = serde_json::json!({
+            "username": "lord",
+            "ip": "shard.example",
+            "port": "not a port",
+            "fps": 144,
+            "plugins": ["Razor/Razor.dll"],
+            "some_future_option": { "enabled": true }
+        });
+        let serde_json::Value::Object(fields) = fields else { unreachable!() };
+        let (settings, skipped) = merge_tolerant(fields);
+        assert_eq!(settings.username, "lord");
+        assert_eq!(settings.fps, 144);
+        assert_eq!(settings.port, OuoSettings::default().port);
+        assert_eq!(skipped, ["port"]);
+        assert_eq!(settings.plugins, ["Razor/Razor.dll"]);
+
+        // 未知字段在保存时原样写回
+        let written = serde_json::to_value(&settings).unwrap();
+        assert_eq!(written["some_future_option"]["enabled"], true);
+    }
+
+    #[test]
+    fn test_import() {
+        let root = temp_dir("import");
+        let dir = root.join("OldInstall");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        fs::write(&path, r#"{"ip": "", "profilespath": "C:\\Old\\Profiles", "launcher_scale_factor": 2.0}"#).unwrap();
+
+        let summary = import(&path).unwrap();
+        assert_eq!(summary.profile.index.name, "OldInstall");
+        assert!(summary.profile.settings.profiles_path.is_empty());
+        assert_eq!(summary.profile.settings.launcher_scale_factor, None);
+        assert!(!summary.profile.settings.ultima_online_directory.is_empty());
+
+        fs::write(&path, "[1, 2]").unwrap();
+        assert!(import(&path).is_err());
+    }
+}
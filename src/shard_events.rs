@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// 默认在活动开始前几分钟提醒
+pub const DEFAULT_NOTIFY_MINUTES: u32 = 15;
+
+/// 更新清单中声明的服务器活动
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ShardEvent {
+    pub title: String,
+    /// 开始时间，RFC 3339，例如 "2024-06-01T18:00:00Z"
+    pub starts_at: String,
+    /// 结束时间（可选），未填写时活动开始后就不再显示
+    #[serde(default)]
+    pub ends_at: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 活动详情页（可选）
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl ShardEvent {
+    pub fn starts(&self) -> Option<i64> {
+        crate::clock::parse_rfc3339(&self.starts_at)
+    }
+
+    pub fn ends(&self) -> Option<i64> {
+        self.ends_at.as_deref().and_then(crate::clock::parse_rfc3339)
+    }
+
+    /// 用于记录已经提醒过的活动；活动改期后会重新提醒
+    pub fn key(&self) -> String {
+        format!("{}@{}", self.title, self.starts_at)
+    }
+
+    /// 活动已经开始且尚未结束
+    pub fn is_ongoing(&self, now: i64) -> bool {
+        self.starts().is_some_and(|start| start <= now) && self.ends().is_some_and(|end| now < end)
+    }
+}
+
+/// 尚未结束的活动（正在进行的在前），按开始时间排列，最多 `limit` 个；时间无效的活动忽略
+pub fn upcoming(events: &[ShardEvent], now: i64, limit: usize) -> Vec<&ShardEvent> {
+    let mut upcoming: Vec<(i64, &ShardEvent)> = events
+        .iter()
+        .filter_map(|event| Some((event.starts()?, event)))
+        .filter(|(start, event)| *start > now || event.is_ongoing(now))
+        .collect();
+    upcoming.sort_by_key(|(start, _)| *start);
+    upcoming.into_iter().take(limit).map(|(_, event)| event).collect()
+}
+
+/// 将在 `lead_secs` 内开始、还没有提醒过的活动
+pub fn due<'a>(events: &'a [ShardEvent], now: i64, lead_secs: i64, notified: &HashSet<String>) -> Vec<&'a ShardEvent> {
+    events
+        .iter()
+        .filter(|event| event.starts().is_some_and(|start| now < start && start - now <= lead_secs))
+        .filter(|event| !notified.contains(&event.key()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, starts_at: &str, ends_at: Option<&str>) -> ShardEvent {
+        ShardEvent {
+            title: title.to_string(),
+            starts_at: starts_at.to_string(),
+            ends_at: ends_at.map(str::to_string),
+            description: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_upcoming_and_due() {
+        // 2024-05-01T18:30:00Z
+        let now = 1_714_588_200;
+        let events = vec![
+            event("Treasure Hunt", "2024-05-02T18:00:00Z", None),
+            event("Past", "2024-05-01T12:00:00Z", None),
+            event("Siege", "2024-05-01T18:00:00Z", Some("2024-05-01T20:00:00Z")),
+            event("Invasion", "2024-05-01T20:40:00+02:00", None),
+            event("Broken", "tomorrow", None),
+        ];
+        let titles: Vec<&str> = upcoming(&events, now, 5).iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Siege", "Invasion", "Treasure Hunt"]);
+        assert_eq!(upcoming(&events, now, 1).len(), 1);
+
+        let mut notified = HashSet::new();
+        let due_titles = |notified: &HashSet<String>| -> Vec<String> {
+            due(&events, now, 15 * 60, notified).iter().map(|e| e.title.clone()).collect()
+        };
+        assert_eq!(due_titles(&notified), ["Invasion"]);
+        notified.insert(events[3].key());
+        assert!(due_titles(&notified).is_empty());
+    }
+}
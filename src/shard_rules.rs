@@ -0,0 +1,38 @@
+use serde::Deserialize;
+
+/// 更新清单中声明的服务器规则，玩家第一次用某个档案启动前需要阅读并确认
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ShardRules {
+    /// 规则的版本，修改规则后更换版本，玩家需要重新确认
+    pub version: String,
+    /// 对话框标题（可选）
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 规则内容（Markdown）
+    pub text: String,
+}
+
+impl ShardRules {
+    /// 档案确认过的版本与当前版本不同时需要确认；内容为空的规则不需要
+    pub fn needs_ack(&self, acknowledged: Option<&str>) -> bool {
+        !self.text.trim().is_empty() && acknowledged != Some(self.version.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_ack() {
+        let rules: ShardRules =
+            serde_json::from_str(r#"{ "version": "2024-05", "text": "- One client per account" }"#).unwrap();
+        assert_eq!(rules.title, None);
+        assert!(rules.needs_ack(None));
+        assert!(rules.needs_ack(Some("2024-01")));
+        assert!(!rules.needs_ack(Some("2024-05")));
+
+        let empty = ShardRules { text: " \n".to_string(), ..rules };
+        assert!(!empty.needs_ack(None));
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result, anyhow};
+use minisign_verify::{PublicKey, Signature};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// 编译时嵌入的更新签名公钥，例如 `OPENUO_UPDATE_PUBLIC_KEY=RWQ... cargo build --release`
+const EMBEDDED_PUBLIC_KEY: Option<&str> = option_env!("OPENUO_UPDATE_PUBLIC_KEY");
+
+/// 选择用于校验更新的公钥，没有配置时返回 None（不校验签名）
+///
+/// 嵌入的公钥优先，更新源配置中的公钥只在没有嵌入公钥时使用，
+/// 避免改写 update_source.json 就能换掉服主发布的公钥。
+pub fn trusted_key(configured: Option<&str>) -> Result<Option<PublicKey>> {
+    let key = EMBEDDED_PUBLIC_KEY
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .or_else(|| configured.map(str::trim).filter(|k| !k.is_empty()));
+    key.map(parse_public_key).transpose()
+}
+
+/// 接受单行的 base64 公钥，或 `minisign -G` 生成的 .pub 文件内容
+pub fn parse_public_key(value: &str) -> Result<PublicKey> {
+    let value = value.trim();
+    let key = if value.lines().count() > 1 {
+        PublicKey::decode(value)
+    } else {
+        PublicKey::from_base64(value)
+    };
+    key.map_err(|e| anyhow!("无效的签名公钥: {e}"))
+}
+
+/// 校验文件的 minisign 签名，`signature` 为 .minisig 文件的完整内容
+///
+/// 只接受默认的预哈希签名（minisign 0.10 起的 `-S` 默认格式），文件按块读取，不会整个载入内存。
+pub fn verify_file(path: &Path, key: &PublicKey, signature: &str) -> Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let signature = Signature::decode(signature.trim()).map_err(|e| anyhow!("{} 的签名格式无效: {e}", name))?;
+    let mut verifier = key
+        .verify_stream(&signature)
+        .map_err(|e| anyhow!("{} 的签名不是由受信任的公钥生成的: {e}", name))?;
+
+    let mut file = fs::File::open(path).with_context(|| format!("无法打开 {}", path.display()))?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        verifier.update(&buffer[..n]);
+    }
+    verifier.finalize().map_err(|_| anyhow!("{} 签名校验失败，文件可能被篡改", name))
+}
+
+/// 校验内存中数据（例如更新清单）的 minisign 签名，`name` 用于错误信息
+pub fn verify_bytes(data: &[u8], key: &PublicKey, signature: &str, name: &str) -> Result<()> {
+    let signature = Signature::decode(signature.trim()).map_err(|e| anyhow!("{} 的签名格式无效: {e}", name))?;
+    key.verify(data, &signature, false)
+        .map_err(|e| anyhow!("{} 签名校验失败: {e}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    const PUBLIC_KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+    /// 上面公钥对应的私钥对 "hello" 的签名
+    const HELLO_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCBFDKMQvERvH/kQaDIAof8BqBmmUX2C3HO2jB9zNjZ0iv02d5H6PoYLKAJy2tl9HlnKmqDFx8sa6Mf7JCgtdngk=
+trusted comment: timestamp:1714588200\tfile:linux-x64.zip
+GJRm6V0jCtkNNUAHmmpMemgRCsmDlu+V3uWLqr37RipDjQh0CitXNoAfZGJz3yAMSUpLaLpRPuUNPTKmUgHaCQ==
+";
+
+    #[test]
+    fn test_parse_public_key() {
+        assert!(parse_public_key(PUBLIC_KEY).is_ok());
+        let file = format!("untrusted comment: minisign public key 0807060504030201\n{PUBLIC_KEY}\n");
+        assert!(parse_public_key(&file).is_ok());
+        assert!(parse_public_key("not a key").is_err());
+    }
+
+    #[test]
+    fn test_verify_file() {
+        let key = parse_public_key(PUBLIC_KEY).unwrap();
+        let dir = temp_dir("signature");
+        let path = dir.join("update_source.json");
+        fs::write(&path, b"hello").unwrap();
+        assert!(verify_file(&path, &key, HELLO_SIGNATURE).is_ok());
+        assert!(verify_file(&path, &key, "garbage").is_err());
+
+        assert!(verify_bytes(b"hello", &key, HELLO_SIGNATURE, "hello").is_ok());
+        assert!(verify_bytes(b"hellp", &key, HELLO_SIGNATURE, "hello").is_err());
+
+        fs::write(&path, b"hellp").unwrap();
+        let err = verify_file(&path, &key, HELLO_SIGNATURE).unwrap_err().to_string();
+        assert!(err.contains("签名校验失败"), "{err}");
+    }
+}
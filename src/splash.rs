@@ -0,0 +1,51 @@
+use egui::{Color32, RichText};
+use std::sync::mpsc;
+use std::time::Instant;
+
+use crate::config::{load_config_from_disk, load_launcher_settings};
+use crate::i18n::t;
+use crate::ui::LauncherUi;
+
+/// 在后台读取配置、档案和启动历史，主线程同时初始化显卡
+pub fn spawn_loader() -> mpsc::Receiver<LauncherUi> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        // 先从同步目录取回其他电脑的修改，再读取档案
+        let sync = load_launcher_settings().sync_folder.map(|folder| crate::folder_sync::sync(&folder).map_err(|e| format!("{e:#}")));
+        let config = load_config_from_disk();
+        crate::session_password::scrub_all(&config.profiles);
+        let mut ui = LauncherUi::new(config);
+        if let Some(result) = sync {
+            ui.on_folder_sync(result, false);
+        }
+        tracing::info!("Profiles loaded in {:?}", started.elapsed());
+        let _ = tx.send(ui);
+    });
+    rx
+}
+
+/// 加载完成前显示的简单画面
+pub fn show(ctx: &egui::Context) {
+    egui::CentralPanel::default()
+        .frame(egui::Frame::none().fill(Color32::from_rgb(26, 26, 26)))
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space((ui.available_height() / 2.0 - 50.0).max(0.0));
+                ui.label(
+                    RichText::new("OpenUO Launcher")
+                        .size(28.0)
+                        .strong()
+                        .color(Color32::from_rgb(230, 230, 230)),
+                );
+                ui.add_space(16.0);
+                ui.spinner();
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new(t!("splash.loading"))
+                        .size(13.0)
+                        .color(Color32::from_rgb(160, 160, 160)),
+                );
+            });
+        });
+}
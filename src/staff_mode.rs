@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+/// 没有在清单中指定时，详细日志使用的参数
+const DEFAULT_VERBOSE_ARGS: &str = "-debug";
+
+/// 更新清单中的员工模式设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct StaffManifest {
+    /// 为 true 时所有档案都可以打开员工模式，否则需要在 Launcher 设置中手动解锁
+    #[serde(default)]
+    pub enabled: bool,
+    /// 测试服地址，"host" 或 "host:port"
+    #[serde(default)]
+    pub test_center: Option<String>,
+    /// 详细日志使用的参数（可选）
+    #[serde(default)]
+    pub verbose_args: Option<String>,
+    /// 服务器提供的其他参数预设
+    #[serde(default)]
+    pub presets: Vec<StaffPreset>,
+}
+
+/// 员工模式的参数预设，按名称在档案中启用
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StaffPreset {
+    pub name: String,
+    pub args: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// 档案的员工模式设置，只对 OpenUO 生效
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaffConfig {
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    /// 登录清单中的测试服
+    #[serde(rename = "TestCenter")]
+    pub test_center: bool,
+    /// 使用其他端口连接（登录测试服时忽略）
+    #[serde(rename = "AlternatePort", skip_serializing_if = "Option::is_none")]
+    pub alternate_port: Option<u16>,
+    #[serde(rename = "VerboseLogging")]
+    pub verbose_logging: bool,
+    /// 启用的清单预设的名称
+    #[serde(rename = "Presets", skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<String>,
+}
+
+impl StaffManifest {
+    /// 测试服的地址和端口
+    pub fn test_center(&self) -> Option<(&str, Option<u16>)> {
+        let address = self.test_center.as_deref()?.trim();
+        if address.is_empty() {
+            return None;
+        }
+        match address.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() => Some((host, Some(port.parse().ok()?))),
+            Some(_) => None,
+            None => Some((address, None)),
+        }
+    }
+}
+
+/// 员工模式追加的启动参数；清单中没有的测试服或预设忽略
+pub fn args(config: &StaffConfig, manifest: &StaffManifest) -> Vec<String> {
+    let mut args = Vec::new();
+    if !config.enabled {
+        return args;
+    }
+    let test_center = manifest.test_center().filter(|_| config.test_center);
+    if let Some((host, port)) = test_center {
+        args.extend(["-ip".to_string(), host.to_string()]);
+        if let Some(port) = port {
+            args.extend(["-port".to_string(), port.to_string()]);
+        }
+    } else if let Some(port) = config.alternate_port {
+        args.extend(["-port".to_string(), port.to_string()]);
+    }
+    if config.verbose_logging {
+        let verbose = manifest.verbose_args.as_deref().unwrap_or(DEFAULT_VERBOSE_ARGS);
+        args.extend(verbose.split_whitespace().map(str::to_string));
+    }
+    for preset in manifest.presets.iter().filter(|p| config.presets.contains(&p.name)) {
+        args.extend(preset.args.split_whitespace().map(str::to_string));
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let manifest: StaffManifest = serde_json::from_str(
+            r#"{
+                "enabled": true,
+                "test_center": "tc.shard.example:2594",
+                "presets": [
+                    { "name": "GM body", "args": "-gmbody -nofog" },
+                    { "name": "Unused", "args": "-x" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.test_center(), Some(("tc.shard.example", Some(2594))));
+
+        let mut config = StaffConfig {
+            enabled: false,
+            test_center: true,
+            alternate_port: Some(2600),
+            verbose_logging: true,
+            presets: vec!["GM body".to_string(), "Removed".to_string()],
+        };
+        assert!(args(&config, &manifest).is_empty());
+        config.enabled = true;
+        assert_eq!(
+            args(&config, &manifest),
+            ["-ip", "tc.shard.example", "-port", "2594", "-debug", "-gmbody", "-nofog"]
+        );
+        // 没有测试服时使用其他端口
+        config.verbose_logging = false;
+        assert_eq!(args(&config, &StaffManifest::default()), ["-port", "2600"]);
+
+        let host_only = StaffManifest { test_center: Some("tc.example".to_string()), ..Default::default() };
+        assert_eq!(host_only.test_center(), Some(("tc.example", None)));
+        let broken = StaffManifest { test_center: Some("tc.example:abc".to_string()), ..Default::default() };
+        assert_eq!(broken.test_center(), None);
+    }
+}
@@ -0,0 +1,175 @@
+use egui::RichText;
+use std::time::{Duration, Instant};
+
+use crate::i18n::t;
+
+/// 后台任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadTarget {
+    OpenUO,
+    Launcher,
+    /// 游戏数据包（UO 的数据文件）
+    GameData,
+}
+
+/// 当前后台活动，用于状态栏摘要
+#[derive(Debug, Clone, PartialEq)]
+pub enum Activity {
+    Idle,
+    Checking,
+    Downloading {
+        target: DownloadTarget,
+        received: u64,
+        total: u64,
+        bytes_per_sec: f64,
+    },
+    Installing(DownloadTarget),
+    Paused(DownloadTarget),
+    Restarting,
+}
+
+/// 下载速度估算（指数平滑，避免数字跳动）
+#[derive(Debug, Default)]
+pub struct TransferMeter {
+    last_sample: Option<(Instant, u64)>,
+    rate: f64,
+}
+
+impl TransferMeter {
+    /// 两次采样的最小间隔
+    const MIN_INTERVAL: Duration = Duration::from_millis(500);
+    /// 平滑系数，越大越跟随最新速度
+    const SMOOTHING: f64 = 0.3;
+
+    pub fn reset(&mut self) {
+        self.last_sample = None;
+        self.rate = 0.0;
+    }
+
+    pub fn record(&mut self, received: u64, now: Instant) {
+        let Some((last_time, last_bytes)) = self.last_sample else {
+            self.last_sample = Some((now, received));
+            return;
+        };
+
+        let elapsed = now.saturating_duration_since(last_time);
+        if elapsed < Self::MIN_INTERVAL {
+            return;
+        }
+
+        let instant_rate = received.saturating_sub(last_bytes) as f64 / elapsed.as_secs_f64();
+        self.rate = if self.rate == 0.0 {
+            instant_rate
+        } else {
+            self.rate + Self::SMOOTHING * (instant_rate - self.rate)
+        };
+        self.last_sample = Some((now, received));
+    }
+
+    pub fn bytes_per_sec(&self) -> f64 {
+        self.rate
+    }
+}
+
+/// 格式化传输速度，例如 "45.2 MB/s"
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    t!("humanize.rate", size = crate::humanize::bytes(bytes_per_sec.max(0.0) as u64)).to_string()
+}
+
+/// 生成状态栏的一行摘要
+pub fn summary(activity: &Activity) -> String {
+    match activity {
+        Activity::Idle => t!("status_bar.idle").to_string(),
+        Activity::Checking => t!("status_bar.checking").to_string(),
+        Activity::Downloading {
+            target,
+            received,
+            total,
+            bytes_per_sec,
+        } => {
+            let name = target_name(*target);
+            let speed = format_rate(*bytes_per_sec);
+            if *total > 0 {
+                let percent = (*received as f64 / *total as f64 * 100.0).min(100.0);
+                t!(
+                    "status_bar.downloading",
+                    target = name,
+                    speed = speed,
+                    percent = format!("{:.0}", percent)
+                )
+                .to_string()
+            } else {
+                t!(
+                    "status_bar.downloading_unknown",
+                    target = name,
+                    speed = speed,
+                    received = crate::humanize::bytes(*received)
+                )
+                .to_string()
+            }
+        }
+        Activity::Installing(target) => {
+            t!("status_bar.installing", target = target_name(*target)).to_string()
+        }
+        Activity::Paused(target) => t!("status_bar.paused", target = target_name(*target)).to_string(),
+        Activity::Restarting => t!("status_bar.restarting").to_string(),
+    }
+}
+
+fn target_name(target: DownloadTarget) -> String {
+    match target {
+        DownloadTarget::OpenUO => "OpenUO".to_string(),
+        DownloadTarget::Launcher => "Launcher".to_string(),
+        DownloadTarget::GameData => t!("status_bar.game_data").to_string(),
+    }
+}
+
+/// 绘制状态栏
+pub fn show(ui: &mut egui::Ui, activity: &Activity) {
+    let color = match activity {
+        Activity::Idle => egui::Color32::from_rgb(150, 150, 150),
+        _ => egui::Color32::from_rgb(200, 200, 140),
+    };
+
+    egui::Frame::none()
+        .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 80))
+        .inner_margin(egui::Margin::symmetric(12.0, 3.0))
+        .show(ui, |ui| {
+            ui.set_min_width(ui.available_width());
+            ui.horizontal(|ui| {
+                if !matches!(activity, Activity::Idle) {
+                    ui.spinner();
+                }
+                ui.label(RichText::new(summary(activity)).size(12.0).color(color));
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_meter() {
+        let start = Instant::now();
+        let mut meter = TransferMeter::default();
+        meter.record(0, start);
+        assert_eq!(meter.bytes_per_sec(), 0.0);
+
+        // 间隔太短的采样会被忽略
+        meter.record(1024, start + Duration::from_millis(100));
+        assert_eq!(meter.bytes_per_sec(), 0.0);
+
+        meter.record(1024 * 1024, start + Duration::from_secs(1));
+        assert!((meter.bytes_per_sec() - 1024.0 * 1024.0).abs() < 1.0);
+
+        meter.reset();
+        assert_eq!(meter.bytes_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(512.0 * 1024.0), "512 KB/s");
+        assert_eq!(format_rate(45.2 * 1024.0 * 1024.0), "45.2 MB/s");
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+
+use crate::config::{ProfileIndex, load_launcher_settings, profiles_dir};
+use crate::github::version_newer;
+use crate::history::{LaunchHistory, LaunchOutcome};
+
+/// `--status` 输出的 Launcher 状态，供外部监控工具使用
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LauncherStatus {
+    pub launcher_version: String,
+    pub remote_launcher_version: Option<String>,
+    pub open_uo_version: Option<String>,
+    pub remote_open_uo_version: Option<String>,
+    /// 更新清单中声明的最低版本
+    pub required_open_uo_version: Option<String>,
+    pub open_uo_update_available: bool,
+    pub launcher_update_available: bool,
+    /// 上次检查更新的时间（Unix 时间戳，秒）
+    pub last_update_check: Option<i64>,
+    pub active_profile: Option<ProfileStatus>,
+    pub last_launch: Option<LaunchStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileStatus {
+    pub id: String,
+    pub name: String,
+}
+
+/// 最近一次启动的结果，不包含错误详情和笔记
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchStatus {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub started_at: i64,
+    pub outcome: LaunchOutcome,
+    pub exit_code: Option<i32>,
+    pub duration_secs: Option<u64>,
+}
+
+impl LauncherStatus {
+    /// 只读取本地文件和缓存，不访问网络
+    pub fn collect() -> Self {
+        let open_uo = crate::github::cached_latest_release(true);
+        let launcher = crate::github::cached_latest_release(false);
+        let last_update_check = [&open_uo, &launcher].iter().filter_map(|r| r.as_ref().map(|r| r.fetched_at)).max();
+
+        let active_profile = load_launcher_settings().last_profile.and_then(|id| {
+            let raw = fs::read_to_string(profiles_dir().join(format!("{id}.json"))).ok()?;
+            let index: ProfileIndex = serde_json::from_str(&raw).ok()?;
+            Some(ProfileStatus { id, name: index.name })
+        });
+        let last_launch = LaunchHistory::load().records.last().map(|r| LaunchStatus {
+            profile_id: r.profile_id.clone(),
+            profile_name: r.profile_name.clone(),
+            started_at: r.started_at,
+            outcome: r.outcome,
+            exit_code: r.exit_code,
+            duration_secs: r.duration_secs,
+        });
+
+        let mut status = Self {
+            launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+            remote_launcher_version: launcher.map(|r| r.version),
+            open_uo_version: crate::github::detect_open_uo_version(),
+            required_open_uo_version: open_uo.as_ref().and_then(|r| r.required_version.clone()),
+            remote_open_uo_version: open_uo.map(|r| r.version),
+            last_update_check,
+            active_profile,
+            last_launch,
+            ..Default::default()
+        };
+        status.open_uo_update_available = update_available(status.open_uo_version.as_deref(), status.remote_open_uo_version.as_deref());
+        status.launcher_update_available =
+            update_available(Some(&status.launcher_version), status.remote_launcher_version.as_deref());
+        status
+    }
+
+    /// 每行一个 `key: value`，方便 shell 脚本处理
+    pub fn to_text(&self) -> String {
+        let opt = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+        let mut lines = vec![
+            format!("launcher_version: {}", self.launcher_version),
+            format!("remote_launcher_version: {}", opt(&self.remote_launcher_version)),
+            format!("open_uo_version: {}", opt(&self.open_uo_version)),
+            format!("remote_open_uo_version: {}", opt(&self.remote_open_uo_version)),
+            format!("required_open_uo_version: {}", opt(&self.required_open_uo_version)),
+            format!("open_uo_update_available: {}", self.open_uo_update_available),
+            format!("launcher_update_available: {}", self.launcher_update_available),
+            format!("last_update_check: {}", opt(&self.last_update_check.map(crate::clock::format_local))),
+            format!("active_profile: {}", opt(&self.active_profile.as_ref().map(|p| p.name.clone()))),
+        ];
+        if let Some(launch) = &self.last_launch {
+            lines.push(format!(
+                "last_launch: {} {} {:?}",
+                launch.profile_name,
+                crate::clock::format_local(launch.started_at),
+                launch.outcome
+            ));
+        } else {
+            lines.push("last_launch: -".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// 本地未安装时，只要知道远程版本就视为需要更新
+fn update_available(local: Option<&str>, remote: Option<&str>) -> bool {
+    match (local, remote) {
+        (Some(local), Some(remote)) => version_newer(remote, local),
+        (None, Some(_)) => true,
+        _ => false,
+    }
+}
+
+/// `--status [--json]`：输出状态后退出
+pub fn run_cli(json: bool) -> Result<()> {
+    let status = LauncherStatus::collect();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+    } else {
+        println!("{}", status.to_text());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_available() {
+        assert!(update_available(Some("1.0.0"), Some("1.1.0")));
+        assert!(!update_available(Some("1.1.0"), Some("1.1.0")));
+        assert!(update_available(None, Some("1.1.0")));
+        assert!(!update_available(Some("1.0.0"), None));
+    }
+
+    #[test]
+    fn test_json_fields() {
+        let status = LauncherStatus {
+            launcher_version: "0.1.5".to_string(),
+            active_profile: Some(ProfileStatus { id: "p1".to_string(), name: "Main".to_string() }),
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(value["launcher_version"], "0.1.5");
+        assert_eq!(value["active_profile"]["name"], "Main");
+        assert!(value["remote_open_uo_version"].is_null());
+        assert!(status.to_text().contains("active_profile: Main"));
+    }
+}
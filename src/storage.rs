@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use crate::config::{backups_dir, open_uo_dir, profiles_dir};
+use crate::disk_space::dir_size;
+
+/// Launcher 在临时目录中使用的名称前缀：游戏数据包、补丁和单文件更新的暂存目录
+const TEMP_PREFIXES: &[&str] = &["openuo-data-", "openuo-patch-", "openuo-files-"];
+/// OpenUO 安装包以平台命名，例如 win-x64.zip、linux-x64.tar.gz
+const ASSET_PLATFORMS: &[&str] = &["osx-arm64.", "osx-x64.", "linux-x64.", "win-x64."];
+/// 超过这个时间没有修改的临时文件视为中断的下载留下的残留
+const STALE_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// 临时目录中 Launcher 留下的文件或目录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TempEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    /// 可以安全删除（不是正在进行的下载）
+    pub stale: bool,
+}
+
+/// 各部分占用的磁盘空间
+#[derive(Debug, Clone, Default)]
+pub struct Usage {
+    /// OpenUO 安装目录，包含并存安装的版本
+    pub install: u64,
+    /// 档案和角色设置
+    pub profiles: u64,
+    /// 角色设置的备份
+    pub backups: u64,
+    pub temp: Vec<TempEntry>,
+}
+
+impl Usage {
+    pub fn temp_size(&self) -> u64 {
+        self.temp.iter().map(|e| e.size).sum()
+    }
+
+    pub fn stale_size(&self) -> u64 {
+        self.temp.iter().filter(|e| e.stale).map(|e| e.size).sum()
+    }
+}
+
+fn is_launcher_temp(name: &str) -> bool {
+    TEMP_PREFIXES.iter().chain(ASSET_PLATFORMS).any(|prefix| name.starts_with(prefix))
+}
+
+/// `dir`（临时目录）中 Launcher 留下的文件
+pub fn temp_entries(dir: &Path, now: SystemTime) -> Vec<TempEntry> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<TempEntry> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_str().is_some_and(is_launcher_temp))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let size = if metadata.is_dir() { dir_size(&entry.path()) } else { metadata.len() };
+            // 无法读取修改时间时不删除
+            let stale = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age >= STALE_AFTER);
+            Some(TempEntry { path: entry.path(), size, stale })
+        })
+        .collect();
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    found
+}
+
+/// 删除残留的临时文件，返回释放的字节数；删除失败的跳过
+pub fn clean_temp(entries: &[TempEntry]) -> u64 {
+    entries
+        .iter()
+        .filter(|entry| entry.stale)
+        .filter(|entry| {
+            let result = if entry.path.is_dir() { fs::remove_dir_all(&entry.path) } else { fs::remove_file(&entry.path) };
+            if let Err(e) = &result {
+                tracing::warn!("Failed to remove {}: {}", entry.path.display(), e);
+            }
+            result.is_ok()
+        })
+        .map(|entry| entry.size)
+        .sum()
+}
+
+pub fn scan() -> Usage {
+    Usage {
+        install: dir_size(&open_uo_dir()),
+        profiles: dir_size(&profiles_dir()),
+        backups: dir_size(&backups_dir()),
+        temp: temp_entries(&std::env::temp_dir(), SystemTime::now()),
+    }
+}
+
+/// 在后台线程统计占用；`clean` 为 true 时先删除残留的临时文件，同时返回释放的字节数
+pub fn spawn_scan(clean: bool) -> mpsc::Receiver<(Usage, u64)> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        // 正在安装时临时文件可能还在使用
+        let freed = if clean && crate::operation_lock::current().is_none() {
+            clean_temp(&temp_entries(&std::env::temp_dir(), SystemTime::now()))
+        } else {
+            0
+        };
+        let _ = tx.send((scan(), freed));
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_temp_entries() {
+        let dir = temp_dir("storage");
+        fs::create_dir_all(dir.join("openuo-files-1234")).unwrap();
+        fs::write(dir.join("openuo-files-1234").join("OpenUO.dll"), "12345").unwrap();
+        fs::write(dir.join("openuo-patch-5678.zip"), "123").unwrap();
+        fs::write(dir.join("win-x64.zip"), "1").unwrap();
+        fs::write(dir.join("other-app.zip"), "ignored").unwrap();
+        fs::write(dir.join("openuo-settings.json"), "ignored").unwrap();
+
+        // 刚写入的文件可能是正在进行的下载
+        let entries = temp_entries(&dir, SystemTime::now());
+        let sizes: Vec<u64> = entries.iter().map(|e| e.size).collect();
+        assert_eq!(sizes, [5, 3, 1]);
+        assert!(entries.iter().all(|e| !e.stale));
+        assert_eq!(clean_temp(&entries), 0);
+
+        let later = SystemTime::now() + STALE_AFTER;
+        let entries = temp_entries(&dir, later);
+        assert!(entries.iter().all(|e| e.stale));
+        assert_eq!(clean_temp(&entries), 9);
+        assert!(temp_entries(&dir, later).is_empty());
+        assert!(dir.join("other-app.zip").exists());
+    }
+}
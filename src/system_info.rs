@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::OnceLock;
 
 /// System info helpers.
@@ -58,6 +59,126 @@ pub fn system_info_string() -> String {
         .clone()
 }
 
+/// 当前可用的物理内存（MB）
+pub fn available_memory_mb() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let content = std::fs::read_to_string("/proc/meminfo").ok()?;
+        parse_meminfo_available_mb(&content)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        get_windows_available_memory_mb()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        get_macos_available_memory_mb()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// 路径所在磁盘的剩余空间（字节）
+///
+/// 路径尚不存在时（例如还未安装），使用最近的已存在的上级目录。
+pub fn free_disk_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+
+    #[cfg(unix)]
+    {
+        get_unix_free_space(existing)
+    }
+
+    #[cfg(windows)]
+    {
+        get_windows_free_space(existing)
+    }
+}
+
+/// 解析 /proc/meminfo 中的 MemAvailable（单位 kB）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo_available_mb(content: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn get_unix_free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // 字段类型随平台不同（macOS 上为 u32）
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn get_windows_free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut free = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut free), None, None) }.ok()?;
+    Some(free)
+}
+
+#[cfg(target_os = "windows")]
+fn get_windows_available_memory_mb() -> Option<u64> {
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status = MEMORYSTATUSEX {
+        dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+        ..Default::default()
+    };
+    unsafe { GlobalMemoryStatusEx(&mut status) }.ok()?;
+    Some(status.ullAvailPhys / (1024 * 1024))
+}
+
+#[cfg(target_os = "macos")]
+fn get_macos_available_memory_mb() -> Option<u64> {
+    use std::process::Command;
+
+    // vm_stat 输出以页为单位，空闲 + 可回收（inactive）近似为可用内存
+    let output = Command::new("vm_stat").output().ok()?;
+    let text = String::from_utf8(output.stdout).ok()?;
+    let page_size = text
+        .lines()
+        .next()
+        .and_then(|line| line.split("page size of ").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|n| n.parse::<u64>().ok())
+        .unwrap_or(4096);
+    let pages = |key: &str| -> u64 {
+        text.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .map(|rest| rest.trim().trim_end_matches('.'))
+            .and_then(|n| n.parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    let available = (pages("Pages free:") + pages("Pages inactive:")) * page_size;
+    Some(available / (1024 * 1024))
+}
+
 #[cfg(target_os = "windows")]
 fn get_windows_version() -> String {
     use std::os::windows::process::CommandExt;
@@ -114,4 +235,18 @@ mod tests {
         println!("Arch: {}", arch());
         println!("Full: {}", system_info_string());
     }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let content = "MemTotal:       16303428 kB\nMemFree:         1234567 kB\nMemAvailable:    8388608 kB\n";
+        assert_eq!(parse_meminfo_available_mb(content), Some(8192));
+        assert_eq!(parse_meminfo_available_mb("MemTotal: 1 kB"), None);
+    }
+
+    #[test]
+    fn test_free_disk_space() {
+        // 不存在的子路径会回退到已存在的上级目录
+        let path = std::env::temp_dir().join("openuo-launcher-missing").join("dir");
+        assert!(free_disk_space(&path).is_some());
+    }
 }
@@ -0,0 +1,34 @@
+// 测试用的临时目录：测试结束时删除，断言失败时也不会留下垃圾。
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// 离开作用域时删除的临时目录
+pub struct TempDir(PathBuf);
+
+/// 在系统临时目录中创建 openuo-<name>-<uuid>
+pub fn temp_dir(name: &str) -> TempDir {
+    let dir = std::env::temp_dir().join(format!("openuo-{}-{}", name, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    TempDir(dir)
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
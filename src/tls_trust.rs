@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// 更新源的证书信任设置，用于使用内部 CA 或自签名证书的服务器
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TlsTrust {
+    /// 额外信任的 CA 证书（PEM，可以包含多个证书），相对路径基于 Launcher 目录
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    /// 固定的服务器证书 SHA-256 指纹，设置后该主机不再做常规的证书链校验
+    #[serde(default)]
+    pub pin_sha256: Option<String>,
+    /// 更新源所在的主机，指纹只对这个主机生效
+    #[serde(skip)]
+    pub host: Option<String>,
+}
+
+impl TlsTrust {
+    pub fn for_url(mut self, url: &str) -> Self {
+        self.host = reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_string));
+        self
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.ca_cert.is_some() || self.pin_sha256.is_some()
+    }
+
+    pub fn ca_path(&self) -> Option<PathBuf> {
+        let path = PathBuf::from(self.ca_cert.as_deref()?.trim());
+        Some(if path.is_absolute() { path } else { crate::config::base_dir().join(path) })
+    }
+
+    /// 请求 `url` 时要求的证书指纹；其他主机（例如存放资产的 CDN）仍按常规校验
+    pub fn pin_for(&self, url: &str) -> Result<Option<String>> {
+        let Some(pin) = &self.pin_sha256 else {
+            return Ok(None);
+        };
+        let host = reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_string));
+        if host.is_none() || host != self.host {
+            return Ok(None);
+        }
+        normalize_fingerprint(pin).map(Some).with_context(|| format!("无效的证书指纹: {}", pin))
+    }
+
+    fn root_certificates(&self) -> Result<Vec<reqwest::Certificate>> {
+        let Some(path) = self.ca_path() else {
+            return Ok(Vec::new());
+        };
+        let pem = std::fs::read(&path).with_context(|| format!("无法读取 CA 证书 {}", path.display()))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("无效的 CA 证书 {}", path.display()))?;
+        if certs.is_empty() {
+            anyhow::bail!("{} 中没有证书", path.display());
+        }
+        Ok(certs)
+    }
+
+    /// 按设置配置请求 `url` 用的客户端，返回需要在每个响应上检查的指纹
+    pub fn apply_blocking(
+        &self,
+        mut builder: reqwest::blocking::ClientBuilder,
+        url: &str,
+    ) -> Result<(reqwest::blocking::ClientBuilder, Option<String>)> {
+        for cert in self.root_certificates()? {
+            builder = builder.add_root_certificate(cert);
+        }
+        let pin = self.pin_for(url)?;
+        if pin.is_some() {
+            builder = builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .tls_info(true);
+        }
+        Ok((builder, pin))
+    }
+
+    /// 与 `apply_blocking` 相同，用于异步下载
+    pub fn apply(&self, mut builder: reqwest::ClientBuilder, url: &str) -> Result<(reqwest::ClientBuilder, Option<String>)> {
+        for cert in self.root_certificates()? {
+            builder = builder.add_root_certificate(cert);
+        }
+        let pin = self.pin_for(url)?;
+        if pin.is_some() {
+            builder = builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .tls_info(true);
+        }
+        Ok((builder, pin))
+    }
+}
+
+/// 接受 `AA:BB:...`、带空格或 `sha256:` 前缀的指纹，返回小写十六进制
+pub fn normalize_fingerprint(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value = value.strip_prefix("sha256:").unwrap_or(value);
+    let hex: String = value.chars().filter(|c| *c != ':' && !c.is_whitespace()).collect();
+    crate::checksum::normalize_hash(&hex)
+}
+
+/// 检查本次连接的服务器证书与固定的指纹一致，使用了固定指纹的客户端必须检查每个响应
+///
+/// 客户端跳过了证书链校验，所以跳转到其他主机时指纹不一致也会报错。
+pub fn verify_pin(pin: Option<&str>, tls_info: Option<&reqwest::tls::TlsInfo>) -> Result<()> {
+    let Some(pin) = pin else {
+        return Ok(());
+    };
+    let cert = tls_info
+        .and_then(|info| info.peer_certificate())
+        .context("无法获取服务器证书，不能校验证书指纹（更新源必须使用 HTTPS）")?;
+    let actual = crate::checksum::sha256_bytes(cert);
+    if actual != pin {
+        anyhow::bail!("服务器证书指纹不匹配（期望 {}，实际 {}），连接可能被劫持", pin, actual);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FINGERPRINT: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_normalize_fingerprint() {
+        let colons = FINGERPRINT
+            .to_uppercase()
+            .as_bytes()
+            .chunks(2)
+            .map(|c| std::str::from_utf8(c).unwrap())
+            .collect::<Vec<_>>()
+            .join(":");
+        assert_eq!(normalize_fingerprint(&colons).as_deref(), Some(FINGERPRINT));
+        assert_eq!(normalize_fingerprint(&format!("sha256:{FINGERPRINT}")).as_deref(), Some(FINGERPRINT));
+        assert_eq!(normalize_fingerprint("AA:BB"), None);
+    }
+
+    #[test]
+    fn test_pin_only_for_source_host() {
+        let trust = TlsTrust {
+            pin_sha256: Some(FINGERPRINT.to_string()),
+            ..Default::default()
+        }
+        .for_url("https://updates.shard.example:8443/openuo/latest.json");
+        assert_eq!(
+            trust.pin_for("https://updates.shard.example/openuo/linux-x64.zip").unwrap().as_deref(),
+            Some(FINGERPRINT)
+        );
+        assert_eq!(trust.pin_for("https://github.com/a/b/releases/download/v1/a.zip").unwrap(), None);
+
+        let invalid = TlsTrust {
+            pin_sha256: Some("nope".to_string()),
+            ..trust
+        };
+        assert!(invalid.pin_for("https://updates.shard.example/latest.json").is_err());
+    }
+
+    #[test]
+    fn test_verify_pin_requires_certificate() {
+        assert!(verify_pin(None, None).is_ok());
+        assert!(verify_pin(Some(FINGERPRINT), None).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use wgpu::CompositeAlphaMode;
+
+/// 半透明模式下背景图的不透明度
+pub const BACKGROUND_ALPHA: f32 = 0.75;
+
+/// 选择支持透明的合成模式，不支持时返回 None（退回不透明窗口）
+pub fn pick_alpha_mode(modes: &[CompositeAlphaMode]) -> Option<CompositeAlphaMode> {
+    [CompositeAlphaMode::PreMultiplied, CompositeAlphaMode::PostMultiplied]
+        .into_iter()
+        .find(|mode| modes.contains(mode))
+}
+
+/// 清屏颜色；半透明时按预乘 alpha 计算
+pub fn clear_color(translucent: bool) -> wgpu::Color {
+    let (base, alpha) = if translucent { (0.1, 0.35) } else { (0.1, 1.0) };
+    wgpu::Color {
+        r: base * alpha,
+        g: base * alpha,
+        b: base * alpha,
+        a: alpha,
+    }
+}
+
+/// 请求系统合成器在窗口后方做模糊
+///
+/// macOS 和 KDE Wayland 由 winit 处理；Windows 11 使用 DWM 的亚克力背景，
+/// 较旧的 Windows 不支持，保持普通的透明效果。
+pub fn enable_blur(window: &winit::window::Window) {
+    window.set_blur(true);
+    #[cfg(target_os = "windows")]
+    enable_dwm_backdrop(window);
+}
+
+#[cfg(target_os = "windows")]
+fn enable_dwm_backdrop(window: &winit::window::Window) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE, DwmSetWindowAttribute};
+    use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    let Ok(handle) = window.window_handle() else {
+        return;
+    };
+    let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+        return;
+    };
+    let hwnd = HWND(handle.hwnd.get() as _);
+    let backdrop = DWMSBT_TRANSIENTWINDOW;
+    let result = unsafe {
+        DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_SYSTEMBACKDROP_TYPE,
+            &backdrop as *const _ as *const std::ffi::c_void,
+            std::mem::size_of_val(&backdrop) as u32,
+        )
+    };
+    if let Err(e) = result {
+        tracing::info!("DWM backdrop not available: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_alpha_mode() {
+        assert_eq!(
+            pick_alpha_mode(&[CompositeAlphaMode::Opaque, CompositeAlphaMode::PostMultiplied]),
+            Some(CompositeAlphaMode::PostMultiplied)
+        );
+        assert_eq!(
+            pick_alpha_mode(&[CompositeAlphaMode::PostMultiplied, CompositeAlphaMode::PreMultiplied]),
+            Some(CompositeAlphaMode::PreMultiplied)
+        );
+        assert_eq!(pick_alpha_mode(&[CompositeAlphaMode::Opaque, CompositeAlphaMode::Inherit]), None);
+    }
+
+    #[test]
+    fn test_clear_color_is_premultiplied() {
+        let c = clear_color(true);
+        assert!(c.a < 1.0 && c.r <= c.a);
+        assert_eq!(clear_color(false).a, 1.0);
+    }
+}
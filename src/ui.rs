@@ -1,23 +1,66 @@
 use anyhow::{Context, Result};
 use egui::{Color32, ColorImage, RichText};
-use std::process::Command;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
+use crate::ab_compare::{self, AbComparison, AbOutcome, AbSide};
+use crate::activity_log::{self, LogEntryType, LogPalette, LogVerbosity, LogWriter};
+use crate::bootstrap::{self, Bootstrap};
+use crate::child_group;
+use crate::client_versions::{self, InstalledVersion};
+use crate::cloud_sync::{self, CloudProvider, RelocateEvent};
 use crate::config::*;
+use crate::data_dedup::{self, DedupEvent, DuplicateGroup};
+use crate::disk_space::{self, InsufficientSpace};
+use crate::download::{self, DownloadContext, DownloadEvent, DownloadOutcome};
+use crate::error_help::{self, ErrorReport, FailureKind, Remedy};
+use crate::folder_sync::{self, Keep, SyncReport};
+use crate::event_pump::{EventPump, PumpEvent};
+use crate::game_data::{self, Addon, DataPackage};
 use crate::github::*;
+use crate::global_hotkey::{self, Hotkey, HotkeyAction};
+use crate::gpu_preference;
+use crate::history::{self, GameEvent, LaunchHistory, LaunchOutcome, LaunchRecord, LaunchedGame};
+use crate::humanize;
 use crate::i18n::t;
+use crate::install_dir::{self, Plan};
+use crate::kiosk;
+use crate::launch_target::{self, LaunchConfirmation, LaunchTarget};
+use crate::legacy_migration::{self, LegacyInstall};
+use crate::mod_packs::{self, AppliedPack, ModEvent, ModTask, Pack, SharedDir};
+use crate::network_check;
+use crate::notification;
+use crate::offline_install::{self, METADATA_FILE};
+use crate::operation_lock::{self, Operation};
+use crate::password_rekey::{self, RekeyResult};
+use crate::pending_download::{self, PendingDownload};
+use crate::playtime;
+use crate::power::{self, PowerState};
+use crate::process;
+use crate::process_tuning;
+use crate::profile_backup::{self, Snapshot};
+use crate::profile_bundle::{self, BUNDLE_EXTENSION, Bundle};
 use crate::profile_editor::ProfileEditor;
-
-/// 日志条目类型
-#[derive(Debug, Clone)]
-pub enum LogEntryType {
-    Info,
-    Success,
-    Warning,
-    Error,
-    Checking,
-}
+use crate::profile_lint::{self, LintContext};
+use crate::release_chain::ReleaseChain;
+use crate::release_notes;
+use crate::requirements::{self, GpuInfo, HardwareSnapshot, RequirementIssue};
+use crate::scheduler::{self, ScheduleForm, ScheduleMode, ScheduledLaunch};
+use crate::server_list::{self, ServerListReceiver, ShardServer};
+use crate::session_password;
+use crate::settings_export::{self, ExportOptions};
+use crate::shard_events::{self, ShardEvent};
+use crate::shard_rules::ShardRules;
+use crate::staff_mode::{self, StaffManifest};
+use crate::status_bar::{self, Activity, DownloadTarget, TransferMeter};
+use crate::storage::{self, Usage};
+use crate::uo_data;
+use crate::update_policy::{self, MAX_CHECK_MINUTES, PolicyDecision, UpdateCheckInterval, UpdatePolicy};
+use crate::vote::{self, VoteSite};
+use crate::watchdog;
 
 /// 日志条目
 #[derive(Debug, Clone)]
@@ -34,81 +77,707 @@ pub enum LogAction {
     UpdateLauncher,
     UpdateOpenUO,
     RetryDownload,
+    /// 打开当前 profile 的编辑器（例如调整渲染驱动）
+    OpenProfileEditor,
+    /// 从这个目录中的旧 Launcher 迁移档案
+    MigrateLegacy(PathBuf),
+    /// 下载更新清单中的游戏数据包到当前 profile 的 UO 目录
+    DownloadGameData,
+    /// 下载这个附加包（id）到当前 profile 的 UO 目录
+    DownloadAddon(String),
+    /// 并存安装这个版本的 OpenUO
+    InstallVersion(String),
+    /// 继续上次关闭 Launcher 时没有完成的下载
+    ResumeDownload(PendingDownload),
+    /// 网络检查失败后重新尝试启动
+    RetryLaunch,
+}
+
+/// 主界面标签页
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainTab {
+    Play,
+    History,
 }
 
 pub struct LauncherUi {
     pub config: LauncherConfig,
+    pub current_tab: MainTab,
     pub profile_editor: ProfileEditor,
     pub open_uo_version: Option<String>,
+    /// 已安装的 OpenUO 的安装时间（Unix 时间戳）
+    pub open_uo_installed_at: Option<i64>,
     pub launcher_version: String,
-    pub download_rx: Option<mpsc::Receiver<DownloadEvent>>,
+    /// 下载任务和更新检查的事件
+    pub events: EventPump,
     pub download_progress: Option<(u64, u64)>,
+    pub transfer_meter: TransferMeter,
     pub downloading_launcher: bool,
+    /// 正在下载游戏数据包，失败时重试数据包而不是 OpenUO
+    pub downloading_game_data: bool,
+    /// 正在下载的附加包（id），失败时重试这个附加包
+    pub downloading_addon: Option<String>,
+    /// 正在并存安装的 OpenUO 版本，失败时重试这个版本
+    pub downloading_side_by_side: Option<String>,
+    /// 正在进行的下载，关闭窗口时记下来，下次启动时提示继续
+    pub current_download: Option<PendingDownload>,
     pub launcher_restarting: bool,
-    pub update_rx: Option<mpsc::Receiver<UpdateEvent>>,
     pub remote_open_uo: Option<String>,
+    /// 最新 OpenUO / Launcher 的发布时间（Unix 时间戳）
+    pub open_uo_published: Option<i64>,
+    pub launcher_published: Option<i64>,
+    /// 更新清单中声明的最低 OpenUO 版本
+    pub required_open_uo: Option<String>,
+    /// 最新 OpenUO / Launcher 的发布说明
+    pub open_uo_notes: Option<String>,
+    /// 已安装的 OpenUO 到最新版本之间的 release
+    pub open_uo_chain: Option<ReleaseChain>,
+    /// 更新清单中声明的游戏数据包
+    pub data_package: Option<DataPackage>,
+    /// 更新清单中声明的附加包
+    pub addons: Vec<Addon>,
+    /// 正在显示附加包窗口
+    pub show_addons: bool,
+    /// 更新清单中声明的服务器活动
+    pub shard_events: Vec<ShardEvent>,
+    /// 本次运行中已经通知过的活动（`ShardEvent::key`）
+    pub notified_events: HashSet<String>,
+    /// 更新清单中声明的投票网站
+    pub vote_sites: Vec<VoteSite>,
+    /// 更新清单中声明的服务器规则
+    pub shard_rules: Option<ShardRules>,
+    /// 服务器规则窗口
+    pub rules_view: Option<RulesView>,
+    /// 更新清单中的员工模式设置
+    pub staff_manifest: StaffManifest,
+    /// 本次运行中已经提醒过的投票（`vote::due_reminders` 返回的标识）
+    pub reminded_votes: HashSet<String>,
+    pub launcher_notes: Option<String>,
+    /// 发布说明窗口
+    pub release_notes: Option<ReleaseNotesView>,
+    /// “要求最新版本”的 profile 正在等待版本检查结果
+    pub pending_policy_launch: bool,
+    /// “保持 OpenUO 最新”：正在等待版本检查结果
+    pub pending_auto_update: bool,
+    /// “保持 OpenUO 最新”：更新完成后继续启动
+    pub launch_after_update: bool,
+    /// 电源和网络状态
+    pub power: PowerState,
+    pub power_rx: mpsc::Receiver<PowerState>,
+    /// 使用电池或计费网络时推迟了自动更新，条件解除后再下载
+    pub deferred_update: bool,
     pub remote_launcher: Option<String>,
-    pub last_update_poll: Instant,
+    pub last_update_poll: Option<Instant>,
+    /// 更新源被限流时，限流解除前不再自动检查更新
+    pub rate_limited_until: Option<Instant>,
+    /// 上次完成更新检查的时间（Unix 时间戳，已修正时钟偏差）
+    pub last_update_check: Option<i64>,
+    /// 已提醒过本机时钟偏差
+    pub clock_skew_warned: bool,
+    /// 已提醒过使用自定义证书信任设置的更新源
+    pub trust_warned: HashSet<String>,
     pub checking_open_uo: bool,
     pub checking_launcher: bool,
     pub background_texture: Option<egui::TextureHandle>,
     pub logo_texture: Option<egui::TextureHandle>,
     pub screen_info: Option<ScreenInfo>,
+    pub gpu_info: Option<GpuInfo>,
+    /// 窗口当前是否为半透明（合成器不支持时即使开启了设置也为 false）
+    pub translucent: bool,
     pub current_locale: String,
+    /// 上一帧的语言版本，变化时刷新缓存的文本
+    pub locale_generation: u64,
     pub logs: Vec<LogEntry>,
+    /// 在后台把日志写入 launcher.log
+    pub log_writer: LogWriter,
     pub download_failed: bool,
+    pub history: LaunchHistory,
+    pub history_filter: Option<String>,
+    pub game_tx: mpsc::Sender<GameEvent>,
+    pub game_rx: mpsc::Receiver<GameEvent>,
+    /// 启动前的服务器列表查询（profile 下标，查询结果）
+    pub server_query_rx: Option<(usize, ServerListReceiver)>,
+    /// 启动前的网络检查（profile 下标，检查结果）
+    pub network_check_rx: Option<(usize, mpsc::Receiver<Result<(), String>>)>,
+    /// 网络检查已通过，下一次启动跳过检查
+    pub network_checked: bool,
+    /// 正在迁移 UO 目录（profile 下标，进度通道）
+    pub relocate_rx: Option<(usize, mpsc::Receiver<RelocateEvent>)>,
+    pub relocate_progress: Option<(u64, u64)>,
+    /// 正在把 OpenUO 安装移动到新的安装目录
+    pub install_move_rx: Option<mpsc::Receiver<RelocateEvent>>,
+    pub install_move_progress: Option<(u64, u64)>,
+    /// 正在与同步目录同步
+    pub folder_sync_rx: Option<mpsc::Receiver<Result<SyncReport, String>>>,
+    /// 两边都修改过、等待选择保留哪一边的文件
+    pub sync_conflicts: Vec<String>,
+    pub schedule_form: ScheduleForm,
+    pub show_schedule_form: bool,
+    pub scheduled_launch: Option<ScheduledLaunch>,
+    /// 正在编辑的游戏笔记（记录 ID，内容）
+    pub session_note: Option<(String, String)>,
+    /// 需要弹窗说明的错误
+    pub error_report: Option<ErrorReport>,
+    /// 正在显示启动确认对话框
+    pub confirming_launch: bool,
+    /// 正在显示重新安装 OpenUO 的确认对话框
+    pub confirming_reinstall: bool,
+    /// 有下载或安装在进行时关闭窗口，等待确认
+    pub confirming_close: bool,
+    /// 已确认关闭，主循环保存状态后退出
+    pub close_confirmed: bool,
+    /// 关闭时正在执行不可中断的步骤，完成后自动退出
+    quit_when_finished: bool,
+    /// 启动前发现磁盘空间不足，等待用户确认
+    pub low_disk: Option<InsufficientSpace>,
+    /// 用户选择了“仍然启动”，下一次启动跳过磁盘空间检查
+    pub low_disk_acknowledged: bool,
+    /// 启动前询问本次运行使用的账号密码
+    pub credential_prompt: Option<CredentialPrompt>,
+    /// 安装前发现 OpenUO 正在运行，等待用户关闭
+    pub game_running_prompt: Option<GameRunningPrompt>,
+    /// 本次启动输入的密码（profile 索引，加密后的密码），启动时写入设置文件后丢弃
+    pub session_password: Option<(usize, String)>,
+    /// 等待游戏退出后清除密码的设置文件（启动记录 ID，设置文件路径）
+    pub password_scrubs: Vec<(String, PathBuf)>,
+    /// 机器名变化后无法解密、需要在下次启动时重新输入密码的档案（file_name）
+    pub password_reentry: HashSet<String>,
+    /// 安装更新期间推迟的 profile 保存
+    pub save_pending: bool,
+    /// OpenUO 版本选择窗口
+    pub release_picker: Option<ReleasePicker>,
+    /// A/B 对比窗口
+    pub ab_compare: Option<AbCompare>,
+    /// 档案检查报告
+    pub lint_report: Option<String>,
+    /// 导出游戏设置窗口
+    pub settings_export: Option<ExportOptions>,
+    /// 分享档案包时用口令加密密码
+    pub bundle_encrypt_password: bool,
+    pub bundle_passphrase: String,
+    /// 导入带密码的档案包，等待输入口令（档案包，口令，错误信息）
+    pub bundle_import: Option<(Bundle, String, Option<String>)>,
+    /// 磁盘占用窗口
+    pub storage: Option<StorageView>,
+    pub mods: Option<ModsView>,
+    /// 启动时扫描在 Launcher 之外运行的 OpenUO
+    pub external_scan_rx: Option<mpsc::Receiver<Vec<process::RunningProcess>>>,
+    /// 在 Launcher 之外启动、仍在运行的 OpenUO
+    pub external_game: Option<ExternalGame>,
+    /// 第一次启动时查找可以迁移的旧 Launcher
+    pub legacy_scan_rx: Option<mpsc::Receiver<Vec<LegacyInstall>>>,
+    /// 已注册的全局快捷键
+    pub global_hotkey: Option<global_hotkey::Registration>,
+    /// 设置中正在编辑的快捷键
+    pub hotkey_input: String,
+    /// 设置中输入的引导地址
+    pub bootstrap_input: String,
+    /// 等待确认的引导地址（来自 openuo:// 链接或设置）
+    pub bootstrap_prompt: Option<Bootstrap>,
+    /// openuo:// 链接是否已交给这个 Launcher 打开
+    pub url_scheme_registered: bool,
+}
+
+/// 在 Launcher 之外启动的 OpenUO 进程
+pub struct ExternalGame {
+    pid: u32,
+    /// 附加监视后写入启动历史的记录和附加的时间
+    attached: Option<(String, Instant)>,
+}
+
+/// 启动前输入的账号密码
+pub struct CredentialPrompt {
+    profile_idx: usize,
+    username: String,
+    password: String,
+    /// 密码只用于这一次启动，不保留在内存中
+    session_only: bool,
+}
+
+/// 因为 OpenUO 正在运行而推迟的安装操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingInstall {
+    /// 下载指定版本，为空时下载最新版本
+    Download(Option<String>),
+    Verify,
+    Reinstall,
+    File(PathBuf),
+}
+
+/// 安装前发现 OpenUO 正在运行时的对话框
+pub struct GameRunningPrompt {
+    pids: Vec<u32>,
+    pending: PendingInstall,
+    /// 已请求结束进程，等待它们退出
+    terminating: bool,
+    last_check: Instant,
+}
+
+/// A/B 对比窗口的状态
+pub struct AbCompare {
+    a: usize,
+    b: usize,
+    timeout_secs: u64,
+    run: Option<AbComparison>,
+    /// 结果已写入启动历史
+    recorded: bool,
+}
+
+/// 发布说明窗口的状态
+pub struct ReleaseNotesView {
+    /// Launcher 的说明，否则为 OpenUO 的
+    launcher: bool,
+    /// Launcher 更新后第一次启动时显示的“新功能”，不显示更新按钮
+    whats_new: bool,
+    version: String,
+    blocks: Vec<release_notes::Block>,
+}
+
+/// 磁盘占用窗口的状态
+#[derive(Default)]
+pub struct StorageView {
+    /// 正在统计（和清理）
+    rx: Option<mpsc::Receiver<(Usage, u64)>>,
+    usage: Option<Usage>,
+    /// 正在查找或合并重复的 UO 数据
+    dedup_rx: Option<mpsc::Receiver<DedupEvent>>,
+    dedup_progress: Option<(u64, u64)>,
+    /// 正在合并，完成前不能关闭窗口
+    consolidating: bool,
+    /// 正在把链接换回独立的副本，完成前不能关闭窗口
+    copying_back: bool,
+    /// 内容相同的 UO 数据目录，None 表示还没有查找
+    duplicates: Option<Vec<DuplicateGroup>>,
+    /// 点击合并后等待确认（合并后通过任一路径的修改都会影响所有档案）
+    confirm_link: bool,
+    /// 已经是链接的档案 UO 目录，可以换回独立的副本
+    linked: Vec<PathBuf>,
+    /// Data/Profiles 的备份，最新的在前
+    game_backups: Vec<Snapshot>,
+}
+
+/// 补丁包窗口的状态
+pub struct ModsView {
+    /// 当前档案的 UO 目录
+    dir: PathBuf,
+    packs: Vec<Pack>,
+    applied: Vec<AppliedPack>,
+    /// 正在导入、启用或停用
+    rx: Option<mpsc::Receiver<ModEvent>>,
+    /// UO 目录不只属于当前档案，这时不能启用补丁包，只能停用已启用的
+    shared: Option<SharedDir>,
+}
+
+impl ModsView {
+    /// `others` 为其他档案的名称和 UO 目录
+    fn new(dir: PathBuf, others: &[(String, PathBuf)]) -> ModsView {
+        let shared = mod_packs::shared_dir(&dir, others);
+        let mut view = ModsView { dir, packs: Vec::new(), applied: Vec::new(), rx: None, shared };
+        view.reload();
+        view
+    }
+
+    fn reload(&mut self) {
+        self.packs = mod_packs::library();
+        self.applied = mod_packs::applied(&self.dir);
+    }
+}
+
+/// 服务器规则窗口的状态
+pub struct RulesView {
+    profile_idx: usize,
+    blocks: Vec<release_notes::Block>,
+    /// 已勾选“已阅读并同意”
+    agreed: bool,
+    /// 确认后继续启动（启动前弹出时），否则只是查看
+    launch: bool,
 }
 
-fn version_newer(remote: &str, local: &str) -> bool {
-    let parse = |s: &str| -> Vec<u32> {
-        s.split(|c| c == '.' || c == '-')
-            .filter_map(|p| p.parse::<u32>().ok())
-            .collect()
-    };
-    let r = parse(remote);
-    let l = parse(local);
-    let len = r.len().max(l.len());
-    for i in 0..len {
-        let rv = *r.get(i).unwrap_or(&0);
-        let lv = *l.get(i).unwrap_or(&0);
-        if rv != lv {
-            return rv > lv;
-        }
-    }
-    false
+/// OpenUO 版本选择窗口的状态
+pub struct ReleasePicker {
+    rx: Option<mpsc::Receiver<Result<Vec<GithubRelease>, String>>>,
+    releases: Vec<GithubRelease>,
+    error: Option<String>,
+    /// 已并存安装的版本
+    side_by_side: Vec<InstalledVersion>,
 }
 
 impl LauncherUi {
-    pub fn new(config: LauncherConfig) -> Self {
-        Self {
+    pub fn new(mut config: LauncherConfig) -> Self {
+        let (game_tx, game_rx) = mpsc::channel();
+        let rekey = password_rekey::check(&mut config.profiles, config.launcher_settings.machine_name.as_deref());
+        let legacy_scan_rx = (!config.launcher_settings.legacy_migration_offered).then(|| {
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(legacy_migration::detect());
+            });
+            rx
+        });
+        let hotkey_input = config.launcher_settings.global_hotkey.clone().unwrap_or_default();
+        let mut ui = Self {
             config,
+            current_tab: MainTab::Play,
             profile_editor: ProfileEditor::new(),
             open_uo_version: detect_open_uo_version(),
+            open_uo_installed_at: read_open_uo_installed_at_in(&open_uo_dir()),
             launcher_version: format!("v{}", env!("CARGO_PKG_VERSION")),
-            download_rx: None,
+            events: EventPump::default(),
             download_progress: None,
+            transfer_meter: TransferMeter::default(),
             downloading_launcher: false,
+            downloading_game_data: false,
+            downloading_addon: None,
+            downloading_side_by_side: None,
+            current_download: None,
             launcher_restarting: false,
-            update_rx: None,
             remote_open_uo: None,
+            open_uo_published: None,
+            launcher_published: None,
+            required_open_uo: None,
+            open_uo_notes: None,
+            open_uo_chain: None,
+            data_package: None,
+            addons: Vec::new(),
+            show_addons: false,
+            shard_events: Vec::new(),
+            notified_events: HashSet::new(),
+            vote_sites: Vec::new(),
+            shard_rules: None,
+            rules_view: None,
+            staff_manifest: StaffManifest::default(),
+            reminded_votes: HashSet::new(),
+            launcher_notes: None,
+            release_notes: crate::self_update::take_whats_new().map(|whats_new| ReleaseNotesView {
+                launcher: true,
+                whats_new: true,
+                version: whats_new.version,
+                blocks: release_notes::parse(&whats_new.notes),
+            }),
+            pending_policy_launch: false,
+            pending_auto_update: false,
+            launch_after_update: false,
+            power: PowerState::default(),
+            power_rx: power::watch(),
+            deferred_update: false,
             screen_info: None,
+            gpu_info: None,
+            translucent: false,
             remote_launcher: None,
-            last_update_poll: Instant::now() - Duration::from_secs(601),
+            last_update_poll: None,
+            rate_limited_until: None,
+            last_update_check: crate::github::cached_latest_release(true).map(|r| r.fetched_at),
+            clock_skew_warned: false,
+            trust_warned: HashSet::new(),
             checking_open_uo: false,
             checking_launcher: false,
             background_texture: None,
             logo_texture: None,
             current_locale: crate::i18n::current_locale().to_string(),
+            locale_generation: crate::i18n::locale_generation(),
             logs: Vec::new(),
+            log_writer: LogWriter::start(),
             download_failed: false,
+            history: LaunchHistory::load(),
+            history_filter: None,
+            game_tx,
+            game_rx,
+            server_query_rx: None,
+            network_check_rx: None,
+            network_checked: false,
+            relocate_rx: None,
+            relocate_progress: None,
+            install_move_rx: None,
+            install_move_progress: None,
+            folder_sync_rx: None,
+            sync_conflicts: Vec::new(),
+            schedule_form: ScheduleForm::default(),
+            show_schedule_form: false,
+            scheduled_launch: None,
+            session_note: None,
+            error_report: None,
+            confirming_launch: false,
+            confirming_reinstall: false,
+            confirming_close: false,
+            close_confirmed: false,
+            quit_when_finished: false,
+            low_disk: None,
+            low_disk_acknowledged: false,
+            credential_prompt: None,
+            game_running_prompt: None,
+            session_password: None,
+            password_scrubs: Vec::new(),
+            password_reentry: HashSet::new(),
+            save_pending: false,
+            external_scan_rx: Some(process::scan_in_background(open_uo_binary_path())),
+            external_game: None,
+            legacy_scan_rx,
+            release_picker: None,
+            ab_compare: None,
+            lint_report: None,
+            settings_export: None,
+            bundle_encrypt_password: false,
+            bundle_passphrase: String::new(),
+            bundle_import: None,
+            storage: None,
+            mods: None,
+            global_hotkey: None,
+            hotkey_input,
+            bootstrap_input: String::new(),
+            bootstrap_prompt: None,
+            url_scheme_registered: crate::url_scheme::SUPPORTED
+                && std::env::current_exe().is_ok_and(|exe| crate::url_scheme::is_registered(&exe)),
+        };
+        ui.apply_rekey(rekey);
+        if let Some(pending) = pending_download::take() {
+            ui.add_log(LogEntryType::Warning, &format!("⏸ {}", t!("shutdown.interrupted")), Some(LogAction::ResumeDownload(pending)));
+        }
+        // 其他平台同步过来的设置不提示
+        if global_hotkey::SUPPORTED
+            && let Err(e) = ui.register_global_hotkey()
+        {
+            ui.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("global_hotkey.failed", error = format!("{e:#}"))), None);
+        }
+        if let Some(link) = bootstrap::link_from_args(std::env::args()) {
+            if kiosk::active() {
+                ui.add_log(LogEntryType::Warning, &format!("🔒 {}", t!("kiosk.bootstrap_ignored")), None);
+            } else {
+                ui.open_bootstrap(&link);
+            }
+        }
+        ui
+    }
+
+    /// 解析引导链接或地址，确认后才使用
+    fn open_bootstrap(&mut self, input: &str) {
+        match Bootstrap::parse(input) {
+            Ok(bootstrap) => self.bootstrap_prompt = Some(bootstrap),
+            Err(e) => self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("bootstrap.invalid", error = format!("{e:#}"))), None),
+        }
+    }
+
+    fn set_bootstrap(&mut self, bootstrap: Option<Bootstrap>) {
+        self.config.launcher_settings.bootstrap = bootstrap.clone();
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+        bootstrap::set(bootstrap.clone());
+        match bootstrap {
+            Some(bootstrap) => {
+                self.add_log(LogEntryType::Info, &format!("🔗 {}", t!("bootstrap.enabled", url = bootstrap.url)), None);
+                self.trigger_update_checks(true, true);
+            }
+            None => self.add_log(LogEntryType::Info, &format!("🔗 {}", t!("bootstrap.disabled")), None),
+        }
+    }
+
+    /// 使用引导地址前的确认对话框：配置决定了从哪里下载更新
+    fn show_bootstrap_prompt(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &self.bootstrap_prompt else {
+            return;
+        };
+        let mut accept = false;
+        let mut cancel = false;
+        egui::Window::new(t!("bootstrap.title"))
+            .id(egui::Id::new("bootstrap_prompt_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                ui.label(RichText::new(t!("bootstrap.message")).size(13.0));
+                ui.add_space(4.0);
+                ui.label(RichText::new(&prompt.url).size(12.0).monospace());
+                let key = match &prompt.public_key {
+                    Some(key) => t!("bootstrap.key", key = key),
+                    None => t!("bootstrap.embedded_key"),
+                };
+                ui.add_space(4.0);
+                ui.label(RichText::new(key).size(11.0).color(Color32::from_rgb(150, 150, 150)));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new(t!("bootstrap.accept")).fill(Color32::from_rgb(80, 120, 200));
+                    accept = ui.add(btn).clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if !(accept || cancel) {
+            return;
+        }
+        let bootstrap = self.bootstrap_prompt.take();
+        if accept {
+            self.bootstrap_input.clear();
+            self.set_bootstrap(bootstrap);
+        }
+    }
+
+    fn start_folder_sync(&mut self) {
+        if let Some(folder) = self.config.launcher_settings.sync_folder.clone()
+            && self.folder_sync_rx.is_none()
+        {
+            self.folder_sync_rx = Some(folder_sync::spawn(folder));
+        }
+    }
+
+    /// 记录同步结果；`reload` 为 true 时重新读取取回的档案（启动时同步在读取档案之前完成）
+    pub fn on_folder_sync(&mut self, result: Result<SyncReport, String>, reload: bool) {
+        let report = match result {
+            Ok(report) => report,
+            Err(e) => {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("folder_sync.failed", error = e)), None);
+                return;
+            }
+        };
+        if !report.pulled.is_empty() || report.pushed > 0 {
+            let msg = t!("folder_sync.synced", pulled = report.pulled.len(), pushed = report.pushed);
+            self.add_log(LogEntryType::Info, &format!("🔄 {}", msg), None);
+        }
+        if !report.skipped.is_empty() {
+            let msg = t!("folder_sync.skipped", parts = report.skipped.join(", "));
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", msg), None);
+        }
+        if reload && report.pulled.iter().any(|path| path.starts_with("Profiles/")) {
+            self.reload_profiles();
+        }
+        for conflict in report.conflicts {
+            if !self.sync_conflicts.contains(&conflict) {
+                self.sync_conflicts.push(conflict);
+            }
+        }
+    }
+
+    /// 重新读取档案，保持当前选中的档案；其他电脑保存的密码无法解密时提示重新输入
+    fn reload_profiles(&mut self) {
+        let active = self.active_profile().map(|p| p.index.file_name.clone());
+        self.config.profiles = load_config_from_disk().profiles;
+        self.config.active_profile = active
+            .and_then(|id| self.config.profiles.iter().position(|p| p.index.file_name == id))
+            .unwrap_or(0);
+        sort_profiles(&mut self.config);
+        let rekey = password_rekey::check(&mut self.config.profiles, self.config.launcher_settings.machine_name.as_deref());
+        self.apply_rekey(rekey);
+    }
+
+    /// 两边都修改过的文件：逐个选择保留本机的还是同步目录中的
+    fn show_sync_conflicts(&mut self, ctx: &egui::Context) {
+        let Some(folder) = self.config.launcher_settings.sync_folder.clone() else {
+            self.sync_conflicts.clear();
+            return;
+        };
+        if self.sync_conflicts.is_empty() {
+            return;
+        }
+        let mut chosen = Vec::new();
+        let mut later = false;
+        egui::Window::new(t!("folder_sync.conflicts_title"))
+            .id(egui::Id::new("sync_conflicts_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(520.0);
+                ui.label(RichText::new(t!("folder_sync.conflicts_message")).size(13.0));
+                ui.add_space(6.0);
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    egui::Grid::new("sync_conflicts_grid").num_columns(3).spacing([8.0, 4.0]).show(ui, |ui| {
+                        for path in &self.sync_conflicts {
+                            ui.label(RichText::new(path).size(11.0).monospace());
+                            if ui.small_button(t!("folder_sync.keep_local")).clicked() {
+                                chosen.push((path.clone(), Keep::Local));
+                            }
+                            if ui.small_button(t!("folder_sync.keep_synced")).clicked() {
+                                chosen.push((path.clone(), Keep::Synced));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t!("folder_sync.keep_all_local")).clicked() {
+                        chosen.extend(self.sync_conflicts.iter().map(|p| (p.clone(), Keep::Local)));
+                    }
+                    if ui.button(t!("folder_sync.keep_all_synced")).clicked() {
+                        chosen.extend(self.sync_conflicts.iter().map(|p| (p.clone(), Keep::Synced)));
+                    }
+                    later = ui.button(t!("folder_sync.later")).on_hover_text(t!("folder_sync.later_hint")).clicked();
+                });
+            });
+        if later || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.sync_conflicts.clear();
+            return;
+        }
+        let mut reload = false;
+        for (path, keep) in chosen {
+            self.sync_conflicts.retain(|p| *p != path);
+            match folder_sync::resolve(&folder, &path, keep) {
+                Ok(()) => reload |= keep == Keep::Synced && path.starts_with("Profiles/"),
+                Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("folder_sync.resolve_failed", path = path), e), None),
+            }
+        }
+        if reload {
+            self.reload_profiles();
+        }
+    }
+
+    /// 机器名变化后的密码处理：记录当前机器名，保存重新加密的密码，提示重新输入无法恢复的密码
+    fn apply_rekey(&mut self, rekey: RekeyResult) {
+        let machine = crate::crypter::calculate_key();
+        if self.config.launcher_settings.machine_name.as_deref() != Some(machine.as_str()) {
+            self.config.launcher_settings.machine_name = Some(machine);
+            if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+                tracing::warn!("Failed to save launcher settings: {}", e);
+            }
+        }
+        if rekey.is_empty() {
+            return;
+        }
+        let changed = |p: &&ProfileConfig| rekey.migrated.contains(&p.index.file_name) || rekey.lost.contains(&p.index.file_name);
+        for profile in self.config.profiles.iter().filter(changed) {
+            if let Err(e) = save_profile(profile) {
+                tracing::warn!("Failed to save profile {}: {}", profile.index.name, e);
+            }
+        }
+        let names = |ids: &[String]| -> String {
+            self.config
+                .profiles
+                .iter()
+                .filter(|p| ids.contains(&p.index.file_name))
+                .map(|p| p.index.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let (migrated, lost) = (names(&rekey.migrated), names(&rekey.lost));
+        if !rekey.migrated.is_empty() {
+            let msg = t!("password_rekey.migrated", profiles = migrated);
+            self.add_log(LogEntryType::Info, &format!("🔑 {}", msg), None);
+        }
+        if !rekey.lost.is_empty() {
+            let msg = t!("password_rekey.lost", profiles = lost);
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", msg), None);
+            self.password_reentry.extend(rekey.lost);
         }
     }
 
     pub fn ui(&mut self, ctx: &egui::Context) {
+        if self.locale_generation != crate::i18n::locale_generation() {
+            self.on_locale_changed(ctx);
+        }
         self.poll_channels();
+        self.poll_global_hotkey(ctx);
         self.maybe_schedule_updates();
         self.ensure_textures(ctx);
 
+        // 把安装包拖放到窗口中安装
+        let dropped: Vec<PathBuf> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).collect());
+        if !kiosk::active()
+            && let Some(path) = dropped.into_iter().find(|p| offline_install::looks_like_package(p))
+        {
+            self.install_from_file(path);
+        }
+
         // Global visuals: keep panels transparent
         {
             let mut style = (*ctx.style()).clone();
@@ -118,18 +787,43 @@ impl LauncherUi {
         }
 
         self.show_profile_editor(ctx);
+        self.show_session_note_window(ctx);
+        self.show_error_dialog(ctx);
+        self.show_launch_confirmation(ctx);
+        self.show_reinstall_confirmation(ctx);
+        self.show_close_confirmation(ctx);
+        self.show_low_disk_warning(ctx);
+        self.show_rules_window(ctx);
+        self.show_credential_prompt(ctx);
+        self.show_bootstrap_prompt(ctx);
+        self.show_sync_conflicts(ctx);
+        self.show_game_running_prompt(ctx);
+        self.show_release_picker(ctx);
+        self.show_release_notes(ctx);
+        self.show_ab_compare(ctx);
+        self.show_lint_report(ctx);
+        self.show_settings_export(ctx);
+        self.show_bundle_import(ctx);
+        self.show_storage(ctx);
+        self.show_addons_window(ctx);
+        self.show_mods_window(ctx);
         self.show_main_panel(ctx);
     }
 
     fn show_profile_editor(&mut self, ctx: &egui::Context) {
+        self.profile_editor.game_running = self.external_game.is_some()
+            || self.history.records.iter().any(|r| r.outcome == LaunchOutcome::Running);
+        self.profile_editor.staff_unlocked = self.staff_unlocked();
         if let Some((idx, mut profile)) = self.profile_editor.show(ctx) {
             // 加密密码后再保存
             profile.settings.password = crate::crypter::encrypt(&profile.settings.password);
             self.config.profiles[idx] = profile;
             self.config.active_profile = idx;
+            sort_profiles(&mut self.config);
             // 保存配置到文件（带屏幕信息）
             match self.save_config_with_screen_info() {
-                Ok(_) => self.set_status(&t!("status.config_saved")),
+                Ok(true) => self.set_status(&t!("status.config_saved")),
+                Ok(false) => {}
                 Err(_err) => self.set_status(&t!("status.save_failed")),
             }
         }
@@ -145,16 +839,17 @@ impl LauncherUi {
                 ui.visuals_mut().widgets.hovered.bg_fill = Color32::TRANSPARENT;
                 ui.visuals_mut().widgets.open.bg_fill = Color32::TRANSPARENT;
 
-                paint_background(ui, &self.background_texture, &self.logo_texture);
+                paint_background(ui, &self.background_texture, &self.logo_texture, self.translucent);
                 
                 let margin = 12.0;
                 let available_rect = ui.available_rect_before_wrap();
                 let footer_height = 30.0;
+                let status_bar_height = 22.0;
                 
                 // 主内容区域
                 let content_rect = egui::Rect::from_min_size(
                     available_rect.min,
-                    egui::vec2(available_rect.width(), available_rect.height() - footer_height)
+                    egui::vec2(available_rect.width(), available_rect.height() - footer_height - status_bar_height)
                 );
                 
                 let mut content_ui = ui.child_ui(content_rect, egui::Layout::top_down(egui::Align::Min));
@@ -166,27 +861,59 @@ impl LauncherUi {
                     ui.vertical(|ui| {
                         // 标题
                         ui.heading(RichText::new(t!("window.title")).size(24.0).strong());
-                        ui.add_space(12.0);
-
-                        // 语言选择
-                        self.show_language_selector(ui);
                         ui.add_space(8.0);
-                        
-                        // 配置选择
-                        self.show_profile_selector(ui);
+
+                        // 标签页切换
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut self.current_tab, MainTab::Play, t!("tabs.play"));
+                            ui.selectable_value(&mut self.current_tab, MainTab::History, t!("tabs.history"));
+                        });
                         ui.add_space(8.0);
-                        
-                        // 启动按钮
-                        self.show_launch_button(ui);
-                        ui.add_space(12.0);
-                        
-                        // 日志区域
-                        self.show_log_area(ui);
+
+                        match self.current_tab {
+                            MainTab::Play => {
+                                // 语言选择
+                                self.show_language_selector(ui);
+                                ui.add_space(8.0);
+                                
+                                // 配置选择
+                                self.show_profile_selector(ui);
+                                ui.add_space(8.0);
+                                
+                                // 服务器活动
+                                self.show_shard_events(ui);
+
+                                // 投票网站
+                                self.show_vote_buttons(ui);
+
+                                // 服务器规则
+                                self.show_rules_link(ui);
+
+                                // 云同步目录警告
+                                self.show_cloud_sync_warning(ui);
+
+                                // 启动按钮
+                                self.show_launch_button(ui);
+                                ui.add_space(12.0);
+                                
+                                // 日志区域
+                                self.show_log_area(ui);
+                            }
+                            MainTab::History => self.show_history_tab(ui),
+                        }
                     });
                     
                     ui.add_space(margin);
                 });
                 
+                // 状态栏（位于底部信息栏上方）
+                let status_bar_rect = egui::Rect::from_min_size(
+                    egui::pos2(available_rect.min.x, available_rect.max.y - footer_height - status_bar_height),
+                    egui::vec2(available_rect.width(), status_bar_height)
+                );
+                let mut status_bar_ui = ui.child_ui(status_bar_rect, egui::Layout::top_down(egui::Align::Min));
+                status_bar::show(&mut status_bar_ui, &self.current_activity());
+                
                 // 底部信息栏（固定在底部）
                 let footer_rect = egui::Rect::from_min_size(
                     egui::pos2(available_rect.min.x, available_rect.max.y - footer_height),
@@ -204,8 +931,9 @@ impl LauncherUi {
             ui.horizontal(|ui| {
                 ui.label(t!("main.language"));
                 
-                // 获取可用语言列表
-                let languages = crate::i18n::available_languages();
+                // 获取可用语言列表（按当前语言的排序规则）
+                let mut languages = crate::i18n::available_languages();
+                crate::collation::sort_by_key(&mut languages, &self.current_locale, |lang| lang.native_name.as_str());
                 
                 // 查找当前语言的显示名称
                 let current_name = languages
@@ -221,9 +949,9 @@ impl LauncherUi {
                         for lang in languages {
                             let is_selected = self.current_locale == lang.code;
                             if ui.selectable_label(is_selected, &lang.native_name).clicked() {
-                                self.current_locale = lang.code.clone();
+                                // 其余状态在下一帧开始时由 on_locale_changed 统一刷新
                                 crate::i18n::set_locale(&lang.code);
-                                
+
                                 // 保存用户选择的语言
                                 self.config.launcher_settings.language = Some(lang.code.clone());
                                 if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
@@ -232,6 +960,17 @@ impl LauncherUi {
                             }
                         }
                     });
+
+                ui.add_space(16.0);
+                let translucent = &mut self.config.launcher_settings.translucent_window;
+                if ui
+                    .checkbox(translucent, t!("main.translucent_window"))
+                    .on_hover_text(t!("main.translucent_window_hint"))
+                    .changed()
+                    && let Err(e) = save_launcher_settings(&self.config.launcher_settings)
+                {
+                    tracing::warn!("Failed to save launcher settings: {}", e);
+                }
             });
         });
     }
@@ -239,19 +978,39 @@ impl LauncherUi {
     fn show_profile_selector(&mut self, ui: &mut egui::Ui) {
         egui::Frame::none().show(ui, |ui| {
             ui.set_min_width(ui.available_width());
+            let bounds = ui.max_rect();
             ui.horizontal(|ui| {
-                ui.label(t!("main.profile"));
+                let label = ui.label(t!("main.profile"));
                 let profile_name = self
                     .active_profile()
                     .map(|p| p.index.name.as_str())
                     .unwrap_or("");
 
-                egui::ComboBox::from_id_source("profile_combo")
+                let combo = egui::ComboBox::from_id_source("profile_combo")
                     .selected_text(profile_name)
                     .show_ui(ui, |ui| {
+                        let mut moved = None;
                         for (idx, profile) in self.config.profiles.iter().enumerate() {
                             let selected = idx == self.config.active_profile;
-                            if ui.selectable_label(selected, &profile.index.name).clicked() {
+                            // 拖到其他档案上调整顺序；锁定模式不能调整
+                            let row = if kiosk::active() {
+                                ui.selectable_label(selected, &profile.index.name)
+                            } else {
+                                ui.dnd_drag_source(egui::Id::new(("profile_drag", idx)), idx, |ui| {
+                                    ui.selectable_label(selected, &profile.index.name)
+                                })
+                                .response
+                            };
+                            if let Some(from) = row.dnd_hover_payload::<usize>()
+                                && *from != idx
+                            {
+                                let y = if *from > idx { row.rect.top() } else { row.rect.bottom() };
+                                ui.painter().hline(row.rect.x_range(), y, ui.visuals().selection.stroke);
+                            }
+                            if let Some(from) = row.dnd_release_payload::<usize>() {
+                                moved = Some((*from, idx));
+                            }
+                            if row.clicked() {
                                 self.config.active_profile = idx;
                                 // 保存上次选择的 profile
                                 self.config.launcher_settings.last_profile = Some(profile.index.file_name.clone());
@@ -260,35 +1019,111 @@ impl LauncherUi {
                                 }
                             }
                         }
+                        if !kiosk::active() && !self.config.launcher_settings.profile_order.is_empty() {
+                            ui.separator();
+                            if ui.small_button(t!("profile_order.sort_by_name")).clicked() {
+                                moved = None;
+                                self.config.launcher_settings.profile_order.clear();
+                                sort_profiles(&mut self.config);
+                                if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+                                    tracing::warn!("Failed to save launcher settings: {}", e);
+                                }
+                            }
+                        }
+                        if let Some((from, to)) = moved {
+                            self.move_profile(from, to);
+                        }
                     });
 
+                // 锁定模式只能选择和启动档案
+                if kiosk::active() {
+                    ui.label("🔒").on_hover_text(t!("kiosk.locked_hint"));
+                    crate::layout_check::check(ui, bounds, &[("main.profile", &label), ("profile_combo", &combo.response)]);
+                    return;
+                }
+
                 let edit_btn = egui::Button::new(t!("main.edit"))
                     .fill(egui::Color32::from_rgba_unmultiplied(50, 120, 200, 200))
                     .min_size(egui::vec2(60.0, 24.0));
-                if ui.add(edit_btn).clicked() {
+                let edit = ui.add(edit_btn);
+                if edit.clicked() {
                     self.open_profile_editor();
                 }
                 
                 let new_btn = egui::Button::new(t!("main.new"))
                     .fill(egui::Color32::from_rgba_unmultiplied(50, 180, 100, 200))
                     .min_size(egui::vec2(60.0, 24.0));
-                if ui.add(new_btn).clicked() {
+                let new = ui.add(new_btn);
+                if new.clicked() {
                     self.add_profile();
                 }
                 
                 let copy_btn = egui::Button::new(t!("main.copy"))
                     .fill(egui::Color32::from_rgba_unmultiplied(100, 150, 200, 200))
                     .min_size(egui::vec2(60.0, 24.0));
-                if ui.add(copy_btn).clicked() {
+                let copy = ui.add(copy_btn);
+                if copy.clicked() {
                     self.duplicate_profile();
                 }
                 
                 let delete_btn = egui::Button::new(t!("main.delete"))
                     .fill(egui::Color32::from_rgba_unmultiplied(200, 80, 80, 200))
                     .min_size(egui::vec2(60.0, 24.0));
-                if ui.add(delete_btn).clicked() {
+                let delete = ui.add(delete_btn);
+                if delete.clicked() {
                     self.delete_profile();
                 }
+
+                let lint_btn = egui::Button::new("🔍")
+                    .fill(egui::Color32::from_rgba_unmultiplied(100, 100, 120, 200))
+                    .min_size(egui::vec2(24.0, 24.0));
+                let lint = ui.add(lint_btn).on_hover_text(t!("lint.button"));
+                if lint.clicked() {
+                    self.lint_all_profiles();
+                }
+
+                let export_btn = egui::Button::new("📤")
+                    .fill(egui::Color32::from_rgba_unmultiplied(100, 100, 120, 200))
+                    .min_size(egui::vec2(24.0, 24.0));
+                let export = ui.add(export_btn).on_hover_text(t!("settings_export.button"));
+                if export.clicked() && self.active_profile().is_some() {
+                    self.settings_export = Some(ExportOptions::default());
+                }
+
+                let import_btn = egui::Button::new("📥")
+                    .fill(egui::Color32::from_rgba_unmultiplied(100, 100, 120, 200))
+                    .min_size(egui::vec2(24.0, 24.0));
+                let import = ui.add(import_btn).on_hover_text(t!("settings_import.button"));
+                if import.clicked() {
+                    self.import_settings_file();
+                }
+                let mut pick_legacy = false;
+                import.context_menu(|ui| {
+                    if ui.button(t!("legacy_migration.pick")).clicked() {
+                        pick_legacy = true;
+                        ui.close_menu();
+                    }
+                });
+                if pick_legacy && let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                    self.migrate_legacy(dir);
+                }
+
+                // 新语言的按钮文字过长时，按钮会被挤出窗口
+                crate::layout_check::check(
+                    ui,
+                    bounds,
+                    &[
+                        ("main.profile", &label),
+                        ("profile_combo", &combo.response),
+                        ("main.edit", &edit),
+                        ("main.new", &new),
+                        ("main.copy", &copy),
+                        ("main.delete", &delete),
+                        ("lint.button", &lint),
+                        ("settings_export.button", &export),
+                        ("settings_import.button", &import),
+                    ],
+                );
             });
         });
     }
@@ -312,6 +1147,9 @@ impl LauncherUi {
                     t!("version.launcher_local"), launcher_version,
                     t!("version.launcher_remote"), launcher_remote
                 ));
+                if let Some(at) = self.launcher_published.filter(|_| !self.checking_launcher) {
+                    show_age(ui, "version.published_ago", at);
+                }
                 
                 let is_busy = self.downloading_launcher || self.launcher_restarting;
 
@@ -324,6 +1162,9 @@ impl LauncherUi {
                     if ui.add(update_btn).clicked() {
                         self.start_launcher_update();
                     }
+                    if self.launcher_notes.is_some() && ui.button("📝").on_hover_text(t!("release_notes.button")).clicked() {
+                        self.open_release_notes(true);
+                    }
                 } else if self.launcher_restarting {
                     ui.label(RichText::new(t!("version.restarting")).color(egui::Color32::from_rgb(180, 180, 180)));
                 } else if self.downloading_launcher {
@@ -331,20 +1172,17 @@ impl LauncherUi {
                 }
 
                 // 显示下载进度，只在下载 Launcher 时显示
-                if self.downloading_launcher {
-                    if let Some((cur, total)) = self.download_progress {
-                        if total > 0 {
-                            let progress = (cur as f32) / (total as f32);
-                            let total_mb = (total as f32) / (1024.0 * 1024.0);
-                            let cur_mb = (cur as f32) / (1024.0 * 1024.0);
-
-                            ui.add(
-                                egui::ProgressBar::new(progress)
-                                    .text(format!("{:.1}/{:.1} MB", cur_mb, total_mb))
-                                    .desired_width(150.0)
-                            );
-                        }
-                    }
+                if self.downloading_launcher
+                    && let Some((cur, total)) = self.download_progress
+                    && total > 0
+                {
+                    let progress = (cur as f32) / (total as f32);
+
+                    ui.add(
+                        egui::ProgressBar::new(progress)
+                            .text(t!("download.progress", current = humanize::bytes(cur), total = humanize::bytes(total)))
+                            .desired_width(150.0)
+                    );
                 }
             });
             
@@ -358,17 +1196,34 @@ impl LauncherUi {
                 } else {
                     self.remote_open_uo.as_deref().map(|s| s.to_string()).unwrap_or_else(|| t!("version.check_failed").to_string())
                 };
-                ui.label(format!("{} {}  {}: {}", 
+                let version_label = ui.label(format!("{} {}  {}: {}", 
                     t!("version.openuo_local"), open_uo_text,
                     t!("version.openuo_remote"), remote
                 ));
+                if let Some(checked) = self.last_update_check {
+                    let ago = humanize::ago(crate::clock::now_unix().saturating_sub(checked).max(0) as u64);
+                    let time = format!("{} ({})", ago, crate::clock::format_local(checked));
+                    version_label.on_hover_text(t!("version.last_checked", time = time));
+                }
+                if let Some(at) = self.open_uo_installed_at.filter(|_| self.open_uo_version.is_some()) {
+                    show_age(ui, "version.installed_ago", at);
+                }
+                if let Some(at) = self.open_uo_published.filter(|_| !self.checking_open_uo) {
+                    show_age(ui, "version.published_ago", at);
+                }
+                if let Some(profile) = self.active_profile().filter(|p| p.index.launch_target == LaunchTarget::OpenUO)
+                    && let Some(tag) = &profile.index.pinned_open_uo
+                {
+                    let hint = t!("client_versions.pinned_hint", profile = &profile.index.name, version = tag);
+                    ui.label(RichText::new(format!("📌 {}", tag)).color(Color32::from_rgb(255, 210, 120))).on_hover_text(hint);
+                }
                 
                 // 判断是否需要显示下载/更新按钮
                 let has_openuo_update = self.remote_open_uo.as_ref()
                     .and_then(|remote| self.open_uo_version.as_ref().map(|local| version_newer(remote, local)))
                     .unwrap_or(false);
                 
-                let is_downloading_openuo = !self.downloading_launcher && self.download_rx.is_some();
+                let is_downloading_openuo = !self.downloading_launcher && self.events.is_downloading();
                 
                 if self.open_uo_version.is_none() || has_openuo_update || is_downloading_openuo {
                     let (btn_text, btn_color) = if is_downloading_openuo {
@@ -391,304 +1246,4162 @@ impl LauncherUi {
                     if ui.add(btn).clicked() && !is_downloading_openuo {
                         self.start_download();
                     }
+                    if !is_downloading_openuo
+                        && self.open_uo_notes.is_some()
+                        && ui.button("📝").on_hover_text(t!("release_notes.button")).clicked()
+                    {
+                        self.open_release_notes(false);
+                    }
+                }
+
+                if let Some((copied, total)) = self.install_move_progress {
+                    let progress = if total > 0 { copied as f32 / total as f32 } else { 0.0 };
+                    ui.label(t!("install_dir.moving_short"));
+                    ui.add(egui::ProgressBar::new(progress).desired_width(160.0).show_percentage());
+                }
+                // 锁定模式不能安装其他版本、导入文件或修改设置，只保留检查和修复
+                let kiosk = kiosk::active();
+                if !is_downloading_openuo
+                    && !kiosk
+                    && ui.button(t!("version.pick")).on_hover_text(t!("version.pick_hint")).clicked()
+                {
+                    self.open_release_picker();
+                }
+                if !is_downloading_openuo
+                    && self.open_uo_version.is_some()
+                    && ui.button("🩺").on_hover_text(t!("verify.button_hint")).clicked()
+                {
+                    self.start_verify();
+                }
+                if !is_downloading_openuo
+                    && self.open_uo_version.is_some()
+                    && ui.button("♻").on_hover_text(t!("reinstall.button_hint")).clicked()
+                {
+                    self.confirming_reinstall = true;
+                }
+                if !is_downloading_openuo
+                    && !kiosk
+                    && ui.button("📂").on_hover_text(t!("version.install_file_hint")).clicked()
+                    && let Some(path) = rfd::FileDialog::new().add_filter("zip", &["zip"]).pick_file()
+                {
+                    self.install_from_file(path);
+                }
+                if !kiosk && ui.button("💾").on_hover_text(t!("storage.button_hint")).clicked() {
+                    self.open_storage();
+                }
+                if !kiosk && !self.addons.is_empty() && ui.button("🎵").on_hover_text(t!("addons.button_hint")).clicked() {
+                    self.show_addons = true;
+                }
+                if !kiosk && ui.button("🧩").on_hover_text(t!("mod_packs.button_hint")).clicked() {
+                    let others: Vec<(String, PathBuf)> = self
+                        .config
+                        .profiles
+                        .iter()
+                        .enumerate()
+                        .filter(|(idx, p)| *idx != self.config.active_profile && !p.settings.ultima_online_directory.trim().is_empty())
+                        .map(|(_, p)| (p.index.name.clone(), PathBuf::from(p.settings.ultima_online_directory.trim())))
+                        .collect();
+                    self.mods = self.game_data_dir().map(|dir| ModsView::new(dir, &others));
+                    if self.mods.is_none() {
+                        self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("mod_packs.no_uo_dir")), None);
+                    }
+                }
+                if !is_downloading_openuo && !kiosk {
+                    self.show_download_settings(ui);
                 }
                 
                 // 显示下载进度（仅当正在下载 OpenUO 时）
-                if !self.downloading_launcher && self.download_rx.is_some() {
-                    if let Some((cur, total)) = self.download_progress {
-                        if total > 0 {
-                            let progress = (cur as f32) / (total as f32);
-                            let total_mb = (total as f32) / (1024.0 * 1024.0);
-                            let cur_mb = (cur as f32) / (1024.0 * 1024.0);
-                            
-                            ui.add(
-                                egui::ProgressBar::new(progress)
-                                    .text(format!("{:.1}/{:.1} MB", cur_mb, total_mb))
-                                    .desired_width(150.0)
-                            );
-                        }
-                    }
+                if !self.downloading_launcher
+                    && self.events.is_downloading()
+                    && let Some((cur, total)) = self.download_progress
+                    && total > 0
+                {
+                    let progress = (cur as f32) / (total as f32);
+                    
+                    ui.add(
+                        egui::ProgressBar::new(progress)
+                            .text(t!("download.progress", current = humanize::bytes(cur), total = humanize::bytes(total)))
+                            .desired_width(150.0)
+                    );
                 }
                 // 版本一致时不显示任何按钮
             });
         });
     }
 
-    fn show_launch_button(&mut self, ui: &mut egui::Ui) {
-        egui::Frame::none().show(ui, |ui| {
-            ui.set_min_width(ui.available_width());
+    /// 版本栏的 ⚙ 菜单：下载连接数
+    fn show_download_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.config.launcher_settings;
+        let mut changed = false;
+        let mut change_dir = None;
+        let mut apply_hotkey = false;
+        let mut apply_bootstrap = false;
+        let mut remove_bootstrap = false;
+        let mut url_scheme = self.url_scheme_registered;
+        let mut sync_now = false;
+        ui.menu_button("⚙", |ui| {
             ui.horizontal(|ui| {
-                let launch_btn = egui::Button::new(
-                    RichText::new(t!("main.launch")).size(18.0).strong()
-                )
-                .fill(egui::Color32::from_rgba_unmultiplied(80, 180, 80, 220))
-                .min_size(egui::vec2(150.0, 40.0));
-                
-                if ui.add(launch_btn).clicked() {
-                    match self.launch_open_uo() {
-                        Ok(msg) => self.add_log(LogEntryType::Success, &msg, None),
-                        Err(err) => self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("status.launch_failed"), err), None),
+                ui.label(t!("download_settings.connections"));
+                let mut connections = settings.download_connections.max(1);
+                changed = ui
+                    .add(egui::DragValue::new(&mut connections).clamp_range(1..=MAX_DOWNLOAD_CONNECTIONS))
+                    .on_hover_text(t!("download_settings.connections_hint"))
+                    .changed();
+                settings.download_connections = connections;
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("download_settings.retry_attempts"));
+                let mut attempts = if settings.retry_attempts == 0 { crate::retry::DEFAULT_ATTEMPTS } else { settings.retry_attempts };
+                changed |= ui
+                    .add(egui::DragValue::new(&mut attempts).clamp_range(1..=crate::retry::MAX_ATTEMPTS))
+                    .on_hover_text(t!("download_settings.retry_attempts_hint"))
+                    .changed();
+                settings.retry_attempts = attempts;
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("download_settings.connect_timeout"));
+                let mut secs = if settings.connect_timeout_secs == 0 { DEFAULT_CONNECT_TIMEOUT_SECS } else { settings.connect_timeout_secs };
+                changed |= ui
+                    .add(egui::DragValue::new(&mut secs).clamp_range(1..=MAX_TIMEOUT_SECS).suffix(" s"))
+                    .on_hover_text(t!("download_settings.connect_timeout_hint"))
+                    .changed();
+                settings.connect_timeout_secs = secs;
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("download_settings.read_timeout"));
+                let mut secs = if settings.read_timeout_secs == 0 { DEFAULT_READ_TIMEOUT_SECS } else { settings.read_timeout_secs };
+                changed |= ui
+                    .add(egui::DragValue::new(&mut secs).clamp_range(1..=MAX_TIMEOUT_SECS).suffix(" s"))
+                    .on_hover_text(t!("download_settings.read_timeout_hint"))
+                    .changed();
+                settings.read_timeout_secs = secs;
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("update_check.label"));
+                let interval = &mut settings.update_check_interval;
+                let every = match *interval {
+                    UpdateCheckInterval::Every { minutes } => UpdateCheckInterval::Every { minutes },
+                    _ => UpdateCheckInterval::default(),
+                };
+                egui::ComboBox::from_id_source("update_check_interval")
+                    .selected_text(t!(interval.label_key()))
+                    .show_ui(ui, |ui| {
+                        for option in [UpdateCheckInterval::Never, UpdateCheckInterval::OnStartup, every] {
+                            let selected = interval.label_key() == option.label_key();
+                            if ui.selectable_label(selected, t!(option.label_key())).clicked() && !selected {
+                                *interval = option;
+                                changed = true;
+                            }
+                        }
+                    });
+                if let UpdateCheckInterval::Every { minutes } = interval {
+                    changed |= ui
+                        .add(egui::DragValue::new(minutes).clamp_range(1..=MAX_CHECK_MINUTES).suffix(" min"))
+                        .changed();
+                }
+            })
+            .response
+            .on_hover_text(t!("update_check.hint"));
+            changed |= ui
+                .checkbox(&mut settings.auto_update_openuo, t!("download_settings.auto_update"))
+                .on_hover_text(t!("download_settings.auto_update_hint"))
+                .changed();
+            changed |= ui
+                .checkbox(&mut settings.tie_children, t!("download_settings.tie_children"))
+                .on_hover_text(t!("download_settings.tie_children_hint"))
+                .changed();
+            changed |= ui
+                .checkbox(&mut settings.staff_mode, t!("staff_mode.unlock"))
+                .on_hover_text(t!("staff_mode.unlock_hint"))
+                .changed();
+            changed |= ui
+                .checkbox(&mut settings.ignore_battery, t!("power.ignore_battery"))
+                .on_hover_text(t!("power.ignore_battery_hint"))
+                .changed();
+            changed |= ui
+                .checkbox(&mut settings.ignore_metered, t!("power.ignore_metered"))
+                .on_hover_text(t!("power.ignore_metered_hint"))
+                .changed();
+            if let Some(version) = settings.launcher_update_reminder.skipped_version.clone() {
+                ui.horizontal(|ui| {
+                    ui.label(t!("launcher_update.skipped_label", version = version));
+                    if ui.small_button(t!("launcher_update.unskip")).clicked() {
+                        settings.launcher_update_reminder.skipped_version = None;
+                        changed = true;
                     }
+                });
+            }
+            ui.separator();
+            ui.label(t!("install_dir.label"));
+            ui.label(RichText::new(open_uo_dir().display().to_string()).size(11.0).monospace());
+            ui.horizontal(|ui| {
+                if ui.button(t!("install_dir.change")).on_hover_text(t!("install_dir.change_hint")).clicked() {
+                    change_dir = rfd::FileDialog::new().pick_folder();
+                }
+                if settings.openuo_dir.is_some() && ui.button(t!("install_dir.reset")).clicked() {
+                    change_dir = Some(install_dir::default_dir());
                 }
             });
-        });
-    }
-
-    fn show_footer(&mut self, ui: &mut egui::Ui) {
-        // 添加半透明背景
-        let footer_frame = egui::Frame::none()
-            .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 120))
-            .inner_margin(egui::Margin::symmetric(12.0, 6.0));
-        
-        footer_frame.show(ui, |ui| {
+            ui.separator();
+            ui.label(t!("bootstrap.label"));
+            match &settings.bootstrap {
+                Some(current) => {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(&current.url).size(11.0).monospace());
+                        remove_bootstrap = ui.small_button("✕").on_hover_text(t!("bootstrap.remove_hint")).clicked();
+                    });
+                }
+                None => {
+                    let response = ui
+                        .add(egui::TextEdit::singleline(&mut self.bootstrap_input).hint_text("openuo://bootstrap?url=…").desired_width(240.0))
+                        .on_hover_text(t!("bootstrap.hint"));
+                    apply_bootstrap = response.lost_focus() && !self.bootstrap_input.trim().is_empty();
+                }
+            }
+            if crate::url_scheme::SUPPORTED {
+                ui.checkbox(&mut url_scheme, t!("bootstrap.url_scheme")).on_hover_text(t!("bootstrap.url_scheme_hint"));
+            }
+            ui.separator();
+            ui.label(t!("folder_sync.label"));
             ui.horizontal(|ui| {
-                // 左侧：OpenUO 版本
-                let openuo_version = self.open_uo_version.as_deref().unwrap_or("N/A");
-                ui.label(
-                    RichText::new(format!("OpenUO: {}", openuo_version))
-                        .size(11.0)
-                        .color(egui::Color32::from_rgb(180, 180, 180))
-                );
-                
+                match &settings.sync_folder {
+                    Some(folder) => {
+                        ui.label(RichText::new(folder.display().to_string()).size(11.0).monospace());
+                        if ui.small_button("✕").on_hover_text(t!("folder_sync.remove_hint")).clicked() {
+                            settings.sync_folder = None;
+                            changed = true;
+                        }
+                    }
+                    None => {
+                        if ui.button(t!("folder_sync.choose")).on_hover_text(t!("folder_sync.hint")).clicked()
+                            && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                        {
+                            settings.sync_folder = Some(folder);
+                            changed = true;
+                            sync_now = true;
+                        }
+                    }
+                }
+            });
+            if settings.sync_folder.is_some() {
+                let response = ui.add_enabled(self.folder_sync_rx.is_none(), egui::Button::new(format!("🔄 {}", t!("folder_sync.now"))));
+                sync_now |= response.clicked();
+            }
+            if global_hotkey::SUPPORTED {
                 ui.separator();
-                
-                // 中间：语言和操作系统
-                let system_info = crate::system_info::system_info_string();
-                let languages = crate::i18n::available_languages();
-                let current_lang = languages
-                    .iter()
-                    .find(|lang| lang.code == self.current_locale)
-                    .map(|lang| lang.native_name.as_str())
-                    .unwrap_or(&self.current_locale);
-                
+                ui.horizontal(|ui| {
+                    ui.label(t!("global_hotkey.label"));
+                    apply_hotkey = ui
+                        .add(egui::TextEdit::singleline(&mut self.hotkey_input).hint_text("Ctrl+Alt+U").desired_width(120.0))
+                        .on_hover_text(t!("global_hotkey.hint"))
+                        .lost_focus();
+                });
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(t!("download_settings.github_token"));
+                let mut token = settings.github_token.as_str().to_string();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut token).password(true).desired_width(180.0))
+                    .on_hover_text(t!("download_settings.github_token_hint"))
+                    .changed()
+                {
+                    settings.github_token = GithubToken::new(&token);
+                    changed = true;
+                }
+            });
+            if github_token_from_env() {
                 ui.label(
-                    RichText::new(format!("{} | {}", current_lang, system_info))
+                    RichText::new(t!("download_settings.github_token_env"))
                         .size(11.0)
-                        .color(egui::Color32::from_rgb(160, 160, 160))
+                        .color(Color32::from_rgb(150, 150, 150)),
                 );
+            }
+        })
+        .response
+        .on_hover_text(t!("download_settings.title"));
+        if changed {
+            set_download_connections(settings.download_connections);
+            crate::retry::set_max_attempts(settings.retry_attempts);
+            set_network_timeouts(settings.connect_timeout_secs, settings.read_timeout_secs);
+            set_github_token(&settings.github_token);
+            if let Err(e) = save_launcher_settings(settings) {
+                tracing::warn!("Failed to save launcher settings: {}", e);
+            }
+        }
+        if let Some(dir) = change_dir {
+            self.change_install_dir(dir);
+        }
+        if apply_hotkey {
+            self.apply_global_hotkey();
+        }
+        if apply_bootstrap {
+            let input = self.bootstrap_input.clone();
+            self.open_bootstrap(&input);
+        }
+        if remove_bootstrap {
+            self.set_bootstrap(None);
+        }
+        if url_scheme != self.url_scheme_registered {
+            self.set_url_scheme(url_scheme);
+        }
+        if sync_now {
+            self.start_folder_sync();
+        }
+    }
+
+    /// 注册或取消注册 openuo:// 链接
+    fn set_url_scheme(&mut self, enabled: bool) {
+        let result = if enabled {
+            std::env::current_exe().map_err(anyhow::Error::from).and_then(|exe| crate::url_scheme::register(&exe))
+        } else {
+            crate::url_scheme::unregister()
+        };
+        match result {
+            Ok(()) => {
+                self.url_scheme_registered = enabled;
+                let message = if enabled { t!("bootstrap.url_scheme_registered") } else { t!("bootstrap.url_scheme_unregistered") };
+                self.add_log(LogEntryType::Success, &format!("✓ {}", message), None);
+            }
+            Err(e) => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}", t!("bootstrap.url_scheme_failed", error = e.to_string())), None);
+            }
+        }
+    }
+
+    /// 应用设置中输入的全局快捷键，为空时取消
+    fn apply_global_hotkey(&mut self) {
+        let input = self.hotkey_input.trim();
+        let hotkey = match input {
+            "" => None,
+            _ => match Hotkey::parse(input) {
+                Ok(hotkey) => Some(hotkey.to_string()),
+                Err(e) => {
+                    self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("global_hotkey.invalid", error = e.to_string())), None);
+                    return;
+                }
+            },
+        };
+        self.hotkey_input = hotkey.clone().unwrap_or_default();
+        if hotkey == self.config.launcher_settings.global_hotkey {
+            return;
+        }
+        self.config.launcher_settings.global_hotkey = hotkey.clone();
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+        match (self.register_global_hotkey(), hotkey) {
+            (Ok(()), Some(hotkey)) => {
+                self.add_log(LogEntryType::Success, &format!("⌨ {}", t!("global_hotkey.registered", hotkey = hotkey)), None);
+            }
+            (Ok(()), None) => self.add_log(LogEntryType::Info, &format!("⌨ {}", t!("global_hotkey.removed")), None),
+            (Err(e), _) => {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("global_hotkey.failed", error = format!("{e:#}"))), None);
+            }
+        }
+    }
+
+    /// 按设置注册全局快捷键，替换之前的注册
+    fn register_global_hotkey(&mut self) -> anyhow::Result<()> {
+        // 先注销，重新注册同一组合时才不会冲突
+        self.global_hotkey = None;
+        let Some(text) = &self.config.launcher_settings.global_hotkey else {
+            return Ok(());
+        };
+        self.global_hotkey = Some(global_hotkey::register(&Hotkey::parse(text)?)?);
+        Ok(())
+    }
+
+    /// 全局快捷键：恢复并激活窗口，加按 Shift 时启动当前档案
+    fn poll_global_hotkey(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.global_hotkey.as_ref().and_then(|r| r.try_recv()) else {
+            return;
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        if action == HotkeyAction::Launch
+            && let Some(profile) = self.active_profile()
+        {
+            let msg = t!("global_hotkey.launching", profile = profile.index.name.clone());
+            self.add_log(LogEntryType::Info, &format!("⌨ {}", msg), None);
+            self.request_launch();
+        }
+    }
+
+    /// 更换 OpenUO 安装目录，原来的安装需要时先移动过去
+    fn change_install_dir(&mut self, target: PathBuf) {
+        let game_running = self.external_game.is_some() || self.history.records.iter().any(|r| r.outcome == LaunchOutcome::Running);
+        if self.events.is_downloading() || self.install_move_rx.is_some() || game_running {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("install_dir.busy")), None);
+            return;
+        }
+        let current = open_uo_dir();
+        match install_dir::plan(&current, &target) {
+            Ok(Plan::Move) => {
+                let msg = t!("install_dir.moving", from = current.display().to_string(), to = target.display().to_string());
+                self.add_log(LogEntryType::Info, &format!("⏳ {}", msg), None);
+                self.install_move_rx = Some(install_dir::spawn_move(current, target));
+                self.install_move_progress = Some((0, 0));
+            }
+            Ok(Plan::UseExisting | Plan::Switch) => self.apply_install_dir(target),
+            Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("install_dir.invalid"), e), None),
+        }
+    }
+
+    /// 保存新的安装目录并重新检测已安装的版本
+    fn apply_install_dir(&mut self, dir: PathBuf) {
+        let setting = (dir != install_dir::default_dir()).then(|| dir.to_string_lossy().to_string());
+        self.config.launcher_settings.openuo_dir = setting.clone();
+        set_open_uo_dir_override(setting);
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+        self.open_uo_version = detect_open_uo_version();
+        self.open_uo_installed_at = read_open_uo_installed_at_in(&open_uo_dir());
+        let path = dir.display().to_string();
+        self.add_log(LogEntryType::Success, &format!("✓ {}", t!("install_dir.changed", path = path)), None);
+        if self.open_uo_version.is_none() {
+            self.add_log(LogEntryType::Info, &t!("install_dir.not_installed"), Some(LogAction::UpdateOpenUO));
+        }
+    }
+
+    fn poll_install_move(&mut self) {
+        let Some(rx) = &self.install_move_rx else {
+            return;
+        };
+        let mut finished = None;
+        for event in rx.try_iter() {
+            match event {
+                RelocateEvent::Progress { copied, total } => self.install_move_progress = Some((copied, total)),
+                RelocateEvent::Done(dest) => finished = Some(Ok(dest)),
+                RelocateEvent::Failed(e) => finished = Some(Err(e)),
+            }
+        }
+        let Some(result) = finished else {
+            return;
+        };
+        self.install_move_rx = None;
+        self.install_move_progress = None;
+        match result {
+            Ok(dest) => self.apply_install_dir(dest),
+            Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("install_dir.move_failed"), e), None),
+        }
+    }
+
+    fn show_launch_button(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::none().show(ui, |ui| {
+            ui.set_min_width(ui.available_width());
+            ui.horizontal(|ui| {
+                let launch_btn = egui::Button::new(
+                    RichText::new(t!("main.launch")).size(18.0).strong()
+                )
+                .fill(egui::Color32::from_rgba_unmultiplied(80, 180, 80, 220))
+                .min_size(egui::vec2(150.0, 40.0));
                 
-                // 右侧：Launcher 版本
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let querying = self.server_query_rx.is_some() || self.network_check_rx.is_some() || self.pending_policy_launch;
+                let confirmation = self
+                    .active_profile()
+                    .map(|p| p.index.launch_confirmation)
+                    .unwrap_or_default();
+                let response = ui.add_enabled(!querying, launch_btn);
+                match confirmation {
+                    LaunchConfirmation::None => {
+                        if response.clicked() {
+                            self.request_launch();
+                        }
+                    }
+                    LaunchConfirmation::Dialog => {
+                        if response.clicked() {
+                            self.confirming_launch = true;
+                        }
+                    }
+                    LaunchConfirmation::DoubleClick => {
+                        if response.on_hover_text(t!("launch_confirm.double_click_hint")).double_clicked() {
+                            self.request_launch();
+                        }
+                    }
+                }
+                if querying {
+                    ui.spinner();
+                }
+
+                ui.add_space(8.0);
+                self.show_window_mode_selector(ui);
+
+                if self.scheduled_launch.is_none() {
+                    let schedule_btn = egui::Button::new(t!("scheduler.button"))
+                        .selected(self.show_schedule_form)
+                        .min_size(egui::vec2(0.0, 20.0));
+                    if ui.add(schedule_btn).on_hover_text(t!("scheduler.hint")).clicked() {
+                        self.show_schedule_form = !self.show_schedule_form;
+                    }
+                }
+            });
+            self.show_scheduler(ui);
+        });
+    }
+
+    /// 定时启动：倒计时显示或设置表单
+    fn show_scheduler(&mut self, ui: &mut egui::Ui) {
+        if let Some(scheduled) = &self.scheduled_launch {
+            let remaining = scheduler::format_countdown(scheduled.remaining(Instant::now()));
+            let text = t!("scheduler.countdown", profile = &scheduled.profile_name, time = remaining);
+            let mut cancel = false;
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("⏰ {}", text)).size(14.0).strong().color(Color32::from_rgb(230, 200, 120)));
+                cancel = ui.button(t!("scheduler.cancel")).clicked();
+            });
+            if cancel {
+                self.scheduled_launch = None;
+                self.add_log(LogEntryType::Info, &t!("scheduler.cancelled"), None);
+            }
+            return;
+        }
+        if !self.show_schedule_form {
+            return;
+        }
+
+        ui.add_space(6.0);
+        let form = &mut self.schedule_form;
+        let mut start = false;
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut form.mode, ScheduleMode::AtTime, t!("scheduler.at_time"));
+            ui.add_enabled(form.mode == ScheduleMode::AtTime, egui::DragValue::new(&mut form.hour).clamp_range(0..=23).custom_formatter(|v, _| format!("{:02}", v as u8)));
+            ui.label(":");
+            ui.add_enabled(form.mode == ScheduleMode::AtTime, egui::DragValue::new(&mut form.minute).clamp_range(0..=59).custom_formatter(|v, _| format!("{:02}", v as u8)));
+            ui.add_space(12.0);
+            ui.radio_value(&mut form.mode, ScheduleMode::AfterMinutes, t!("scheduler.after"));
+            ui.add_enabled(form.mode == ScheduleMode::AfterMinutes, egui::DragValue::new(&mut form.minutes).clamp_range(1..=24 * 60).suffix(t!("scheduler.minutes_suffix")));
+            ui.add_space(12.0);
+            start = ui.button(t!("scheduler.start")).clicked();
+        });
+        if start {
+            self.schedule_launch();
+        }
+    }
+
+    fn schedule_launch(&mut self) {
+        let Some(profile) = self.active_profile() else {
+            self.add_log(LogEntryType::Error, &format!("✗ {}", t!("status.no_profile")), None);
+            return;
+        };
+        let delay = self.schedule_form.delay(crate::clock::now_local());
+        let scheduled = ScheduledLaunch {
+            profile_id: profile.index.file_name.clone(),
+            profile_name: profile.index.name.clone(),
+            fire_at: Instant::now() + delay,
+        };
+        let msg = t!(
+            "scheduler.scheduled",
+            profile = &scheduled.profile_name,
+            time = scheduler::format_countdown(delay)
+        );
+        self.scheduled_launch = Some(scheduled);
+        self.show_schedule_form = false;
+        self.add_log(LogEntryType::Info, &format!("⏰ {}", msg), None);
+    }
+
+    /// 看门狗检测到掉线：旧进程结束后经过一段延迟再重新启动
+    fn schedule_watchdog_relaunch(&mut self, profile_id: &str) {
+        let Some(profile) = self.config.profiles.iter().find(|p| p.index.file_name == profile_id) else {
+            return;
+        };
+        let delay = Duration::from_secs(profile.index.watchdog.relaunch_delay_secs);
+        let scheduled = ScheduledLaunch {
+            profile_id: profile_id.to_string(),
+            profile_name: profile.index.name.clone(),
+            fire_at: Instant::now() + delay,
+        };
+        let msg = t!(
+            "log.watchdog_disconnect",
+            profile = &scheduled.profile_name,
+            time = scheduler::format_countdown(delay)
+        );
+        self.add_log(LogEntryType::Warning, &format!("⚠ {}", msg), None);
+        self.scheduled_launch = Some(scheduled);
+    }
+
+    fn poll_scheduled_launch(&mut self) {
+        let Some(scheduled) = &self.scheduled_launch else {
+            return;
+        };
+        if !scheduled.is_due(Instant::now()) {
+            return;
+        }
+        let Some(scheduled) = self.scheduled_launch.take() else {
+            return;
+        };
+        match self.config.profiles.iter().position(|p| p.index.file_name == scheduled.profile_id) {
+            Some(idx) => {
+                self.config.active_profile = idx;
+                self.add_log(LogEntryType::Info, &t!("scheduler.launching", profile = &scheduled.profile_name), None);
+                self.request_launch();
+            }
+            None => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}", t!("scheduler.profile_missing", profile = &scheduled.profile_name)), None);
+            }
+        }
+    }
+
+    /// 即将开始的服务器活动，时间按本地时区显示
+    fn show_shard_events(&mut self, ui: &mut egui::Ui) {
+        let now = crate::clock::now_unix();
+        let upcoming = shard_events::upcoming(&self.shard_events, now, 5);
+        if upcoming.is_empty() {
+            return;
+        }
+        let mut changed = false;
+        egui::Frame::none()
+            .fill(Color32::from_rgba_unmultiplied(30, 40, 60, 180))
+            .rounding(4.0)
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.set_max_width(ui.available_width() * 0.7);
+                ui.label(RichText::new(format!("📅 {}", t!("shard_events.title"))).size(13.0).strong());
+                for event in upcoming {
+                    let Some(start) = event.starts() else {
+                        continue;
+                    };
+                    let when = if event.is_ongoing(now) {
+                        t!("shard_events.ongoing").to_string()
+                    } else {
+                        t!("shard_events.starts_in", time = humanize::duration((start - now) as u64)).to_string()
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(crate::clock::format_local(start)).size(12.0).monospace());
+                        let title = ui.label(RichText::new(&event.title).size(12.0).strong());
+                        if let Some(description) = event.description.as_deref().filter(|d| !d.trim().is_empty()) {
+                            title.on_hover_text(description);
+                        }
+                        ui.label(RichText::new(when).size(11.0).color(Color32::from_rgb(180, 180, 180)));
+                        if let Some(url) = &event.url {
+                            ui.hyperlink_to(t!("shard_events.details"), url);
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    let settings = &mut self.config.launcher_settings;
+                    changed |= ui
+                        .checkbox(&mut settings.event_notifications, t!("shard_events.notify"))
+                        .on_hover_text(t!("shard_events.notify_hint"))
+                        .changed();
+                    if settings.event_notifications {
+                        let mut minutes = match settings.event_notify_minutes {
+                            0 => shard_events::DEFAULT_NOTIFY_MINUTES,
+                            m => m,
+                        };
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut minutes).clamp_range(1..=24 * 60).suffix(t!("shard_events.minutes_suffix")))
+                            .changed();
+                        settings.event_notify_minutes = minutes;
+                    }
+                });
+            });
+        ui.add_space(8.0);
+        if changed && let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+    }
+
+    /// 活动即将开始时显示系统通知，每个活动只通知一次
+    fn poll_event_notifications(&mut self) {
+        let settings = &self.config.launcher_settings;
+        if !settings.event_notifications || self.shard_events.is_empty() {
+            return;
+        }
+        let minutes = match settings.event_notify_minutes {
+            0 => shard_events::DEFAULT_NOTIFY_MINUTES,
+            m => m,
+        };
+        let now = crate::clock::now_unix();
+        let due: Vec<ShardEvent> = shard_events::due(&self.shard_events, now, minutes as i64 * 60, &self.notified_events)
+            .into_iter()
+            .cloned()
+            .collect();
+        for event in due {
+            self.notified_events.insert(event.key());
+            let Some(start) = event.starts() else {
+                continue;
+            };
+            let body = t!(
+                "shard_events.notification",
+                time = crate::clock::format_local(start),
+                remaining = humanize::duration((start - now) as u64)
+            );
+            notification::show(event.title.clone(), body.to_string());
+            self.add_log(LogEntryType::Info, &format!("📅 {} — {}", event.title, body), None);
+        }
+    }
+
+    /// 服务器的投票网站，投票后按各网站的冷却时间禁用按钮
+    fn show_vote_buttons(&mut self, ui: &mut egui::Ui) {
+        if self.vote_sites.is_empty() {
+            return;
+        }
+        let now = crate::clock::now_unix();
+        let mut voted = None;
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.set_max_width(ui.available_width() * 0.7);
+            ui.label(RichText::new(format!("🗳 {}", t!("vote.title"))).size(13.0).strong());
+            let settings = &mut self.config.launcher_settings;
+            for site in &self.vote_sites {
+                match site.remaining(&settings.vote_times, now) {
+                    Some(remaining) => {
+                        let text = format!("{} ({})", site.name, humanize::duration(remaining as u64));
+                        ui.add_enabled(false, egui::Button::new(text))
+                            .on_disabled_hover_text(t!("vote.cooling_down", time = crate::clock::format_local(now + remaining)));
+                    }
+                    None => {
+                        let btn = egui::Button::new(format!("✔ {}", site.name)).fill(Color32::from_rgb(80, 140, 80));
+                        if ui.add(btn).on_hover_text(&site.url).clicked() {
+                            voted = Some(site.url.clone());
+                        }
+                    }
+                }
+            }
+            changed |= ui
+                .checkbox(&mut settings.vote_reminders, t!("vote.reminders"))
+                .on_hover_text(t!("vote.reminders_hint"))
+                .changed();
+        });
+        ui.add_space(8.0);
+        if let Some(url) = voted {
+            ui.ctx().open_url(egui::OpenUrl::new_tab(&url));
+            self.config.launcher_settings.vote_times.insert(url, now);
+            changed = true;
+        }
+        if changed && let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+    }
+
+    /// 查看服务器规则的链接，当前档案还没有确认时标出
+    fn show_rules_link(&mut self, ui: &mut egui::Ui) {
+        let Some(rules) = &self.shard_rules else {
+            return;
+        };
+        let pending = self
+            .active_profile()
+            .is_some_and(|p| rules.needs_ack(p.index.rules_acknowledged.as_deref()));
+        let text = if pending {
+            RichText::new(format!("📜 {}", t!("shard_rules.view_pending"))).color(Color32::from_rgb(255, 210, 120))
+        } else {
+            RichText::new(format!("📜 {}", t!("shard_rules.view")))
+        };
+        if ui.link(text).clicked() {
+            self.open_rules(false);
+        }
+        ui.add_space(8.0);
+    }
+
+    fn open_rules(&mut self, launch: bool) {
+        let Some(rules) = &self.shard_rules else {
+            return;
+        };
+        self.rules_view = Some(RulesView {
+            profile_idx: self.config.active_profile,
+            blocks: release_notes::parse(&rules.text),
+            agreed: false,
+            launch,
+        });
+    }
+
+    /// 服务器规则窗口：档案没有确认过当前版本时需要勾选同意，确认的版本记录在档案中
+    fn show_rules_window(&mut self, ctx: &egui::Context) {
+        let (Some(view), Some(rules)) = (&mut self.rules_view, &self.shard_rules) else {
+            self.rules_view = None;
+            return;
+        };
+        let Some(profile) = self.config.profiles.get(view.profile_idx) else {
+            self.rules_view = None;
+            return;
+        };
+        let pending = rules.needs_ack(profile.index.rules_acknowledged.as_deref());
+        let title = rules.title.clone().unwrap_or_else(|| t!("shard_rules.title").to_string());
+        let mut open = true;
+        let mut accept = false;
+        let mut close = false;
+        egui::Window::new(title)
+            .id(egui::Id::new("shard_rules_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for block in &view.blocks {
+                        show_notes_block(ui, block);
+                    }
+                });
+                ui.separator();
+                if !pending {
                     ui.label(
-                        RichText::new(format!("Launcher: {}", self.launcher_version))
-                            .size(11.0)
-                            .color(egui::Color32::from_rgb(180, 180, 180))
+                        RichText::new(format!("✓ {}", t!("shard_rules.acknowledged", profile = &profile.index.name)))
+                            .size(12.0)
+                            .color(Color32::from_rgb(120, 200, 120)),
+                    );
+                    close = ui.button(t!("shard_rules.close")).clicked();
+                    return;
+                }
+                ui.checkbox(&mut view.agreed, t!("shard_rules.agree"));
+                ui.horizontal(|ui| {
+                    let label = if view.launch { t!("shard_rules.accept_launch") } else { t!("shard_rules.accept") };
+                    let btn = egui::Button::new(label).fill(Color32::from_rgb(80, 180, 80));
+                    accept = ui.add_enabled(view.agreed, btn).clicked();
+                    close = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if !accept {
+            if !open || close {
+                self.rules_view = None;
+            }
+            return;
+        }
+        let (profile_idx, launch) = (view.profile_idx, view.launch);
+        let version = rules.version.clone();
+        self.rules_view = None;
+        let Some(profile) = self.config.profiles.get_mut(profile_idx) else {
+            return;
+        };
+        profile.index.rules_acknowledged = Some(version.clone());
+        let name = profile.index.name.clone();
+        if let Err(e) = save_profile(profile) {
+            self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("status.save_failed"), e), None);
+            return;
+        }
+        self.add_log(LogEntryType::Info, &format!("📜 {}", t!("shard_rules.accepted", profile = name, version = version)), None);
+        if launch && profile_idx == self.config.active_profile {
+            self.request_launch();
+        }
+    }
+
+    /// 投票冷却结束时显示系统通知，每次投票后只提醒一次
+    fn poll_vote_reminders(&mut self) {
+        let settings = &self.config.launcher_settings;
+        if !settings.vote_reminders || self.vote_sites.is_empty() {
+            return;
+        }
+        let now = crate::clock::now_unix();
+        let due: Vec<(String, String)> = vote::due_reminders(&self.vote_sites, &settings.vote_times, now, &self.reminded_votes)
+            .into_iter()
+            .map(|(site, key)| (site.name.clone(), key))
+            .collect();
+        for (name, key) in due {
+            self.reminded_votes.insert(key);
+            let body = t!("vote.available", site = &name).to_string();
+            notification::show(t!("vote.title").to_string(), body.clone());
+            self.add_log(LogEntryType::Info, &format!("🗳 {}", body), None);
+        }
+    }
+
+    /// UO 目录或 OpenUO 目录位于云同步文件夹时显示醒目的警告
+    fn show_cloud_sync_warning(&mut self, ui: &mut egui::Ui) {
+        let Some(profile) = self.active_profile() else {
+            return;
+        };
+        let uo_dir = std::path::PathBuf::from(&profile.settings.ultima_online_directory);
+        let uo_provider = cloud_sync::detect(&uo_dir);
+        let openuo_provider = cloud_sync::detect(&open_uo_dir());
+        if uo_provider.is_none() && openuo_provider.is_none() {
+            return;
+        }
+
+        egui::Frame::none()
+            .fill(Color32::from_rgba_unmultiplied(120, 40, 30, 200))
+            .rounding(4.0)
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.set_max_width(ui.available_width() * 0.7);
+                let warn = |ui: &mut egui::Ui, key: &str, provider: CloudProvider| {
+                    ui.label(
+                        RichText::new(format!("⚠ {}", t!(key, provider = provider.name())))
+                            .size(13.0)
+                            .strong()
+                            .color(Color32::from_rgb(255, 210, 120)),
                     );
+                };
+                if let Some(provider) = uo_provider {
+                    warn(ui, "cloud_sync.uo_dir", provider);
+                }
+                if let Some(provider) = openuo_provider {
+                    warn(ui, "cloud_sync.openuo_dir", provider);
+                }
+                ui.label(RichText::new(t!("cloud_sync.guidance")).size(11.0).color(Color32::from_rgb(220, 220, 220)));
+
+                if uo_provider.is_some() && uo_dir.is_dir() {
+                    ui.horizontal(|ui| {
+                        if let Some((copied, total)) = self.relocate_progress {
+                            let progress = if total > 0 { copied as f32 / total as f32 } else { 0.0 };
+                            ui.add(egui::ProgressBar::new(progress).desired_width(200.0).show_percentage());
+                        } else if self.relocate_rx.is_none() {
+                            let btn = egui::Button::new(t!("cloud_sync.relocate"))
+                                .fill(Color32::from_rgb(80, 120, 200));
+                            if ui.add(btn).on_hover_text(t!("cloud_sync.relocate_hint")).clicked()
+                                && let Some(dest) = rfd::FileDialog::new().pick_folder()
+                            {
+                                self.relocate_rx = Some((self.config.active_profile, cloud_sync::spawn_relocate(uo_dir.clone(), dest)));
+                                self.relocate_progress = Some((0, 0));
+                            }
+                        }
+                    });
+                }
+            });
+        ui.add_space(8.0);
+    }
+
+    fn poll_relocate(&mut self) {
+        let Some((idx, rx)) = &self.relocate_rx else {
+            return;
+        };
+        let idx = *idx;
+        let mut finished = None;
+        for event in rx.try_iter() {
+            match event {
+                RelocateEvent::Progress { copied, total } => self.relocate_progress = Some((copied, total)),
+                RelocateEvent::Done(dest) => finished = Some(Ok(dest)),
+                RelocateEvent::Failed(e) => finished = Some(Err(e)),
+            }
+        }
+        let Some(result) = finished else {
+            return;
+        };
+        self.relocate_rx = None;
+        self.relocate_progress = None;
+
+        match result {
+            Ok(dest) => {
+                let dest = dest.to_string_lossy().to_string();
+                if let Some(profile) = self.config.profiles.get_mut(idx) {
+                    profile.settings.ultima_online_directory = dest.clone();
+                }
+                if let Err(e) = self.save_config_with_screen_info() {
+                    self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("status.save_failed"), e), None);
+                }
+                self.add_log(LogEntryType::Success, &format!("✓ {}", t!("cloud_sync.relocated", path = dest)), None);
+            }
+            Err(e) => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("cloud_sync.relocate_failed"), e), None);
+            }
+        }
+    }
+
+    /// 启动按钮旁的窗口模式快捷切换，修改后立即保存到当前 profile
+    fn show_window_mode_selector(&mut self, ui: &mut egui::Ui) {
+        let idx = self.config.active_profile;
+        let Some(profile) = self.config.profiles.get_mut(idx) else {
+            return;
+        };
+        let current = profile.settings.window_mode();
+        let mut selected = current;
+        egui::ComboBox::from_id_source("window_mode_combo")
+            .selected_text(t!(current.label_key()))
+            .width(110.0)
+            .show_ui(ui, |ui| {
+                for mode in WindowMode::ALL {
+                    ui.selectable_value(&mut selected, mode, t!(mode.label_key()));
+                }
+            })
+            .response
+            .on_hover_text(t!("window_mode.hint"));
+
+        if selected != current {
+            profile.settings.set_window_mode(selected);
+            if let Err(e) = self.save_config_with_screen_info() {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("status.save_failed"), e), None);
+            }
+        }
+    }
+
+    fn show_footer(&mut self, ui: &mut egui::Ui) {
+        // 添加半透明背景
+        let footer_frame = egui::Frame::none()
+            .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 120))
+            .inner_margin(egui::Margin::symmetric(12.0, 6.0));
+        
+        footer_frame.show(ui, |ui| {
+            ui.horizontal(|ui| {
+                // 左侧：OpenUO 版本
+                let openuo_version = self.open_uo_version.as_deref().unwrap_or("N/A");
+                ui.label(
+                    RichText::new(format!("OpenUO: {}", openuo_version))
+                        .size(11.0)
+                        .color(egui::Color32::from_rgb(180, 180, 180))
+                );
+                self.show_external_game(ui);
+                
+                ui.separator();
+                
+                // 中间：语言和操作系统
+                let system_info = crate::system_info::system_info_string();
+                let languages = crate::i18n::available_languages();
+                let current_lang = languages
+                    .iter()
+                    .find(|lang| lang.code == self.current_locale)
+                    .map(|lang| lang.native_name.as_str())
+                    .unwrap_or(&self.current_locale);
+                
+                ui.label(
+                    RichText::new(format!("{} | {}", current_lang, system_info))
+                        .size(11.0)
+                        .color(egui::Color32::from_rgb(160, 160, 160))
+                );
+                
+                // 右侧：Launcher 版本
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(
+                        RichText::new(format!("Launcher: {}", self.launcher_version))
+                            .size(11.0)
+                            .color(egui::Color32::from_rgb(180, 180, 180))
+                    );
+                });
+            });
+        });
+    }
+
+    fn poll_channels(&mut self) {
+        // 安装完成后补上推迟的保存
+        if self.save_pending && operation_lock::current().is_none() {
+            self.save_pending = false;
+            match self.save_config_with_screen_info() {
+                Ok(true) => self.set_status(&t!("status.config_saved")),
+                Ok(false) => {}
+                Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("status.save_failed"), e), None),
+            }
+        }
+
+        for event in self.events.poll() {
+            match event {
+                PumpEvent::Download(event) => self.handle_download_event(event),
+                PumpEvent::Update(event) => self.handle_update_event(event),
+            }
+        }
+
+        if let Some(state) = self.power_rx.try_iter().last() {
+            self.on_power_changed(state);
+        }
+        self.apply_power_saving();
+
+        if let Some(found) = self.legacy_scan_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.legacy_scan_rx = None;
+            for install in &found {
+                let msg = t!("legacy_migration.found", count = install.profiles.len(), path = install.dir.display());
+                self.add_log(LogEntryType::Info, &format!("📦 {}", msg), Some(LogAction::MigrateLegacy(install.dir.clone())));
+            }
+            // 只提示一次，之后可以右键 📥 手动迁移
+            if !found.is_empty() {
+                self.config.launcher_settings.legacy_migration_offered = true;
+                if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+                    tracing::warn!("Failed to save launcher settings: {}", e);
+                }
+            }
+        }
+
+        if let Some(found) = self.external_scan_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.external_scan_rx = None;
+            if let Some(running) = found.first() {
+                self.add_log(LogEntryType::Info, &format!("▶ {}", t!("process.detected", pid = running.pid)), None);
+                process::watch(running.pid, self.game_tx.clone());
+                self.external_game = Some(ExternalGame {
+                    pid: running.pid,
+                    attached: None,
+                });
+            }
+        }
+
+        // 网络检查通过后继续启动，失败时说明原因并提供重试
+        let network_result = self
+            .network_check_rx
+            .as_ref()
+            .and_then(|(idx, rx)| rx.try_recv().ok().map(|res| (*idx, res)));
+        if let Some((idx, res)) = network_result {
+            self.network_check_rx = None;
+            if idx == self.config.active_profile {
+                match res {
+                    Ok(()) => {
+                        self.network_checked = true;
+                        self.request_launch();
+                    }
+                    Err(e) => {
+                        let name = self.active_profile().map(|p| p.index.name.clone()).unwrap_or_default();
+                        let msg = format!("✗ {} {}", t!("network_check.blocked", profile = name, error = e), t!("network_check.blocked_hint"));
+                        self.add_log(LogEntryType::Error, &msg, Some(LogAction::RetryLaunch));
+                    }
+                }
+            }
+        }
+
+        // 处理启动前的服务器列表查询
+        let query_result = self
+            .server_query_rx
+            .as_ref()
+            .and_then(|(idx, rx)| rx.try_recv().ok().map(|res| (*idx, res)));
+        if let Some((idx, res)) = query_result {
+            self.server_query_rx = None;
+            self.apply_server_list(idx, res);
+            // 查询期间切换了档案时不启动，否则会用另一个档案的设置启动
+            if idx == self.config.active_profile {
+                self.launch_and_log();
+            } else {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.server_list_profile_changed")), None);
+            }
+        }
+
+        // 处理 UO 目录迁移
+        self.poll_relocate();
+        self.poll_install_move();
+        if let Some(result) = self.folder_sync_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.folder_sync_rx = None;
+            self.on_folder_sync(result, true);
+        }
+
+        // 定时启动
+        self.poll_scheduled_launch();
+
+        // 服务器活动和投票提醒
+        self.poll_event_notifications();
+        self.poll_vote_reminders();
+
+        // 处理游戏进程事件
+        let events: Vec<_> = self.game_rx.try_iter().collect();
+        for event in events {
+            match event {
+                GameEvent::Exited { record_id, exit_code, duration } => {
+                    if let Some(run) = self.ab_compare.as_mut().and_then(|ab| ab.run.as_mut()) {
+                        run.on_exited(&record_id, exit_code);
+                    }
+                    self.password_scrubs.retain(|(id, path)| {
+                        if *id == record_id {
+                            session_password::scrub_logged(path);
+                        }
+                        *id != record_id
+                    });
+                    self.history.finish(&record_id, exit_code, duration);
+                    self.save_history();
+                    // 游戏退出时保存了宏和界面位置
+                    self.start_folder_sync();
+                    if self.config.launcher_settings.prompt_session_notes {
+                        self.session_note = Some((record_id.clone(), String::new()));
+                    }
+                    let code = exit_code.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string());
+                    let duration = humanize::duration(duration.as_secs());
+                    if exit_code == Some(0) {
+                        self.add_log(LogEntryType::Info, &t!("log.game_exited", duration = duration), None);
+                    } else {
+                        self.add_log(LogEntryType::Error, &format!("✗ {}", t!("log.game_crashed", code = code, duration = duration)), None);
+                    }
+                }
+                GameEvent::Disconnected { record_id, profile_id } => {
+                    self.history.annotate(&record_id, &t!("history.watchdog_note"));
+                    self.schedule_watchdog_relaunch(&profile_id);
+                }
+                GameEvent::Connected { record_id } => {
+                    if let Some(run) = self.ab_compare.as_mut().and_then(|ab| ab.run.as_mut()) {
+                        run.on_connected(&record_id, Instant::now());
+                    }
+                }
+                GameEvent::Ended { pid } => {
+                    let Some(game) = self.external_game.take_if(|game| game.pid == pid) else {
+                        continue;
+                    };
+                    if let Some((record_id, attached_at)) = game.attached {
+                        self.history.finish_external(&record_id, attached_at.elapsed());
+                        self.save_history();
+                    }
+                    self.add_log(LogEntryType::Info, &t!("process.exited", pid = pid), None);
+                }
+            }
+        }
+        self.poll_ab_compare();
+    }
+
+    fn handle_download_event(&mut self, event: DownloadEvent) {
+        match event {
+            DownloadEvent::Progress { received, total } => {
+                self.download_progress = Some((received, total));
+                self.transfer_meter.record(received, Instant::now());
+            }
+            DownloadEvent::DeltaFailed(error) => {
+                self.download_progress = None;
+                self.transfer_meter.reset();
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.delta_failed", error = error)), None);
+            }
+            DownloadEvent::Retrying { attempt, max_attempts, error } => {
+                // 重试时从头下载
+                self.download_progress = None;
+                self.transfer_meter.reset();
+                self.add_log(
+                    LogEntryType::Warning,
+                    &format!("⚠ {}", t!("log.download_retry", attempt = attempt, max = max_attempts, error = error)),
+                    None,
+                );
+            }
+            DownloadEvent::Fallback { failed, next, error } => {
+                // 切换镜像后从头开始计算进度
+                self.download_progress = None;
+                self.transfer_meter.reset();
+                self.add_log(
+                    LogEntryType::Warning,
+                    &format!("⚠ {}", t!("log.mirror_fallback", failed = failed, next = next, error = error)),
+                    None,
+                );
+            }
+            DownloadEvent::Cancelled => {
+                self.download_progress = None;
+                self.transfer_meter.reset();
+                self.downloading_launcher = false;
+                self.downloading_game_data = false;
+                self.downloading_addon = None;
+                self.downloading_side_by_side = None;
+                self.current_download = None;
+                self.add_log(LogEntryType::Warning, &format!("⏹ {}", t!("log.download_cancelled")), None);
+                if std::mem::take(&mut self.launch_after_update) {
+                    self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.auto_update_launch_aborted")), None);
+                }
+            }
+            DownloadEvent::Finished(result) => {
+                self.download_progress = None;
+                self.transfer_meter.reset();
+                self.current_download = None;
+                
+                match result {
+                    Ok(DownloadOutcome::LauncherUpdated(version)) => {
+                        self.add_log(LogEntryType::Success, &format!("✅ {}", t!("log.launcher_update_complete", version = version)), None);
+                        // 更新成功后同步远程版本，避免按钮残留
+                        self.remote_launcher = Some(self.launcher_version.clone());
+                        self.launcher_restarting = true;
+                        std::thread::spawn(|| {
+                            std::thread::sleep(std::time::Duration::from_secs(2));
+                            crate::child_group::terminate_all();
+                            std::process::exit(0);
+                        });
+                        self.downloading_launcher = false;
+                        self.download_failed = false;
+                    }
+                    Ok(DownloadOutcome::OpenUO(version)) => {
+                        self.add_log(LogEntryType::Success, &format!("✓ {}", t!("log.openuo_download_complete", version = &version)), None);
+                        self.open_uo_version = Some(version);
+                        self.open_uo_installed_at = read_open_uo_installed_at_in(&open_uo_dir());
+                        self.downloading_launcher = false;
+                        self.download_failed = false;
+                        if std::mem::take(&mut self.launch_after_update) {
+                            self.launch_with_policy();
+                        }
+                    }
+                    Ok(DownloadOutcome::Verified { version, repaired }) => {
+                        if repaired.is_empty() {
+                            self.add_log(LogEntryType::Success, &format!("✓ {}", t!("verify.all_ok", version = version)), None);
+                        } else {
+                            let msg = humanize::count("verify.repaired", repaired.len() as u64);
+                            self.add_log(LogEntryType::Success, &format!("✓ {}: {}", msg, repaired.join(", ")), None);
+                        }
+                        self.download_failed = false;
+                    }
+                    Ok(DownloadOutcome::GameData(version)) => {
+                        self.add_log(LogEntryType::Success, &format!("✓ {}", t!("game_data.installed", version = version)), None);
+                        self.logs.retain(|l| !matches!(l.action, Some(LogAction::DownloadGameData)));
+                        self.downloading_game_data = false;
+                        self.download_failed = false;
+                    }
+                    Ok(DownloadOutcome::Addon { name, version }) => {
+                        self.add_log(LogEntryType::Success, &format!("✓ {}", t!("addons.installed", name = name, version = version)), None);
+                        if let Some(id) = self.downloading_addon.take() {
+                            self.logs.retain(|l| !matches!(&l.action, Some(LogAction::DownloadAddon(a)) if *a == id));
+                        }
+                        self.download_failed = false;
+                    }
+                    Ok(DownloadOutcome::SideBySide { tag, version }) => {
+                        let msg = t!("client_versions.installed", version = version, dir = &tag);
+                        self.add_log(LogEntryType::Success, &format!("✓ {}", msg), None);
+                        self.logs.retain(|l| !matches!(&l.action, Some(LogAction::InstallVersion(t)) if *t == tag));
+                        self.downloading_side_by_side = None;
+                        self.download_failed = false;
+                        if let Some(picker) = &mut self.release_picker {
+                            picker.side_by_side = client_versions::installed();
+                        }
+                        if std::mem::take(&mut self.launch_after_update) {
+                            self.query_servers_and_launch();
+                        }
+                    }
+                    Err(err) => {
+                        // 游戏数据包或并存安装的版本失败时重试它们
+                        let retry = if std::mem::take(&mut self.downloading_game_data) {
+                            LogAction::DownloadGameData
+                        } else if let Some(id) = self.downloading_addon.take() {
+                            LogAction::DownloadAddon(id)
+                        } else if let Some(tag) = self.downloading_side_by_side.take() {
+                            LogAction::InstallVersion(tag)
+                        } else {
+                            LogAction::RetryDownload
+                        };
+                        self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("log.download_error"), err), Some(retry));
+                        if let Some(kind) = error_help::classify_message(&err) {
+                            self.error_report = Some(ErrorReport {
+                                kind,
+                                detail: err.clone(),
+                                launcher_update: self.downloading_launcher,
+                            });
+                        }
+                        self.downloading_launcher = false;
+                        self.download_failed = true;
+                        if std::mem::take(&mut self.launch_after_update) {
+                            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.auto_update_launch_aborted")), None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_update_event(&mut self, event: UpdateEvent) {
+        match event {
+            UpdateEvent::OpenUO(res) => {
+                self.checking_open_uo = false;
+                match res {
+                    Ok(v) => {
+                        self.remote_open_uo = Some(v.clone());
+                        self.open_uo_published = None;
+                        self.required_open_uo = None;
+                        self.open_uo_notes = None;
+                        self.open_uo_chain = None;
+                        if let Some(local) = &self.open_uo_version {
+                            if version_newer(&v, local) {
+                                self.add_log(LogEntryType::Info, &format!("{}: {}", t!("log.openuo_new_version"), v), Some(LogAction::UpdateOpenUO));
+                            } else {
+                                self.add_log(LogEntryType::Success, &format!("✓ {}: {}", t!("log.openuo_latest"), v), None);
+                                self.logs.retain(|l| !matches!(l.action, Some(LogAction::UpdateOpenUO)));
+                            }
+                        } else {
+                            self.add_log(LogEntryType::Info, &format!("{}: {}", t!("log.openuo_not_installed"), v), Some(LogAction::UpdateOpenUO));
+                        }
+                    }
+                    Err(e) => {
+                        self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("log.openuo_check_error"), e), None);
+                        update_policy::discard_after_failed_check(
+                            self.pending_policy_launch,
+                            &mut self.remote_open_uo,
+                            &mut self.required_open_uo,
+                        );
+                    }
+                }
+            }
+            UpdateEvent::Launcher(res) => {
+                self.checking_launcher = false;
+                match res {
+                    Ok(v) => {
+                        self.remote_launcher = Some(v.clone());
+                        self.launcher_published = None;
+                        self.launcher_notes = None;
+                        let reminder = &self.config.launcher_settings.launcher_update_reminder;
+                        if version_newer(&v, &self.launcher_version) {
+                            if reminder.should_prompt(&v, crate::clock::now_unix()) {
+                                self.add_log(LogEntryType::Info, &format!("{}: {}", t!("log.launcher_new_version"), v), Some(LogAction::UpdateLauncher));
+                            } else {
+                                tracing::info!("Launcher {} 的更新提示已跳过或推迟", v);
+                            }
+                        } else {
+                            self.add_log(LogEntryType::Success, &format!("✓ {}: {}", t!("log.launcher_latest"), v), None);
+                            self.logs.retain(|l| !matches!(l.action, Some(LogAction::UpdateLauncher)));
+                        }
+                    }
+                    Err(e) => {
+                        self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("log.launcher_check_error"), e), None);
+                    }
+                }
+            }
+            UpdateEvent::Published { open_uo: true, at } => self.open_uo_published = Some(at),
+            UpdateEvent::Published { open_uo: false, at } => self.launcher_published = Some(at),
+            UpdateEvent::RequiredOpenUO(v) => {
+                self.required_open_uo = Some(v);
+            }
+            UpdateEvent::OpenUONotes(notes) => {
+                self.open_uo_notes = Some(notes);
+            }
+            UpdateEvent::LauncherNotes(notes) => {
+                self.launcher_notes = Some(notes);
+            }
+            UpdateEvent::OpenUOChain(chain) => {
+                let skipped = chain.skipped();
+                if !skipped.is_empty() {
+                    let versions: Vec<&str> = skipped.iter().map(|r| r.version.as_str()).collect();
+                    self.add_log(
+                        LogEntryType::Info,
+                        &format!("⏭ {}: {}", humanize::count("log.openuo_skipping", skipped.len() as u64), versions.join(", ")),
+                        None,
+                    );
+                    // 发布说明窗口显示所有跳过版本的说明
+                    self.open_uo_notes = Some(chain.combined_notes());
+                }
+                self.open_uo_chain = Some(chain);
+            }
+            UpdateEvent::DataPackage(package) => {
+                self.logs.retain(|l| !matches!(l.action, Some(LogAction::DownloadGameData)));
+                if let Some(dir) = self.game_data_dir()
+                    && game_data::needs_install(&package, &dir)
+                {
+                    let msg = t!("game_data.available", version = &package.version, dir = dir.display().to_string());
+                    self.add_log(LogEntryType::Info, &msg, Some(LogAction::DownloadGameData));
+                }
+                self.data_package = Some(package);
+            }
+            UpdateEvent::Addons(addons) => {
+                self.logs.retain(|l| !matches!(l.action, Some(LogAction::DownloadAddon(_))));
+                // 只提醒已经安装过的附加包有更新，没有安装的由玩家自行选择
+                if let Some(dir) = self.game_data_dir() {
+                    for addon in &addons {
+                        if let Some(installed) = game_data::installed_addon_version(&dir, &addon.id)
+                            && installed != addon.package.version
+                        {
+                            let msg = t!("addons.update_available", name = &addon.name, version = &addon.package.version);
+                            self.add_log(LogEntryType::Info, &msg, Some(LogAction::DownloadAddon(addon.id.clone())));
+                        }
+                    }
+                }
+                if addons.is_empty() {
+                    self.show_addons = false;
+                }
+                self.addons = addons;
+            }
+            UpdateEvent::ShardEvents(events) => self.shard_events = events,
+            UpdateEvent::ShardRules(rules) => self.shard_rules = rules,
+            UpdateEvent::StaffMode(manifest) => {
+                self.profile_editor.staff_manifest = manifest.clone();
+                self.staff_manifest = manifest;
+            }
+            UpdateEvent::VoteSites(sites) => {
+                // 清单不再列出的网站不再需要记录
+                if !sites.is_empty()
+                    && vote::prune(&mut self.config.launcher_settings.vote_times, &sites)
+                    && let Err(e) = save_launcher_settings(&self.config.launcher_settings)
+                {
+                    tracing::warn!("Failed to save launcher settings: {}", e);
+                }
+                self.vote_sites = sites;
+            }
+            UpdateEvent::RateLimited { open_uo, retry_after, cached } => {
+                self.rate_limited_until = Some(Instant::now() + retry_after);
+                let time = humanize::duration(retry_after.as_secs());
+                let msg = if cached {
+                    t!("log.rate_limited_cached", time = time)
+                } else {
+                    // 不会再收到这次检查的结果
+                    if open_uo {
+                        self.checking_open_uo = false;
+                    } else {
+                        self.checking_launcher = false;
+                    }
+                    t!("log.rate_limited", time = time)
+                };
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", msg), None);
+            }
+            UpdateEvent::Bootstrap(Ok(changed)) => {
+                if changed {
+                    self.add_log(LogEntryType::Success, &format!("✓ {}", t!("bootstrap.refreshed")), None);
+                }
+            }
+            UpdateEvent::Bootstrap(Err(e)) => {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("bootstrap.refresh_failed", error = e)), None);
+            }
+            UpdateEvent::CustomTrust { url, ca_cert, pin } => {
+                if self.trust_warned.insert(url.clone()) {
+                    if let Some(path) = ca_cert {
+                        self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.tls_custom_ca", url = url, path = path)), None);
+                    }
+                    if let Some(fingerprint) = pin {
+                        self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.tls_pinned", url = url, fingerprint = fingerprint)), None);
+                    }
+                }
+            }
+            UpdateEvent::Done => {
+                self.last_update_check = Some(crate::clock::now_unix());
+                self.check_clock_skew();
+                if self.pending_auto_update {
+                    self.pending_auto_update = false;
+                    self.finish_auto_update_check();
+                }
+                if self.pending_policy_launch {
+                    self.pending_policy_launch = false;
+                    self.finish_policy_check();
+                }
+            }
+        }
+    }
+
+    fn start_download(&mut self) {
+        self.start_download_version(None);
+    }
+
+    /// 启动下载任务，无法启动时记录错误并返回 false
+    fn spawn_download<F, Fut>(&mut self, job: F) -> bool
+    where
+        F: FnOnce(DownloadContext) -> Fut,
+        Fut: std::future::Future<Output = Result<DownloadOutcome>> + Send + 'static,
+    {
+        match self.events.start_download(|| download::spawn(job)) {
+            Ok(started) => started,
+            Err(e) => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("log.download_start_failed"), e), None);
+                false
+            }
+        }
+    }
+
+    /// 下载指定版本的 OpenUO，`tag` 为空时下载最新版本
+    fn start_download_version(&mut self, tag: Option<String>) {
+        // 移动安装目录期间不能写入安装
+        if self.events.is_downloading() || self.install_move_rx.is_some() {
+            return;
+        }
+        if self.defer_while_game_running(PendingInstall::Download(tag.clone())) {
+            return;
+        }
+        match &tag {
+            Some(tag) => self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("log.downloading_openuo_version", version = tag)), None),
+            None => self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("log.downloading_openuo")), None),
+        }
+        self.backup_before_update();
+        let pending = PendingDownload::OpenUO { tag: tag.clone() };
+        if !self.spawn_download(move |ctx| download_and_unpack_open_uo(tag, ctx)) {
+            return;
+        }
+        self.current_download = Some(pending);
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+    }
+
+    /// 更新或重装 OpenUO 前备份 Data/Profiles，失败时只记录警告，不阻止更新
+    fn backup_before_update(&mut self) {
+        let settings = &self.config.launcher_settings.update_backup;
+        if !settings.enabled {
+            return;
+        }
+        match profile_backup::snapshot_game_profiles(settings.keep) {
+            Ok(Some(snapshot)) => {
+                tracing::info!("Backed up OpenUO profiles to {}", snapshot.path.display());
+                self.add_log(LogEntryType::Info, &format!("💾 {}", t!("update_backup.created")), None);
+            }
+            Ok(None) => {}
+            Err(e) => self.add_log(LogEntryType::Warning, &format!("⚠ {}: {:#}", t!("profile_backup.failed"), e), None),
+        }
+    }
+
+    /// 把指定版本并存安装到 OpenUO/<tag>，不影响主安装
+    fn start_side_by_side(&mut self, tag: String) {
+        if self.events.is_downloading() || self.install_move_rx.is_some() {
+            return;
+        }
+        self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("client_versions.installing", version = &tag)), None);
+        self.downloading_side_by_side = Some(tag.clone());
+        self.current_download = Some(PendingDownload::SideBySide { tag: tag.clone() });
+        if !self.spawn_download(move |ctx| download_side_by_side(tag, ctx)) {
+            self.downloading_side_by_side = None;
+            self.current_download = None;
+            return;
+        }
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+    }
+
+    /// 当前档案固定使用 `tag`（为 None 时取消固定），版本还没有安装时开始安装
+    fn pin_active_profile(&mut self, tag: Option<String>) {
+        let Some(profile) = self.config.profiles.get_mut(self.config.active_profile) else {
+            return;
+        };
+        profile.index.pinned_open_uo = tag.clone();
+        let name = profile.index.name.clone();
+        if let Err(e) = save_profile(profile) {
+            self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("status.save_failed"), e), None);
+            return;
+        }
+        match tag {
+            Some(tag) => {
+                self.add_log(LogEntryType::Info, &format!("📌 {}", t!("client_versions.pinned", profile = name, version = &tag)), None);
+                if !client_versions::is_installed(&tag) {
+                    self.start_side_by_side(tag);
+                }
+            }
+            None => self.add_log(LogEntryType::Info, &format!("📌 {}", t!("client_versions.unpinned", profile = name)), None),
+        }
+    }
+
+    /// 校验已安装的 OpenUO，只重新下载缺失或损坏的文件
+    fn start_verify(&mut self) {
+        if self.events.is_downloading() || self.install_move_rx.is_some() {
+            return;
+        }
+        if self.defer_while_game_running(PendingInstall::Verify) {
+            return;
+        }
+        self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("verify.verifying")), None);
+        if !self.spawn_download(verify_and_repair_open_uo) {
+            return;
+        }
+        self.current_download = Some(PendingDownload::Verify);
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+    }
+
+    /// 重新下载并安装最新版本，安装前清空 OpenUO 目录（保留 Data/Profiles）
+    fn start_reinstall(&mut self) {
+        if self.events.is_downloading() || self.install_move_rx.is_some() {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("reinstall.busy")), None);
+            return;
+        }
+        if self.defer_while_game_running(PendingInstall::Reinstall) {
+            return;
+        }
+        self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("reinstall.started")), None);
+        self.backup_before_update();
+        let protected = install_dir::protected_dirs(&self.config.profiles);
+        if !self.spawn_download(move |ctx| reinstall_open_uo(ctx, protected)) {
+            return;
+        }
+        self.current_download = Some(PendingDownload::Reinstall);
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+    }
+
+    /// 安装前检查 OpenUO 是否正在运行（包括在 Launcher 之外启动的），正在运行时弹出对话框并返回 true
+    ///
+    /// Windows 上运行中的程序文件被占用，这时解压会留下一半新一半旧的安装。
+    fn defer_while_game_running(&mut self, pending: PendingInstall) -> bool {
+        let pids: Vec<u32> = process::find_running(&open_uo_binary_path()).into_iter().map(|p| p.pid).collect();
+        if pids.is_empty() {
+            return false;
+        }
+        self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("game_running.deferred")), None);
+        self.game_running_prompt = Some(GameRunningPrompt {
+            pids,
+            pending,
+            terminating: false,
+            last_check: Instant::now(),
+        });
+        true
+    }
+
+    fn resume_install(&mut self, pending: PendingInstall) {
+        match pending {
+            PendingInstall::Download(tag) => self.start_download_version(tag),
+            PendingInstall::Verify => self.start_verify(),
+            PendingInstall::Reinstall => self.start_reinstall(),
+            PendingInstall::File(path) => self.install_from_file(path),
+        }
+    }
+
+    /// 继续上次关闭 Launcher 时没有完成的下载；游戏数据和附加包要等更新清单加载后才能开始
+    fn resume_download(&mut self, pending: PendingDownload) {
+        match pending {
+            PendingDownload::OpenUO { tag } => self.start_download_version(tag),
+            PendingDownload::Verify => self.start_verify(),
+            PendingDownload::Reinstall => self.start_reinstall(),
+            PendingDownload::SideBySide { tag } => self.start_side_by_side(tag),
+            PendingDownload::GameData => self.start_game_data_download(),
+            PendingDownload::Addon { id } => self.start_addon_download(&id),
+            PendingDownload::Launcher => self.start_launcher_update(),
+        }
+        if self.events.is_downloading() {
+            self.logs.retain(|l| !matches!(l.action, Some(LogAction::ResumeDownload(_))));
+        }
+    }
+
+    /// OpenUO 正在运行时的对话框：关闭游戏后重试，或者由 Launcher 结束进程后继续安装
+    fn show_game_running_prompt(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &mut self.game_running_prompt else {
+            return;
+        };
+        // 结束进程后每秒检查一次，全部退出后自动继续
+        if prompt.terminating && prompt.last_check.elapsed() >= Duration::from_secs(1) {
+            prompt.last_check = Instant::now();
+            prompt.pids.retain(|pid| process::is_running(*pid));
+        }
+        if prompt.terminating && prompt.pids.is_empty() {
+            let pending = prompt.pending.clone();
+            self.game_running_prompt = None;
+            self.resume_install(pending);
+            return;
+        }
+        if prompt.terminating {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+        let pids: Vec<String> = prompt.pids.iter().map(u32::to_string).collect();
+        let mut retry = false;
+        let mut terminate = false;
+        let mut cancel = false;
+        egui::Window::new(t!("game_running.title"))
+            .id(egui::Id::new("game_running_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(380.0);
+                ui.label(RichText::new(t!("game_running.message", pids = pids.join(", "))).size(13.0));
+                ui.add_space(8.0);
+                if prompt.terminating {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t!("game_running.waiting"));
+                    });
+                }
+                ui.horizontal(|ui| {
+                    retry = ui.button(t!("game_running.retry")).on_hover_text(t!("game_running.retry_hint")).clicked();
+                    let btn = egui::Button::new(t!("game_running.terminate")).fill(Color32::from_rgb(200, 90, 70));
+                    terminate = ui
+                        .add_enabled(!prompt.terminating, btn)
+                        .on_hover_text(t!("game_running.terminate_hint"))
+                        .clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if terminate {
+            let mut failed = Vec::new();
+            for pid in &prompt.pids {
+                if let Err(e) = process::terminate(*pid) {
+                    failed.push(format!("{pid}: {e}"));
+                }
+            }
+            prompt.terminating = true;
+            prompt.last_check = Instant::now();
+            if !failed.is_empty() {
+                let msg = format!("✗ {}: {}", t!("game_running.terminate_failed"), failed.join(", "));
+                self.add_log(LogEntryType::Error, &msg, None);
+            }
+        } else if retry {
+            let pending = prompt.pending.clone();
+            self.game_running_prompt = None;
+            self.resume_install(pending);
+        } else if cancel {
+            self.game_running_prompt = None;
+            if std::mem::take(&mut self.launch_after_update) {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.auto_update_launch_aborted")), None);
+            }
+        }
+    }
+
+    /// 从本地安装包安装 OpenUO，不需要联网
+    fn install_from_file(&mut self, path: PathBuf) {
+        if self.events.is_downloading() || self.install_move_rx.is_some() {
+            return;
+        }
+        if self.defer_while_game_running(PendingInstall::File(path.clone())) {
+            return;
+        }
+        let file = path.display().to_string();
+        let package = match offline_install::inspect(&path) {
+            Ok(package) => package,
+            Err(e) => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("log.install_file_invalid", file = file), e), None);
+                return;
+            }
+        };
+        if package.version_from_file_name {
+            self.add_log(
+                LogEntryType::Warning,
+                &format!("⚠ {}", t!("log.install_file_no_version", file = METADATA_FILE, version = &package.version)),
+                None,
+            );
+        }
+        self.add_log(
+            LogEntryType::Info,
+            &format!("⏳ {}", t!("log.installing_openuo_file", version = &package.version, file = file)),
+            None,
+        );
+        if !self.spawn_download(move |ctx| offline_install::install(package, ctx)) {
+            return;
+        }
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+    }
+
+    /// “跳过此版本”（`skip`）或“稍后提醒”，出现更新的版本时重新提示
+    fn dismiss_launcher_update(&mut self, skip: bool) {
+        let Some(version) = self.remote_launcher.clone() else {
+            return;
+        };
+        let reminder = &mut self.config.launcher_settings.launcher_update_reminder;
+        let msg = if skip {
+            reminder.skip(&version);
+            t!("launcher_update.skipped", version = version)
+        } else {
+            reminder.snooze(&version, crate::clock::now_unix());
+            t!("launcher_update.snoozed", version = version)
+        };
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+        self.logs.retain(|l| !matches!(l.action, Some(LogAction::UpdateLauncher)));
+        self.add_log(LogEntryType::Info, &msg, None);
+    }
+
+    /// 当前 profile 的 UO 目录，游戏数据包解压到这里
+    fn game_data_dir(&self) -> Option<PathBuf> {
+        self.active_profile()
+            .map(|p| p.settings.ultima_online_directory.trim())
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    }
+
+    fn start_game_data_download(&mut self) {
+        if self.events.is_downloading() {
+            return;
+        }
+        let (Some(package), Some(dir)) = (self.data_package.clone(), self.game_data_dir()) else {
+            return;
+        };
+        self.add_log(
+            LogEntryType::Info,
+            &format!("⏳ {}", t!("game_data.downloading", version = &package.version, dir = dir.display().to_string())),
+            None,
+        );
+        if !self.spawn_download(move |ctx| download_game_data(package, dir, ctx)) {
+            return;
+        }
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+        self.downloading_game_data = true;
+        self.current_download = Some(PendingDownload::GameData);
+    }
+
+    /// 补丁包：导入到 Mods 目录，按档案的 UO 目录启用或停用
+    fn show_mods_window(&mut self, ctx: &egui::Context) {
+        let Some(view) = &mut self.mods else {
+            return;
+        };
+        let mut logs = Vec::new();
+        if let Some(event) = view.rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            match event {
+                ModEvent::Imported(info) => {
+                    logs.push((LogEntryType::Success, format!("✓ {}", t!("mod_packs.imported", name = &info.name, version = &info.version))));
+                }
+                ModEvent::Removed(name) => logs.push((LogEntryType::Info, format!("🗑 {}", t!("mod_packs.removed", name = name)))),
+                ModEvent::Enabled(name) => logs.push((LogEntryType::Success, format!("✓ {}", t!("mod_packs.enabled", name = name)))),
+                ModEvent::Disabled { name, kept } => {
+                    logs.push((LogEntryType::Success, format!("✓ {}", t!("mod_packs.disabled", name = name))));
+                    if !kept.is_empty() {
+                        logs.push((LogEntryType::Warning, format!("⚠ {}", t!("mod_packs.kept", files = kept.join(", ")))));
+                    }
+                }
+                ModEvent::Failed(e) => logs.push((LogEntryType::Error, format!("✗ {}: {}", t!("mod_packs.failed"), e))),
+            }
+            view.rx = None;
+            view.reload();
+        }
+
+        let game_running = self.external_game.is_some() || self.history.records.iter().any(|r| r.outcome == LaunchOutcome::Running);
+        let busy = view.rx.is_some() || self.events.is_downloading();
+        let mut task = None;
+        let mut open = true;
+        egui::Window::new(t!("mod_packs.title"))
+            .id(egui::Id::new("mods_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(460.0);
+                ui.label(RichText::new(t!("mod_packs.target", dir = view.dir.display().to_string())).size(12.0).color(Color32::from_rgb(150, 150, 150)));
+                let shared_warning = match &view.shared {
+                    Some(SharedDir::Link) => Some(t!("mod_packs.shared_link")),
+                    Some(SharedDir::Profiles(names)) => Some(t!("mod_packs.shared_profiles", profiles = names.join(", "))),
+                    None => None,
+                };
+                if let Some(warning) = &shared_warning {
+                    ui.add_space(4.0);
+                    ui.label(RichText::new(format!("⚠ {}", warning)).size(12.0).color(Color32::from_rgb(230, 180, 80)));
+                }
+                ui.add_space(6.0);
+                if view.packs.is_empty() {
+                    ui.label(RichText::new(t!("mod_packs.empty")).color(Color32::from_rgb(150, 150, 150)));
+                }
+                egui::Grid::new("mods_grid").num_columns(3).spacing([12.0, 6.0]).show(ui, |ui| {
+                    for pack in &view.packs {
+                        let applied = view.applied.iter().find(|a| a.name == pack.info.name);
+                        let mut enabled = applied.is_some();
+                        // 共用的目录中只能停用已启用的补丁包
+                        let blocked = shared_warning.is_some() && !enabled;
+                        let response = ui.add_enabled(!busy && !game_running && !blocked, egui::Checkbox::new(&mut enabled, RichText::new(&pack.info.name).strong()));
+                        let response = if game_running {
+                            response.on_disabled_hover_text(t!("mod_packs.game_running"))
+                        } else if let Some(warning) = shared_warning.as_ref().filter(|_| blocked) {
+                            response.on_disabled_hover_text(warning.as_ref())
+                        } else {
+                            response
+                        };
+                        if response.changed() {
+                            task = Some(if enabled {
+                                ModTask::Enable { uo_dir: view.dir.clone(), pack: pack.clone() }
+                            } else {
+                                ModTask::Disable { uo_dir: view.dir.clone(), name: pack.info.name.clone() }
+                            });
+                        }
+                        match applied {
+                            Some(a) if a.version != pack.info.version => {
+                                ui.label(t!("mod_packs.outdated", applied = &a.version, version = &pack.info.version));
+                            }
+                            _ => {
+                                ui.label(t!("mod_packs.version", version = &pack.info.version, count = pack.files.len()));
+                            }
+                        }
+                        ui.horizontal(|ui| {
+                            if let Some(a) = applied
+                                && a.version != pack.info.version
+                                && ui.add_enabled(!busy && !game_running && shared_warning.is_none(), egui::Button::new(t!("mod_packs.reapply"))).clicked()
+                            {
+                                task = Some(ModTask::Enable { uo_dir: view.dir.clone(), pack: pack.clone() });
+                            }
+                            if ui.add_enabled(!busy, egui::Button::new("🗑")).on_hover_text(t!("mod_packs.remove_hint")).clicked() {
+                                task = Some(ModTask::Remove(pack.clone()));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+                // 补丁包已从 Mods 目录删除，但仍应用在 UO 目录中，只能停用
+                for applied in view.applied.iter().filter(|a| !view.packs.iter().any(|p| p.info.name == a.name)) {
+                    ui.horizontal(|ui| {
+                        ui.label(t!("mod_packs.orphaned", name = &applied.name));
+                        if ui.add_enabled(!busy && !game_running, egui::Button::new(t!("mod_packs.disable"))).clicked() {
+                            task = Some(ModTask::Disable { uo_dir: view.dir.clone(), name: applied.name.clone() });
+                        }
+                    });
+                }
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!busy, egui::Button::new(format!("📂 {}", t!("mod_packs.import")))).on_hover_text(t!("mod_packs.import_hint")).clicked()
+                        && let Some(path) = rfd::FileDialog::new().add_filter("zip", &["zip"]).pick_file()
+                    {
+                        task = Some(ModTask::Import(path));
+                    }
+                    if busy && view.rx.is_some() {
+                        ui.spinner();
+                    }
+                });
+            });
+        if let Some(task) = task {
+            view.rx = Some(mod_packs::spawn(task));
+        }
+        // 正在写入 UO 目录时不能关闭窗口
+        if (!open || ctx.input(|i| i.key_pressed(egui::Key::Escape))) && view.rx.is_none() {
+            self.mods = None;
+        }
+        for (kind, msg) in logs {
+            self.add_log(kind, &msg, None);
+        }
+    }
+
+    fn start_addon_download(&mut self, id: &str) {
+        if self.events.is_downloading() {
+            return;
+        }
+        let (Some(addon), Some(dir)) = (self.addons.iter().find(|a| a.id == id).cloned(), self.game_data_dir()) else {
+            return;
+        };
+        self.add_log(
+            LogEntryType::Info,
+            &format!("⏳ {}", t!("addons.downloading", name = &addon.name, version = &addon.package.version, dir = dir.display().to_string())),
+            None,
+        );
+        if !self.spawn_download(move |ctx| download_addon(addon, dir, ctx)) {
+            return;
+        }
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = false;
+        self.downloading_addon = Some(id.to_string());
+        self.current_download = Some(PendingDownload::Addon { id: id.to_string() });
+    }
+
+    /// 更新清单中的附加包（音乐、音效等），安装到当前档案的 UO 目录
+    fn show_addons_window(&mut self, ctx: &egui::Context) {
+        if !self.show_addons {
+            return;
+        }
+        let dir = self.game_data_dir();
+        let busy = self.events.is_downloading();
+        let mut install = None;
+        let mut login_music = self.active_profile().map(|p| p.settings.login_music);
+        let mut open = true;
+        egui::Window::new(t!("addons.title"))
+            .id(egui::Id::new("addons_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                let Some(dir) = &dir else {
+                    ui.label(t!("addons.no_uo_dir"));
+                    return;
+                };
+                ui.label(RichText::new(t!("addons.target", dir = dir.display().to_string())).size(12.0).color(Color32::from_rgb(150, 150, 150)));
+                ui.add_space(6.0);
+                egui::Grid::new("addons_grid").num_columns(3).spacing([12.0, 6.0]).show(ui, |ui| {
+                    for addon in &self.addons {
+                        let installed = game_data::installed_addon_version(dir, &addon.id);
+                        ui.label(RichText::new(&addon.name).strong());
+                        let (status, label) = match &installed {
+                            None => (t!("addons.not_installed").to_string(), t!("addons.install")),
+                            Some(v) if *v == addon.package.version => (t!("addons.up_to_date", version = v).to_string(), t!("addons.reinstall")),
+                            Some(v) => (t!("addons.outdated", installed = v, version = &addon.package.version).to_string(), t!("addons.update")),
+                        };
+                        ui.label(status);
+                        let response = ui.add_enabled(!busy, egui::Button::new(label));
+                        if response.on_disabled_hover_text(t!("addons.busy")).clicked() {
+                            install = Some(addon.id.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(login_music) = &mut login_music {
+                    ui.add_space(6.0);
+                    ui.checkbox(login_music, t!("addons.login_music").as_ref()).on_hover_text(t!("addons.login_music_hint"));
+                }
+            });
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_addons = false;
+        }
+        if let Some(id) = install {
+            self.start_addon_download(&id);
+        }
+        let idx = self.config.active_profile;
+        if let (Some(login_music), Some(profile)) = (login_music, self.config.profiles.get_mut(idx))
+            && profile.settings.login_music != login_music
+        {
+            profile.settings.login_music = login_music;
+            if let Err(e) = save_profile(profile) {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("status.save_failed"), e), None);
+            }
+        }
+    }
+
+    fn start_launcher_update(&mut self) {
+        if self.events.is_downloading() {
+            return;
+        }
+        self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("log.downloading_launcher")), None);
+        if !self.spawn_download(download_launcher_update) {
+            return;
+        }
+        self.download_progress = None;
+        self.transfer_meter.reset();
+        self.downloading_launcher = true;
+        self.current_download = Some(PendingDownload::Launcher);
+    }
+
+    fn trigger_update_checks(&mut self, open_uo: bool, launcher: bool) {
+        if !open_uo && !launcher {
+            return;
+        }
+        if open_uo && !self.checking_open_uo {
+            self.checking_open_uo = true;
+            self.add_log(LogEntryType::Checking, &format!("⟳ {}", t!("log.checking_openuo")), None);
+        }
+        if launcher && !self.checking_launcher {
+            self.checking_launcher = true;
+            self.add_log(LogEntryType::Checking, &format!("⟳ {}", t!("log.checking_launcher")), None);
+        }
+        self.last_update_poll = Some(Instant::now());
+        self.events.start_update_check(trigger_update_check_impl(open_uo, launcher));
+    }
+
+    /// 本机时钟偏差过大时提醒一次（会导致定时启动不准和 TLS 证书校验失败）
+    fn check_clock_skew(&mut self) {
+        let skew = crate::clock::skew_secs();
+        if self.clock_skew_warned || skew.abs() < crate::clock::SKEW_WARNING_SECS {
+            return;
+        }
+        self.clock_skew_warned = true;
+        let offset = humanize::duration(skew.unsigned_abs());
+        let msg = if skew > 0 {
+            t!("clock.skew_behind", offset = offset)
+        } else {
+            t!("clock.skew_ahead", offset = offset)
+        };
+        self.add_log(LogEntryType::Warning, &format!("⚠ {}", msg), None);
+    }
+
+    fn maybe_schedule_updates(&mut self) {
+        if self.checking_open_uo || self.checking_launcher {
+            return;
+        }
+        if self.rate_limited_until.is_some_and(|until| Instant::now() < until) {
+            return;
+        }
+        let interval = self.config.launcher_settings.update_check_interval;
+        if interval.is_due(self.last_update_poll.map(|poll| poll.elapsed())) {
+            self.trigger_update_checks(true, true);
+        }
+    }
+
+    fn ensure_textures(&mut self, ctx: &egui::Context) {
+        if self.background_texture.is_none() {
+            self.background_texture = load_embedded_texture(
+                ctx,
+                "launcher_background",
+                include_bytes!("../assets/background.png"),
+            );
+        }
+        if self.logo_texture.is_none() {
+            self.logo_texture = load_embedded_texture(
+                ctx,
+                "launcher_logo",
+                include_bytes!("../assets/logo.png"),
+            );
+        }
+    }
+
+    /// 点击启动：先按 profile 的更新策略检查 OpenUO 版本
+    fn request_launch(&mut self) {
+        // 服务器规则：档案第一次启动前（或规则更新后）需要确认
+        if let Some(rules) = &self.shard_rules
+            && self
+                .active_profile()
+                .is_some_and(|p| rules.needs_ack(p.index.rules_acknowledged.as_deref()))
+        {
+            self.open_rules(true);
+            return;
+        }
+        if let Some(profile) = self.active_profile()
+            && profile.index.launch_target == LaunchTarget::OpenUO
+        {
+            // 仅本次使用的密码：每次启动都询问
+            let session_only = profile.index.session_password.enabled;
+            let missing = if session_only {
+                !matches!(&self.session_password, Some((idx, _)) if *idx == self.config.active_profile)
+            } else {
+                // 自动登录但不保存账号：重启 Launcher 后内存中没有密码，先询问；
+                // 机器名变化后无法恢复的密码也在第一次启动时询问
+                profile.settings.session_credentials_missing() || self.password_reentry.contains(&profile.index.file_name)
+            };
+            if missing {
+                self.credential_prompt = Some(CredentialPrompt {
+                    profile_idx: self.config.active_profile,
+                    // 锁定模式不显示保存的账号，由玩家自己填写
+                    username: if kiosk::active() { String::new() } else { profile.settings.username.clone() },
+                    password: String::new(),
+                    session_only,
+                });
+                return;
+            }
+        }
+        // OpenUO 运行时写入缓存和日志，磁盘写满时可能崩溃或丢失设置
+        if !std::mem::take(&mut self.low_disk_acknowledged)
+            && let Some(profile) = self.active_profile()
+            && profile.index.hardware_check.enabled
+            && let Some(shortfall) = disk_space::check_free(
+                &client_versions::dir_for(profile),
+                profile.index.hardware_check.min_free_disk_mb * 1024 * 1024,
+            )
+        {
+            self.low_disk = Some(shortfall);
+            return;
+        }
+        // 需要 VPN 等网络条件的档案：在后台检查，通过后再继续启动
+        if !std::mem::take(&mut self.network_checked)
+            && let Some(profile) = self.active_profile()
+            && profile.index.network_requirement.enabled
+        {
+            if self.network_check_rx.is_none() {
+                let requirement = profile.index.network_requirement.clone();
+                let endpoint = requirement.endpoint_or(&profile.settings.ip, profile.settings.port);
+                self.add_log(LogEntryType::Checking, &format!("⟳ {}", t!("network_check.checking", endpoint = &endpoint)), None);
+                self.network_check_rx = Some((self.config.active_profile, network_check::spawn(requirement, endpoint)));
+            }
+            return;
+        }
+        if let Some(profile) = self.active_profile()
+            && profile.index.launch_target == LaunchTarget::OpenUO
+            && let Some(tag) = profile.index.pinned_open_uo.clone()
+        {
+            // 固定了版本的档案不自动更新，也不受更新策略限制
+            if client_versions::is_installed(&tag) {
+                self.query_servers_and_launch();
+            } else if self.events.is_downloading() {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("client_versions.busy", version = tag)), None);
+            } else {
+                self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("client_versions.install_before_launch", version = &tag)), None);
+                self.launch_after_update = true;
+                self.start_side_by_side(tag);
+            }
+            return;
+        }
+        let auto_update = self.config.launcher_settings.auto_update_openuo
+            && !self.events.is_downloading()
+            && self.active_profile().is_some_and(|p| p.index.launch_target == LaunchTarget::OpenUO);
+        // 正在运行的 OpenUO 占用着安装目录中的文件，这时不自动更新
+        if auto_update && self.external_game.is_some() {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("process.auto_update_skipped")), None);
+        } else if auto_update && let Some(reason) = self.download_deferral() {
+            self.deferred_update = true;
+            self.add_log(LogEntryType::Info, &format!("⏸ {}", t!("power.update_deferred", reason = reason)), None);
+        } else if auto_update {
+            // 每次都重新获取，避免使用过期的版本信息
+            self.pending_auto_update = true;
+            self.trigger_update_checks(true, self.checking_launcher);
+            return;
+        }
+        self.launch_with_policy();
+    }
+
+    /// 使用电池或计费网络时返回推迟自动下载的原因（可在设置中忽略）
+    fn download_deferral(&self) -> Option<String> {
+        let settings = &self.config.launcher_settings;
+        if self.power.metered && !settings.ignore_metered {
+            Some(t!("power.reason_metered").to_string())
+        } else if self.power.on_battery && !settings.ignore_battery {
+            Some(t!("power.reason_battery").to_string())
+        } else {
+            None
+        }
+    }
+
+    fn on_power_changed(&mut self, state: PowerState) {
+        let previous = std::mem::replace(&mut self.power, state);
+        if state.on_battery && !previous.on_battery && !self.config.launcher_settings.ignore_battery {
+            self.add_log(LogEntryType::Info, &format!("🔋 {}", t!("power.on_battery")), None);
+        }
+        if state.metered && !previous.metered && !self.config.launcher_settings.ignore_metered {
+            self.add_log(LogEntryType::Info, &format!("📶 {}", t!("power.metered")), None);
+        }
+    }
+
+    /// 每帧调用：电池供电时降低帧率，条件解除后下载推迟的更新
+    fn apply_power_saving(&mut self) {
+        power::set_low_frame_rate(self.power.on_battery && !self.config.launcher_settings.ignore_battery);
+        if !self.deferred_update
+            || self.download_deferral().is_some()
+            || self.events.is_downloading()
+            || self.external_game.is_some()
+            || operation_lock::current().is_some()
+        {
+            return;
+        }
+        self.deferred_update = false;
+        if let Some(version) = update_policy::auto_update_target(self.open_uo_version.as_deref(), self.remote_open_uo.as_deref()) {
+            self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("power.update_resumed", version = version)), None);
+            self.start_download();
+        }
+    }
+
+    /// “保持 OpenUO 最新”：有新版本时先下载安装，完成后再启动
+    fn finish_auto_update_check(&mut self) {
+        let target = update_policy::auto_update_target(self.open_uo_version.as_deref(), self.remote_open_uo.as_deref());
+        match target {
+            Some(version) if !self.events.is_downloading() => {
+                self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("log.auto_update_before_launch", version = version)), None);
+                self.launch_after_update = true;
+                self.start_download();
+            }
+            _ => self.launch_with_policy(),
+        }
+    }
+
+    /// 按 profile 的更新策略检查后启动
+    fn launch_with_policy(&mut self) {
+        let policy = match self.active_profile() {
+            Some(profile) if profile.index.launch_target == LaunchTarget::OpenUO => profile.index.update_policy,
+            _ => UpdatePolicy::Never,
+        };
+        match policy {
+            UpdatePolicy::Never => self.query_servers_and_launch(),
+            UpdatePolicy::Notify => {
+                if self.remote_open_uo.is_none() && !self.checking_open_uo {
+                    self.trigger_update_checks(true, false);
+                }
+                let decision = update_policy::evaluate(
+                    policy,
+                    self.open_uo_version.as_deref(),
+                    self.remote_open_uo.as_deref(),
+                    self.required_open_uo.as_deref(),
+                );
+                if let PolicyDecision::Notify { latest } = decision {
+                    self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.launching_outdated", version = latest)), Some(LogAction::UpdateOpenUO));
+                }
+                self.query_servers_and_launch();
+            }
+            UpdatePolicy::Require => {
+                // 每次都重新获取，避免使用过期的版本信息
+                self.pending_policy_launch = true;
+                self.trigger_update_checks(true, self.checking_launcher);
+            }
+        }
+    }
+
+    /// “要求最新版本”：版本检查完成后决定是否继续启动
+    fn finish_policy_check(&mut self) {
+        let decision = update_policy::evaluate(
+            UpdatePolicy::Require,
+            self.open_uo_version.as_deref(),
+            self.remote_open_uo.as_deref(),
+            self.required_open_uo.as_deref(),
+        );
+        if !decision.blocks_launch() {
+            self.query_servers_and_launch();
+            return;
+        }
+        let msg = match &decision {
+            PolicyDecision::Outdated { local, required } => {
+                t!("log.launch_blocked_outdated", local = local, required = required).to_string()
+            }
+            PolicyDecision::NotInstalled { required } => {
+                t!("log.launch_blocked_not_installed", required = required).to_string()
+            }
+            _ => t!("log.launch_blocked_unverified").to_string(),
+        };
+        let action = (!matches!(decision, PolicyDecision::Unverified)).then_some(LogAction::UpdateOpenUO);
+        self.add_log(LogEntryType::Error, &format!("✗ {}", msg), action);
+    }
+
+    /// 需要时先查询服务器列表，查询完成后再启动
+    fn query_servers_and_launch(&mut self) {
+        let Some(profile) = self.active_profile() else {
+            self.launch_and_log();
+            return;
+        };
+        let settings = &profile.settings;
+        if !profile.index.query_server_list || settings.username.is_empty() {
+            self.launch_and_log();
+            return;
+        }
+        if !server_list::supports_plain_login(settings) {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.server_list_encrypted")), None);
+            self.launch_and_log();
+            return;
+        }
+
+        let password = crate::crypter::decrypt(&settings.password);
+        let rx = server_list::spawn_query(settings, password);
+        self.server_query_rx = Some((self.config.active_profile, rx));
+        self.add_log(LogEntryType::Checking, &format!("⟳ {}", t!("log.server_list_querying")), None);
+    }
+
+    /// 将查询到的服务器写入 profile（lastservernum / last_server_name）
+    fn apply_server_list(&mut self, idx: usize, res: Result<Vec<ShardServer>, String>) {
+        let servers = match res {
+            Ok(servers) => servers,
+            Err(e) => {
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}: {}", t!("log.server_list_failed"), e), None);
+                return;
+            }
+        };
+        let Some(profile) = self.config.profiles.get_mut(idx) else {
+            return;
+        };
+        let Some((num, server)) = server_list::pick_server(&servers, &profile.settings.last_server_name) else {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("log.server_list_empty")), None);
+            return;
+        };
+        profile.settings.last_server_num = num;
+        profile.settings.last_server_name = server.name.clone();
+        let msg = t!("log.server_list_selected", name = &server.name, index = num);
+        self.add_log(LogEntryType::Info, &msg, None);
+    }
+
+    fn launch_and_log(&mut self) {
+        self.check_requirements();
+        match self.launch_open_uo() {
+            Ok(msg) => self.add_log(LogEntryType::Success, &msg, None),
+            Err(err) => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("status.launch_failed"), err), None);
+                let uo_data_missing = self.active_profile().is_some_and(|p| {
+                    p.index.launch_target != LaunchTarget::Custom
+                        && !uo_data::missing(Path::new(&p.settings.ultima_online_directory)).is_empty()
+                });
+                if uo_data_missing {
+                    self.add_log(LogEntryType::Info, &t!("uo_data.fix_hint"), Some(LogAction::OpenProfileEditor));
+                }
+                let openuo_missing = self.active_profile().is_some_and(|p| {
+                    p.index.launch_target == LaunchTarget::OpenUO && !open_uo_binary_in(&client_versions::dir_for(p)).exists()
+                });
+                let kind = if openuo_missing {
+                    Some(FailureKind::OpenUOMissing)
+                } else {
+                    error_help::classify(&err)
+                };
+                if let Some(kind) = kind {
+                    self.error_report = Some(ErrorReport {
+                        kind,
+                        detail: format!("{err:#}"),
+                        launcher_update: false,
+                    });
+                }
+            }
+        }
+    }
+
+    fn launch_open_uo(&mut self) -> Result<String> {
+        let Some(profile) = self.active_profile().cloned() else {
+            anyhow::bail!("{}", t!("status.no_profile"));
+        };
+        self.launch_profile(&profile)?;
+        Ok(t!("status.launch_success").to_string())
+    }
+
+    /// 启动指定的 profile 并记录到启动历史
+    fn launch_profile(&mut self, profile: &ProfileConfig) -> Result<LaunchedGame> {
+        let mut record = LaunchRecord {
+            profile_id: profile.index.file_name.clone(),
+            profile_name: profile.index.name.clone(),
+            ..Default::default()
+        };
+
+        if profile.index.profile_backup.enabled && profile.index.launch_target == LaunchTarget::OpenUO {
+            match profile_backup::snapshot(profile) {
+                Ok(Some(snapshot)) => tracing::info!("Backed up game profile data to {}", snapshot.path.display()),
+                Ok(None) => {}
+                Err(e) => self.add_log(LogEntryType::Warning, &format!("⚠ {}: {:#}", t!("profile_backup.failed"), e), None),
+            }
+        }
+        let session_password = profile.index.session_password.clone();
+        match self.spawn_game(profile) {
+            Ok(child) => {
+                if session_password.enabled {
+                    let settings_path = profile_settings_path(profile);
+                    if session_password.scrub_after_secs > 0 {
+                        session_password::schedule_scrub(
+                            settings_path.clone(),
+                            Duration::from_secs(session_password.scrub_after_secs),
+                        );
+                    }
+                    self.password_scrubs.push((record.id.clone(), settings_path));
+                }
+                record.outcome = LaunchOutcome::Running;
+                let pid = child.id();
+                let control = history::watch_game_process(child, record.id.clone(), self.game_tx.clone());
+                if profile.index.watchdog.enabled {
+                    watchdog::spawn(
+                        pid,
+                        profile.index.watchdog.clone(),
+                        profile.index.last_character_name.clone(),
+                        record.id.clone(),
+                        profile.index.file_name.clone(),
+                        control.clone(),
+                        self.game_tx.clone(),
+                    );
+                }
+                let launched = LaunchedGame {
+                    record_id: record.id.clone(),
+                    pid,
+                    control,
+                };
+                self.history.push(record);
+                self.save_history();
+                Ok(launched)
+            }
+            Err(err) => {
+                if session_password.enabled {
+                    session_password::scrub_logged(&profile_settings_path(profile));
+                }
+                record.outcome = LaunchOutcome::Failed;
+                record.error = Some(format!("{err:#}"));
+                self.history.push(record);
+                self.save_history();
+                Err(err)
+            }
+        }
+    }
+
+    /// 启动前检查硬件，只给出警告，不阻止启动
+    fn check_requirements(&mut self) {
+        let Some(profile) = self.active_profile() else {
+            return;
+        };
+        if !profile.index.hardware_check.enabled {
+            return;
+        }
+        let snapshot = HardwareSnapshot::capture(self.gpu_info.clone(), &client_versions::dir_for(profile));
+        let mut issues = requirements::check(&snapshot, &profile.index.hardware_check);
+        // 磁盘空间不足已经在启动前弹窗确认过
+        issues.retain(|issue| !matches!(issue, RequirementIssue::LowDisk { .. }));
+        for issue in &issues {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", issue.message()), None);
+        }
+        if issues.iter().any(|i| i.suggests_driver_change()) {
+            self.add_log(LogEntryType::Info, &t!("requirements.try_force_driver"), Some(LogAction::OpenProfileEditor));
+        } else if !issues.is_empty() {
+            self.add_log(LogEntryType::Info, &t!("requirements.may_crash"), None);
+        }
+    }
+
+    fn spawn_game(&mut self, profile: &ProfileConfig) -> Result<Child> {
+        // 缺少 UO 数据文件时 OpenUO 只会给出难以理解的错误，启动前先检查
+        if profile.index.launch_target != LaunchTarget::Custom {
+            let dir = &profile.settings.ultima_online_directory;
+            let missing = uo_data::missing(Path::new(dir));
+            if !missing.is_empty() {
+                anyhow::bail!("{}", t!("uo_data.missing", dir = dir, files = missing.join("; ")));
+            }
+        }
+
+        // 保存上次启动的 profile
+        self.config.launcher_settings.last_profile = Some(profile.index.file_name.clone());
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save last profile: {}", e);
+        }
+        
+        // 保存配置时带上屏幕信息，安装更新期间不能启动
+        if !self.save_config_with_screen_info()? {
+            anyhow::bail!("{}", t!("operation_lock.launch_blocked"));
+        }
+        let settings_path = profile_settings_path(profile);
+        let mut cmd = match profile.index.launch_target {
+            LaunchTarget::OpenUO => {
+                let dir = client_versions::dir_for(profile);
+                let exe = open_uo_binary_in(&dir);
+                if !exe.exists() {
+                    anyhow::bail!("{}", t!("status.openuo_not_found"));
+                }
+
+                let mut cmd = Command::new(exe);
+                cmd.current_dir(dir);
+                cmd.arg("-settings")
+                    .arg(&settings_path)
+                    .arg("-skipupdatecheck");
+
+                if profile.index.session_password.enabled {
+                    // 密码只在启动前写入设置文件，启动后再清除
+                    let Some((_, password)) = self.session_password.take() else {
+                        anyhow::bail!("{}", t!("credential_prompt.password_missing"));
+                    };
+                    session_password::write_password(&settings_path, &password)?;
+                    if profile.settings.uses_session_credentials() {
+                        cmd.arg("-username").arg(&profile.settings.username);
+                    }
+                } else if profile.settings.uses_session_credentials() {
+                    // 设置文件中没有保存账号，只通过命令行传入本次使用的账号
+                    cmd.arg("-username")
+                        .arg(&profile.settings.username)
+                        .arg("-password")
+                        .arg(crate::crypter::decrypt(&profile.settings.password));
+                }
+                if profile.settings.auto_login {
+                    cmd.arg("-skiploginscreen");
+                    if !profile.index.last_character_name.is_empty() {
+                        let last = profile.index.last_character_name.clone();
+                        cmd.arg("-lastcharactername").arg(last);
+                    }
+                }
+                if self.staff_unlocked() {
+                    let args = staff_mode::args(&profile.index.staff, &self.staff_manifest);
+                    if !args.is_empty() {
+                        tracing::info!("Staff mode arguments: {}", args.join(" "));
+                        cmd.args(args);
+                    }
+                }
+                cmd
+            }
+            LaunchTarget::ClassicClient | LaunchTarget::Custom => {
+                let Some(exe) = launch_target::resolve_executable(profile) else {
+                    anyhow::bail!("{}", t!("status.executable_not_set"));
+                };
+                if !exe.exists() {
+                    anyhow::bail!("{}: {}", t!("status.executable_not_found"), exe.display());
+                }
+
+                let mut cmd = Command::new(&exe);
+                // 只有文件名时 parent 为空路径，不能作为工作目录
+                if let Some(dir) = exe.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    cmd.current_dir(dir);
+                }
+                cmd.args(launch_target::expand_args(profile.index.argument_template(), profile, &settings_path));
+                cmd
+            }
+        };
+
+        if !profile.index.additional_args.is_empty() {
+            cmd.args(profile.index.additional_args.split_whitespace());
+        }
+
+        if profile.index.prefer_dedicated_gpu {
+            let exe = std::path::PathBuf::from(cmd.get_program());
+            if let Err(e) = gpu_preference::prefer_dedicated(&mut cmd, &exe) {
+                tracing::warn!("设置独立显卡偏好失败: {:#}", e);
+                self.add_log(LogEntryType::Warning, &format!("⚠ {}: {:#}", t!("profile_editor.prefer_dedicated_gpu_failed"), e), None);
+            }
+        }
+
+        let tie_children = self.config.launcher_settings.tie_children;
+        if tie_children {
+            child_group::prepare(&mut cmd);
+        }
+        let child = cmd.spawn()
+            .with_context(|| t!("status.launch_failed").to_string())?;
+        if tie_children {
+            child_group::adopt(&child);
+        }
+        if let Err(e) = process_tuning::apply(&child, &profile.index.process_tuning) {
+            tracing::warn!("调整游戏进程失败: {:#}", e);
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}: {:#}", t!("process_tuning.apply_failed"), e), None);
+        }
+        Ok(child)
+    }
+
+    fn save_history(&self) {
+        if let Err(e) = self.history.save() {
+            tracing::warn!("Failed to save launch history: {}", e);
+        }
+    }
+
+    /// 启动历史标签页
+    fn show_history_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t!("history.filter"));
+            let selected_text = self
+                .history_filter
+                .as_ref()
+                .and_then(|id| self.config.profiles.iter().find(|p| &p.index.file_name == id))
+                .map(|p| p.index.name.clone())
+                .unwrap_or_else(|| t!("history.all_profiles").to_string());
+            egui::ComboBox::from_id_source("history_filter_combo")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.history_filter, None, t!("history.all_profiles"));
+                    for profile in &self.config.profiles {
+                        ui.selectable_value(
+                            &mut self.history_filter,
+                            Some(profile.index.file_name.clone()),
+                            &profile.index.name,
+                        );
+                    }
+                });
+            ui.add_space(12.0);
+            let prompt = &mut self.config.launcher_settings.prompt_session_notes;
+            if ui.checkbox(prompt, t!("history.prompt_notes").as_ref()).changed()
+                && let Err(e) = save_launcher_settings(&self.config.launcher_settings)
+            {
+                tracing::warn!("Failed to save launcher settings: {}", e);
+            }
+            ui.add_space(12.0);
+            if ui.button(t!("history.export")).on_hover_text(t!("history.export_hint")).clicked() {
+                self.export_playtime();
+            }
+            if ui.button(t!("ab_compare.open")).on_hover_text(t!("ab_compare.open_hint")).clicked() {
+                self.open_ab_compare();
+            }
+        });
+        ui.add_space(8.0);
+
+        let max_width = ui.available_width() * 0.7;
+        let mut edit_note = None;
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                ui.set_max_width(max_width);
+                let mut records = self.history.filtered(self.history_filter.as_deref()).peekable();
+                if records.peek().is_none() {
+                    ui.label(
+                        RichText::new(t!("history.empty"))
+                            .size(12.0)
+                            .color(egui::Color32::from_rgb(150, 150, 150))
+                    );
+                    return;
+                }
+
+                egui::Grid::new("history_grid")
+                    .striped(true)
+                    .spacing(egui::vec2(16.0, 4.0))
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(t!("history.time")).strong());
+                        ui.label(RichText::new(t!("history.profile")).strong());
+                        ui.label(RichText::new(t!("history.outcome")).strong());
+                        ui.label(RichText::new(t!("history.exit_code")).strong());
+                        ui.label(RichText::new(t!("history.duration")).strong());
+                        ui.label(RichText::new(t!("history.note")).strong());
+                        ui.end_row();
+
+                        for record in records {
+                            let (outcome, color) = outcome_label(record.outcome);
+                            ui.label(crate::clock::format_local(record.started_at));
+                            ui.label(&record.profile_name);
+                            let outcome_label = ui.label(RichText::new(outcome).color(color));
+                            if let Some(error) = &record.error {
+                                outcome_label.on_hover_text(error);
+                            }
+                            ui.label(record.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string()));
+                            ui.label(
+                                record
+                                    .duration_secs
+                                    .map(humanize::duration)
+                                    .unwrap_or_else(|| "-".to_string()),
+                            );
+                            let note_text = match &record.note {
+                                Some(note) => truncate_chars(note.lines().next().unwrap_or_default(), 24),
+                                None => "📝".to_string(),
+                            };
+                            let response = ui.add(egui::Label::new(note_text).sense(egui::Sense::click()));
+                            let response = match &record.note {
+                                Some(note) => response.on_hover_text(note),
+                                None => response.on_hover_text(t!("history.add_note")),
+                            };
+                            if response.clicked() {
+                                edit_note = Some((record.id.clone(), record.note.clone().unwrap_or_default()));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        if edit_note.is_some() {
+            self.session_note = edit_note;
+        }
+    }
+
+    /// 导出（当前筛选范围内的）每日游戏时长
+    fn export_playtime(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name("playtime.csv")
+            .add_filter("CSV", &["csv"])
+            .add_filter("JSON", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        let rows = playtime::daily_totals(self.history.filtered(self.history_filter.as_deref()), crate::clock::local_offset());
+        match playtime::export(&rows, &path) {
+            Ok(()) => self.add_log(LogEntryType::Success, &format!("✓ {}", t!("history.exported", path = path.display())), None),
+            Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("history.export_failed"), e), None),
+        }
+    }
+
+    /// 游戏笔记输入框
+    fn show_session_note_window(&mut self, ctx: &egui::Context) {
+        let Some((record_id, text)) = &mut self.session_note else {
+            return;
+        };
+        let (profile_name, started_at) = self
+            .history
+            .get(record_id)
+            .map(|r| (r.profile_name.clone(), r.started_at))
+            .unwrap_or_default();
+
+        let mut save = false;
+        let mut close = false;
+        egui::Window::new(t!("history.note_title"))
+            .id(egui::Id::new("session_note_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!("{} · {}", profile_name, crate::clock::format_local(started_at)))
+                        .size(12.0)
+                        .color(Color32::from_rgb(180, 180, 180)),
+                );
+                ui.add(
+                    egui::TextEdit::multiline(text)
+                        .hint_text(t!("history.note_hint"))
+                        .desired_rows(4)
+                        .desired_width(320.0),
+                );
+                ui.horizontal(|ui| {
+                    save = ui.button(t!("profile_editor.save")).clicked();
+                    close = ui.button(t!("history.note_skip")).clicked();
+                });
+            });
+
+        if save {
+            self.history.set_note(record_id, text);
+            self.save_history();
+        }
+        if save || close {
+            self.session_note = None;
+        }
+    }
+
+    fn open_ab_compare(&mut self) {
+        if self.ab_compare.is_some() {
+            return;
+        }
+        let a = self.config.active_profile;
+        let b = if a == 0 { 1 } else { 0 };
+        self.ab_compare = Some(AbCompare {
+            a,
+            b,
+            timeout_secs: ab_compare::DEFAULT_TIMEOUT_SECS,
+            run: None,
+            recorded: false,
+        });
+    }
+
+    /// 同时启动两个 profile，不经过更新检查和服务器查询
+    fn start_ab_compare(&mut self) {
+        let Some(ab) = &self.ab_compare else {
+            return;
+        };
+        let timeout = Duration::from_secs(ab.timeout_secs);
+        let (Some(a), Some(b)) = (self.config.profiles.get(ab.a).cloned(), self.config.profiles.get(ab.b).cloned()) else {
+            return;
+        };
+        self.add_log(
+            LogEntryType::Info,
+            &format!("⟳ {}", t!("ab_compare.started", a = &a.index.name, b = &b.index.name)),
+            None,
+        );
+        let mut sides = [
+            AbSide::new(&a.index.name, &a.index.last_character_name),
+            AbSide::new(&b.index.name, &b.index.last_character_name),
+        ];
+        let started = Instant::now();
+        for (side, profile) in sides.iter_mut().zip([a, b]) {
+            match self.launch_profile(&profile) {
+                Ok(game) => {
+                    ab_compare::spawn_probe(
+                        game.pid,
+                        profile.index.last_character_name.clone(),
+                        game.record_id.clone(),
+                        game.control,
+                        timeout,
+                        self.game_tx.clone(),
+                    );
+                    side.record_id = Some(game.record_id);
+                }
+                Err(err) => side.outcome = AbOutcome::Failed(format!("{err:#}")),
+            }
+        }
+        if let Some(ab) = &mut self.ab_compare {
+            ab.run = Some(AbComparison::new(sides, timeout, started));
+            ab.recorded = false;
+        }
+    }
+
+    /// 超时检查，结束后把结果写入启动历史
+    fn poll_ab_compare(&mut self) {
+        let Some(ab) = &mut self.ab_compare else {
+            return;
+        };
+        let Some(run) = &mut ab.run else {
+            return;
+        };
+        run.tick(Instant::now());
+        if !run.is_finished() || ab.recorded {
+            return;
+        }
+        ab.recorded = true;
+        let results: Vec<_> = run
+            .sides
+            .iter()
+            .filter_map(|side| Some((side.record_id.clone()?, side.outcome.label())))
+            .collect();
+        for (record_id, label) in results {
+            self.history.set_note(&record_id, &t!("ab_compare.history_note", result = label));
+        }
+        self.save_history();
+        self.add_log(LogEntryType::Info, &t!("ab_compare.finished"), None);
+    }
+
+    /// A/B 对比窗口：选择两个 profile，显示各自是否进入了游戏
+    fn show_ab_compare(&mut self, ctx: &egui::Context) {
+        let Some(ab) = &mut self.ab_compare else {
+            return;
+        };
+        let names: Vec<String> = self.config.profiles.iter().map(|p| p.index.name.clone()).collect();
+        let shard = self
+            .config
+            .profiles
+            .get(ab.a)
+            .map(|p| format!("{}:{}", p.settings.ip, p.settings.port))
+            .unwrap_or_default();
+        let now = Instant::now();
+        let mut start = false;
+        let mut open = true;
+        egui::Window::new(t!("ab_compare.title"))
+            .id(egui::Id::new("ab_compare_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                ui.label(RichText::new(t!("ab_compare.description")).size(12.0));
+                ui.add_space(6.0);
+                let running = ab.run.as_ref().is_some_and(|run| !run.is_finished());
+                ui.add_enabled_ui(!running, |ui| {
+                    egui::Grid::new("ab_compare_grid").num_columns(2).show(ui, |ui| {
+                        for (label, idx) in [("A", &mut ab.a), ("B", &mut ab.b)] {
+                            ui.label(label);
+                            egui::ComboBox::from_id_source(format!("ab_compare_{label}"))
+                                .selected_text(names.get(*idx).cloned().unwrap_or_default())
+                                .show_ui(ui, |ui| {
+                                    for (i, name) in names.iter().enumerate() {
+                                        ui.selectable_value(idx, i, name);
+                                    }
+                                });
+                            ui.end_row();
+                        }
+                        ui.label(t!("ab_compare.timeout"));
+                        ui.add(egui::DragValue::new(&mut ab.timeout_secs).clamp_range(15..=600).suffix(" s"));
+                        ui.end_row();
+                    });
+                    ui.add_space(6.0);
+                    let ready = ab.a != ab.b && ab.a < names.len() && ab.b < names.len();
+                    let btn = egui::Button::new(t!("ab_compare.start")).fill(Color32::from_rgb(80, 180, 80));
+                    start = ui.add_enabled(ready, btn).clicked();
+                });
+
+                let Some(run) = &ab.run else {
+                    return;
+                };
+                ui.separator();
+                egui::Grid::new("ab_compare_results").num_columns(3).show(ui, |ui| {
+                    for (label, side) in ["A", "B"].iter().zip(&run.sides) {
+                        let color = match side.outcome {
+                            AbOutcome::Connected { .. } => Color32::from_rgb(120, 220, 120),
+                            AbOutcome::Waiting | AbOutcome::Unconfirmed => Color32::from_rgb(200, 200, 200),
+                            _ => Color32::from_rgb(230, 120, 120),
+                        };
+                        ui.label(*label);
+                        ui.label(&side.profile_name);
+                        ui.label(RichText::new(side.outcome.label()).color(color));
+                        ui.end_row();
+                    }
+                });
+                if !run.is_finished() {
+                    let secs = run.remaining(now).as_secs();
+                    ui.label(RichText::new(t!("ab_compare.remaining", secs = secs)).size(11.0));
+                    ctx.request_repaint_after(Duration::from_secs(1));
+                    return;
+                }
+                let report = run.report(&shard);
+                if let Some(verdict) = report.lines().last() {
+                    ui.label(RichText::new(verdict).strong());
+                }
+                if ui.button(t!("ab_compare.copy_report")).clicked() {
+                    ui.output_mut(|o| o.copied_text = report);
+                }
+            });
+        if start {
+            self.start_ab_compare();
+        }
+        if !open {
+            self.ab_compare = None;
+        }
+    }
+
+    /// 切换语言后刷新依赖语言的状态，在整帧绘制之前调用，界面一次性切换
+    ///
+    /// 窗口使用固定的 id，标题变化不会丢失位置和大小；已有的日志保持原来的语言。
+    fn on_locale_changed(&mut self, ctx: &egui::Context) {
+        self.locale_generation = crate::i18n::locale_generation();
+        self.current_locale = crate::i18n::current_locale();
+        sort_profiles(&mut self.config);
+        // 保存档案时按界面语言设置游戏语言
+        if let Some(info) = &mut self.screen_info {
+            info.lang = self.current_locale.clone();
+        }
+        if self.lint_report.is_some() {
+            self.lint_report = Some(profile_lint::report(&self.lint_results()));
+        }
+        ctx.request_repaint();
+    }
+
+    fn lint_results(&self) -> Vec<profile_lint::ProfileLint> {
+        let ctx = LintContext {
+            local_open_uo: self.open_uo_version.clone(),
+            latest_open_uo: self.remote_open_uo.clone(),
+            required_open_uo: self.required_open_uo.clone(),
+        };
+        self.config.profiles.iter().map(|p| profile_lint::lint_profile(p, &ctx)).collect()
+    }
+
+    /// 检查所有档案，结果显示在报告窗口中
+    fn lint_all_profiles(&mut self) {
+        let results = self.lint_results();
+        let failing = results.iter().filter(|r| r.has_errors()).count();
+        if failing > 0 {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", humanize::count("lint.summary_errors", failing as u64)), None);
+        } else {
+            self.add_log(LogEntryType::Success, &format!("✓ {}", t!("lint.summary_clean")), None);
+        }
+        self.lint_report = Some(profile_lint::report(&results));
+    }
+
+    fn show_lint_report(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.lint_report else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(t!("lint.title"))
+            .id(egui::Id::new("lint_report_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(RichText::new(t!("lint.hint")).size(12.0));
+                ui.add_space(6.0);
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut report.as_str())
+                            .font(egui::TextStyle::Monospace)
+                            .desired_width(520.0),
+                    );
+                });
+                ui.add_space(6.0);
+                if ui.button(t!("lint.copy")).clicked() {
+                    ui.output_mut(|o| o.copied_text = report.clone());
+                }
+            });
+        if !open {
+            self.lint_report = None;
+        }
+    }
+
+    fn show_settings_export(&mut self, ctx: &egui::Context) {
+        let Some(options) = &mut self.settings_export else {
+            return;
+        };
+        let mut open = true;
+        let mut export = false;
+        let mut share = false;
+        egui::Window::new(t!("settings_export.title"))
+            .id(egui::Id::new("settings_export_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(RichText::new(t!("settings_export.hint")).size(12.0));
+                ui.add_space(6.0);
+                ui.checkbox(&mut options.include_profiles, t!("settings_export.include_profiles"));
+                ui.checkbox(&mut options.include_password, t!("settings_export.include_password"))
+                    .on_hover_text(t!("settings_export.include_password_hint"));
+                ui.add_space(6.0);
+                export = ui.button(t!("settings_export.choose_folder")).clicked();
+
+                // 分享给其他玩家：一个可以导入 Launcher 的档案包
+                ui.separator();
+                ui.label(RichText::new(t!("profile_bundle.hint")).size(12.0));
+                ui.add_space(6.0);
+                ui.checkbox(&mut self.bundle_encrypt_password, t!("profile_bundle.encrypt_password"))
+                    .on_hover_text(t!("profile_bundle.encrypt_password_hint"));
+                if self.bundle_encrypt_password {
+                    ui.horizontal(|ui| {
+                        ui.label(t!("profile_bundle.passphrase"));
+                        ui.add(egui::TextEdit::singleline(&mut self.bundle_passphrase).password(true));
+                    });
+                }
+                ui.add_space(6.0);
+                let ready = !self.bundle_encrypt_password || !self.bundle_passphrase.is_empty();
+                share = ui.add_enabled(ready, egui::Button::new(t!("profile_bundle.save"))).clicked();
+            });
+        let options = *options;
+        if !open {
+            self.settings_export = None;
+            self.bundle_passphrase.clear();
+        }
+        if share {
+            self.export_profile_bundle();
+            return;
+        }
+        if !export {
+            return;
+        }
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+        self.settings_export = None;
+        let Some(profile) = self.active_profile().cloned() else {
+            return;
+        };
+        match settings_export::export(&profile, &dir, options) {
+            Ok(summary) => self.add_log(
+                LogEntryType::Success,
+                &format!(
+                    "✓ {}",
+                    t!(
+                        "settings_export.exported",
+                        path = summary.settings_path.display(),
+                        count = summary.profile_files
+                    )
+                ),
+                None,
+            ),
+            Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_export.failed"), e), None),
+        }
+    }
+
+    /// 把当前档案保存为档案包
+    fn export_profile_bundle(&mut self) {
+        let Some(profile) = self.active_profile().cloned() else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(t!("profile_bundle.filter"), &[BUNDLE_EXTENSION])
+            .set_file_name(format!("{}.{}", profile.index.name, BUNDLE_EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+        let passphrase = self.bundle_encrypt_password.then_some(self.bundle_passphrase.as_str());
+        match profile_bundle::export(&profile, &path, passphrase) {
+            Ok(()) => {
+                self.settings_export = None;
+                self.bundle_passphrase.clear();
+                self.add_log(LogEntryType::Success, &format!("✓ {}", t!("profile_bundle.exported", path = path.display())), None);
+            }
+            Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_export.failed"), e), None),
+        }
+    }
+
+    /// 从档案包创建新档案，包中有加密的密码时先询问口令
+    fn import_profile_bundle(&mut self, path: &Path) {
+        match profile_bundle::read(path) {
+            Ok(bundle) if bundle.has_password() => self.bundle_import = Some((bundle, String::new(), None)),
+            Ok(bundle) => match bundle.to_profile(None) {
+                Ok(profile) => self.add_imported_profile(profile),
+                Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_import.failed"), e), None),
+            },
+            Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_import.failed"), e), None),
+        }
+    }
+
+    fn show_bundle_import(&mut self, ctx: &egui::Context) {
+        let Some((bundle, passphrase, error)) = &mut self.bundle_import else {
+            return;
+        };
+        let mut import = false;
+        let mut skip_password = false;
+        let mut cancel = false;
+        egui::Window::new(t!("profile_bundle.import_title"))
+            .id(egui::Id::new("bundle_import_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                ui.label(RichText::new(t!("profile_bundle.import_message", name = bundle.name())).size(13.0));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.label(t!("profile_bundle.passphrase"));
+                    let response = ui.add(egui::TextEdit::singleline(passphrase).password(true));
+                    import = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                });
+                if let Some(error) = error {
+                    ui.label(RichText::new(format!("⚠ {}", error)).size(11.0).color(Color32::from_rgb(200, 100, 100)));
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new(t!("profile_bundle.import")).fill(Color32::from_rgb(80, 120, 200));
+                    import |= ui.add_enabled(!passphrase.is_empty(), btn).clicked();
+                    skip_password = ui.button(t!("profile_bundle.import_without_password")).clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        let result = if import && !passphrase.is_empty() {
+            bundle.to_profile(Some(passphrase.as_str()))
+        } else if skip_password {
+            bundle.to_profile(None)
+        } else {
+            if cancel {
+                self.bundle_import = None;
+            }
+            return;
+        };
+        match result {
+            Ok(profile) => {
+                self.bundle_import = None;
+                self.add_imported_profile(profile);
+            }
+            Err(e) => *error = Some(format!("{e:#}")),
+        }
+    }
+
+    /// 保存导入的档案并切换到它
+    fn add_imported_profile(&mut self, profile: ProfileConfig) {
+        if let Err(e) = save_profile(&profile) {
+            self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_import.failed"), e), None);
+            return;
+        }
+        let name = profile.index.name.clone();
+        self.config.profiles.push(profile);
+        self.config.active_profile = self.config.profiles.len().saturating_sub(1);
+        sort_profiles(&mut self.config);
+        self.add_log(LogEntryType::Success, &format!("✓ {}", t!("settings_import.imported", name = name)), None);
+    }
+
+    /// 从任意 OpenUO 的 settings.json 或档案包创建新档案
+    fn import_settings_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("settings.json", &["json"])
+            .add_filter(t!("profile_bundle.filter"), &[BUNDLE_EXTENSION, "zip"])
+            .pick_file()
+        else {
+            return;
+        };
+        if path.extension().is_some_and(|ext| ext != "json") {
+            self.import_profile_bundle(&path);
+            return;
+        }
+        let summary = match crate::settings_import::import(&path) {
+            Ok(summary) => summary,
+            Err(e) => {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_import.failed"), e), None);
+                return;
+            }
+        };
+        if !summary.skipped.is_empty() {
+            self.add_log(
+                LogEntryType::Warning,
+                &format!("⚠ {}", t!("settings_import.skipped", fields = summary.skipped.join(", "))),
+                None,
+            );
+        }
+        self.add_imported_profile(summary.profile);
+    }
+
+    /// 一键迁移旧 Launcher 的档案、密码（重新加密）和已安装的 OpenUO
+    fn migrate_legacy(&mut self, dir: PathBuf) {
+        if kiosk::active() {
+            return;
+        }
+        let Some(install) = legacy_migration::inspect(&dir) else {
+            self.add_log(LogEntryType::Error, &format!("✗ {}", t!("legacy_migration.not_found", path = dir.display())), None);
+            return;
+        };
+        let existing: Vec<String> = self.config.profiles.iter().map(|p| p.index.name.clone()).collect();
+        let summary = legacy_migration::migrate(&install, &existing);
+        for (file, error) in &summary.failed {
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}: {}", t!("legacy_migration.profile_failed", file = file), error), None);
+        }
+        let mut imported = 0;
+        for profile in summary.profiles {
+            if let Err(e) = save_profile(&profile) {
+                self.add_log(LogEntryType::Error, &format!("✗ {}: {:#}", t!("settings_import.failed"), e), None);
+                continue;
+            }
+            self.config.profiles.push(profile);
+            imported += 1;
+        }
+        sort_profiles(&mut self.config);
+        if !summary.password_lost.is_empty() {
+            let names = summary.password_lost.join(", ");
+            self.add_log(LogEntryType::Warning, &format!("⚠ {}", t!("legacy_migration.password_lost", profiles = names)), None);
+        }
+        // 本 Launcher 还没有安装 OpenUO 时沿用旧 Launcher 安装的客户端
+        if let Some(client) = install.client_dir.filter(|_| !open_uo_binary_path().exists()) {
+            let client = client.to_string_lossy().to_string();
+            self.config.launcher_settings.openuo_dir = Some(client.clone());
+            set_open_uo_dir_override(Some(client.clone()));
+            self.open_uo_version = detect_open_uo_version();
+            self.open_uo_installed_at = read_open_uo_installed_at_in(&open_uo_dir());
+            self.add_log(LogEntryType::Info, &t!("legacy_migration.client_reused", path = client), None);
+        }
+        self.config.launcher_settings.legacy_migration_offered = true;
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+        self.logs.retain(|l| !matches!(&l.action, Some(LogAction::MigrateLegacy(d)) if *d == install.dir));
+        self.add_log(
+            LogEntryType::Success,
+            &format!("✓ {}", t!("legacy_migration.done", count = imported, path = install.dir.display())),
+            None,
+        );
+    }
+
+    fn open_release_notes(&mut self, launcher: bool) {
+        let (notes, version) = if launcher {
+            (&self.launcher_notes, &self.remote_launcher)
+        } else {
+            (&self.open_uo_notes, &self.remote_open_uo)
+        };
+        let Some(notes) = notes else {
+            return;
+        };
+        let version = match &self.open_uo_chain {
+            Some(chain) if !launcher && !chain.skipped().is_empty() => format!("{} → {}", chain.from, chain.to),
+            _ => version.clone().unwrap_or_default(),
+        };
+        self.release_notes = Some(ReleaseNotesView {
+            launcher,
+            whats_new: false,
+            version,
+            blocks: release_notes::parse(notes),
+        });
+    }
+
+    /// 更新前查看最新版本的发布说明
+    fn show_release_notes(&mut self, ctx: &egui::Context) {
+        let Some(view) = &self.release_notes else {
+            return;
+        };
+        let title = if view.whats_new {
+            t!("release_notes.whats_new_title", version = view.version)
+        } else if view.launcher {
+            t!("release_notes.launcher_title", version = view.version)
+        } else {
+            t!("release_notes.openuo_title", version = view.version)
+        };
+        let mut open = true;
+        let mut update = false;
+        let mut dismiss = false;
+        egui::Window::new(title)
+            .id(egui::Id::new("release_notes_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for block in &view.blocks {
+                        show_notes_block(ui, block);
+                    }
                 });
+                ui.separator();
+                if view.whats_new {
+                    dismiss = ui.button(t!("release_notes.got_it")).clicked();
+                } else {
+                    update = ui.button(t!("release_notes.update")).clicked();
+                }
             });
+
+        if update {
+            let launcher = view.launcher;
+            self.release_notes = None;
+            if launcher {
+                self.start_launcher_update();
+            } else {
+                self.start_download();
+            }
+        } else if !open || dismiss {
+            self.release_notes = None;
+        }
+    }
+
+    fn open_release_picker(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(fetch_open_uo_releases().map_err(|e| format!("{e:#}")));
+        });
+        self.release_picker = Some(ReleasePicker {
+            rx: Some(rx),
+            releases: Vec::new(),
+            error: None,
+            side_by_side: client_versions::installed(),
         });
     }
 
-    fn poll_channels(&mut self) {
-        // 处理下载事件
-        if let Some(rx) = &self.download_rx {
-            let events: Vec<_> = rx.try_iter().collect();
-            for event in events {
-                match event {
-                    DownloadEvent::Progress { received, total } => {
-                        self.download_progress = Some((received, total));
-                    }
-                    DownloadEvent::Finished(result) => {
-                        self.download_rx = None;
-                        self.download_progress = None;
-                        
-                        match result {
-                            Ok(tag) => {
-                                if tag.starts_with("UPDATE_AND_RESTART:") {
-                                    let version = tag.strip_prefix("UPDATE_AND_RESTART:").unwrap_or("");
-                                    self.add_log(LogEntryType::Success, &format!("✅ {}", t!("log.launcher_update_complete", version = version)), None);
-                                    // 更新成功后同步远程版本，避免按钮残留
-                                    self.remote_launcher = Some(self.launcher_version.clone());
-                                    self.launcher_restarting = true;
-                                    std::thread::spawn(|| {
-                                        std::thread::sleep(std::time::Duration::from_secs(2));
-                                        std::process::exit(0);
-                                    });
-                                } else {
-                                    self.open_uo_version = Some(tag.clone());
-                                    self.add_log(LogEntryType::Success, &format!("✓ {}", t!("log.openuo_download_complete", version = &tag)), None);
-                                }
-                                self.downloading_launcher = false;
-                                self.download_failed = false;
-                            }
-                            Err(err) => {
-                                self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("log.download_error"), err), Some(LogAction::RetryDownload));
-                                self.downloading_launcher = false;
-                                self.download_failed = true;
-                            }
-                        }
+    /// 列出 OpenUO 的历史版本，可以安装任意一个
+    /// 磁盘占用：OpenUO 安装、临时下载和档案，可以清理残留的临时文件
+    fn open_storage(&mut self) {
+        self.storage = Some(StorageView {
+            rx: Some(storage::spawn_scan(false)),
+            game_backups: profile_backup::list_game_profiles(),
+            linked: linked_uo_dirs(&self.config.profiles),
+            ..Default::default()
+        });
+    }
+
+    fn show_storage(&mut self, ctx: &egui::Context) {
+        let Some(view) = &mut self.storage else {
+            return;
+        };
+        let mut freed = None;
+        if let Some(rx) = &view.rx
+            && let Ok((usage, bytes)) = rx.try_recv()
+        {
+            view.usage = Some(usage);
+            view.rx = None;
+            freed = (bytes > 0).then_some(bytes);
+        }
+        let mut logs = Vec::new();
+        let mut dedup_done = false;
+        for event in view.dedup_rx.iter().flat_map(|rx| rx.try_iter()) {
+            match event {
+                DedupEvent::Progress { hashed, total } => view.dedup_progress = Some((hashed, total)),
+                DedupEvent::Found(groups) => {
+                    view.duplicates = Some(groups);
+                    dedup_done = true;
+                }
+                DedupEvent::Consolidated { freed, failed } => {
+                    if freed > 0 {
+                        logs.push((LogEntryType::Success, format!("🔗 {}", t!("data_dedup.linked", size = humanize::bytes(freed)))));
+                    }
+                    for e in failed {
+                        logs.push((LogEntryType::Error, format!("✗ {}: {}", t!("data_dedup.link_failed"), e)));
                     }
+                    view.duplicates = None;
+                    view.rx = Some(storage::spawn_scan(false));
+                    dedup_done = true;
+                }
+                DedupEvent::CopiedBack(result) => {
+                    logs.push(match result {
+                        Ok(dir) => (LogEntryType::Success, format!("✓ {}", t!("data_dedup.copied_back", path = dir.display()))),
+                        Err(e) => (LogEntryType::Error, format!("✗ {}: {}", t!("data_dedup.copy_back_failed"), e)),
+                    });
+                    view.duplicates = None;
+                    view.rx = Some(storage::spawn_scan(false));
+                    dedup_done = true;
+                }
+                DedupEvent::Failed(e) => {
+                    logs.push((LogEntryType::Error, format!("✗ {}: {}", t!("data_dedup.scan_failed"), e)));
+                    dedup_done = true;
                 }
             }
         }
-        
-        // 处理更新检查事件
-        if let Some(rx) = &self.update_rx {
-            let events: Vec<_> = rx.try_iter().collect();
-            for event in events {
-                match event {
-                    UpdateEvent::OpenUO(res) => {
-                        self.checking_open_uo = false;
-                        match res {
-                            Ok(v) => {
-                                self.remote_open_uo = Some(v.clone());
-                                if let Some(local) = &self.open_uo_version {
-                                    if version_newer(&v, local) {
-                                        self.add_log(LogEntryType::Info, &format!("{}: {}", t!("log.openuo_new_version"), v), Some(LogAction::UpdateOpenUO));
-                                    } else {
-                                        self.add_log(LogEntryType::Success, &format!("✓ {}: {}", t!("log.openuo_latest"), v), None);
-                                        self.logs.retain(|l| !matches!(l.action, Some(LogAction::UpdateOpenUO)));
+        if dedup_done {
+            view.dedup_rx = None;
+            view.dedup_progress = None;
+            view.consolidating = false;
+            view.copying_back = false;
+            view.linked = linked_uo_dirs(&self.config.profiles);
+        }
+
+        let busy = self.events.is_downloading() || operation_lock::current().is_some();
+        let game_running = self.external_game.is_some() || self.history.records.iter().any(|r| r.outcome == LaunchOutcome::Running);
+        let mut clean = false;
+        let mut refresh = false;
+        let mut find_duplicates = false;
+        let mut consolidate = false;
+        let mut confirm_link = view.confirm_link;
+        let mut copy_back = None;
+        let mut update_backup = self.config.launcher_settings.update_backup.clone();
+        let mut back_up_now = false;
+        let mut restore_backup = None;
+        let mut open = true;
+        egui::Window::new(t!("storage.title"))
+            .id(egui::Id::new("storage_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                let Some(usage) = view.usage.as_ref().filter(|_| view.rx.is_none()) else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t!("storage.scanning"));
+                    });
+                    return;
+                };
+                egui::Grid::new("storage_grid").striped(true).spacing([16.0, 6.0]).show(ui, |ui| {
+                    let rows = [
+                        (t!("storage.install"), usage.install, open_uo_dir()),
+                        (t!("storage.profiles"), usage.profiles, profiles_dir()),
+                        (t!("storage.backups"), usage.backups, backups_dir()),
+                        (t!("storage.temp"), usage.temp_size(), std::env::temp_dir()),
+                    ];
+                    for (label, size, dir) in rows {
+                        ui.label(label);
+                        ui.label(RichText::new(humanize::bytes(size)).strong());
+                        ui.label(RichText::new(dir.display().to_string()).size(11.0).monospace());
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(8.0);
+                let stale = usage.temp.iter().filter(|e| e.stale).count();
+                if usage.temp.len() > stale {
+                    ui.label(
+                        RichText::new(t!("storage.temp_in_use", count = usage.temp.len() - stale))
+                            .size(11.0)
+                            .color(Color32::from_rgb(150, 150, 150)),
+                    );
+                }
+                ui.horizontal(|ui| {
+                    let label = t!("storage.clean", size = humanize::bytes(usage.stale_size()));
+                    let response = ui.add_enabled(stale > 0 && !busy, egui::Button::new(format!("🧹 {}", label)));
+                    let response = if busy { response.on_disabled_hover_text(t!("storage.busy")) } else { response };
+                    clean = response.on_hover_text(t!("storage.clean_hint")).clicked();
+                    refresh = ui.button(t!("storage.refresh")).clicked();
+                });
+
+                ui.separator();
+                ui.label(RichText::new(t!("update_backup.title")).strong());
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut update_backup.enabled, t!("update_backup.enabled").as_ref()).on_hover_text(t!("update_backup.enabled_hint"));
+                    ui.label(t!("profile_backup.keep"));
+                    ui.add(egui::DragValue::new(&mut update_backup.keep).clamp_range(1..=profile_backup::MAX_KEEP));
+                });
+                let response = ui.add_enabled(!game_running, egui::Button::new(format!("💾 {}", t!("update_backup.now"))));
+                back_up_now = response.on_disabled_hover_text(t!("profile_backup.restore_running")).clicked();
+                egui::CollapsingHeader::new(t!("profile_backup.list", count = view.game_backups.len()))
+                    .id_source("update_backup_list")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                            for snapshot in &view.game_backups {
+                                ui.horizontal(|ui| {
+                                    ui.label(crate::clock::format_local(snapshot.created));
+                                    let size = std::fs::metadata(&snapshot.path).map(|m| m.len()).unwrap_or(0);
+                                    if size > 0 {
+                                        ui.label(RichText::new(humanize::bytes(size)).size(11.0).color(Color32::from_rgb(150, 150, 150)));
                                     }
-                                } else {
-                                    self.add_log(LogEntryType::Info, &format!("{}: {}", t!("log.openuo_not_installed"), v), Some(LogAction::UpdateOpenUO));
-                                }
-                            }
-                            Err(e) => {
-                                self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("log.openuo_check_error"), e), None);
+                                    let response = ui.add_enabled(!game_running && !busy, egui::Button::new(t!("profile_backup.restore")));
+                                    let response = response.on_disabled_hover_text(t!("profile_backup.restore_running"));
+                                    if response.on_hover_text(t!("update_backup.restore_hint")).clicked() {
+                                        restore_backup = Some(snapshot.clone());
+                                    }
+                                });
                             }
+                        });
+                    });
+
+                ui.separator();
+                ui.label(RichText::new(t!("data_dedup.title")).strong());
+                if view.dedup_rx.is_some() {
+                    let (hashed, total) = view.dedup_progress.unwrap_or_default();
+                    let progress = if total > 0 { hashed as f32 / total as f32 } else { 0.0 };
+                    let label = if view.consolidating {
+                        t!("data_dedup.linking")
+                    } else if view.copying_back {
+                        t!("data_dedup.copying_back")
+                    } else {
+                        t!("data_dedup.scanning")
+                    };
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(label);
+                    });
+                    if !view.consolidating && !view.copying_back {
+                        ui.add(egui::ProgressBar::new(progress).desired_width(300.0).show_percentage());
+                    }
+                    return;
+                }
+                for dir in &view.linked {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(format!("🔗 {}", dir.display())).size(11.0).monospace());
+                        let response = ui.add_enabled(!game_running, egui::Button::new(t!("data_dedup.copy_back")));
+                        let response = if game_running { response.on_disabled_hover_text(t!("data_dedup.copy_back_running")) } else { response };
+                        if response.on_hover_text(t!("data_dedup.copy_back_hint")).clicked() {
+                            copy_back = Some(dir.clone());
                         }
+                    });
+                }
+                let Some(groups) = &view.duplicates else {
+                    find_duplicates = ui
+                        .button(format!("🔍 {}", t!("data_dedup.find")))
+                        .on_hover_text(t!("data_dedup.find_hint"))
+                        .clicked();
+                    return;
+                };
+                if groups.is_empty() {
+                    ui.label(RichText::new(t!("data_dedup.none")).color(Color32::from_rgb(150, 150, 150)));
+                    return;
+                }
+                for group in groups {
+                    ui.label(RichText::new(format!("✔ {}", group.keep.display())).size(11.0).monospace());
+                    for duplicate in &group.duplicates {
+                        ui.label(RichText::new(format!("   🔗 {}", duplicate.display())).size(11.0).monospace());
                     }
-                    UpdateEvent::Launcher(res) => {
-                        self.checking_launcher = false;
-                        match res {
-                            Ok(v) => {
-                                self.remote_launcher = Some(v.clone());
-                                if version_newer(&v, &self.launcher_version) {
-                                    self.add_log(LogEntryType::Info, &format!("{}: {}", t!("log.launcher_new_version"), v), Some(LogAction::UpdateLauncher));
-                                } else {
-                                    self.add_log(LogEntryType::Success, &format!("✓ {}: {}", t!("log.launcher_latest"), v), None);
-                                    self.logs.retain(|l| !matches!(l.action, Some(LogAction::UpdateLauncher)));
-                                }
+                }
+                let savings = groups.iter().map(DuplicateGroup::savings).sum();
+                let button = egui::Button::new(format!("🔗 {}", t!("data_dedup.link", size = humanize::bytes(savings))));
+                let response = ui.add_enabled(!game_running, button);
+                let response = if game_running { response.on_disabled_hover_text(t!("data_dedup.game_running")) } else { response };
+                if response.on_hover_text(t!("data_dedup.link_hint")).clicked() {
+                    confirm_link = true;
+                }
+                if confirm_link {
+                    ui.label(RichText::new(format!("⚠ {}", t!("data_dedup.confirm"))).color(Color32::from_rgb(230, 180, 80)));
+                    ui.horizontal(|ui| {
+                        consolidate = ui.add_enabled(!game_running, egui::Button::new(t!("data_dedup.confirm_link"))).clicked();
+                        if ui.button(t!("data_dedup.cancel")).clicked() {
+                            confirm_link = false;
+                        }
+                    });
+                }
+            });
+        view.confirm_link = confirm_link && !consolidate;
+        if clean || refresh {
+            view.rx = Some(storage::spawn_scan(clean));
+        }
+        if find_duplicates {
+            view.dedup_rx = Some(data_dedup::spawn_scan(profile_uo_dirs(&self.config.profiles)));
+        }
+        if consolidate && let Some(groups) = view.duplicates.clone() {
+            view.dedup_rx = Some(data_dedup::spawn_consolidate(groups));
+            view.consolidating = true;
+        }
+        if let Some(dir) = copy_back {
+            view.dedup_rx = Some(data_dedup::spawn_copy_back(dir));
+            view.copying_back = true;
+        }
+        if back_up_now {
+            logs.push(match profile_backup::snapshot_game_profiles(update_backup.keep) {
+                Ok(Some(_)) => (LogEntryType::Success, format!("✓ {}", t!("update_backup.created"))),
+                Ok(None) => (LogEntryType::Info, t!("update_backup.nothing").to_string()),
+                Err(e) => (LogEntryType::Error, format!("✗ {}: {:#}", t!("profile_backup.failed"), e)),
+            });
+        }
+        let restored = restore_backup.is_some();
+        if let Some(snapshot) = restore_backup {
+            let time = crate::clock::format_local(snapshot.created);
+            logs.push(match profile_backup::restore_game_profiles(&snapshot, update_backup.keep) {
+                Ok(()) => (LogEntryType::Success, format!("✓ {}", t!("update_backup.restored", time = time))),
+                Err(e) => (LogEntryType::Error, format!("✗ {}: {:#}", t!("profile_backup.restore_failed"), e)),
+            });
+        }
+        if back_up_now || restored {
+            view.game_backups = profile_backup::list_game_profiles();
+        }
+        if (!open || ctx.input(|i| i.key_pressed(egui::Key::Escape))) && !view.consolidating && !view.copying_back {
+            self.storage = None;
+        }
+        if update_backup != self.config.launcher_settings.update_backup {
+            self.config.launcher_settings.update_backup = update_backup;
+            if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+                tracing::warn!("Failed to save launcher settings: {}", e);
+            }
+        }
+        if let Some(bytes) = freed {
+            self.add_log(LogEntryType::Success, &format!("🧹 {}", t!("storage.cleaned", size = humanize::bytes(bytes))), None);
+        }
+        for (kind, msg) in logs {
+            self.add_log(kind, &msg, None);
+        }
+    }
+
+    fn show_release_picker(&mut self, ctx: &egui::Context) {
+        let Some(picker) = &mut self.release_picker else {
+            return;
+        };
+        if let Some(rx) = &picker.rx
+            && let Ok(result) = rx.try_recv()
+        {
+            match result {
+                Ok(releases) => picker.releases = releases,
+                Err(e) => picker.error = Some(e),
+            }
+            picker.rx = None;
+        }
+
+        let installed = self.open_uo_version.clone();
+        let active = self.config.profiles.get(self.config.active_profile).filter(|p| p.index.launch_target == LaunchTarget::OpenUO);
+        let pinned = active.and_then(|p| p.index.pinned_open_uo.clone());
+        let active_name = active.map(|p| p.index.name.clone());
+        // 被任何档案固定的版本不能删除
+        let in_use: HashSet<String> = self.config.profiles.iter().filter_map(|p| p.index.pinned_open_uo.clone()).collect();
+        let game_running = self.external_game.is_some() || self.history.records.iter().any(|r| r.outcome == LaunchOutcome::Running);
+        let mut install = None;
+        let mut pin = None;
+        let mut remove = None;
+        let mut open = true;
+        egui::Window::new(t!("version.pick_title"))
+            .id(egui::Id::new("release_picker_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                if picker.rx.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(t!("version.checking"));
+                    });
+                    return;
+                }
+                if let Some(error) = &picker.error {
+                    ui.label(RichText::new(format!("✗ {}: {}", t!("version.pick_failed"), error)).color(Color32::from_rgb(255, 120, 120)));
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("release_picker_grid").striped(true).spacing([16.0, 6.0]).show(ui, |ui| {
+                        for release in &picker.releases {
+                            ui.label(RichText::new(&release.name).strong());
+                            ui.label(&release.tag_name);
+                            let date = release.published_at.as_deref().unwrap_or_default();
+                            let date_label = ui.label(date.get(..10).unwrap_or(date));
+                            if let Some(at) = crate::clock::parse_rfc3339(date) {
+                                date_label.on_hover_text(humanize::ago(crate::clock::now_unix().saturating_sub(at).max(0) as u64));
                             }
-                            Err(e) => {
-                                self.add_log(LogEntryType::Error, &format!("✗ {}: {}", t!("log.launcher_check_error"), e), None);
+                            if installed.as_deref() == Some(release.name.as_str()) {
+                                ui.label(RichText::new(t!("version.pick_installed")).color(Color32::from_rgb(100, 200, 100)));
+                            } else if ui.button(t!("version.pick_install")).clicked() {
+                                install = Some(release.tag_name.clone());
                             }
+                            let tag = &release.tag_name;
+                            ui.horizontal(|ui| {
+                                if let Some(name) = &active_name {
+                                    if pinned.as_deref() == Some(tag.as_str()) {
+                                        if ui.button(format!("📌 {}", t!("client_versions.unpin"))).clicked() {
+                                            pin = Some(None);
+                                        }
+                                    } else if ui
+                                        .button("📌")
+                                        .on_hover_text(t!("client_versions.pin_hint", profile = name, version = tag))
+                                        .clicked()
+                                    {
+                                        pin = Some(Some(tag.clone()));
+                                    }
+                                }
+                                if picker.side_by_side.iter().any(|v| v.tag == *tag) {
+                                    let removable = !in_use.contains(tag) && !game_running;
+                                    let hint = if removable { t!("client_versions.remove_hint") } else { t!("client_versions.remove_in_use") };
+                                    if ui.add_enabled(removable, egui::Button::new("🗑")).on_hover_text(hint).on_disabled_hover_text(t!("client_versions.remove_in_use")).clicked() {
+                                        remove = Some(tag.clone());
+                                    }
+                                }
+                            });
+                            ui.end_row();
                         }
-                    }
-                    UpdateEvent::Done => {}
+                    });
+                });
+            });
+
+        if let Some(tag) = install {
+            self.release_picker = None;
+            self.start_download_version(Some(tag));
+        } else if let Some(tag) = pin {
+            self.pin_active_profile(tag);
+        } else if let Some(tag) = remove {
+            match client_versions::remove(&tag) {
+                Ok(()) => self.add_log(LogEntryType::Info, &t!("client_versions.removed", version = tag), None),
+                Err(e) => self.add_log(LogEntryType::Error, &format!("✗ {:#}", e), None),
+            }
+            if let Some(picker) = &mut self.release_picker {
+                picker.side_by_side = client_versions::installed();
+            }
+        } else if !open {
+            self.release_picker = None;
+        }
+    }
+
+    /// 常见错误的说明对话框，提供一键处理
+    /// 启动前的确认对话框（profile 设置了“弹出确认”时）
+    fn show_launch_confirmation(&mut self, ctx: &egui::Context) {
+        if !self.confirming_launch {
+            return;
+        }
+        let Some(profile) = self.active_profile() else {
+            self.confirming_launch = false;
+            return;
+        };
+        let name = profile.index.name.clone();
+        let auto_login = profile.settings.auto_login && !profile.settings.username.is_empty();
+        let username = profile.settings.username.clone();
+        let mut launch = false;
+        let mut cancel = false;
+        egui::Window::new(t!("launch_confirm.title"))
+            .id(egui::Id::new("launch_confirm_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                ui.label(RichText::new(t!("launch_confirm.message", profile = &name)).size(13.0));
+                if auto_login {
+                    ui.add_space(4.0);
+                    ui.label(
+                        RichText::new(t!("launch_confirm.auto_login", username = &username))
+                            .size(12.0)
+                            .color(Color32::from_rgb(255, 210, 120)),
+                    );
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new(t!("main.launch")).fill(Color32::from_rgb(80, 180, 80));
+                    launch = ui.add(btn).clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if launch || cancel {
+            self.confirming_launch = false;
+        }
+        if launch {
+            self.request_launch();
+        }
+    }
+
+    /// 重新安装 OpenUO 前的确认对话框
+    fn show_reinstall_confirmation(&mut self, ctx: &egui::Context) {
+        if !self.confirming_reinstall {
+            return;
+        }
+        let mut reinstall = false;
+        let mut cancel = false;
+        egui::Window::new(t!("reinstall.title"))
+            .id(egui::Id::new("reinstall_confirm_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                ui.label(RichText::new(t!("reinstall.message", path = open_uo_dir().display().to_string())).size(13.0));
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(t!("reinstall.keeps"))
+                        .size(12.0)
+                        .color(Color32::from_rgb(150, 150, 150)),
+                );
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new(t!("reinstall.confirm")).fill(Color32::from_rgb(200, 120, 60));
+                    reinstall = ui.add(btn).clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if reinstall || cancel {
+            self.confirming_reinstall = false;
+        }
+        if reinstall {
+            self.start_reinstall();
+        }
+    }
+
+    /// 正在进行的工作，关闭窗口前需要确认
+    fn busy_work(&self) -> Option<String> {
+        if self.events.is_downloading() {
+            Some(t!("shutdown.busy_download").to_string())
+        } else if self.install_move_rx.is_some() {
+            Some(t!("shutdown.busy_install_move").to_string())
+        } else if self.mods.as_ref().is_some_and(|m| m.rx.is_some()) {
+            Some(t!("shutdown.busy_mods").to_string())
+        } else if self.folder_sync_rx.is_some() {
+            Some(t!("shutdown.busy_sync").to_string())
+        } else {
+            None
+        }
+    }
+
+    /// 窗口收到关闭请求：没有正在进行的工作时返回 true，否则弹出确认对话框
+    pub fn request_close(&mut self) -> bool {
+        if self.close_confirmed || self.busy_work().is_none() {
+            return true;
+        }
+        self.confirming_close = true;
+        false
+    }
+
+    /// 退出事件循环前调用：记下没完成的下载，保存设置和启动历史，返回是否可以退出
+    ///
+    /// 下载先暂停，已下载的部分留在临时目录中，下次启动时从断点继续。
+    /// 正在解压、清空安装目录或替换可执行文件时不能退出，返回 false 并显示等待的对话框。
+    pub fn shutdown(&mut self) -> bool {
+        if let Some(task) = self.events.download() {
+            if !task.stop_for_exit() {
+                self.confirming_close = true;
+                return false;
+            }
+            if let Some(pending) = &self.current_download {
+                match pending_download::save(pending) {
+                    Ok(()) => self.add_log(LogEntryType::Info, &format!("⏸ {}", t!("shutdown.download_saved")), None),
+                    Err(e) => tracing::warn!("Failed to save pending download: {:#}", e),
                 }
             }
         }
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+        self.save_history();
+        tracing::info!("Launcher closed");
+        true
     }
 
-    fn start_download(&mut self) {
-        if self.download_rx.is_some() {
+    fn show_close_confirmation(&mut self, ctx: &egui::Context) {
+        if !self.confirming_close {
             return;
         }
-        self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("log.downloading_openuo")), None);
-        let (tx, rx) = mpsc::channel();
-        let tx_progress = tx.clone();
-        std::thread::spawn(move || {
-            let result = download_and_unpack_open_uo_with_progress(move |evt| {
-                let _ = tx_progress.send(evt);
+        let Some(work) = self.busy_work() else {
+            // 工作已经完成，不再需要确认
+            self.confirming_close = false;
+            self.close_confirmed = true;
+            return;
+        };
+        // 解压或替换文件时中途退出会留下不完整的安装，只能等它完成
+        let finishing = self.events.download().is_some_and(|task| task.is_finishing());
+        if finishing {
+            self.quit_when_finished = true;
+        } else if std::mem::take(&mut self.quit_when_finished) {
+            self.confirming_close = false;
+            self.close_confirmed = true;
+            return;
+        }
+        let mut quit = false;
+        let mut cancel = false;
+        egui::Window::new(t!("shutdown.title"))
+            .id(egui::Id::new("close_confirm_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                if finishing {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(RichText::new(t!("shutdown.finishing")).size(13.0));
+                    });
+                } else {
+                    ui.label(RichText::new(t!("shutdown.message", work = work)).size(13.0));
+                }
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(if finishing { t!("shutdown.finishing_hint") } else { t!("shutdown.resume_hint") })
+                        .size(12.0)
+                        .color(Color32::from_rgb(150, 150, 150)),
+                );
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new(t!("shutdown.quit")).fill(Color32::from_rgb(200, 120, 60));
+                    quit = ui.add_enabled(!finishing, btn).clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
             });
-            let _ = tx.send(DownloadEvent::Finished(result.map_err(|e| format!("{e:#}"))));
-        });
-        self.download_rx = Some(rx);
-        self.download_progress = None;
-        self.downloading_launcher = false;
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if quit || cancel {
+            self.confirming_close = false;
+        }
+        if cancel {
+            self.quit_when_finished = false;
+        }
+        self.close_confirmed = quit;
     }
 
-    fn start_launcher_update(&mut self) {
-        if self.download_rx.is_some() {
+    /// 启动前磁盘空间不足的警告，可以仍然启动
+    fn show_low_disk_warning(&mut self, ctx: &egui::Context) {
+        let Some(shortfall) = &self.low_disk else {
             return;
-        }
-        self.add_log(LogEntryType::Info, &format!("⏳ {}", t!("log.downloading_launcher")), None);
-        let (tx, rx) = mpsc::channel();
-        let tx_progress = tx.clone();
-        std::thread::spawn(move || {
-            let result = crate::github::download_launcher_update(move |evt| {
-                let _ = tx_progress.send(evt);
+        };
+        let mut launch = false;
+        let mut storage = false;
+        let mut cancel = false;
+        egui::Window::new(t!("low_disk.title"))
+            .id(egui::Id::new("low_disk_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(380.0);
+                let message = t!(
+                    "low_disk.message",
+                    path = shortfall.path.display().to_string(),
+                    free = humanize::bytes(shortfall.available),
+                    required = humanize::bytes(shortfall.required)
+                );
+                ui.label(RichText::new(message).size(13.0));
+                ui.add_space(4.0);
+                ui.label(RichText::new(t!("low_disk.hint")).size(12.0).color(Color32::from_rgb(150, 150, 150)));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new(t!("low_disk.launch_anyway")).fill(Color32::from_rgb(200, 120, 60));
+                    launch = ui.add(btn).clicked();
+                    storage = ui.button(format!("💾 {}", t!("storage.title"))).clicked();
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
             });
-            let _ = tx.send(DownloadEvent::Finished(result.map_err(|e| format!("{e:#}"))));
-        });
-        self.download_rx = Some(rx);
-        self.download_progress = None;
-        self.downloading_launcher = true;
-    }
-
-    fn trigger_update_checks(&mut self, open_uo: bool, launcher: bool) {
-        if !open_uo && !launcher {
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            cancel = true;
+        }
+        if !(launch || storage || cancel) {
             return;
         }
-        if open_uo && !self.checking_open_uo {
-            self.checking_open_uo = true;
-            self.add_log(LogEntryType::Checking, &format!("⟳ {}", t!("log.checking_openuo")), None);
+        self.low_disk = None;
+        if storage {
+            self.open_storage();
         }
-        if launcher && !self.checking_launcher {
-            self.checking_launcher = true;
-            self.add_log(LogEntryType::Checking, &format!("⟳ {}", t!("log.checking_launcher")), None);
+        if launch {
+            self.low_disk_acknowledged = true;
+            self.request_launch();
         }
-        self.last_update_poll = Instant::now();
-        self.update_rx = Some(trigger_update_check_impl(open_uo, launcher));
     }
 
-    fn maybe_schedule_updates(&mut self) {
-        if self.checking_open_uo || self.checking_launcher {
+    /// 询问本次运行使用的账号密码，只保存在内存中
+    fn show_credential_prompt(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &mut self.credential_prompt else {
+            return;
+        };
+        let mut launch = false;
+        let mut cancel = false;
+        egui::Window::new(t!("credential_prompt.title"))
+            .id(egui::Id::new("credential_prompt_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                let message = if prompt.session_only { t!("credential_prompt.session_message") } else { t!("credential_prompt.message") };
+                ui.label(RichText::new(message).size(12.0));
+                ui.add_space(6.0);
+                egui::Grid::new("credential_prompt_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(t!("profile_editor.username"));
+                    ui.text_edit_singleline(&mut prompt.username);
+                    ui.end_row();
+                    ui.label(t!("profile_editor.password"));
+                    let response = ui.add(egui::TextEdit::singleline(&mut prompt.password).password(true));
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        launch = true;
+                    }
+                    ui.end_row();
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let ready = !prompt.username.is_empty() && !prompt.password.is_empty();
+                    let btn = egui::Button::new(t!("main.launch")).fill(Color32::from_rgb(80, 180, 80));
+                    launch |= ui.add_enabled(ready, btn).clicked();
+                    launch &= ready;
+                    cancel = ui.button(t!("launch_confirm.cancel")).clicked();
+                });
+            });
+        if cancel {
+            self.credential_prompt = None;
             return;
         }
-        if self.last_update_poll.elapsed() > Duration::from_secs(600) {
-            self.trigger_update_checks(true, true);
+        if !launch {
+            return;
+        }
+        let Some(prompt) = self.credential_prompt.take() else {
+            return;
+        };
+        if let Some(profile) = self.config.profiles.get_mut(prompt.profile_idx) {
+            profile.settings.username = prompt.username;
+            if prompt.session_only {
+                self.session_password = Some((prompt.profile_idx, crate::crypter::encrypt(&prompt.password)));
+            } else {
+                // 与编辑器一致，内存中保存加密后的密码；保存档案时会因 save_account 关闭而清空
+                profile.settings.password = crate::crypter::encrypt(&prompt.password);
+                if self.password_reentry.remove(&profile.index.file_name)
+                    && let Err(e) = save_profile(profile)
+                {
+                    tracing::warn!("Failed to save profile {}: {}", profile.index.name, e);
+                }
+            }
+            self.config.active_profile = prompt.profile_idx;
+            self.request_launch();
         }
     }
 
-    fn ensure_textures(&mut self, ctx: &egui::Context) {
-        if self.background_texture.is_none() {
-            self.background_texture = load_embedded_texture(
-                ctx,
-                "launcher_background",
-                include_bytes!("../assets/background.png"),
-            );
+    fn show_error_dialog(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.error_report else {
+            return;
+        };
+        let kind = report.kind;
+        let mut chosen = None;
+        let mut close = false;
+        egui::Window::new(format!("⚠ {}", t!(kind.title_key())))
+            .id(egui::Id::new("error_help_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                ui.label(RichText::new(t!(kind.explanation_key())).size(13.0));
+                ui.add_space(6.0);
+                ui.label(
+                    RichText::new(t!(kind.cause_key()))
+                        .size(12.0)
+                        .color(Color32::from_rgb(255, 210, 120)),
+                );
+                ui.add_space(6.0);
+                egui::CollapsingHeader::new(t!("error_help.details"))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(&report.detail).size(11.0).color(Color32::from_rgb(180, 180, 180)));
+                    });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    for remedy in kind.remedies(report.launcher_update) {
+                        let btn = egui::Button::new(t!(remedy.label_key())).fill(Color32::from_rgb(80, 120, 200));
+                        if ui.add(btn).clicked() {
+                            chosen = Some(remedy);
+                        }
+                    }
+                    close = ui.button(t!("error_help.close")).clicked();
+                });
+            });
+
+        if let Some(remedy) = chosen {
+            self.apply_remedy(remedy);
         }
-        if self.logo_texture.is_none() {
-            self.logo_texture = load_embedded_texture(
-                ctx,
-                "launcher_logo",
-                include_bytes!("../assets/logo.png"),
-            );
+        if chosen.is_some() || close {
+            self.error_report = None;
         }
     }
 
-    fn launch_open_uo(&mut self) -> Result<String> {
-        let Some(profile) = self.active_profile().cloned() else {
-            anyhow::bail!("{}", t!("status.no_profile"));
+    fn apply_remedy(&mut self, remedy: Remedy) {
+        let open = |path: &std::path::Path| {
+            if let Err(e) = error_help::open_folder(path) {
+                tracing::warn!("Failed to open {}: {}", path.display(), e);
+            }
         };
-        
-        // 保存上次启动的 profile
-        self.config.launcher_settings.last_profile = Some(profile.index.file_name.clone());
-        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
-            tracing::warn!("Failed to save last profile: {}", e);
-        }
-        
-        // 保存配置时带上屏幕信息
-        self.save_config_with_screen_info()?;
-        let settings_path = profile_settings_path(&profile);
-        let exe = open_uo_binary_path();
-        if !exe.exists() {
-            anyhow::bail!("{}", t!("status.openuo_not_found"));
+        match remedy {
+            Remedy::Redownload => {
+                if !self.events.is_downloading() {
+                    self.download_failed = false;
+                    self.start_download();
+                }
+            }
+            Remedy::RetryLauncherUpdate => {
+                if !self.events.is_downloading() {
+                    self.download_failed = false;
+                    self.start_launcher_update();
+                }
+            }
+            Remedy::ChangeInstallDir => {
+                let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+                    return;
+                };
+                self.change_install_dir(dir);
+                // 新目录中还没有安装时直接下载；需要移动时等移动完成后再由用户决定
+                if self.open_uo_version.is_none() && self.install_move_rx.is_none() && !self.events.is_downloading() {
+                    self.download_failed = false;
+                    self.start_download();
+                }
+            }
+            Remedy::OpenInstallDir => {
+                let dir = open_uo_dir();
+                // 目录可能还不存在（从未下载过）
+                let _ = std::fs::create_dir_all(&dir);
+                open(&dir);
+            }
+            Remedy::OpenLauncherDir => open(&base_dir()),
         }
+    }
 
-        let mut cmd = Command::new(exe);
-        cmd.current_dir(open_uo_dir());
-        cmd.arg("-settings")
-            .arg(settings_path)
-            .arg("-skipupdatecheck");
-
-        if profile.settings.auto_login {
-            cmd.arg("-skiploginscreen");
-            if !profile.index.last_character_name.is_empty() {
-                let last = profile.index.last_character_name.clone();
-                cmd.arg("-lastcharactername").arg(last);
+    /// 汇总当前的后台活动，供状态栏显示
+    fn current_activity(&self) -> Activity {
+        if self.launcher_restarting {
+            return Activity::Restarting;
+        }
+        if let Some(task) = self.events.download() {
+            let target = if self.downloading_launcher {
+                DownloadTarget::Launcher
+            } else if self.downloading_game_data || self.downloading_addon.is_some() {
+                DownloadTarget::GameData
+            } else {
+                DownloadTarget::OpenUO
+            };
+            if task.is_paused() {
+                return Activity::Paused(target);
             }
+            let (received, total) = self.download_progress.unwrap_or((0, 0));
+            // 数据已全部接收，后台线程正在解压/替换文件
+            if total > 0 && received >= total {
+                return Activity::Installing(target);
+            }
+            return Activity::Downloading {
+                target,
+                received,
+                total,
+                bytes_per_sec: self.transfer_meter.bytes_per_sec(),
+            };
         }
-        if !profile.index.additional_args.is_empty() {
-            cmd.args(profile.index.additional_args.split_whitespace());
+        if self.checking_open_uo || self.checking_launcher {
+            return Activity::Checking;
         }
+        Activity::Idle
+    }
 
-        cmd.spawn()
-            .with_context(|| t!("status.launch_failed").to_string())?;
-
-        Ok(t!("status.launch_success").to_string())
+    /// 服务器清单开放了员工模式，或者在本机手动解锁
+    fn staff_unlocked(&self) -> bool {
+        self.staff_manifest.enabled || self.config.launcher_settings.staff_mode
     }
 
     fn active_profile(&self) -> Option<&ProfileConfig> {
@@ -696,6 +5409,9 @@ impl LauncherUi {
     }
 
     fn open_profile_editor(&mut self) {
+        if kiosk::active() {
+            return;
+        }
         if let Some(profile) = self.active_profile().cloned() {
             let idx = self.config.active_profile;
             self.profile_editor.open(profile, idx);
@@ -706,6 +5422,7 @@ impl LauncherUi {
         let p = new_profile(&format!("{} {}", t!("main.profile"), self.config.profiles.len() + 1));
         self.config.profiles.push(p);
         self.config.active_profile = self.config.profiles.len().saturating_sub(1);
+        sort_profiles(&mut self.config);
         self.set_status(&t!("status.profile_added"));
     }
 
@@ -717,10 +5434,28 @@ impl LauncherUi {
             cloned.index.file_name = uuid::Uuid::new_v4().to_string();
             self.config.profiles.push(cloned);
             self.config.active_profile = self.config.profiles.len().saturating_sub(1);
+            sort_profiles(&mut self.config);
             self.set_status(&t!("status.profile_copied"));
         }
     }
 
+    /// 拖动排序：把档案移到 `to` 的位置，保存新的顺序
+    fn move_profile(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.config.profiles.len() || to >= self.config.profiles.len() {
+            return;
+        }
+        let active_id = self.active_profile().map(|p| p.index.file_name.clone());
+        let profile = self.config.profiles.remove(from);
+        self.config.profiles.insert(to, profile);
+        if let Some(idx) = active_id.and_then(|id| self.config.profiles.iter().position(|p| p.index.file_name == id)) {
+            self.config.active_profile = idx;
+        }
+        self.config.launcher_settings.profile_order = self.config.profiles.iter().map(|p| p.index.file_name.clone()).collect();
+        if let Err(e) = save_launcher_settings(&self.config.launcher_settings) {
+            tracing::warn!("Failed to save launcher settings: {}", e);
+        }
+    }
+
     fn delete_profile(&mut self) {
         if self.config.profiles.len() <= 1 {
             self.set_status(&t!("status.profile_keep_one"));
@@ -740,8 +5475,42 @@ impl LauncherUi {
         self.add_log(LogEntryType::Info, msg, None);
     }
     
+    /// 在底部信息栏显示在 Launcher 之外运行的 OpenUO，可以附加监视并记入启动历史
+    fn show_external_game(&mut self, ui: &mut egui::Ui) {
+        let Some(game) = &mut self.external_game else {
+            return;
+        };
+        ui.label(
+            RichText::new(format!("▶ {}", t!("process.running", pid = game.pid)))
+                .size(11.0)
+                .color(egui::Color32::from_rgb(120, 200, 120)),
+        );
+        if game.attached.is_some() {
+            ui.label(
+                RichText::new(format!("👁 {}", t!("process.monitoring")))
+                    .size(11.0)
+                    .color(egui::Color32::from_rgb(160, 160, 160)),
+            );
+            return;
+        }
+        let attach = ui
+            .small_button(t!("process.attach"))
+            .on_hover_text(t!("process.attach_hint"));
+        if attach.clicked() {
+            let record = LaunchRecord {
+                profile_name: t!("process.external_profile").to_string(),
+                outcome: LaunchOutcome::Running,
+                ..Default::default()
+            };
+            game.attached = Some((record.id.clone(), Instant::now()));
+            self.history.push(record);
+            self.save_history();
+        }
+    }
+
     /// 添加日志条目
     pub fn add_log(&mut self, entry_type: LogEntryType, message: &str, action: Option<LogAction>) {
+        self.log_writer.write(&entry_type, message);
         self.logs.push(LogEntry {
             timestamp: Instant::now(),
             entry_type,
@@ -749,9 +5518,58 @@ impl LauncherUi {
             action,
         });
         
-        // 限制日志数量，保留最近 50 条
-        if self.logs.len() > 50 {
-            self.logs.remove(0);
+        // 按设置限制日志数量和时长
+        self.config.launcher_settings.log_retention.prune(&mut self.logs, Instant::now(), |l| l.timestamp);
+    }
+
+    /// 日志显示级别和保留设置
+    fn show_log_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.config.launcher_settings;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(t!("log_settings.verbosity"));
+            egui::ComboBox::from_id_source("log_verbosity")
+                .selected_text(t!(settings.log_verbosity.label_key()))
+                .show_ui(ui, |ui| {
+                    for verbosity in LogVerbosity::ALL {
+                        changed |= ui
+                            .selectable_value(&mut settings.log_verbosity, verbosity, t!(verbosity.label_key()))
+                            .changed();
+                    }
+                });
+        })
+        .response
+        .on_hover_text(t!("log_settings.verbosity_hint"));
+        ui.horizontal(|ui| {
+            ui.label(t!("log_settings.palette"));
+            egui::ComboBox::from_id_source("log_palette")
+                .selected_text(t!(settings.log_palette.label_key()))
+                .show_ui(ui, |ui| {
+                    for palette in LogPalette::ALL {
+                        changed |= ui
+                            .selectable_value(&mut settings.log_palette, palette, t!(palette.label_key()))
+                            .changed();
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label(t!("log_settings.max_entries"));
+            changed |= ui
+                .add(egui::DragValue::new(&mut settings.log_retention.max_entries).clamp_range(10..=1000))
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label(t!("log_settings.max_age"));
+            changed |= ui
+                .add(egui::DragValue::new(&mut settings.log_retention.max_age_minutes).clamp_range(0..=1440))
+                .on_hover_text(t!("log_settings.max_age_hint"))
+                .changed();
+        });
+        if changed {
+            settings.log_retention.prune(&mut self.logs, Instant::now(), |l| l.timestamp);
+            if let Err(e) = save_launcher_settings(settings) {
+                tracing::warn!("Failed to save launcher settings: {}", e);
+            }
         }
     }
     
@@ -765,12 +5583,22 @@ impl LauncherUi {
             ui.set_min_height(200.0);
             ui.set_max_height(300.0);
             
+            ui.horizontal(|ui| {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.menu_button("⚙", |ui| self.show_log_settings(ui))
+                        .response
+                        .on_hover_text(t!("log_settings.title"));
+                });
+            });
+
+            let verbosity = self.config.launcher_settings.log_verbosity;
+
             egui::ScrollArea::vertical()
                 .auto_shrink([false, false])
                 .show(ui, |ui| {
                     ui.set_max_width(max_width);
                     
-                    if self.logs.is_empty() {
+                    if !self.logs.iter().any(|l| verbosity.shows(&l.entry_type)) {
                         ui.label(
                             RichText::new(t!("log.ready"))
                                 .size(12.0)
@@ -778,23 +5606,46 @@ impl LauncherUi {
                         );
                     } else {
                         let logs = self.logs.clone();
-                        for log in &logs {
+                        for log in logs.iter().filter(|l| verbosity.shows(&l.entry_type)) {
                             self.show_log_entry(ui, log);
                         }
                     }
                 });
             
             // 单独展示一次下载进度条，避免每条日志下面都重复出现
-            if let (Some((cur, total)), Some(_)) = (self.download_progress, self.download_rx.as_ref()) {
-                if total > 0 {
-                    let progress = (cur as f32) / (total as f32);
-                    let total_mb = (total as f32) / (1024.0 * 1024.0);
-                    let cur_mb = (cur as f32) / (1024.0 * 1024.0);
-                    ui.add(
-                        egui::ProgressBar::new(progress)
-                            .text(format!("{:.1}/{:.1} MB", cur_mb, total_mb))
-                            .desired_width(max_width - 10.0)
-                    );
+            if let Some(task) = self.events.download() {
+                let mut cancel = false;
+                let mut toggle_pause = false;
+                ui.horizontal(|ui| {
+                    // 数据接收完后进入安装阶段，不再允许暂停或取消
+                    let installing = matches!(self.current_activity(), Activity::Installing(_));
+                    let active = !installing && !task.is_cancelling();
+                    let pause_btn = if task.is_paused() {
+                        egui::Button::new(format!("▶ {}", t!("main.resume_download")))
+                    } else {
+                        egui::Button::new(format!("⏸ {}", t!("main.pause_download")))
+                    };
+                    toggle_pause = ui.add_enabled(active, pause_btn).clicked();
+                    let cancel_btn = egui::Button::new(format!("⏹ {}", t!("main.cancel_download")));
+                    cancel = ui.add_enabled(active, cancel_btn).clicked();
+                    if let Some((cur, total)) = self.download_progress
+                        && total > 0
+                    {
+                        let progress = (cur as f32) / (total as f32);
+                        ui.add(
+                            egui::ProgressBar::new(progress)
+                                .text(t!("download.progress", current = humanize::bytes(cur), total = humanize::bytes(total)))
+                                .desired_width(ui.available_width() - 10.0)
+                        );
+                    }
+                });
+                if toggle_pause {
+                    task.set_paused(!task.is_paused());
+                    // 继续后重新估算速度
+                    self.transfer_meter.reset();
+                }
+                if cancel {
+                    task.cancel();
                 }
             }
         });
@@ -804,13 +5655,9 @@ impl LauncherUi {
     fn show_log_entry(&mut self, ui: &mut egui::Ui, log: &LogEntry) {
         ui.horizontal_wrapped(|ui| {
             // 图标和颜色
-            let (icon, color) = match log.entry_type {
-                LogEntryType::Info => ("ℹ", egui::Color32::from_rgb(150, 150, 200)),
-                LogEntryType::Success => ("✓", egui::Color32::from_rgb(100, 200, 100)),
-                LogEntryType::Warning => ("⚠", egui::Color32::from_rgb(200, 200, 100)),
-                LogEntryType::Error => ("✗", egui::Color32::from_rgb(200, 100, 100)),
-                LogEntryType::Checking => ("⟳", egui::Color32::from_rgb(150, 150, 200)),
-            };
+            let palette = self.config.launcher_settings.log_palette;
+            let icon = activity_log::icon(&log.entry_type);
+            let color = palette.color(&log.entry_type);
             
             ui.label(RichText::new(icon).size(14.0).color(color));
             
@@ -818,8 +5665,9 @@ impl LauncherUi {
             ui.label(
                 RichText::new(&log.message)
                     .size(12.0)
-                    .color(egui::Color32::from_rgb(200, 200, 200))
-            );
+                    .color(palette.text_color())
+            )
+            .on_hover_text(humanize::ago(log.timestamp.elapsed().as_secs()));
             
             // 显示操作按钮
             if let Some(action) = &log.action {
@@ -832,10 +5680,16 @@ impl LauncherUi {
                             if ui.add(btn).clicked() {
                                 self.start_launcher_update();
                             }
+                            if ui.small_button("⏰").on_hover_text(t!("launcher_update.remind_later")).clicked() {
+                                self.dismiss_launcher_update(false);
+                            }
+                            if ui.small_button("⏭").on_hover_text(t!("launcher_update.skip_version")).clicked() {
+                                self.dismiss_launcher_update(true);
+                            }
                         }
                     }
                     LogAction::UpdateOpenUO => {
-                        if self.download_rx.is_none() {
+                        if !self.events.is_downloading() {
                             let btn = egui::Button::new("🔄 更新")
                                 .fill(egui::Color32::from_rgb(80, 120, 200))
                                 .min_size(egui::vec2(60.0, 20.0));
@@ -845,7 +5699,7 @@ impl LauncherUi {
                         }
                     }
                     LogAction::RetryDownload => {
-                        if self.download_rx.is_none() {
+                        if !self.events.is_downloading() {
                             let btn = egui::Button::new("🔄 重试")
                                 .fill(egui::Color32::from_rgb(200, 120, 80))
                                 .min_size(egui::vec2(60.0, 20.0));
@@ -859,6 +5713,78 @@ impl LauncherUi {
                             }
                         }
                     }
+                    LogAction::OpenProfileEditor => {
+                        if !kiosk::active() {
+                            let btn = egui::Button::new(t!("main.edit"))
+                                .fill(egui::Color32::from_rgb(100, 100, 120))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.open_profile_editor();
+                            }
+                        }
+                    }
+                    LogAction::MigrateLegacy(dir) => {
+                        if !kiosk::active() {
+                            let btn = egui::Button::new(t!("legacy_migration.migrate"))
+                                .fill(egui::Color32::from_rgb(80, 120, 200))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.migrate_legacy(dir.clone());
+                            }
+                        }
+                    }
+                    LogAction::DownloadGameData => {
+                        if !self.events.is_downloading() {
+                            let btn = egui::Button::new(t!("game_data.download"))
+                                .fill(egui::Color32::from_rgb(80, 120, 200))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.start_game_data_download();
+                            }
+                        }
+                    }
+                    LogAction::DownloadAddon(id) => {
+                        if !self.events.is_downloading() {
+                            let btn = egui::Button::new(t!("game_data.download"))
+                                .fill(egui::Color32::from_rgb(80, 120, 200))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.start_addon_download(&id.clone());
+                            }
+                        }
+                    }
+                    LogAction::InstallVersion(tag) => {
+                        if !self.events.is_downloading() {
+                            let btn = egui::Button::new("🔄 重试")
+                                .fill(egui::Color32::from_rgb(200, 120, 80))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.download_failed = false;
+                                self.start_side_by_side(tag.clone());
+                            }
+                        }
+                    }
+                    LogAction::RetryLaunch => {
+                        if self.network_check_rx.is_none() {
+                            let btn = egui::Button::new("🔄 重试")
+                                .fill(egui::Color32::from_rgb(200, 120, 80))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.logs.retain(|l| !matches!(l.action, Some(LogAction::RetryLaunch)));
+                                self.request_launch();
+                            }
+                        }
+                    }
+                    LogAction::ResumeDownload(pending) => {
+                        if !self.events.is_downloading() {
+                            let btn = egui::Button::new(format!("▶ {}", t!("shutdown.resume")))
+                                .fill(egui::Color32::from_rgb(80, 120, 200))
+                                .min_size(egui::vec2(60.0, 20.0));
+                            if ui.add(btn).clicked() {
+                                self.resume_download(pending.clone());
+                            }
+                        }
+                    }
                 }
             }
         });
@@ -866,108 +5792,152 @@ impl LauncherUi {
         ui.add_space(4.0);
     }
 
-    pub fn set_screen_info(&mut self, width: u32, height: u32, scale_factor: f64) {
-        self.screen_info = Some(ScreenInfo {
-            width,
-            height,
-            scale_factor,
-            lang: crate::i18n::current_locale(),
-        });
+    /// 启动时以及窗口移到缩放比例不同的显示器上时更新
+    pub fn set_screen_info(&mut self, width: u32, height: u32, scale_factor: f64, monitor: Option<String>) {
+        let info = ScreenInfo::new(width, height, scale_factor, monitor, crate::i18n::current_locale());
+        if self.screen_info.as_ref().is_some_and(|old| old.monitor != info.monitor) {
+            tracing::info!(
+                "Moved to monitor {}: {}x{} @ {:.2}x",
+                info.monitor.as_deref().unwrap_or("?"),
+                info.width,
+                info.height,
+                info.scale_factor
+            );
+        }
+        self.screen_info = Some(info);
+    }
+
+    pub fn set_translucent(&mut self, translucent: bool) {
+        self.translucent = translucent;
+    }
+
+    pub fn set_gpu_info(&mut self, info: GpuInfo) {
+        tracing::info!("GPU: {} ({}), software: {}, full feature level: {}", info.name, info.backend, info.software, info.full_feature_level);
+        self.gpu_info = Some(info);
     }
 
-    fn save_config_with_screen_info(&mut self) -> Result<()> {
+    /// 保存所有档案，返回是否已写入
+    ///
+    /// 正在安装更新时不写入，记下待保存，安装完成后由 `poll_channels` 补上。
+    fn save_config_with_screen_info(&mut self) -> Result<bool> {
+        let _guard = match operation_lock::try_acquire(Operation::ProfileSave) {
+            Ok(guard) => guard,
+            Err(running) => {
+                if !self.save_pending {
+                    self.save_pending = true;
+                    self.add_log(
+                        LogEntryType::Info,
+                        &format!("⏳ {}", t!("operation_lock.save_waiting", operation = t!(running.label_key()))),
+                        None,
+                    );
+                }
+                return Ok(false);
+            }
+        };
         // 保存所有档案，带上屏幕信息
         for profile in &self.config.profiles {
             save_profile_with_screen_info(profile, self.screen_info.clone())?;
         }
-        Ok(())
+        self.save_pending = false;
+        Ok(true)
     }
 }
 
-fn poll_download_channel(
-    download_rx: &mut Option<mpsc::Receiver<DownloadEvent>>,
-    download_progress: &mut Option<(u64, u64)>,
-    downloading_launcher: &mut bool,
-    launcher_restarting: &mut bool,
-    status: &mut String,
-    open_uo_version: &mut Option<String>,
-) {
-    if let Some(rx) = download_rx {
-        let events: Vec<_> = rx.try_iter().collect();
-        for event in events {
-            match event {
-                DownloadEvent::Progress { received, total } => {
-                    *download_progress = Some((received, total));
-                }
-                DownloadEvent::Finished(result) => {
-                    *download_rx = None;
-                    *download_progress = None;
-                    *downloading_launcher = false; // 重置下载标记
-                    match result {
-                        Ok(tag) => {
-                            // 判断是否是 Launcher 更新并需要重启
-                            if tag.starts_with("UPDATE_AND_RESTART:") {
-                                // Launcher 更新完成，程序即将退出
-                                let version = tag.strip_prefix("UPDATE_AND_RESTART:").unwrap_or("");
-                                *status = t!("status.launcher_update_complete", version = version).to_string();
-                                *launcher_restarting = true; // 标记正在重启
-                                // 延迟退出，让用户看到消息
-                                std::thread::spawn(|| {
-                                    std::thread::sleep(std::time::Duration::from_secs(2));
-                                    std::process::exit(0);
-                                });
-                            } else {
-                                // OpenUO 下载完成
-                                *open_uo_version = Some(tag.clone());
-                                *status = t!("status.download_complete", version = &tag).to_string();
-                            }
-                        }
-                        Err(_err) => {
-                            *status = t!("status.download_failed").to_string();
-                        }
-                    }
-                }
-            }
+/// 显示发布说明中的一个段落
+fn show_notes_block(ui: &mut egui::Ui, block: &release_notes::Block) {
+    use release_notes::Block;
+    match block {
+        Block::Heading(level, spans) => {
+            ui.add_space(4.0);
+            let size = if *level <= 2 { 17.0 } else { 14.0 };
+            ui.horizontal_wrapped(|ui| show_notes_spans(ui, spans, Some(size)));
+        }
+        Block::Bullet(depth, spans) => {
+            ui.horizontal_wrapped(|ui| {
+                ui.add_space(12.0 * *depth as f32);
+                ui.label("•");
+                show_notes_spans(ui, spans, None);
+            });
+        }
+        Block::Paragraph(spans) => {
+            ui.horizontal_wrapped(|ui| show_notes_spans(ui, spans, None));
+        }
+        Block::Code(code) => {
+            ui.label(RichText::new(code).monospace());
+        }
+        Block::Rule => {
+            ui.separator();
         }
     }
 }
 
-fn poll_update_channel(
-    update_rx: &mut Option<mpsc::Receiver<UpdateEvent>>,
-    remote_open_uo: &mut Option<String>,
-    remote_launcher: &mut Option<String>,
-    status: &mut String,
-    checking_open_uo: &mut bool,
-    checking_launcher: &mut bool,
-) {
-    if let Some(rx) = update_rx {
-        let events: Vec<_> = rx.try_iter().collect();
-        for event in events {
-            match event {
-                UpdateEvent::OpenUO(res) => {
-                    *checking_open_uo = false;
-                    match res {
-                        Ok(v) => *remote_open_uo = Some(v),
-                        Err(_e) => {
-                            *remote_open_uo = None;
-                            *status = t!("status.openuo_check_failed").to_string();
-                        }
-                    }
-                }
-                UpdateEvent::Launcher(res) => {
-                    *checking_launcher = false;
-                    match res {
-                        Ok(v) => *remote_launcher = Some(v),
-                        Err(_e) => {
-                            *remote_launcher = None;
-                            *status = t!("status.launcher_check_failed").to_string();
-                        }
-                    }
-                }
-                UpdateEvent::Done => {}
+/// `heading` 为标题字号，标题中的文字都加粗
+fn show_notes_spans(ui: &mut egui::Ui, spans: &[release_notes::Span], heading: Option<f32>) {
+    use release_notes::Span;
+    ui.spacing_mut().item_spacing.x = 0.0;
+    for span in spans {
+        let text = |s: &str| match heading {
+            Some(size) => RichText::new(s).size(size).strong(),
+            None => RichText::new(s),
+        };
+        match span {
+            Span::Text(s) => {
+                ui.label(text(s));
             }
+            Span::Strong(s) => {
+                ui.label(text(s).strong());
+            }
+            Span::Code(s) => {
+                ui.label(text(s).code());
+            }
+            Span::Link { text: label, url } => {
+                ui.hyperlink_to(text(label), url);
+            }
+        }
+    }
+}
+
+/// 按字符截断（避免切断多字节字符）
+/// 版本栏中灰色的“N 天前安装/发布”，悬停显示具体时间
+fn show_age(ui: &mut egui::Ui, key: &str, at: i64) {
+    let ago = humanize::ago(crate::clock::now_unix().saturating_sub(at).max(0) as u64);
+    ui.label(RichText::new(t!(key, ago = ago)).size(11.0).color(Color32::from_rgb(150, 150, 150)))
+        .on_hover_text(crate::clock::format_local(at));
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    }
+}
+
+fn outcome_label(outcome: LaunchOutcome) -> (String, Color32) {
+    match outcome {
+        LaunchOutcome::Running => (t!("history.running").to_string(), Color32::from_rgb(150, 150, 200)),
+        LaunchOutcome::Exited => (t!("history.exited").to_string(), Color32::from_rgb(100, 200, 100)),
+        LaunchOutcome::Crashed => (t!("history.crashed").to_string(), Color32::from_rgb(200, 100, 100)),
+        LaunchOutcome::Failed => (t!("history.failed").to_string(), Color32::from_rgb(200, 100, 100)),
+        LaunchOutcome::Unknown => (t!("history.unknown").to_string(), Color32::from_rgb(150, 150, 150)),
+    }
+}
+
+/// 所有档案的 UO 目录（去重）
+fn profile_uo_dirs(profiles: &[ProfileConfig]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for profile in profiles {
+        let dir = PathBuf::from(profile.settings.ultima_online_directory.trim());
+        if !dir.as_os_str().is_empty() && !dirs.contains(&dir) {
+            dirs.push(dir);
         }
     }
+    dirs
+}
+
+/// 已经替换为链接的档案 UO 目录
+fn linked_uo_dirs(profiles: &[ProfileConfig]) -> Vec<PathBuf> {
+    profile_uo_dirs(profiles).into_iter().filter(|dir| data_dedup::is_link(dir)).collect()
 }
 
 fn load_embedded_texture(
@@ -1005,6 +5975,7 @@ fn paint_background(
     ui: &egui::Ui,
     background: &Option<egui::TextureHandle>,
     logo: &Option<egui::TextureHandle>,
+    translucent: bool,
 ) {
     let rect = ui.max_rect();
     let painter = ui.painter();
@@ -1020,7 +5991,11 @@ fn paint_background(
             bg.id(),
             dest,
             egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-            egui::Color32::WHITE,
+            if translucent {
+                egui::Color32::WHITE.gamma_multiply(crate::translucency::BACKGROUND_ALPHA)
+            } else {
+                egui::Color32::WHITE
+            },
         );
     }
 
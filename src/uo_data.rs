@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// 启动游戏必需的 UO 数据文件
+///
+/// 每一项可以由几种文件组合中的任意一种满足：新版客户端用 UOP，旧版用 MUL 和索引文件。
+const REQUIRED: &[&[&[&str]]] = &[
+    &[&["artLegacyMUL.uop"], &["art.mul", "artidx.mul"]],
+    &[&["gumpartLegacyMUL.uop"], &["gumpart.mul", "gumpidx.mul"]],
+    &[&["map0LegacyMUL.uop"], &["map0.mul"]],
+    &[&["MultiCollection.uop"], &["multi.mul", "multi.idx"]],
+    &[&["tiledata.mul"]],
+    &[&["hues.mul"]],
+    &[&["fonts.mul"]],
+    &[&["cliloc.enu"]],
+];
+
+/// `dir` 中缺少的数据文件，每一项列出可以满足要求的文件，例如 "artLegacyMUL.uop / art.mul + artidx.mul"
+///
+/// 文件名不区分大小写，Linux 上从 Windows 复制来的数据经常大小写不一致。
+pub fn missing(dir: &Path) -> Vec<String> {
+    let present: HashSet<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_lowercase))
+                .collect()
+        })
+        .unwrap_or_default();
+    REQUIRED
+        .iter()
+        .filter(|alternatives| {
+            !alternatives
+                .iter()
+                .any(|files| files.iter().all(|file| present.contains(&file.to_lowercase())))
+        })
+        .map(|alternatives| alternatives.iter().map(|files| files.join(" + ")).collect::<Vec<_>>().join(" / "))
+        .collect()
+}
+
+/// `dir` 中 cliloc 语言文件对应的语言代码（大写），例如 cliloc.enu → "ENU"
+pub fn cliloc_languages(dir: &Path) -> Vec<String> {
+    let mut languages: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_str()?.to_uppercase();
+                    let code = name.strip_prefix("CLILOC.")?;
+                    (!code.is_empty() && code.len() <= 4 && code.chars().all(|c| c.is_ascii_alphabetic())).then(|| code.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    languages.sort();
+    languages.dedup();
+    languages
+}
+
+/// 常见 cliloc 语言代码的名称（用该语言书写）
+pub fn cliloc_language_name(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "ENU" => "English",
+        "CHS" => "简体中文",
+        "CHT" => "繁體中文",
+        "DEU" => "Deutsch",
+        "ESP" => "Español",
+        "FRA" => "Français",
+        "ITA" => "Italiano",
+        "JPN" => "日本語",
+        "KOR" => "한국어",
+        "PTB" => "Português (Brasil)",
+        "RUS" => "Русский",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::temp_dir;
+
+    #[test]
+    fn test_missing() {
+        let dir = temp_dir("uo-data");
+        assert_eq!(missing(&dir).len(), REQUIRED.len());
+
+        fs::create_dir_all(dir.join("cliloc.enu")).unwrap();
+        for file in ["ARTLEGACYMUL.UOP", "gumpart.mul", "map0.mul", "multi.mul", "multi.idx", "TileData.mul", "hues.mul", "fonts.mul"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        // 目录不算文件，MUL 缺少索引文件也不算
+        assert_eq!(missing(&dir), ["gumpartLegacyMUL.uop / gumpart.mul + gumpidx.mul", "cliloc.enu"]);
+
+        fs::write(dir.join("gumpidx.mul"), "").unwrap();
+        fs::remove_dir(dir.join("cliloc.enu")).unwrap();
+        fs::write(dir.join("Cliloc.enu"), "").unwrap();
+        assert!(missing(&dir).is_empty());
+    }
+
+    #[test]
+    fn test_cliloc_languages() {
+        let dir = temp_dir("cliloc");
+        fs::create_dir_all(dir.join("cliloc.kor")).unwrap();
+        for file in ["Cliloc.enu", "cliloc.DEU", "CLILOC.ENU", "cliloc.enu.bak", "cliloc.", "clilocs.jpn"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        // Windows 上文件名不区分大小写，Cliloc.enu 和 CLILOC.ENU 是同一个文件
+        assert_eq!(cliloc_languages(&dir), ["DEU", "ENU"]);
+        assert_eq!(cliloc_languages(&dir.join("missing")), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::github::version_newer;
+
+/// 启动前的 OpenUO 更新策略（按 profile 配置）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UpdatePolicy {
+    /// 不检查
+    Never,
+    /// 有新版本时提示，不影响启动
+    #[default]
+    Notify,
+    /// 版本不满足服务器要求时禁止启动
+    Require,
+}
+
+impl UpdatePolicy {
+    pub const ALL: [UpdatePolicy; 3] = [UpdatePolicy::Never, UpdatePolicy::Notify, UpdatePolicy::Require];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            UpdatePolicy::Never => "update_policy.never",
+            UpdatePolicy::Notify => "update_policy.notify",
+            UpdatePolicy::Require => "update_policy.require",
+        }
+    }
+}
+
+/// Launcher 在后台检查 OpenUO 和 Launcher 更新的频率（全局设置）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpdateCheckInterval {
+    /// 不在后台检查，只在启动游戏前按档案的更新策略检查
+    Never,
+    /// 只在 Launcher 启动时检查一次
+    OnStartup,
+    /// 启动时检查，之后每隔若干分钟检查一次
+    Every { minutes: u32 },
+}
+
+impl Default for UpdateCheckInterval {
+    fn default() -> Self {
+        UpdateCheckInterval::Every {
+            minutes: DEFAULT_CHECK_MINUTES,
+        }
+    }
+}
+
+pub const DEFAULT_CHECK_MINUTES: u32 = 10;
+/// 设置中允许的最长间隔（一周）
+pub const MAX_CHECK_MINUTES: u32 = 7 * 24 * 60;
+
+impl UpdateCheckInterval {
+    pub fn label_key(self) -> &'static str {
+        match self {
+            UpdateCheckInterval::Never => "update_check.never",
+            UpdateCheckInterval::OnStartup => "update_check.on_startup",
+            UpdateCheckInterval::Every { .. } => "update_check.every",
+        }
+    }
+
+    /// `since_last` 为距上次检查的时间，本次运行还没检查过时为 `None`
+    pub fn is_due(self, since_last: Option<Duration>) -> bool {
+        match (self, since_last) {
+            (UpdateCheckInterval::Never, _) => false,
+            (_, None) => true,
+            (UpdateCheckInterval::OnStartup, Some(_)) => false,
+            (UpdateCheckInterval::Every { minutes }, Some(elapsed)) => {
+                elapsed >= Duration::from_secs(u64::from(minutes.clamp(1, MAX_CHECK_MINUTES)) * 60)
+            }
+        }
+    }
+}
+
+/// 启动前检查的结论
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    Proceed,
+    /// 可以启动，但应提示有新版本
+    Notify { latest: String },
+    /// 本地版本低于要求
+    Outdated { local: String, required: String },
+    NotInstalled { required: String },
+    /// 无法获取服务器要求的版本
+    Unverified,
+}
+
+impl PolicyDecision {
+    pub fn blocks_launch(&self) -> bool {
+        matches!(
+            self,
+            PolicyDecision::Outdated { .. } | PolicyDecision::NotInstalled { .. } | PolicyDecision::Unverified
+        )
+    }
+}
+
+/// 根据策略和已知的版本信息判断能否启动
+///
+/// `required` 为更新清单中声明的最低版本，未声明时要求与最新版本一致。
+pub fn evaluate(
+    policy: UpdatePolicy,
+    local: Option<&str>,
+    latest: Option<&str>,
+    required: Option<&str>,
+) -> PolicyDecision {
+    match policy {
+        UpdatePolicy::Never => PolicyDecision::Proceed,
+        UpdatePolicy::Notify => match latest {
+            Some(latest) if local.is_none_or(|local| version_newer(latest, local)) => PolicyDecision::Notify {
+                latest: latest.to_string(),
+            },
+            _ => PolicyDecision::Proceed,
+        },
+        UpdatePolicy::Require => {
+            let Some(required) = required.or(latest) else {
+                return PolicyDecision::Unverified;
+            };
+            match local {
+                None => PolicyDecision::NotInstalled {
+                    required: required.to_string(),
+                },
+                Some(local) if version_newer(required, local) => PolicyDecision::Outdated {
+                    local: local.to_string(),
+                    required: required.to_string(),
+                },
+                Some(_) => PolicyDecision::Proceed,
+            }
+        }
+    }
+}
+
+/// 版本检查失败后的处理：“要求最新版本”的启动正在等待结果时丢弃之前获取的最新版本和最低版本
+///
+/// 否则 `evaluate` 会按上一次检查的结果放行，而这时可能已经发布了新的最低版本。
+pub fn discard_after_failed_check(launch_pending: bool, latest: &mut Option<String>, required: &mut Option<String>) {
+    if launch_pending {
+        *latest = None;
+        *required = None;
+    }
+}
+
+/// “保持 OpenUO 最新”：启动前需要先安装的版本，无法获取最新版本时不更新
+pub fn auto_update_target(local: Option<&str>, latest: Option<&str>) -> Option<String> {
+    let latest = latest?;
+    local
+        .is_none_or(|local| version_newer(latest, local))
+        .then(|| latest.to_string())
+}
+
+/// “稍后提醒”推迟的时长（一天）
+pub const SNOOZE_SECS: i64 = 24 * 60 * 60;
+
+/// 用户对 Launcher 更新提示的选择（全局设置），出现更新的版本时重新提示
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateReminder {
+    /// “跳过此版本”选择的版本
+    #[serde(default)]
+    pub skipped_version: Option<String>,
+    /// “稍后提醒”选择的版本和到期时间（Unix 时间戳）
+    #[serde(default)]
+    pub snoozed: Option<(String, i64)>,
+}
+
+impl UpdateReminder {
+    /// 是否提示 `latest` 这个新版本
+    pub fn should_prompt(&self, latest: &str, now: i64) -> bool {
+        let skipped = self.skipped_version.as_deref().is_some_and(|v| !version_newer(latest, v));
+        let snoozed = self
+            .snoozed
+            .as_ref()
+            .is_some_and(|(v, until)| now < *until && !version_newer(latest, v));
+        !skipped && !snoozed
+    }
+
+    pub fn skip(&mut self, version: &str) {
+        self.skipped_version = Some(version.to_string());
+        self.snoozed = None;
+    }
+
+    pub fn snooze(&mut self, version: &str, now: i64) {
+        self.snoozed = Some((version.to_string(), now + SNOOZE_SECS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_check_interval() {
+        let minute = Duration::from_secs(60);
+        assert!(!UpdateCheckInterval::Never.is_due(None));
+        assert!(UpdateCheckInterval::OnStartup.is_due(None));
+        assert!(!UpdateCheckInterval::OnStartup.is_due(Some(minute * 1000)));
+        let every = UpdateCheckInterval::default();
+        assert!(every.is_due(None));
+        assert!(!every.is_due(Some(minute * 9)));
+        assert!(every.is_due(Some(minute * 10)));
+        // 0 按 1 分钟处理，避免每帧都检查
+        assert!(!UpdateCheckInterval::Every { minutes: 0 }.is_due(Some(Duration::from_secs(30))));
+
+        let json = serde_json::to_string(&UpdateCheckInterval::Every { minutes: 30 }).unwrap();
+        assert_eq!(serde_json::from_str::<UpdateCheckInterval>(&json).unwrap(), UpdateCheckInterval::Every { minutes: 30 });
+    }
+
+    #[test]
+    fn test_notify() {
+        assert_eq!(evaluate(UpdatePolicy::Never, None, Some("2.0"), None), PolicyDecision::Proceed);
+        assert_eq!(
+            evaluate(UpdatePolicy::Notify, Some("1.0"), Some("2.0"), None),
+            PolicyDecision::Notify { latest: "2.0".to_string() }
+        );
+        assert_eq!(evaluate(UpdatePolicy::Notify, Some("2.0"), Some("2.0"), None), PolicyDecision::Proceed);
+        assert_eq!(evaluate(UpdatePolicy::Notify, Some("1.0"), None, None), PolicyDecision::Proceed);
+    }
+
+    #[test]
+    fn test_require() {
+        // 未声明最低版本时要求最新版本
+        assert!(evaluate(UpdatePolicy::Require, Some("1.0"), Some("2.0"), None).blocks_launch());
+        assert_eq!(evaluate(UpdatePolicy::Require, Some("2.0"), Some("2.0"), None), PolicyDecision::Proceed);
+
+        // 声明了最低版本时，较旧但满足要求的版本可以启动
+        assert_eq!(evaluate(UpdatePolicy::Require, Some("1.5"), Some("2.0"), Some("1.5")), PolicyDecision::Proceed);
+        assert_eq!(
+            evaluate(UpdatePolicy::Require, Some("1.4"), Some("2.0"), Some("1.5")),
+            PolicyDecision::Outdated { local: "1.4".to_string(), required: "1.5".to_string() }
+        );
+
+        assert!(matches!(
+            evaluate(UpdatePolicy::Require, None, Some("2.0"), None),
+            PolicyDecision::NotInstalled { .. }
+        ));
+        assert_eq!(evaluate(UpdatePolicy::Require, Some("2.0"), None, None), PolicyDecision::Unverified);
+    }
+
+    #[test]
+    fn test_require_after_failed_check() {
+        // 上一次检查的结果允许启动，这次检查失败
+        let (mut latest, mut required) = (Some("2.0".to_string()), Some("1.5".to_string()));
+        discard_after_failed_check(false, &mut latest, &mut required);
+        assert_eq!(evaluate(UpdatePolicy::Require, Some("2.0"), latest.as_deref(), required.as_deref()), PolicyDecision::Proceed);
+
+        discard_after_failed_check(true, &mut latest, &mut required);
+        let decision = evaluate(UpdatePolicy::Require, Some("2.0"), latest.as_deref(), required.as_deref());
+        assert_eq!(decision, PolicyDecision::Unverified);
+        assert!(decision.blocks_launch());
+    }
+
+    #[test]
+    fn test_auto_update_target() {
+        assert_eq!(auto_update_target(Some("1.0"), Some("2.0")).as_deref(), Some("2.0"));
+        assert_eq!(auto_update_target(None, Some("2.0")).as_deref(), Some("2.0"));
+        assert_eq!(auto_update_target(Some("2.0"), Some("2.0")), None);
+        assert_eq!(auto_update_target(Some("1.0"), None), None);
+    }
+
+    #[test]
+    fn test_update_reminder() {
+        let mut reminder = UpdateReminder::default();
+        assert!(reminder.should_prompt("1.2.0", 0));
+
+        reminder.skip("1.2.0");
+        assert!(!reminder.should_prompt("1.2.0", 0));
+        assert!(reminder.should_prompt("1.3.0", 0));
+
+        reminder.snooze("1.3.0", 100);
+        assert!(!reminder.should_prompt("1.3.0", 100 + SNOOZE_SECS - 1));
+        assert!(reminder.should_prompt("1.3.0", 100 + SNOOZE_SECS));
+        // 推迟期间出现更新的版本时立即提示
+        assert!(reminder.should_prompt("1.4.0", 100));
+    }
+}
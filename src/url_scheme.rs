@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+// 让浏览器把 openuo:// 链接交给 Launcher：需要用户在 ⚙ 菜单中开启，只为当前用户注册。
+// Windows 写入 HKCU\Software\Classes\openuo；Linux 安装处理 x-scheme-handler/openuo 的 .desktop 文件。
+// macOS 通过 Apple Event 而不是命令行参数传递链接，Launcher 收不到，只能把链接粘贴到 ⚙ 菜单中。
+
+/// 当前平台可以注册
+pub const SUPPORTED: bool = cfg!(any(target_os = "windows", target_os = "linux"));
+
+#[cfg(target_os = "windows")]
+const CLASS_KEY: &str = r"HKCU\Software\Classes\openuo";
+
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "openuo-launcher-url.desktop";
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<String> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    let output = std::process::Command::new("reg")
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .args(args)
+        .output()
+        .context("无法运行 reg")?;
+    if !output.status.success() {
+        anyhow::bail!("reg 失败: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn open_command(exe: &Path) -> String {
+    format!("\"{}\" \"%1\"", exe.display())
+}
+
+/// 把 openuo:// 链接交给 `exe`
+#[cfg(target_os = "windows")]
+pub fn register(exe: &Path) -> Result<()> {
+    let command_key = format!(r"{}\shell\open\command", CLASS_KEY);
+    reg(&["add", CLASS_KEY, "/ve", "/d", "URL:OpenUO Launcher", "/f"])?;
+    reg(&["add", CLASS_KEY, "/v", "URL Protocol", "/d", "", "/f"])?;
+    reg(&["add", &command_key, "/ve", "/d", &open_command(exe), "/f"])?;
+    Ok(())
+}
+
+/// openuo:// 链接已经交给 `exe`（Launcher 移动后需要重新注册）
+#[cfg(target_os = "windows")]
+pub fn is_registered(exe: &Path) -> bool {
+    reg(&["query", &format!(r"{}\shell\open\command", CLASS_KEY), "/ve"]).is_ok_and(|out| out.contains(&open_command(exe)))
+}
+
+#[cfg(target_os = "windows")]
+pub fn unregister() -> Result<()> {
+    reg(&["delete", CLASS_KEY, "/f"]).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn applications_dir() -> Result<std::path::PathBuf> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => std::path::PathBuf::from(std::env::var_os("HOME").context("没有设置 HOME")?).join(".local/share"),
+    };
+    Ok(data_home.join("applications"))
+}
+
+/// .desktop 文件中的 Exec 参数需要加引号，并转义 " ` $ \
+#[cfg(any(target_os = "linux", test))]
+fn desktop_entry(exe: &Path) -> String {
+    let mut quoted = String::from("\"");
+    for c in exe.to_string_lossy().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    format!(
+        "[Desktop Entry]\nType=Application\nName=OpenUO Launcher\nExec={} %u\nTerminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/openuo;\n",
+        quoted
+    )
+}
+
+#[cfg(target_os = "linux")]
+pub fn register(exe: &Path) -> Result<()> {
+    let dir = applications_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(DESKTOP_FILE);
+    std::fs::write(&path, desktop_entry(exe)).with_context(|| format!("无法写入 {}", path.display()))?;
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", DESKTOP_FILE, "x-scheme-handler/openuo"])
+        .status()
+        .context("无法运行 xdg-mime")?;
+    if !status.success() {
+        anyhow::bail!("xdg-mime 失败: {}", status);
+    }
+    // 部分桌面环境从缓存中查找处理程序，没有这个命令时忽略
+    std::process::Command::new("update-desktop-database").arg(&dir).status().ok();
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_registered(exe: &Path) -> bool {
+    applications_dir()
+        .and_then(|dir| Ok(std::fs::read_to_string(dir.join(DESKTOP_FILE))?))
+        .is_ok_and(|content| content == desktop_entry(exe))
+}
+
+#[cfg(target_os = "linux")]
+pub fn unregister() -> Result<()> {
+    let path = applications_dir()?.join(DESKTOP_FILE);
+    std::fs::remove_file(&path).with_context(|| format!("无法删除 {}", path.display()))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn register(_exe: &Path) -> Result<()> {
+    anyhow::bail!("当前平台不支持注册 openuo:// 链接")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn is_registered(_exe: &Path) -> bool {
+    false
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn unregister() -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(Path::new("/opt/Open UO/$launcher\""));
+        assert!(entry.contains("Exec=\"/opt/Open UO/\\$launcher\\\"\" %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/openuo;\n"));
+    }
+}
@@ -0,0 +1,96 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// 投票网站默认的冷却时间（分钟）
+const DEFAULT_COOLDOWN_MINUTES: u32 = 12 * 60;
+
+/// 更新清单中声明的投票网站
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VoteSite {
+    pub name: String,
+    pub url: String,
+    /// 两次投票之间的间隔（分钟）
+    #[serde(default = "default_cooldown")]
+    pub cooldown_minutes: u32,
+}
+
+fn default_cooldown() -> u32 {
+    DEFAULT_COOLDOWN_MINUTES
+}
+
+impl VoteSite {
+    /// 距离可以再次投票还有多少秒，已经可以投票时为 None
+    ///
+    /// `last_votes` 为每个网站（按 URL）上次投票的 Unix 时间戳。
+    pub fn remaining(&self, last_votes: &BTreeMap<String, i64>, now: i64) -> Option<i64> {
+        let last = *last_votes.get(&self.url)?;
+        let available = last + self.cooldown_minutes as i64 * 60;
+        (available > now).then(|| available - now)
+    }
+}
+
+/// 冷却已经结束、还没有提醒过的网站，返回网站和用于记录提醒的标识
+///
+/// 从没有投过票的网站不提醒；每次投票后最多提醒一次。
+pub fn due_reminders<'a>(
+    sites: &'a [VoteSite],
+    last_votes: &BTreeMap<String, i64>,
+    now: i64,
+    reminded: &HashSet<String>,
+) -> Vec<(&'a VoteSite, String)> {
+    sites
+        .iter()
+        .filter_map(|site| {
+            let last = *last_votes.get(&site.url)?;
+            let key = format!("{}@{}", site.url, last);
+            (site.remaining(last_votes, now).is_none() && !reminded.contains(&key)).then_some((site, key))
+        })
+        .collect()
+}
+
+/// 删除清单中已经不存在的网站的记录
+pub fn prune(last_votes: &mut BTreeMap<String, i64>, sites: &[VoteSite]) -> bool {
+    let before = last_votes.len();
+    last_votes.retain(|url, _| sites.iter().any(|site| site.url == *url));
+    last_votes.len() != before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_and_reminders() {
+        let sites: Vec<VoteSite> = serde_json::from_str(
+            r#"[
+                { "name": "Top 200", "url": "https://top200.example/vote/1" },
+                { "name": "GameSites", "url": "https://gamesites.example/v", "cooldown_minutes": 90 },
+                { "name": "New", "url": "https://new.example" }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(sites[0].cooldown_minutes, 12 * 60);
+        let now = 100_000;
+        let mut last_votes = BTreeMap::from([
+            (sites[0].url.clone(), now - 3600),
+            (sites[1].url.clone(), now - 2 * 3600),
+            ("https://removed.example".to_string(), now),
+        ]);
+        assert_eq!(sites[0].remaining(&last_votes, now), Some(11 * 3600));
+        assert_eq!(sites[1].remaining(&last_votes, now), None);
+        assert_eq!(sites[2].remaining(&last_votes, now), None);
+
+        let mut reminded = HashSet::new();
+        let due = due_reminders(&sites, &last_votes, now, &reminded);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0.name, "GameSites");
+        reminded.insert(due[0].1.clone());
+        assert!(due_reminders(&sites, &last_votes, now, &reminded).is_empty());
+        // 再次投票后冷却结束时重新提醒
+        last_votes.insert(sites[1].url.clone(), now);
+        assert_eq!(due_reminders(&sites, &last_votes, now + 90 * 60, &reminded).len(), 1);
+
+        assert!(prune(&mut last_votes, &sites));
+        assert_eq!(last_votes.len(), 2);
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::history::{GameEvent, SessionControl};
+
+/// 检查窗口标题的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 挂机看门狗配置（按 profile 配置）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    /// 窗口标题包含这些文字（不区分大小写）时视为掉线
+    #[serde(rename = "DisconnectPatterns")]
+    pub disconnect_patterns: Vec<String>,
+    /// 窗口标题中不再出现角色名时视为掉线（回到了登录界面）
+    #[serde(rename = "CharacterLeftTitle")]
+    pub character_left_title: bool,
+    /// 掉线状态持续多久后才重新启动（秒）
+    #[serde(rename = "GraceSeconds")]
+    pub grace_secs: u64,
+    /// 关闭旧进程后等待多久再重新启动（秒）
+    #[serde(rename = "RelaunchDelaySeconds")]
+    pub relaunch_delay_secs: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            disconnect_patterns: vec![
+                "Disconnected".to_string(),
+                "Connection lost".to_string(),
+                "断开".to_string(),
+            ],
+            character_left_title: true,
+            grace_secs: 60,
+            relaunch_delay_secs: 15,
+        }
+    }
+}
+
+/// 根据窗口标题判断是否掉线
+///
+/// 只有先观察到“正常游戏中”的标题后才会触发，避免服务器宕机时无限重启。
+#[derive(Debug)]
+pub struct DisconnectDetector {
+    config: WatchdogConfig,
+    character: String,
+    seen_in_game: bool,
+    suspect_since: Option<Instant>,
+}
+
+impl DisconnectDetector {
+    pub fn new(config: WatchdogConfig, character: &str) -> Self {
+        Self {
+            config,
+            character: character.trim().to_lowercase(),
+            seen_in_game: false,
+            suspect_since: None,
+        }
+    }
+
+    /// 输入一次窗口标题，返回是否确认掉线
+    pub fn observe(&mut self, title: &str, now: Instant) -> bool {
+        let title = title.to_lowercase();
+        let matches_pattern = self
+            .config
+            .disconnect_patterns
+            .iter()
+            .map(|p| p.trim().to_lowercase())
+            .any(|p| !p.is_empty() && title.contains(&p));
+        let has_character = !self.character.is_empty() && title.contains(&self.character);
+        let character_gone = self.config.character_left_title && !self.character.is_empty() && !has_character;
+
+        if !matches_pattern && !character_gone {
+            self.seen_in_game = true;
+            self.suspect_since = None;
+            return false;
+        }
+        if !self.seen_in_game {
+            return false;
+        }
+
+        let since = *self.suspect_since.get_or_insert(now);
+        now.duration_since(since) >= Duration::from_secs(self.config.grace_secs)
+    }
+}
+
+/// 在后台线程监视游戏窗口，确认掉线后结束进程并通知 UI 重新启动
+pub fn spawn(
+    pid: u32,
+    config: WatchdogConfig,
+    character: String,
+    record_id: String,
+    profile_id: String,
+    control: Arc<SessionControl>,
+    tx: mpsc::Sender<GameEvent>,
+) {
+    std::thread::spawn(move || {
+        let mut detector = DisconnectDetector::new(config, &character);
+        while !control.has_exited() {
+            std::thread::sleep(POLL_INTERVAL);
+            let Some(title) = window_title(pid) else {
+                continue;
+            };
+            if detector.observe(&title, Instant::now()) {
+                tracing::info!("Watchdog: disconnect detected for pid {} (title: {})", pid, title);
+                control.request_kill();
+                let _ = tx.send(GameEvent::Disconnected { record_id, profile_id });
+                return;
+            }
+        }
+    });
+}
+
+/// 获取进程主窗口的标题
+#[cfg(target_os = "windows")]
+pub fn window_title(pid: u32) -> Option<String> {
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible,
+    };
+
+    struct Search {
+        pid: u32,
+        title: Option<String>,
+    }
+
+    unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = unsafe { &mut *(lparam.0 as *mut Search) };
+        let mut window_pid = 0u32;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut window_pid)) };
+        if window_pid != search.pid || !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+            return BOOL(1);
+        }
+        let mut buf = [0u16; 512];
+        let len = unsafe { GetWindowTextW(hwnd, &mut buf) };
+        if len > 0 {
+            search.title = Some(String::from_utf16_lossy(&buf[..len as usize]));
+            return BOOL(0);
+        }
+        BOOL(1)
+    }
+
+    let mut search = Search { pid, title: None };
+    // 找到窗口后回调返回 FALSE，EnumWindows 会因此返回错误，忽略即可
+    let _ = unsafe { EnumWindows(Some(callback), LPARAM(&mut search as *mut Search as isize)) };
+    search.title
+}
+
+/// Linux 上通过 wmctrl 获取（X11，未安装时看门狗不会触发）
+#[cfg(target_os = "linux")]
+pub fn window_title(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("wmctrl").arg("-lp").output().ok()?;
+    parse_wmctrl_title(&String::from_utf8_lossy(&output.stdout), pid)
+}
+
+/// macOS 上通过 System Events 获取（需要辅助功能权限）
+#[cfg(target_os = "macos")]
+pub fn window_title(pid: u32) -> Option<String> {
+    let script = format!(
+        "tell application \"System Events\" to get name of front window of (first process whose unix id is {})",
+        pid
+    );
+    let output = std::process::Command::new("osascript").args(["-e", &script]).output().ok()?;
+    let title = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !title.is_empty()).then_some(title)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn window_title(_pid: u32) -> Option<String> {
+    None
+}
+
+/// 解析 `wmctrl -lp` 的输出：窗口 ID、桌面、PID、主机名、标题
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_wmctrl_title(output: &str, pid: u32) -> Option<String> {
+    output.lines().find_map(|line| {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let line_pid: u32 = parts.get(2)?.parse().ok()?;
+        (line_pid == pid).then(|| parts.get(4..).unwrap_or_default().join(" "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> DisconnectDetector {
+        let config = WatchdogConfig {
+            enabled: true,
+            grace_secs: 30,
+            ..Default::default()
+        };
+        DisconnectDetector::new(config, "Lord British")
+    }
+
+    #[test]
+    fn test_detects_after_grace_period() {
+        let start = Instant::now();
+        let mut d = detector();
+        assert!(!d.observe("OpenUO - Lord British (Britannia)", start));
+        assert!(!d.observe("OpenUO - Lord British - Disconnected", start + Duration::from_secs(5)));
+        assert!(!d.observe("OpenUO - Lord British - Disconnected", start + Duration::from_secs(20)));
+        assert!(d.observe("OpenUO - Lord British - Disconnected", start + Duration::from_secs(40)));
+    }
+
+    #[test]
+    fn test_character_left_title() {
+        let start = Instant::now();
+        let mut d = detector();
+        assert!(!d.observe("OpenUO - Lord British", start));
+        assert!(!d.observe("OpenUO 1.0.0", start + Duration::from_secs(1)));
+        // 短暂离开后恢复，计时重置
+        assert!(!d.observe("OpenUO - Lord British", start + Duration::from_secs(10)));
+        assert!(!d.observe("OpenUO 1.0.0", start + Duration::from_secs(20)));
+        assert!(d.observe("OpenUO 1.0.0", start + Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn test_never_in_game_does_not_trigger() {
+        let start = Instant::now();
+        let mut d = detector();
+        assert!(!d.observe("OpenUO 1.0.0", start));
+        assert!(!d.observe("OpenUO 1.0.0", start + Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn test_parse_wmctrl() {
+        let output = "0x04a00003  0 1234   host OpenUO - Lord British\n0x05000007 -1 99 host  Terminal\n";
+        assert_eq!(parse_wmctrl_title(output, 1234).as_deref(), Some("OpenUO - Lord British"));
+        assert_eq!(parse_wmctrl_title(output, 99).as_deref(), Some("Terminal"));
+        assert_eq!(parse_wmctrl_title(output, 5), None);
+    }
+}